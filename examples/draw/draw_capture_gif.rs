@@ -0,0 +1,72 @@
+// Records the first 100 frames of a simple animation as a looping GIF, written to
+// `/<path_to_nannou>/nannou/draw_capture_gif.gif`.
+//
+// Captured frames are read back from the GPU and encoded on another thread so that the window
+// keeps running smoothly while recording.
+
+use nannou::prelude::*;
+use nannou::video::{self, AnimationRecorder};
+use std::cell::RefCell;
+use std::time::Duration;
+
+const FRAMES: u64 = 100;
+const FPS: f64 = 50.0;
+
+struct Model {
+    recorder: RefCell<Option<AnimationRecorder>>,
+}
+
+fn main() {
+    nannou::app(model).run();
+}
+
+fn model(app: &App) -> Model {
+    app.new_window().size(320, 240).view(view).build().unwrap();
+    app.set_loop_mode(LoopMode::rate_fps(FPS));
+    let path = app
+        .project_path()
+        .expect("failed to locate `project_path`")
+        .join(app.exe_name().unwrap())
+        .with_extension("gif");
+    let encoder = video::gif::Builder::new()
+        .dither(true)
+        .build_file(path)
+        .expect("failed to create GIF file");
+    let recorder = RefCell::new(Some(AnimationRecorder::new(encoder)));
+    Model { recorder }
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(BLACK);
+
+    let t = frame.nth() as f32 / FRAMES as f32 * TAU;
+    let win = app.window_rect();
+    for i in 0..8 {
+        let phase = i as f32 / 8.0 * TAU;
+        draw.ellipse()
+            .x_y(
+                (t + phase).cos() * win.w() * 0.3,
+                (t + phase).sin() * win.h() * 0.3,
+            )
+            .radius(12.0 + 8.0 * (t * 2.0 + phase).sin())
+            .hsv(i as f32 / 8.0, 0.8, 1.0);
+    }
+
+    draw.to_frame(app, &frame).unwrap();
+
+    let mut recorder = model.recorder.borrow_mut();
+    if frame.nth() < FRAMES {
+        if let Some(recorder) = recorder.as_mut() {
+            let delay = Duration::from_secs_f64(1.0 / FPS);
+            recorder.record_frame(frame, delay).unwrap();
+        }
+    } else if let Some(recorder) = recorder.take() {
+        // Wait for the remaining frames to be read back and complete the GIF.
+        let window = app.main_window();
+        let device = window.swap_chain_device();
+        recorder.await_active_snapshots(device).unwrap();
+        let stats = recorder.finish().unwrap();
+        println!("Recorded {} frames", stats.frames_written);
+    }
+}
@@ -0,0 +1,47 @@
+use nannou::lyon::tessellation::LineCap;
+use nannou::prelude::*;
+
+fn main() {
+    nannou::sketch(view).run()
+}
+
+fn view(app: &App, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(BLACK);
+
+    let win = app.window_rect();
+    let t = app.time;
+
+    // A dashed line whose dashes march along with time.
+    draw.line()
+        .start(pt2(win.left() * 0.8, win.top() * 0.6))
+        .end(pt2(win.right() * 0.8, win.top() * 0.6))
+        .weight(4.0)
+        .dash(20.0, 10.0)
+        .dash_offset(-t * 30.0)
+        .color(WHITE);
+
+    // A dash-dot pattern with round caps on each dash.
+    draw.line()
+        .start(pt2(win.left() * 0.8, win.top() * 0.3))
+        .end(pt2(win.right() * 0.8, win.top() * 0.3))
+        .weight(4.0)
+        .dashes(vec![24.0, 10.0, 0.0, 10.0])
+        .dash_cap(LineCap::Round)
+        .color(SKYBLUE);
+
+    // A dotted polyline whose colors remain continuous across the gaps.
+    let n_points = 50;
+    let points = (0..n_points).map(|i| {
+        let fract = i as f32 / (n_points - 1) as f32;
+        let x = map_range(fract, 0.0, 1.0, win.left() * 0.8, win.right() * 0.8);
+        let y = (fract * TAU * 2.0 + t).sin() * win.h() * 0.15 - win.h() * 0.2;
+        (pt2(x, y), hsl(fract, 0.8, 0.6))
+    });
+    draw.polyline()
+        .weight(8.0)
+        .dots(14.0)
+        .points_colored(points);
+
+    draw.to_frame(app, &frame).unwrap();
+}
@@ -0,0 +1,59 @@
+use nannou::draw::properties::gradient::{ColorSpace, Spread};
+use nannou::draw::Gradient;
+use nannou::prelude::*;
+
+fn main() {
+    nannou::sketch(view).run()
+}
+
+fn view(app: &App, frame: Frame) {
+    let win = app.window_rect();
+    let t = app.time;
+    let draw = app.draw();
+
+    draw.background().color(BLACK);
+
+    let size = win.w().min(win.h()) * 0.3;
+
+    // A linear gradient interpolated in the LCh color space.
+    let linear = Gradient::linear(pt2(-size * 0.5, 0.0), pt2(size * 0.5, 0.0))
+        .stop(0.0, PLUM)
+        .stop(1.0, GOLD)
+        .color_space(ColorSpace::Lch);
+    draw.rect()
+        .x(-win.w() * 0.3)
+        .rotate(t * 0.1)
+        .w_h(size, size)
+        .gradient(linear);
+
+    // A repeating radial gradient.
+    let radial = Gradient::radial(pt2(0.0, 0.0), size * 0.15)
+        .stop(0.0, WHITE)
+        .stop(1.0, STEELBLUE)
+        .spread(Spread::Reflect);
+    draw.ellipse().w_h(size, size).gradient(radial);
+
+    // A conic gradient filling a polygon, with a gradient along its stroke.
+    let conic = Gradient::conic(pt2(0.0, 0.0), t)
+        .stop(0.0, RED)
+        .stop(0.5, BLUE)
+        .stop(1.0, RED);
+    let stroke = Gradient::linear(pt2(0.0, -size * 0.5), pt2(0.0, size * 0.5))
+        .stop(0.0, BLACK)
+        .stop(1.0, WHITE);
+    let points = (0..6).map(|i| {
+        let phase = i as f32 / 6.0;
+        pt2(
+            size * 0.5 * (TAU * phase).cos(),
+            size * 0.5 * (TAU * phase).sin(),
+        )
+    });
+    draw.polygon()
+        .x(win.w() * 0.3)
+        .stroke_weight(8.0)
+        .stroke_gradient(stroke)
+        .gradient(conic)
+        .points(points);
+
+    draw.to_frame(app, &frame).unwrap();
+}
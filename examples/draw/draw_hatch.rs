@@ -0,0 +1,57 @@
+use nannou::geom::path::hatch;
+use nannou::prelude::*;
+
+fn main() {
+    nannou::sketch(view).run()
+}
+
+fn view(app: &App, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(WHITE);
+
+    let win = app.window_rect();
+    let t = app.time;
+    let size = win.w().min(win.h()) * 0.2;
+
+    // A ring with a hole, described by two sub-paths.
+    let ring = geom::path()
+        .polygon(&square_points(size))
+        .polygon(&square_points(size * 0.4))
+        .build();
+    let star = geom::Polygon::new(star_points(size));
+
+    // Each hatch is a list of polylines that could equally be sent to a pen plotter or a laser.
+    let opts = hatch::Options::default().spacing(6.0).angle(t * 0.2);
+    let fills = vec![
+        (win.w() * -0.3, ring.hatch(&opts)),
+        (0.0, ring.hatch(&opts.cross_hatch())),
+        (win.w() * 0.3, star.hatch(&opts.concentric())),
+    ];
+    for (x, lines) in fills {
+        let draw = draw.x(x);
+        for line in lines {
+            draw.polyline().weight(1.5).points(line).color(BLACK);
+        }
+    }
+
+    draw.to_frame(app, &frame).unwrap();
+}
+
+fn square_points(half_size: f32) -> Vec<Point2> {
+    vec![
+        pt2(-half_size, -half_size),
+        pt2(half_size, -half_size),
+        pt2(half_size, half_size),
+        pt2(-half_size, half_size),
+    ]
+}
+
+fn star_points(radius: f32) -> Vec<Point2> {
+    (0..10)
+        .map(|i| {
+            let angle = i as f32 / 10.0 * TAU + PI / 2.0;
+            let r = if i % 2 == 0 { radius } else { radius * 0.45 };
+            pt2(angle.cos() * r, angle.sin() * r)
+        })
+        .collect()
+}
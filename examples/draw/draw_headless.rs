@@ -0,0 +1,38 @@
+// Renders a short animation without opening a window, writing each frame as a PNG image file to
+// `/<path_to_nannou>/nannou/draw_headless/<frame_number>.png`.
+//
+// This is useful for rendering on machines without a display, e.g. servers.
+
+use nannou::prelude::*;
+
+fn main() {
+    let capture_dir = nannou::app::find_project_path()
+        .expect("failed to locate `project_path`")
+        .join("draw_headless");
+    nannou::sketch(view)
+        .headless(640, 480)
+        .fps(30.0)
+        .frames(90)
+        .capture_frames(capture_dir)
+        .run();
+}
+
+fn view(app: &App, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(BLACK);
+
+    let win = app.window_rect();
+    let t = app.time;
+    let n = 12;
+    for i in 0..n {
+        let phase = i as f32 / n as f32 * TAU;
+        let x = (t + phase).cos() * win.w() * 0.3;
+        let y = (t * 2.0 + phase).sin() * win.h() * 0.3;
+        draw.ellipse()
+            .x_y(x, y)
+            .radius(20.0)
+            .hsv(i as f32 / n as f32, 0.8, 1.0);
+    }
+
+    draw.to_frame(app, &frame).unwrap();
+}
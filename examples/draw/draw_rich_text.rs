@@ -0,0 +1,63 @@
+//! Demonstrates drawing text whose font size, color and decoration varies between spans via the
+//! `draw.text(..).span(..)` API.
+//!
+//! The same spans are laid out via `text::rich` in order to highlight the glyph under the mouse.
+
+use nannou::prelude::*;
+use nannou::text::rich::{self, Style};
+
+fn main() {
+    nannou::sketch(view).run()
+}
+
+fn spans() -> Vec<(&'static str, Style)> {
+    vec![
+        (
+            "Rich text ",
+            Style::new().font_size(48).color(DARKSLATEBLUE),
+        ),
+        ("may mix ", Style::new()),
+        ("sizes", Style::new().font_size(36)),
+        (", ", Style::new()),
+        ("colors", Style::new().color(CRIMSON)),
+        (", ", Style::new()),
+        ("underlines", Style::new().underline()),
+        (", ", Style::new()),
+        ("strikethroughs", Style::new().strikethrough()),
+        (" and ", Style::new()),
+        ("letter spacing", Style::new().letter_spacing(6.0)),
+        (
+            " within a single block of wrapped and justified text.",
+            Style::new(),
+        ),
+    ]
+}
+
+fn view(app: &App, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(WHITE);
+    let win_rect = app.main_window().rect().pad(40.0);
+
+    // Highlight the glyph under the mouse.
+    let text = spans()
+        .into_iter()
+        .fold(rich::text(), |text, (s, style)| text.span(s, style))
+        .font_size(24)
+        .build(win_rect, &Default::default());
+    let mouse = app.mouse.position();
+    if let Some(glyph) = text.glyphs().find(|g| g.rect.contains(mouse)) {
+        draw.rect()
+            .xy(glyph.rect.xy())
+            .wh(glyph.rect.wh())
+            .color(LIGHTGOLDENRODYELLOW);
+    }
+
+    spans()
+        .into_iter()
+        .fold(draw.text(""), |text, (s, style)| text.span(s, style))
+        .color(BLACK)
+        .font_size(24)
+        .wh(win_rect.wh());
+
+    draw.to_frame(app, &frame).unwrap();
+}
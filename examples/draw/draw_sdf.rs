@@ -0,0 +1,69 @@
+use nannou::geom::sdf::{self, Sdf};
+use nannou::noise::{NoiseFn, Perlin};
+use nannou::prelude::*;
+
+fn main() {
+    nannou::app(model).simple_window(view).run()
+}
+
+struct Model {
+    perlin: Perlin,
+}
+
+fn model(_app: &App) -> Model {
+    Model {
+        perlin: Perlin::new(),
+    }
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(BLACK);
+
+    let win = app.window_rect();
+    let t = app.time;
+    let size = win.w().min(win.h()) * 0.12;
+
+    // A handful of orbiting blobs, smoothly merged into one another.
+    let blob = |i: usize| {
+        let angle = t * 0.3 * (i + 1) as f32 + i as f32;
+        let centre = pt2(angle.cos(), angle.sin()) * size * 2.0;
+        sdf::circle(centre, size * 0.6)
+    };
+    let smoothness = size * 0.8;
+    let blobs = sdf::circle(pt2(0.0, 0.0), size)
+        .smooth_union(blob(0), smoothness)
+        .smooth_union(blob(1), smoothness)
+        .smooth_union(blob(2), smoothness)
+        .smooth_union(blob(3), smoothness);
+
+    // Cut a tiling of rounded squares out of the blobs and roughen their edges with noise.
+    let tiles = sdf::rounded_rect(geom::Rect::from_w_h(size * 0.3, size * 0.3), size * 0.05)
+        .rotate(t * 0.5)
+        .repeat(vec2(size * 0.6, size * 0.6));
+    let perlin = &model.perlin;
+    let noise = move |p: Point2| {
+        let point = [p.x as f64 * 0.01, p.y as f64 * 0.01, t as f64 * 0.2];
+        perlin.get(point) as f32 * size * 0.15
+    };
+    let shape = blobs.smooth_subtract(tiles, size * 0.05).displace(noise);
+
+    // Sample the field and draw a few of its iso-contours.
+    let field = shape.sample(win, 6.0);
+    draw.path()
+        .fill()
+        .color(hsl(0.6, 0.5, 0.3))
+        .events(field.contour_path(0.0).iter());
+    for i in 1..6 {
+        let iso = i as f32 * size * 0.15;
+        let lightness = 0.6 - i as f32 * 0.08;
+        for contour in field.contours(iso) {
+            draw.polyline()
+                .weight(1.5)
+                .points(contour)
+                .color(hsl(0.6, 0.5, lightness));
+        }
+    }
+
+    draw.to_frame(app, &frame).unwrap();
+}
@@ -0,0 +1,35 @@
+//! Demonstrates drawing text via signed distance fields with `draw.text(..).sdf()`.
+//!
+//! The upper text is drawn via the glyph cache while the lower text is drawn via signed distance
+//! fields. Both are scaled by the draw transform, so the glyph cache's text blurs as it grows while
+//! the signed distance field text remains crisp. The lower text is also given an outline and glow.
+
+use nannou::prelude::*;
+
+fn main() {
+    nannou::sketch(view).run()
+}
+
+fn view(app: &App, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(DARKSLATEGREY);
+
+    let scale = map_range((app.time * 0.5).sin(), -1.0, 1.0, 1.0, 6.0);
+    let text = "nannou";
+
+    draw.y(100.0)
+        .scale(scale)
+        .text(text)
+        .font_size(16)
+        .color(WHITE);
+
+    draw.y(-100.0)
+        .scale(scale)
+        .text(text)
+        .font_size(16)
+        .color(WHITE)
+        .outline(1.0, BLACK)
+        .glow(1.5, rgba(1.0, 0.6, 0.0, 0.8));
+
+    draw.to_frame(app, &frame).unwrap();
+}
@@ -0,0 +1,57 @@
+//! Demonstrates laying out text along a path with `draw.text_on_path(..)`.
+//!
+//! The upper text follows a wave via the glyph cache, scrolling along the path over time and
+//! omitting the glyphs that run off either end. The lower text is drawn around a circle by
+//! tessellating the filled outline of each glyph. Moving the mouse along the *x* axis adjusts
+//! the spacing between glyphs.
+
+use nannou::prelude::*;
+
+fn main() {
+    nannou::sketch(view).run()
+}
+
+fn view(app: &App, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(WHITE);
+
+    let win = app.window_rect();
+    let spacing = map_range(app.mouse.x, win.left(), win.right(), 0.0, 12.0);
+
+    // A wave across the top half of the window.
+    let mut builder = geom::path().move_to(pt2(win.left(), 150.0));
+    for i in 1..=100 {
+        let x = map_range(i, 0, 100, win.left(), win.right());
+        let y = 150.0 + (x * 0.02 + app.time).sin() * 50.0;
+        builder = builder.line_to(pt2(x, y));
+    }
+    let wave = builder.build();
+    draw.path()
+        .stroke()
+        .weight(1.0)
+        .color(LIGHTGREY)
+        .events(wave.iter());
+    let offset = (app.time * 100.0) % (win.w() * 2.0) - win.w();
+    draw.text_on_path("text flowing along a path", &wave)
+        .font_size(32)
+        .center_justify()
+        .offset(offset)
+        .spacing(spacing)
+        .clip(true)
+        .color(BLACK);
+
+    // A circle beginning at the top, travelling clockwise.
+    let radius = 120.0;
+    let circle = geom::path()
+        .move_to(pt2(0.0, radius - 150.0))
+        .arc(pt2(0.0, -150.0), vec2(radius, radius), -TAU, 0.0)
+        .build();
+    draw.text_on_path("around and around we go", &circle)
+        .font_size(28)
+        .center_justify()
+        .spacing(spacing)
+        .outlines()
+        .color(STEELBLUE);
+
+    draw.to_frame(app, &frame).unwrap();
+}
@@ -0,0 +1,44 @@
+use nannou::geom::{delaunay, voronoi};
+use nannou::prelude::*;
+
+fn main() {
+    nannou::sketch(view).run()
+}
+
+fn view(app: &App, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(BLACK);
+
+    let win = app.window_rect();
+    let t = app.time * 0.1;
+
+    // Points drifting along lissajous curves.
+    let n_points = 40;
+    let points: Vec<Point2> = (0..n_points)
+        .map(|i| {
+            let f = i as f32 + 1.0;
+            let x = (t * f * 0.37 + f).sin() * win.w() * 0.45;
+            let y = (t * f * 0.23 + f * 2.0).cos() * win.h() * 0.45;
+            pt2(x, y)
+        })
+        .collect();
+
+    // Fill each Voronoi cell with its own hue.
+    let cells = voronoi::cells(points.iter().cloned(), win);
+    for (i, cell) in cells.into_iter().enumerate() {
+        let hue = i as f32 / n_points as f32;
+        draw.polygon().points(cell).color(hsl(hue, 0.5, 0.3));
+    }
+
+    // Draw the edges of the Delaunay triangulation on top.
+    let triangulation = delaunay::Triangulation::new(points.iter().cloned());
+    for (a, b) in triangulation.edges() {
+        let (a, b) = (points[a], points[b]);
+        draw.line().start(a).end(b).weight(1.0).color(WHITE);
+    }
+    for &p in &points {
+        draw.ellipse().xy(p).radius(3.0).color(WHITE);
+    }
+
+    draw.to_frame(app, &frame).unwrap();
+}
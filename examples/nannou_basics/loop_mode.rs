@@ -1,5 +1,4 @@
-//! A simple example demonstrating the behaviour of the three `LoopMode` variants supported by
-//! nannou.
+//! A simple example demonstrating the behaviour of the `LoopMode` variants supported by nannou.
 //!
 //! The `LoopMode` determines how the nannou application loop is driven.
 //!
@@ -40,7 +39,8 @@ fn key_pressed(app: &App, _model: &mut Model, _key: Key) {
     match app.loop_mode() {
         LoopMode::Wait { .. } => app.set_loop_mode(LoopMode::RefreshSync),
         LoopMode::RefreshSync { .. } => app.set_loop_mode(LoopMode::rate_fps(60.0)),
-        LoopMode::Rate { .. } => app.set_loop_mode(LoopMode::loop_once()),
+        LoopMode::Rate { .. } => app.set_loop_mode(LoopMode::fixed_timestep(30.0)),
+        LoopMode::FixedTimestep { .. } => app.set_loop_mode(LoopMode::loop_once()),
         LoopMode::NTimes { .. } => app.set_loop_mode(LoopMode::Wait),
     }
     println!("Loop mode switched to: {:?}", app.loop_mode());
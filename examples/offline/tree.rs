@@ -2,7 +2,6 @@
 // Alexis Andre (@mactuitui)
 
 mod colors;
-mod quadtree;
 use crate::colors::Palette;
 use nannou::prelude::Frame;
 use nannou::prelude::*;
@@ -37,12 +36,6 @@ impl PartialEq for Thing {
         self.position == other.position
     }
 }
-// define what to use for the quadtree
-impl quadtree::WithPos for Thing {
-    fn get_pos(&self) -> Vector2 {
-        self.position
-    }
-}
 
 impl Thing {
     fn new(x: f32, y: f32, s: f32, f: f32, parent: Option<usize>) -> Self {
@@ -128,14 +121,14 @@ fn update(app: &App, model: &mut Model, _update: Update) {
     //try to grow each circle until it hits another one
 
     //recreate the tree
-    let mut tree = quadtree::QuadTree::new();
+    let mut tree = geom::spatial::QuadTree::new(Rect::from_w_h(1024.0, 1024.0));
     for i in 0..model.things.len() {
         model.things[i].grown = false;
     }
     //grow and branch out?
     let max_count = model.things.len();
     for i in 0..model.things.len() {
-        tree.insert(&model.things, i);
+        tree.insert(model.things[i].position, i);
     }
     for i in 0..max_count {
         if model.things[i].parent == None {
@@ -171,7 +164,11 @@ fn update(app: &App, model: &mut Model, _update: Update) {
                 let s = 1.0;
                 let mut candidate = Thing::new(x, y, s, angle / PI, Some(i));
                 candidate.generation = model.things[i].generation + 1;
-                let indices = tree.get_elements(&model.things, x, y, 50.0);
+                let indices: Vec<usize> = tree
+                    .within_radius(pt2(x, y), 50.0)
+                    .into_iter()
+                    .map(|(_, &ix)| ix)
+                    .collect();
                 let mut ok = true;
                 for k in 0..indices.len() {
                     let d = model.things[indices[k]].distancept(x, y);
@@ -206,12 +203,11 @@ fn update(app: &App, model: &mut Model, _update: Update) {
     //check if the grown things are free
     for i in 0..model.things.len() {
         if model.things[i].grown == true {
-            let indices = tree.get_elements(
-                &model.things,
-                model.things[i].position.x,
-                model.things[i].position.y,
-                60.0,
-            );
+            let indices: Vec<usize> = tree
+                .within_radius(model.things[i].position, 60.0)
+                .into_iter()
+                .map(|(_, &ix)| ix)
+                .collect();
             for k in 0..indices.len() {
                 let mut ok = true;
                 if let Some(parent) = model.things[i].parent {
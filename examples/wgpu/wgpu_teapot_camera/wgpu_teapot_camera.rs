@@ -1,10 +1,9 @@
 use nannou::math::cgmath::{self, Matrix3, Matrix4, Point3, Rad, Vector3};
+use nannou::mesh;
 use nannou::prelude::*;
 use nannou::winit;
 use std::cell::RefCell;
 
-mod data;
-
 struct Model {
     camera_is_active: bool,
     graphics: RefCell<Graphics>,
@@ -15,6 +14,7 @@ struct Graphics {
     vertex_buffer: wgpu::Buffer,
     normal_buffer: wgpu::Buffer,
     index_buffer: wgpu::Buffer,
+    index_count: u32,
     uniform_buffer: wgpu::Buffer,
     depth_texture: wgpu::Texture,
     depth_texture_view: wgpu::TextureView,
@@ -102,10 +102,29 @@ fn model(app: &App) -> Model {
     let vs_mod = wgpu::shader_from_spirv_bytes(device, include_bytes!("shaders/vert.spv"));
     let fs_mod = wgpu::shader_from_spirv_bytes(device, include_bytes!("shaders/frag.spv"));
 
+    // Load the teapot model.
+    let assets = app.assets_path().unwrap();
+    let teapot = mesh::io::load(assets.join("models").join("teapot.obj")).unwrap();
+    let vertices: Vec<Vertex> = teapot
+        .points
+        .iter()
+        .map(|p| Vertex {
+            position: (p.x, p.y, p.z),
+        })
+        .collect();
+    let normals: Vec<Normal> = teapot
+        .normals
+        .iter()
+        .map(|n| Normal {
+            normal: (n.x, n.y, n.z),
+        })
+        .collect();
+    let index_count = teapot.indices.len() as u32;
+
     // Create the vertex, normal and index buffers.
-    let vertices_bytes = vertices_as_bytes(&data::VERTICES);
-    let normals_bytes = normals_as_bytes(&data::NORMALS);
-    let indices_bytes = indices_as_bytes(&data::INDICES);
+    let vertices_bytes = vertices_as_bytes(&vertices);
+    let normals_bytes = normals_as_bytes(&normals);
+    let indices_bytes = indices_as_bytes(&teapot.indices);
     let vertex_usage = wgpu::BufferUsage::VERTEX;
     let index_usage = wgpu::BufferUsage::INDEX;
     let vertex_buffer = device.create_buffer_init(&BufferInitDescriptor {
@@ -158,6 +177,7 @@ fn model(app: &App) -> Model {
         vertex_buffer,
         normal_buffer,
         index_buffer,
+        index_count,
         uniform_buffer,
         depth_texture,
         depth_texture_view,
@@ -296,7 +316,7 @@ fn view(_app: &App, model: &Model, frame: Frame) {
     render_pass.set_vertex_buffer(0, g.vertex_buffer.slice(..));
     render_pass.set_vertex_buffer(1, g.normal_buffer.slice(..));
     render_pass.set_index_buffer(g.index_buffer.slice(..));
-    let index_range = 0..data::INDICES.len() as u32;
+    let index_range = 0..g.index_count;
     let start_vertex = 0;
     let instance_range = 0..1;
     render_pass.draw_indexed(index_range, start_vertex, instance_range);
@@ -373,7 +393,7 @@ fn create_render_pipeline(
         .add_vertex_buffer::<Vertex>(&wgpu::vertex_attr_array![0 => Float3])
         .add_vertex_buffer::<Normal>(&wgpu::vertex_attr_array![1 => Float3])
         .depth_format(depth_format)
-        .index_format(wgpu::IndexFormat::Uint16)
+        .index_format(wgpu::IndexFormat::Uint32)
         .sample_count(sample_count)
         .build(device)
 }
@@ -388,7 +408,7 @@ fn normals_as_bytes(data: &[Normal]) -> &[u8] {
     unsafe { wgpu::bytes::from_slice(data) }
 }
 
-fn indices_as_bytes(data: &[u16]) -> &[u8] {
+fn indices_as_bytes(data: &[u32]) -> &[u8] {
     unsafe { wgpu::bytes::from_slice(data) }
 }
 
@@ -7,10 +7,12 @@
 //! - [**Proxy**](./struct.Proxy.html) - a handle to an **App** that may be used from a non-main
 //!   thread.
 //! - [**LoopMode**](./enum.LoopMode.html) - describes the behaviour of the application event loop.
+//! - [**HeadlessBuilder**](./struct.HeadlessBuilder.html) - runs an **App** without a window or
+//!   event loop, drawing into an offscreen texture.
 
 use crate::draw;
 use crate::event::{self, Event, Key, LoopEvent, Update};
-use crate::frame::{Frame, RawFrame};
+use crate::frame::{self, Frame, RawFrame};
 use crate::geom;
 use crate::state;
 use crate::time::DurationF64;
@@ -20,9 +22,9 @@ use crate::window::{self, Window};
 use find_folder;
 use instant::Instant;
 use std;
-use std::cell::{RefCell, RefMut};
+use std::cell::{Cell, RefCell, RefMut};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{self, AtomicBool};
 use std::sync::Arc;
 use std::time::Duration;
@@ -47,6 +49,9 @@ pub type SketchViewFn = fn(&App, Frame);
 /// The user function type allowing them to consume the `model` when the application exits.
 pub type ExitFn<Model> = fn(&App, Model);
 
+/// The user function type for determining whether or not a headless app should stop running.
+pub type StopFn<Model> = fn(&App, &Model) -> bool;
+
 /// The **App**'s view function.
 enum View<Model = ()> {
     /// A view function allows for viewing the user's model.
@@ -74,6 +79,30 @@ pub struct SketchBuilder<E = Event> {
     builder: Builder<(), E>,
 }
 
+/// A nannou `App` builder for running without a window or event loop.
+///
+/// Created via `app::Builder::headless` or `app::SketchBuilder::headless`.
+///
+/// Rather than presenting to a window, the default `view` function is delivered a **Frame** that
+/// targets an offscreen texture of the given size. Rather than being driven by the event loop,
+/// `update` is stepped by a fixed-timestep clock, so that `app.time` advances by exactly the
+/// same amount between each frame regardless of how long rendering takes. Each update is
+/// immediately followed by a call to `view`.
+///
+/// The app runs until the given number of `frames` have been rendered, the `stop` function
+/// returns `true` or `App::quit` is called, whichever comes first.
+pub struct HeadlessBuilder<M = (), E = Event> {
+    builder: Builder<M, E>,
+    size: [u32; 2],
+    scale_factor: f64,
+    msaa_samples: u32,
+    update_interval: Duration,
+    frames: Option<u64>,
+    stop: Option<StopFn<M>>,
+    capture_directory: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy)]
 enum DefaultWindowSize {
     /// Default window size in logical coordinates.
     Logical(winit::dpi::LogicalSize<u32>),
@@ -106,8 +135,10 @@ pub struct App {
     max_capture_frame_jobs: u32,
     capture_frame_timeout: Option<Duration>,
     pub(crate) event_loop_window_target: Option<EventLoopWindowTarget>,
-    pub(crate) event_loop_proxy: Proxy,
+    pub(crate) event_loop_proxy: Option<Proxy>,
     pub(crate) windows: RefCell<HashMap<window::Id, Window>>,
+    /// The offscreen render target used in place of a window when running headless.
+    pub(crate) headless: Option<Headless>,
     /// The wgpu backends to choose between.
     backends: wgpu::BackendBit,
     /// The main wgpu instance.
@@ -136,6 +167,9 @@ pub struct App {
     /// `duration.since_start` specifies the duration since the app started running.
     ///
     /// `duration.since_prev_update` specifies the duration since the previous update event.
+    ///
+    /// Both are driven by the app's clock, so they stop advancing while the app is paused and are
+    /// multiplied by the app's time scale. See `App::pause` and `App::set_time_scale`.
     pub duration: state::Time,
     /// The time in seconds since the `App` started running.
     ///
@@ -162,6 +196,20 @@ struct Config {
     loop_mode: LoopMode,
     exit_on_escape: bool,
     fullscreen_on_shortcut: bool,
+    clock: Clock,
+}
+
+// The controllable clock that drives `app.time`, `app.duration` and `Update` events.
+#[derive(Debug)]
+struct Clock {
+    paused: bool,
+    // The number of single steps requested via `App::step` while paused.
+    pending_steps: u32,
+    time_scale: f64,
+    // Scaled time that has not yet been consumed by `FixedTimestep` updates.
+    accumulator: Duration,
+    // The duration of the most recent update, used for single steps in variable loop modes.
+    last_step: Duration,
 }
 
 // Draw state managed by the **App**.
@@ -171,6 +219,18 @@ struct DrawState {
     renderers: RefCell<HashMap<window::Id, RefCell<draw::Renderer>>>,
 }
 
+// State for an **App** running without a window or event loop.
+//
+// The size, device and frame data of the offscreen texture are described by a stand-in `Window`
+// stored alongside any other windows so that the window API remains available to sketches.
+pub(crate) struct Headless {
+    // The offscreen texture to which each `Frame`'s intermediary image is written.
+    texture: wgpu::Texture,
+    texture_view: wgpu::TextureView,
+    // Set via `App::quit`.
+    quit: Cell<bool>,
+}
+
 /// The app uses a set scalar type in order to provide a simplistic API to users.
 ///
 /// If you require changing the scalar type to something else, consider using a custom
@@ -181,7 +241,8 @@ pub type DrawScalar = geom::scalar::Default;
 /// **App**'s inner event loop.
 #[derive(Clone)]
 pub struct Proxy {
-    event_loop_proxy: winit::event_loop::EventLoopProxy<()>,
+    // `None` in the case that the **App** is running headless and has no event loop.
+    event_loop_proxy: Option<winit::event_loop::EventLoopProxy<()>>,
     // Indicates whether or not the events loop is currently asleep.
     //
     // This is set to `true` each time the events loop is ready to return and the `LoopMode` is
@@ -194,8 +255,8 @@ pub struct Proxy {
 // State related specifically to the application loop, shared between loop modes.
 struct LoopState {
     updates_since_event: usize,
-    loop_start: Instant,
-    last_update: Instant,
+    // The moment at which the clock was last advanced.
+    last_tick: Instant,
     total_updates: u64,
 }
 
@@ -237,6 +298,23 @@ pub enum LoopMode {
         /// The number of updates that must be emited regardless of non-update events
         number_of_updates: usize,
     },
+
+    /// Updates the model in steps of exactly `dt`, independently of the rate at which frames are
+    /// presented.
+    ///
+    /// Elapsed time (multiplied by the app's time scale) is accumulated on each iteration of the
+    /// loop and consumed in steps of `dt`, so `Update::since_last` is always equal to `dt`. If
+    /// rendering lags such that more than `max_steps_per_frame` updates are due within a single
+    /// iteration, the remaining whole steps are dropped so that the loop can catch up.
+    ///
+    /// This is useful for simulations that must produce the same results regardless of the
+    /// display refresh rate or machine load.
+    FixedTimestep {
+        /// The duration of each update.
+        dt: Duration,
+        /// The maximum number of updates applied in a single iteration of the loop.
+        max_steps_per_frame: usize,
+    },
 }
 
 impl<M> Builder<M, Event>
@@ -434,6 +512,20 @@ where
         self
     }
 
+    /// Run the app without a window or event loop.
+    ///
+    /// The default `view` function will draw into an offscreen texture of the given size in
+    /// points and `update` will be stepped by a fixed-timestep clock. This is useful for rendering
+    /// on machines without a display, e.g. servers.
+    ///
+    /// Windows cannot be created while running headless, so any default window requested via
+    /// `simple_window` is ignored (though its `view` function is still used).
+    ///
+    /// See the **HeadlessBuilder** docs for more details.
+    pub fn headless(self, width: u32, height: u32) -> HeadlessBuilder<M, E> {
+        HeadlessBuilder::new(self, [width, height])
+    }
+
     /// Build and run an `App` with the specified parameters.
     ///
     /// This function will not return until the application has exited.
@@ -449,7 +541,7 @@ where
         let event_loop_proxy = event_loop.create_proxy();
         let event_loop_is_asleep = Arc::new(AtomicBool::new(false));
         let event_loop_proxy = Proxy {
-            event_loop_proxy,
+            event_loop_proxy: Some(event_loop_proxy),
             event_loop_is_asleep,
         };
 
@@ -462,7 +554,7 @@ where
             .unwrap_or(Some(Self::DEFAULT_CAPTURE_FRAME_TIMEOUT));
         let event_loop_window_target = Some(EventLoopWindowTarget::Owned(event_loop));
         let app = App::new(
-            event_loop_proxy,
+            Some(event_loop_proxy),
             event_loop_window_target,
             self.default_window_size,
             max_capture_frame_jobs,
@@ -510,6 +602,14 @@ where
         self
     }
 
+    /// Run the sketch without a window or event loop, drawing into an offscreen texture of the
+    /// given size in points.
+    ///
+    /// See `app::Builder::headless` for details.
+    pub fn headless(self, width: u32, height: u32) -> HeadlessBuilder<(), E> {
+        self.builder.headless(width, height)
+    }
+
     /// Build and run a `Sketch` with the specified parameters.
     ///
     /// This calls `App::run` internally. See that method for details!
@@ -518,6 +618,150 @@ where
     }
 }
 
+impl<M, E> HeadlessBuilder<M, E>
+where
+    M: 'static,
+    E: LoopEvent,
+{
+    /// The default rate at which the fixed-timestep clock is stepped.
+    pub const DEFAULT_FPS: f64 = 60.0;
+
+    // Begin building a headless app from the given app builder.
+    fn new(builder: Builder<M, E>, size: [u32; 2]) -> Self {
+        HeadlessBuilder {
+            builder,
+            size,
+            scale_factor: 1.0,
+            msaa_samples: Frame::DEFAULT_MSAA_SAMPLES,
+            update_interval: update_interval(Self::DEFAULT_FPS),
+            frames: None,
+            stop: None,
+            capture_directory: None,
+        }
+    }
+
+    /// The number of pixels per point of the offscreen texture.
+    ///
+    /// By default, this is `1.0`.
+    pub fn scale_factor(mut self, scale_factor: f64) -> Self {
+        assert!(scale_factor > 0.0, "scale factor must be greater than zero");
+        self.scale_factor = scale_factor;
+        self
+    }
+
+    /// The number of MSAA samples used for the `Frame`'s intermediary texture.
+    ///
+    /// By default, this is `Frame::DEFAULT_MSAA_SAMPLES`.
+    pub fn msaa_samples(mut self, msaa_samples: u32) -> Self {
+        self.msaa_samples = msaa_samples;
+        self
+    }
+
+    /// The rate at which the fixed-timestep clock is stepped in frames per second.
+    ///
+    /// Each update advances `app.duration.since_start` by exactly `1.0 / fps` seconds.
+    ///
+    /// By default, this is `HeadlessBuilder::DEFAULT_FPS`.
+    pub fn fps(mut self, fps: f64) -> Self {
+        self.update_interval = update_interval(fps);
+        self
+    }
+
+    /// Stop running once the given number of frames have been rendered.
+    pub fn frames(mut self, frames: u64) -> Self {
+        self.frames = Some(frames);
+        self
+    }
+
+    /// A function called after each frame is rendered. The app stops running as soon as this
+    /// returns `true`.
+    pub fn stop(mut self, stop: StopFn<M>) -> Self {
+        self.stop = Some(stop);
+        self
+    }
+
+    /// Capture every frame to a PNG file within the given directory.
+    ///
+    /// Frames are named after their index, e.g. `00000.png`, `00001.png`, etc.
+    pub fn capture_frames<P>(mut self, directory: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        self.capture_directory = Some(directory.as_ref().to_path_buf());
+        self
+    }
+
+    /// Build and run the headless `App` with the specified parameters.
+    ///
+    /// This function will not return until the application has stopped, at which point the
+    /// `exit` function is called (if any) and all pending frame captures have been written.
+    ///
+    /// **Panics** if no wgpu adapter is available.
+    pub fn run(self) {
+        let HeadlessBuilder {
+            builder,
+            size,
+            scale_factor,
+            msaa_samples,
+            update_interval,
+            frames,
+            stop,
+            capture_directory,
+        } = self;
+
+        // Initialise the app without an event loop.
+        let max_capture_frame_jobs = builder
+            .max_capture_frame_jobs
+            .unwrap_or(num_cpus::get() as u32);
+        let capture_frame_timeout = builder
+            .capture_frame_timeout
+            .unwrap_or(Some(Builder::<M, E>::DEFAULT_CAPTURE_FRAME_TIMEOUT));
+        let mut app = App::new(
+            None,
+            None,
+            builder.default_window_size,
+            max_capture_frame_jobs,
+            capture_frame_timeout,
+            builder.backends,
+        );
+
+        // Create the offscreen render target and the stand-in for the default window.
+        let (headless, window) = Headless::new(
+            &app,
+            size,
+            scale_factor,
+            msaa_samples,
+            max_capture_frame_jobs,
+            capture_frame_timeout,
+        );
+        let window_id = window.id();
+        app.windows.borrow_mut().insert(window_id, window);
+        *app.focused_window.borrow_mut() = Some(window_id);
+        app.headless = Some(headless);
+
+        // Create the capture directory if necessary.
+        if let Some(ref dir) = capture_directory {
+            std::fs::create_dir_all(dir).expect("failed to create frame capture directory");
+        }
+
+        // Call the user's model function.
+        let model = (builder.model)(&app);
+
+        run_headless(
+            app,
+            model,
+            builder.event,
+            builder.update,
+            builder.default_view,
+            builder.exit,
+            update_interval,
+            frames,
+            stop,
+            capture_directory,
+        );
+    }
+}
+
 impl Builder<(), Event> {
     /// Shorthand for building a simple app that has no model, handles no events and simply draws
     /// to a single window.
@@ -547,6 +791,9 @@ impl LoopMode {
     /// The minimum number of updates that will be emitted after an event is triggered in Wait
     /// mode.
     pub const UPDATES_PER_WAIT_EVENT: u32 = 3;
+    /// The default maximum number of updates applied within a single loop iteration in the
+    /// `FixedTimestep` mode.
+    pub const DEFAULT_MAX_STEPS_PER_FRAME: usize = 8;
 
     /// A simplified constructor for the default `RefreshSync` loop mode.
     ///
@@ -578,6 +825,145 @@ impl LoopMode {
     pub fn loop_once() -> Self {
         Self::loop_ntimes(1)
     }
+
+    /// Specify the **FixedTimestep** mode with the given updates-per-second.
+    ///
+    /// Uses `LoopMode::DEFAULT_MAX_STEPS_PER_FRAME`.
+    pub fn fixed_timestep(fps: f64) -> Self {
+        let dt = update_interval(fps);
+        let max_steps_per_frame = Self::DEFAULT_MAX_STEPS_PER_FRAME;
+        LoopMode::FixedTimestep {
+            dt,
+            max_steps_per_frame,
+        }
+    }
+
+    // The step duration and maximum steps per frame if in the `FixedTimestep` mode.
+    fn fixed_timestep_params(&self) -> Option<(Duration, usize)> {
+        match *self {
+            LoopMode::FixedTimestep {
+                dt,
+                max_steps_per_frame,
+            } => Some((dt, max_steps_per_frame)),
+            _ => None,
+        }
+    }
+}
+
+impl Clock {
+    // Advance the clock by the given real duration.
+    //
+    // Returns the number of updates that should be applied along with the `since_last` duration
+    // of each.
+    fn advance(
+        &mut self,
+        elapsed: Duration,
+        fixed_timestep: Option<(Duration, usize)>,
+    ) -> (usize, Duration) {
+        if self.paused {
+            if self.pending_steps == 0 {
+                return (0, Duration::default());
+            }
+            self.pending_steps -= 1;
+            let step = fixed_timestep.map(|(dt, _)| dt).unwrap_or(self.last_step);
+            return (1, step);
+        }
+
+        let scaled = elapsed.mul_f64(self.time_scale);
+        let (dt, max_steps) = match fixed_timestep {
+            None => {
+                self.last_step = scaled;
+                return (1, scaled);
+            }
+            Some(params) => params,
+        };
+
+        // A zero `dt` would never consume the accumulator.
+        assert!(
+            dt > Duration::default(),
+            "`FixedTimestep` requires a non-zero `dt`"
+        );
+        self.accumulator += scaled;
+        let mut steps = 0;
+        while self.accumulator >= dt && steps < max_steps {
+            self.accumulator -= dt;
+            steps += 1;
+        }
+
+        // Drop any whole steps that could not be caught up on, keeping the remainder.
+        if self.accumulator >= dt {
+            let remainder = self.accumulator.as_nanos() % dt.as_nanos();
+            self.accumulator = Duration::from_nanos(remainder as u64);
+        }
+        (steps, dt)
+    }
+
+    // Whether or not the clock is paused with no pending single steps.
+    fn is_idle(&self) -> bool {
+        self.paused && self.pending_steps == 0
+    }
+
+    // The fraction of a step that has accumulated but not yet been consumed by an update.
+    //
+    // Always `1.0` outside of the `FixedTimestep` mode, as the most recent update is current.
+    fn alpha(&self, fixed_timestep: Option<(Duration, usize)>) -> f64 {
+        match fixed_timestep {
+            Some((dt, _)) => self.accumulator.as_secs_f64() / dt.as_secs_f64(),
+            None => 1.0,
+        }
+    }
+}
+
+impl<M> Clone for View<M> {
+    fn clone(&self) -> Self {
+        match *self {
+            View::WithModel(view) => View::WithModel(view),
+            View::Sketch(view) => View::Sketch(view),
+        }
+    }
+}
+
+impl<M, E> Clone for Builder<M, E> {
+    fn clone(&self) -> Self {
+        Builder {
+            model: self.model,
+            event: self.event,
+            update: self.update,
+            default_view: self.default_view.clone(),
+            exit: self.exit,
+            create_default_window: self.create_default_window,
+            default_window_size: self.default_window_size,
+            capture_frame_timeout: self.capture_frame_timeout,
+            max_capture_frame_jobs: self.max_capture_frame_jobs,
+            backends: self.backends,
+        }
+    }
+}
+
+impl<E> Clone for SketchBuilder<E> {
+    fn clone(&self) -> Self {
+        SketchBuilder {
+            builder: self.builder.clone(),
+        }
+    }
+}
+
+impl<E> From<SketchBuilder<E>> for Builder<(), E> {
+    fn from(sketch: SketchBuilder<E>) -> Self {
+        sketch.builder
+    }
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Clock {
+            paused: false,
+            pending_steps: 0,
+            time_scale: 1.0,
+            accumulator: Duration::default(),
+            last_step: update_interval(LoopMode::DEFAULT_RATE_FPS),
+        }
+    }
 }
 
 impl Default for LoopMode {
@@ -591,10 +977,12 @@ impl Default for Config {
         let loop_mode = Default::default();
         let exit_on_escape = App::DEFAULT_EXIT_ON_ESCAPE;
         let fullscreen_on_shortcut = App::DEFAULT_FULLSCREEN_ON_SHORTCUT;
+        let clock = Clock::default();
         Config {
             loop_mode,
             exit_on_escape,
             fullscreen_on_shortcut,
+            clock,
         }
     }
 }
@@ -606,7 +994,7 @@ impl App {
 
     // Create a new `App`.
     fn new(
-        event_loop_proxy: Proxy,
+        event_loop_proxy: Option<Proxy>,
         event_loop_window_target: Option<EventLoopWindowTarget>,
         default_window_size: Option<DefaultWindowSize>,
         max_capture_frame_jobs: u32,
@@ -616,6 +1004,7 @@ impl App {
         let instance = wgpu::Instance::new(backends);
         let adapters = Default::default();
         let windows = RefCell::new(HashMap::new());
+        let headless = None;
         let draw = RefCell::new(draw::Draw::default());
         let config = RefCell::new(Default::default());
         let renderers = RefCell::new(Default::default());
@@ -637,6 +1026,7 @@ impl App {
             instance,
             adapters,
             windows,
+            headless,
             config,
             draw_state,
             ui,
@@ -656,9 +1046,9 @@ impl App {
             }
             _ => {
                 let windows = self.windows.borrow();
-                match windows.values().next() {
+                match windows.values().filter_map(|w| w.window.as_ref()).next() {
                     None => vec![],
-                    Some(window) => window.window.available_monitors().collect(),
+                    Some(window) => window.available_monitors().collect(),
                 }
             }
         }
@@ -672,12 +1062,13 @@ impl App {
             Some(EventLoopWindowTarget::Owned(ref event_loop)) => event_loop.primary_monitor(),
             _ => {
                 let windows = self.windows.borrow();
-                match windows.values().next() {
+                match windows.values().filter_map(|w| w.window.as_ref()).next() {
+                    None if self.headless.is_some() => None,
                     None => unimplemented!(
                         "yet to implement a way to get `primary_monitor` if neither \
                          event loop or window can be safely accessed"
                     ),
-                    Some(window) => window.window.primary_monitor(),
+                    Some(window) => window.primary_monitor(),
                 }
             }
         }
@@ -753,6 +1144,8 @@ impl App {
     ///
     /// The **Rect** coords are described in "points" (pixels divided by the hidpi factor).
     ///
+    /// When running headless, this is the **Rect** of the offscreen texture.
+    ///
     /// **Panics** if there are no windows or if no window is in focus.
     pub fn window_rect(&self) -> geom::Rect<DrawScalar> {
         self.main_window().rect()
@@ -760,6 +1153,8 @@ impl App {
 
     /// A reference to the window currently in focus.
     ///
+    /// When running headless, this is a stand-in window describing the offscreen texture.
+    ///
     /// **Panics** if their are no windows open in the **App**.
     ///
     /// Uses the **App::window** method internally.
@@ -848,11 +1243,99 @@ impl App {
         self.config.borrow_mut().loop_mode = mode;
     }
 
+    /// Pause the **App**'s clock.
+    ///
+    /// While paused, `app.time` and `app.duration` stop advancing and no `Update`s are emitted,
+    /// though windows continue to be redrawn for a few iterations following each event. Use
+    /// `App::step` to advance by a single update.
+    pub fn pause(&self) {
+        self.config.borrow_mut().clock.paused = true;
+    }
+
+    /// Resume the **App**'s clock after a call to `App::pause`.
+    pub fn resume(&self) {
+        let mut config = self.config.borrow_mut();
+        config.clock.paused = false;
+        config.clock.pending_steps = 0;
+    }
+
+    /// Whether or not the **App**'s clock is currently paused.
+    pub fn is_paused(&self) -> bool {
+        self.config.borrow().clock.paused
+    }
+
+    /// Advance the paused clock by a single update on the next iteration of the loop.
+    ///
+    /// In the `FixedTimestep` loop mode the update's duration is `dt`. Otherwise, the duration of
+    /// the most recent update is used.
+    ///
+    /// This has no effect unless the clock is paused.
+    pub fn step(&self) {
+        let mut config = self.config.borrow_mut();
+        if config.clock.paused {
+            config.clock.pending_steps += 1;
+        }
+    }
+
+    /// The fraction of a `dt` step that has elapsed since the most recent update, in the range
+    /// `0.0..1.0`.
+    ///
+    /// In the `FixedTimestep` loop mode, `view` is called on every iteration of the loop even if
+    /// no update was due. Interpolating between the previous and current state of the model by
+    /// this amount allows for smooth animation regardless of the rate of updates.
+    ///
+    /// Always returns `1.0` in other loop modes.
+    pub fn fixed_timestep_alpha(&self) -> f64 {
+        let config = self.config.borrow();
+        config.clock.alpha(config.loop_mode.fixed_timestep_params())
+    }
+
+    /// The rate at which the **App**'s clock advances relative to real time.
+    ///
+    /// By default this is `1.0`.
+    pub fn time_scale(&self) -> f64 {
+        self.config.borrow().clock.time_scale
+    }
+
+    /// Set the rate at which the **App**'s clock advances relative to real time.
+    ///
+    /// For example, `0.5` causes `app.time` to advance at half speed (useful for inspecting
+    /// animations in slow-motion) while `2.0` causes it to advance at double speed.
+    ///
+    /// **Panics** if `scale` is negative or not finite.
+    pub fn set_time_scale(&self, scale: f64) {
+        assert!(
+            scale.is_finite() && scale >= 0.0,
+            "time scale must be finite and not negative"
+        );
+        self.config.borrow_mut().clock.time_scale = scale;
+    }
+
     /// A handle to the **App** that can be shared across threads.
     ///
     /// This can be used to "wake up" the **App**'s inner event loop.
+    ///
+    /// When running headless there is no event loop to wake up, so the returned proxy's `wakeup`
+    /// method does nothing.
     pub fn create_proxy(&self) -> Proxy {
-        self.event_loop_proxy.clone()
+        self.event_loop_proxy.clone().unwrap_or_else(|| Proxy {
+            event_loop_proxy: None,
+            event_loop_is_asleep: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    /// Whether or not the **App** is running without a window or event loop.
+    ///
+    /// See `app::Builder::headless` for details.
+    pub fn is_headless(&self) -> bool {
+        self.headless.is_some()
+    }
+
+    /// The offscreen texture to which each frame is written when running headless.
+    ///
+    /// Returns `None` if the **App** is not running headless.
+    pub fn headless_texture(&self) -> Option<&wgpu::Texture> {
+        self.headless.as_ref().map(|headless| &headless.texture)
     }
 
     /// A builder for creating a new **Ui**.
@@ -876,6 +1359,8 @@ impl App {
 
     /// The number of times the focused window's **view** function has been called since the start
     /// of the program.
+    ///
+    /// When running headless, this is the number of frames rendered to the offscreen texture.
     pub fn elapsed_frames(&self) -> u64 {
         self.main_window().frame_count
     }
@@ -897,6 +1382,9 @@ impl App {
 
     /// Quits the currently running application.
     pub fn quit(&self) {
+        if let Some(ref headless) = self.headless {
+            headless.quit.set(true);
+        }
         self.windows.borrow_mut().clear();
     }
 }
@@ -913,9 +1401,15 @@ impl Proxy {
     /// immediately set the flag to false afterwards. This makes it safe to call the `wakeup`
     /// method as frequently as necessary across methods without causing any underlying OS methods
     /// to be called more than necessary.
+    ///
+    /// This is a no-op for a proxy created by a headless **App**, as its loop never sleeps.
     pub fn wakeup(&self) -> Result<(), winit::event_loop::EventLoopClosed<()>> {
+        let event_loop_proxy = match self.event_loop_proxy {
+            Some(ref proxy) => proxy,
+            None => return Ok(()),
+        };
         if self.event_loop_is_asleep.load(atomic::Ordering::Relaxed) {
-            self.event_loop_proxy.send_event(())?;
+            event_loop_proxy.send_event(())?;
             self.event_loop_is_asleep
                 .store(false, atomic::Ordering::Relaxed);
         }
@@ -923,6 +1417,67 @@ impl Proxy {
     }
 }
 
+impl Headless {
+    // The format of the offscreen texture to which each frame is written.
+    const TEXTURE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+    // Request a device and create the offscreen texture of the given size in points along with
+    // the stand-in window that describes it.
+    fn new(
+        app: &App,
+        size: [u32; 2],
+        scale_factor: f64,
+        msaa_samples: u32,
+        max_capture_frame_jobs: u32,
+        capture_frame_timeout: Option<Duration>,
+    ) -> (Self, Window) {
+        let request_adapter_opts = wgpu::RequestAdapterOptions {
+            power_preference: wgpu::DEFAULT_POWER_PREFERENCE,
+            compatible_surface: None,
+        };
+        let adapter = app
+            .wgpu_adapters()
+            .get_or_request(request_adapter_opts, app.instance())
+            .expect("no available wgpu adapter for headless `App`");
+        let device_queue_pair = adapter.get_or_request_device(wgpu::default_device_descriptor());
+
+        let [w, h] = size;
+        let size_px = [
+            (w as f64 * scale_factor).round() as u32,
+            (h as f64 * scale_factor).round() as u32,
+        ];
+        let device = device_queue_pair.device();
+        let texture = wgpu::TextureBuilder::new()
+            .size(size_px)
+            .format(Self::TEXTURE_FORMAT)
+            .usage(
+                wgpu::TextureUsage::OUTPUT_ATTACHMENT
+                    | wgpu::TextureUsage::SAMPLED
+                    | wgpu::TextureUsage::COPY_SRC,
+            )
+            .build(device);
+        let texture_view = texture.view().build();
+        let render = frame::RenderData::new(device, size_px, Self::TEXTURE_FORMAT, msaa_samples);
+        let capture = frame::CaptureData::new(max_capture_frame_jobs, capture_frame_timeout);
+        let frame_data = window::FrameData { render, capture };
+        let window = Window::headless(
+            device_queue_pair,
+            size_px,
+            Self::TEXTURE_FORMAT,
+            scale_factor,
+            msaa_samples,
+            frame_data,
+        );
+
+        let headless = Headless {
+            texture,
+            texture_view,
+            quit: Cell::new(false),
+        };
+        (headless, window)
+    }
+}
+
 impl draw::Draw {
     /// Render the **Draw**'s inner list of commands to the texture associated with the **Frame**.
     ///
@@ -1058,8 +1613,7 @@ fn run_loop<M, E>(
     // Keep track of state related to the loop mode itself.
     let mut loop_state = LoopState {
         updates_since_event: 0,
-        loop_start,
-        last_update: loop_start,
+        last_tick: loop_start,
         total_updates: 0,
     };
 
@@ -1078,13 +1632,46 @@ fn run_loop<M, E>(
                 if let Some(model) = model.as_mut() {
                     let loop_mode = app.loop_mode();
                     let now = Instant::now();
-                    let mut do_update = |loop_state: &mut LoopState| {
-                        apply_update(&mut app, model, event_fn, update_fn, loop_state, now);
-                    };
+                    let elapsed = now.duration_since(loop_state.last_tick);
+                    loop_state.last_tick = now;
                     match loop_mode {
                         LoopMode::NTimes { number_of_updates }
                             if loop_state.total_updates >= number_of_updates as u64 => {}
-                        _ => do_update(&mut loop_state),
+                        _ => {
+                            let fixed_timestep = loop_mode.fixed_timestep_params();
+                            let (updates, since_last) = app
+                                .config
+                                .borrow_mut()
+                                .clock
+                                .advance(elapsed, fixed_timestep);
+                            for _ in 0..updates {
+                                apply_update(
+                                    &mut app,
+                                    model,
+                                    event_fn,
+                                    update_fn,
+                                    &mut loop_state,
+                                    since_last,
+                                );
+                            }
+                            if updates == 0 {
+                                let is_idle = app.config.borrow().clock.is_idle();
+                                if is_idle {
+                                    // Continue to redraw for a few iterations following each
+                                    // event while paused, e.g. to allow for interactive UI, then
+                                    // allow the loop to go idle.
+                                    let limit = LoopMode::UPDATES_PER_WAIT_EVENT as usize;
+                                    if loop_state.updates_since_event < limit {
+                                        loop_state.updates_since_event += 1;
+                                        request_redraw(&app);
+                                    }
+                                } else if fixed_timestep.is_some() {
+                                    // Redraw even when no step was due so that the view may
+                                    // interpolate via `App::fixed_timestep_alpha`.
+                                    request_redraw(&app);
+                                }
+                            }
+                        }
                     }
                 }
             }
@@ -1094,6 +1681,7 @@ fn run_loop<M, E>(
             // TODO: Only request a frame from the user if this redraw was requested following an
             // update. Otherwise, just use the existing intermediary frame.
             winit::event::Event::RedrawRequested(window_id) => {
+                let window_id = window::Id::from(window_id);
                 // Take the render data and swapchain.
                 // We'll replace them before the end of this block.
                 let (mut swap_chain, nth_frame) = {
@@ -1239,6 +1827,7 @@ fn run_loop<M, E>(
             window_id,
         } = event
         {
+            let window_id = window::Id::from(window_id);
             match event {
                 winit::event::WindowEvent::Resized(new_inner_size) => {
                     let mut windows = app.windows.borrow_mut();
@@ -1286,6 +1875,12 @@ fn run_loop<M, E>(
             {
                 ControlFlow::Wait
             }
+            // There is nothing left to update or redraw until the next event while paused.
+            _ if app.config.borrow().clock.is_idle()
+                && loop_state.updates_since_event >= LoopMode::UPDATES_PER_WAIT_EVENT as usize =>
+            {
+                ControlFlow::Wait
+            }
             _ => ControlFlow::Poll,
         };
 
@@ -1311,6 +1906,144 @@ fn run_loop<M, E>(
     }
 }
 
+// The application loop used when running headless.
+//
+// Rather than waiting on the event loop, each iteration applies an update at a fixed interval
+// and then immediately calls `view` with a frame targeting the offscreen texture.
+#[allow(clippy::too_many_arguments)]
+fn run_headless<M, E>(
+    mut app: App,
+    mut model: M,
+    event_fn: Option<EventFn<M, E>>,
+    update_fn: Option<UpdateFn<M>>,
+    default_view: Option<View<M>>,
+    exit_fn: Option<ExitFn<M>>,
+    update_interval: Duration,
+    frames: Option<u64>,
+    stop_fn: Option<StopFn<M>>,
+    capture_directory: Option<PathBuf>,
+) where
+    M: 'static,
+    E: LoopEvent,
+{
+    let window_id = window::Id::headless();
+
+    // The clock is driven by the fixed interval rather than the wall clock.
+    let mut elapsed = Duration::default();
+    let mut loop_state = LoopState {
+        updates_since_event: 0,
+        last_tick: Instant::now(),
+        total_updates: 0,
+    };
+
+    loop {
+        if let Some(frames) = frames {
+            if app.elapsed_frames() >= frames {
+                break;
+            }
+        }
+
+        match app.loop_mode() {
+            // As with the event loop, no more updates are applied once the requested number have
+            // been, though frames continue to be rendered.
+            LoopMode::NTimes { number_of_updates }
+                if loop_state.total_updates >= number_of_updates as u64 => {}
+            loop_mode => {
+                let fixed_timestep = loop_mode.fixed_timestep_params();
+                let (updates, since_last) = app
+                    .config
+                    .borrow_mut()
+                    .clock
+                    .advance(elapsed, fixed_timestep);
+                for _ in 0..updates {
+                    apply_update(
+                        &mut app,
+                        &mut model,
+                        event_fn,
+                        update_fn,
+                        &mut loop_state,
+                        since_last,
+                    );
+                }
+            }
+        }
+
+        let headless = app.headless.as_ref().expect("missing headless state");
+        if headless.quit.get() {
+            break;
+        }
+
+        let nth_frame = {
+            let mut windows = app.windows.borrow_mut();
+            let window = windows
+                .get_mut(&window_id)
+                .expect("missing headless stand-in window");
+            let nth_frame = window.frame_count;
+            window.frame_count += 1;
+            nth_frame
+        };
+
+        // Borrow the stand-in window for the duration of `view`, as in the event loop.
+        let windows = app.windows.borrow();
+        let window = windows
+            .get(&window_id)
+            .expect("missing headless stand-in window");
+        if let Some(ref dir) = capture_directory {
+            let path = dir.join(format!("{:05}", nth_frame)).with_extension("png");
+            window.capture_frame(path);
+        }
+
+        // Construct and emit a frame via `view` targeting the offscreen texture.
+        let raw_frame = RawFrame::new_empty(
+            window.swap_chain_device_queue_pair().clone(),
+            window_id,
+            nth_frame,
+            &headless.texture_view,
+            Headless::TEXTURE_FORMAT,
+            window.rect(),
+        );
+        let data = window.frame_data.as_ref().expect("missing `frame_data`");
+        match default_view {
+            Some(View::Sketch(view)) => {
+                let frame = Frame::new_empty(raw_frame, &data.render, &data.capture);
+                view(&app, frame);
+            }
+            Some(View::WithModel(view)) => {
+                let frame = Frame::new_empty(raw_frame, &data.render, &data.capture);
+                view(&app, &model, frame);
+            }
+            None => raw_frame.submit(),
+        }
+
+        // Allow the device to make progress on submitted work and pending captures.
+        window.swap_chain_device().poll(wgpu::Maintain::Poll);
+        drop(windows);
+
+        if headless.quit.get() {
+            break;
+        }
+        if let Some(stop_fn) = stop_fn {
+            if stop_fn(&app, &model) {
+                break;
+            }
+        }
+
+        elapsed = update_interval;
+    }
+
+    // Ensure all pending frame captures are written before exiting.
+    if let Some(window) = app.window(window_id) {
+        if window.await_capture_frame_jobs().is_err() {
+            // TODO: Replace eprintlns with proper logging.
+            eprintln!("timed out while waiting for capture jobs to complete");
+        }
+    }
+
+    if let Some(exit_fn) = exit_fn {
+        exit_fn(&app, model);
+    }
+}
+
 // Apply an update to the model via the user's function and update the app and loop state
 // accordingly.
 fn apply_update<M, E>(
@@ -1319,14 +2052,13 @@ fn apply_update<M, E>(
     event_fn: Option<EventFn<M, E>>,
     update_fn: Option<UpdateFn<M>>,
     loop_state: &mut LoopState,
-    now: Instant,
+    since_last: Duration,
 ) where
     M: 'static,
     E: LoopEvent,
 {
     // Update the app's durations.
-    let since_last = now.duration_since(loop_state.last_update);
-    let since_start = now.duration_since(loop_state.loop_start);
+    let since_start = app.duration.since_start + since_last;
     app.duration.since_prev_update = since_last;
     app.duration.since_start = since_start;
     app.time = since_start.secs() as _;
@@ -1343,13 +2075,16 @@ fn apply_update<M, E>(
     if let Some(update_fn) = update_fn {
         update_fn(app, model, update);
     }
-    loop_state.last_update = now;
     loop_state.total_updates += 1;
     loop_state.updates_since_event += 1;
-    // Request redraw from windows.
+    request_redraw(app);
+}
+
+// Request redraw from windows.
+fn request_redraw(app: &App) {
     let windows = app.windows.borrow();
-    for window in windows.values() {
-        window.window.request_redraw();
+    for window in windows.values().filter_map(|window| window.window.as_ref()) {
+        window.request_redraw();
     }
 }
 
@@ -1417,6 +2152,7 @@ where
         ref event,
     } = *winit_event
     {
+        let window_id = window::Id::from(window_id);
         // If we should exit the app on escape, check for the escape key.
         if app.exit_on_escape() {
             if let winit::event::WindowEvent::KeyboardInput { input, .. } = *event {
@@ -1553,6 +2289,7 @@ where
         ref event,
     } = *winit_event
     {
+        let window_id = window::Id::from(window_id);
         // Raw window events.
         if let Some(raw_window_event_fn) = {
             let windows = app.windows.borrow();
@@ -1682,3 +2419,71 @@ where
 
     exit
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Clock;
+    use std::time::Duration;
+
+    #[test]
+    fn fixed_timestep_clock() {
+        let dt = Duration::from_millis(10);
+        let fixed = Some((dt, 4));
+        let mut clock = Clock::default();
+        // Partial steps accumulate.
+        assert_eq!(clock.advance(Duration::from_millis(15), fixed), (1, dt));
+        assert_eq!(clock.advance(Duration::from_millis(5), fixed), (1, dt));
+        // Whole steps beyond the maximum are dropped.
+        assert_eq!(clock.advance(Duration::from_millis(100), fixed), (4, dt));
+        assert_eq!(clock.advance(Duration::default(), fixed), (0, dt));
+        // Only single steps are applied while paused.
+        clock.paused = true;
+        assert_eq!(clock.advance(dt, fixed).0, 0);
+        clock.pending_steps = 1;
+        assert_eq!(clock.advance(dt, fixed), (1, dt));
+        assert_eq!(clock.advance(dt, fixed).0, 0);
+    }
+
+    #[test]
+    fn paused_clock() {
+        let dt = Duration::from_millis(10);
+        let fixed = Some((dt, 4));
+        let mut clock = Clock::default();
+        assert_eq!(clock.advance(Duration::from_millis(5), fixed), (0, dt));
+        clock.paused = true;
+        assert!(clock.is_idle());
+        // Elapsed time neither produces updates nor accumulates while paused.
+        for _ in 0..3 {
+            assert_eq!(clock.advance(Duration::from_millis(100), fixed).0, 0);
+            assert_eq!(clock.advance(Duration::from_millis(100), None).0, 0);
+        }
+        assert!((clock.alpha(fixed) - 0.5).abs() < 1e-9);
+        // Each step applies exactly one update before the clock is idle again.
+        clock.pending_steps = 2;
+        assert!(!clock.is_idle());
+        assert_eq!(clock.advance(Duration::default(), fixed), (1, dt));
+        assert_eq!(clock.advance(Duration::default(), fixed), (1, dt));
+        assert!(clock.is_idle());
+        assert_eq!(clock.advance(Duration::from_millis(100), fixed).0, 0);
+        clock.paused = false;
+        assert!(!clock.is_idle());
+        assert_eq!(clock.advance(Duration::from_millis(5), fixed), (1, dt));
+    }
+
+    #[test]
+    fn fixed_timestep_alpha() {
+        let dt = Duration::from_millis(10);
+        let fixed = Some((dt, 4));
+        let mut clock = Clock::default();
+        assert_eq!(clock.alpha(fixed), 0.0);
+        // An iteration that is too short for an update still advances the alpha.
+        assert_eq!(clock.advance(Duration::from_millis(4), fixed), (0, dt));
+        assert!((clock.alpha(fixed) - 0.4).abs() < 1e-9);
+        assert_eq!(clock.advance(Duration::from_millis(4), fixed), (0, dt));
+        assert!((clock.alpha(fixed) - 0.8).abs() < 1e-9);
+        // The remainder carries over once the step is consumed.
+        assert_eq!(clock.advance(Duration::from_millis(4), fixed), (1, dt));
+        assert!((clock.alpha(fixed) - 0.2).abs() < 1e-9);
+        assert_eq!(clock.alpha(None), 1.0);
+    }
+}
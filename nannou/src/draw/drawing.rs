@@ -1,8 +1,10 @@
 use crate::color::IntoLinSrgba;
 use crate::draw::mesh::vertex::Color;
 use crate::draw::primitive::Primitive;
+use crate::draw::properties::gradient::Gradient;
 use crate::draw::properties::{
-    ColorScalar, SetColor, SetDimensions, SetFill, SetOrientation, SetPosition, SetStroke,
+    ColorScalar, SetColor, SetDash, SetDimensions, SetFill, SetGradient, SetOrientation,
+    SetPosition, SetStroke,
 };
 use crate::draw::{self, Draw};
 use crate::geom::{self, Point2, Point3, Vector2, Vector3};
@@ -568,6 +570,75 @@ where
     }
 }
 
+// SetGradient methods
+
+impl<'a, T, S> Drawing<'a, T, S>
+where
+    T: SetGradient + Into<Primitive<S>>,
+    Primitive<S>: Into<Option<T>>,
+    S: BaseFloat,
+{
+    /// Paint with the given gradient.
+    ///
+    /// Where both a gradient and a color are specified, the gradient takes precedence.
+    pub fn gradient(self, gradient: Gradient) -> Self {
+        self.map_ty(|ty| SetGradient::gradient(ty, gradient))
+    }
+}
+
+// SetDash methods
+
+impl<'a, T, S> Drawing<'a, T, S>
+where
+    T: SetDash + Into<Primitive<S>>,
+    Primitive<S>: Into<Option<T>>,
+    S: BaseFloat,
+{
+    /// Specify the whole dash pattern.
+    pub fn dash_pattern(self, dash: draw::Dash) -> Self {
+        self.map_ty(|ty| ty.dash_pattern(dash))
+    }
+
+    /// Stroke with dashes of the given length separated by gaps of the given length.
+    pub fn dash(self, dash: f32, gap: f32) -> Self {
+        self.map_ty(|ty| ty.dash(dash, gap))
+    }
+
+    /// Stroke with alternating dash and gap lengths, as in the SVG `stroke-dasharray` property.
+    ///
+    /// A list with an odd number of lengths is repeated to produce an even number of lengths.
+    pub fn dashes<I>(self, lengths: I) -> Self
+    where
+        I: IntoIterator<Item = f32>,
+    {
+        self.map_ty(|ty| ty.dashes(lengths))
+    }
+
+    /// The distance into the dash pattern at which the stroke begins.
+    pub fn dash_offset(self, offset: f32) -> Self {
+        self.map_ty(|ty| ty.dash_offset(offset))
+    }
+
+    /// The cap applied to the ends of each dash.
+    ///
+    /// By default, each dash uses the start and end caps of the stroke.
+    pub fn dash_cap(self, cap: LineCap) -> Self {
+        self.map_ty(|ty| ty.dash_cap(cap))
+    }
+
+    /// Stroke with round dots spaced the given distance apart.
+    ///
+    /// The diameter of each dot is the stroke weight.
+    pub fn dots(self, spacing: f32) -> Self {
+        self.map_ty(|ty| ty.dots(spacing))
+    }
+
+    /// Stroke without dashes.
+    pub fn no_dash(self) -> Self {
+        self.map_ty(|ty| ty.no_dash())
+    }
+}
+
 // SetFill methods
 
 impl<'a, T, S> Drawing<'a, T, S>
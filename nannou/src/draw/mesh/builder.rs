@@ -6,12 +6,14 @@
 //! Lyon tessellators assume `f32` data, so we do the same in the following implementations.
 
 use crate::draw;
+use crate::draw::properties::gradient::{Gradient, Kind};
 use crate::geom;
 use cgmath::Matrix4;
 use lyon::tessellation::geometry_builder::{
     self, FillGeometryBuilder, GeometryBuilder, StrokeGeometryBuilder,
 };
 use lyon::tessellation::{FillAttributes, GeometryBuilderError, StrokeAttributes, VertexId};
+use std::collections::HashMap;
 
 pub struct MeshBuilder<'a, A> {
     /// The mesh that is to be extended.
@@ -30,6 +32,24 @@ pub struct SingleColor(draw::mesh::vertex::Color);
 pub struct ColorPerPoint;
 pub struct TexCoordsPerPoint;
 
+/// Extends a mesh with geometry colored by a **Gradient**.
+///
+/// Tessellated geometry is buffered in local coordinates and subdivided on `end_geometry` so that
+/// interpolation between vertex colors closely follows the gradient. The gradient is then
+/// evaluated at each vertex before the geometry is transformed and added to the mesh.
+pub struct GradientMeshBuilder<'a> {
+    mesh: &'a mut draw::Mesh,
+    transform: Matrix4<f32>,
+    gradient: &'a Gradient,
+    points: Vec<geom::Point2>,
+    indices: Vec<u32>,
+}
+
+// The number of segments that the extent of a gradient is divided into when subdividing.
+const GRADIENT_SUBDIVISIONS: f32 = 8.0;
+// The maximum number of times a tessellated triangle may be recursively subdivided.
+const GRADIENT_MAX_DEPTH: u32 = 3;
+
 impl<'a, A> MeshBuilder<'a, A> {
     /// Begin extending the mesh.
     fn new(mesh: &'a mut draw::Mesh, transform: Matrix4<f32>, attributes: A) -> Self {
@@ -233,3 +253,243 @@ impl<'a> StrokeGeometryBuilder for MeshBuilder<'a, TexCoordsPerPoint> {
         Ok(id)
     }
 }
+
+impl<'a> GradientMeshBuilder<'a> {
+    /// Begin extending a mesh colored by the given gradient.
+    pub fn new(mesh: &'a mut draw::Mesh, transform: Matrix4<f32>, gradient: &'a Gradient) -> Self {
+        GradientMeshBuilder {
+            mesh,
+            transform,
+            gradient,
+            points: vec![],
+            indices: vec![],
+        }
+    }
+
+    fn add_vertex(&mut self, position: lyon::math::Point) -> VertexId {
+        let id = VertexId::from_usize(self.points.len());
+        self.points.push(geom::Point2::from(position));
+        id
+    }
+}
+
+impl<'a> GeometryBuilder for GradientMeshBuilder<'a> {
+    fn begin_geometry(&mut self) {
+        self.points.clear();
+        self.indices.clear();
+    }
+
+    fn end_geometry(&mut self) -> geometry_builder::Count {
+        // Subdivide relative to the extent of the gradient, or the geometry for conic gradients.
+        let extent = match self.gradient.extent() {
+            Some(extent) => extent,
+            None => bounds_diagonal(&self.points),
+        };
+        let max_edge = extent / GRADIENT_SUBDIVISIONS;
+        if max_edge > 0.0 && max_edge.is_finite() {
+            subdivide(&mut self.points, &mut self.indices, max_edge);
+        }
+
+        let colors: Vec<_> = match self.gradient.kind() {
+            // Conic offsets already lie within `0.0..=1.0`, so the spread mode has no effect.
+            Kind::Conic { center, angle } => {
+                split_conic_seam(&mut self.points, &mut self.indices, center, angle)
+                    .into_iter()
+                    .map(|offset| self.gradient.color_at_offset(offset))
+                    .collect()
+            }
+            _ => self
+                .points
+                .iter()
+                .map(|&p| self.gradient.color_at(p))
+                .collect(),
+        };
+
+        let vertex_offset = self.mesh.points().len() as u32;
+        for (&p, color) in self.points.iter().zip(colors) {
+            let p = geom::Point3::from(p);
+            let p = cgmath::Transform::transform_point(&self.transform, p.into());
+            let point = geom::vec3(p.x, p.y, p.z);
+            let tex_coords = draw::mesh::vertex::default_tex_coords();
+            let vertex = draw::mesh::vertex::new(point, color, tex_coords);
+            self.mesh.push_vertex(vertex);
+        }
+        let indices = self.indices.iter().map(|&i| vertex_offset + i);
+        self.mesh.extend_indices(indices);
+
+        geometry_builder::Count {
+            vertices: self.points.len() as u32,
+            indices: self.indices.len() as u32,
+        }
+    }
+
+    fn add_triangle(&mut self, a: VertexId, b: VertexId, c: VertexId) {
+        self.indices.push(a.to_usize() as u32);
+        self.indices.push(b.to_usize() as u32);
+        self.indices.push(c.to_usize() as u32);
+    }
+
+    fn abort_geometry(&mut self) {
+        self.points.clear();
+        self.indices.clear();
+    }
+}
+
+impl<'a> FillGeometryBuilder for GradientMeshBuilder<'a> {
+    fn add_fill_vertex(
+        &mut self,
+        position: lyon::math::Point,
+        _attrs: FillAttributes,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        Ok(self.add_vertex(position))
+    }
+}
+
+impl<'a> StrokeGeometryBuilder for GradientMeshBuilder<'a> {
+    fn add_stroke_vertex(
+        &mut self,
+        position: lyon::math::Point,
+        _attrs: StrokeAttributes,
+    ) -> Result<VertexId, GeometryBuilderError> {
+        Ok(self.add_vertex(position))
+    }
+}
+
+// The length of the diagonal of the bounding box of the given points.
+fn bounds_diagonal(points: &[geom::Point2]) -> f32 {
+    let first = match points.first() {
+        None => return 0.0,
+        Some(&p) => p,
+    };
+    let (min, max) = points.iter().fold((first, first), |(min, max), p| {
+        let min = geom::pt2(min.x.min(p.x), min.y.min(p.y));
+        let max = geom::pt2(max.x.max(p.x), max.y.max(p.y));
+        (min, max)
+    });
+    (max - min).magnitude()
+}
+
+// Recursively split each triangle into four until none of its edges are longer than `max_edge`
+// or the maximum depth is reached.
+//
+// Midpoints are shared between triangles that share an edge to avoid introducing cracks.
+fn subdivide(points: &mut Vec<geom::Point2>, indices: &mut Vec<u32>, max_edge: f32) {
+    let max_edge2 = max_edge * max_edge;
+    let mut midpoints: HashMap<(u32, u32), u32> = HashMap::new();
+    let mut midpoint = |points: &mut Vec<geom::Point2>, a: u32, b: u32| {
+        let key = (a.min(b), a.max(b));
+        *midpoints.entry(key).or_insert_with(|| {
+            let mid = (points[a as usize] + points[b as usize]) * 0.5;
+            points.push(mid);
+            points.len() as u32 - 1
+        })
+    };
+    let mut stack: Vec<([u32; 3], u32)> = indices
+        .chunks_exact(3)
+        .map(|t| ([t[0], t[1], t[2]], 0))
+        .collect();
+    let mut subdivided = Vec::with_capacity(indices.len());
+    while let Some(([a, b, c], depth)) = stack.pop() {
+        let edge2 = |i: u32, j: u32| (points[i as usize] - points[j as usize]).magnitude2();
+        let longest2 = edge2(a, b).max(edge2(b, c)).max(edge2(c, a));
+        if depth >= GRADIENT_MAX_DEPTH || longest2 <= max_edge2 {
+            subdivided.extend_from_slice(&[a, b, c]);
+            continue;
+        }
+        let ab = midpoint(points, a, b);
+        let bc = midpoint(points, b, c);
+        let ca = midpoint(points, c, a);
+        let depth = depth + 1;
+        stack.push(([a, ab, ca], depth));
+        stack.push(([ab, b, bc], depth));
+        stack.push(([ca, bc, c], depth));
+        stack.push(([ab, bc, ca], depth));
+    }
+    *indices = subdivided;
+}
+
+// Split the triangles that cross the line through the center of a conic gradient at its starting
+// angle, returning the gradient offset of each of the resulting vertices.
+//
+// The offset wraps from `1.0` back to `0.0` at the seam, so colors interpolated across a triangle
+// spanning it would otherwise sweep through the entire gradient. Vertices are duplicated for the
+// triangles on either side of the line so that those along the seam may take either offset.
+fn split_conic_seam(
+    points: &mut Vec<geom::Point2>,
+    indices: &mut Vec<u32>,
+    center: geom::Point2,
+    angle: f32,
+) -> Vec<f32> {
+    let dir = geom::vec2(angle.cos(), angle.sin());
+    let side = |p: geom::Point2| dir.perp_dot(p - center);
+
+    // Clip each triangle against the line, tagging the pieces by the side on which they lie.
+    let mut crossings: HashMap<(u32, u32), u32> = HashMap::new();
+    let mut tagged = Vec::with_capacity(indices.len() / 3);
+    for t in indices.chunks_exact(3) {
+        let sides = [
+            side(points[t[0] as usize]),
+            side(points[t[1] as usize]),
+            side(points[t[2] as usize]),
+        ];
+        if sides.iter().all(|&s| s >= 0.0) || sides.iter().all(|&s| s <= 0.0) {
+            let ccw = sides.iter().sum::<f32>() >= 0.0;
+            tagged.push(([t[0], t[1], t[2]], ccw));
+            continue;
+        }
+        let (mut ccw, mut cw) = (vec![], vec![]);
+        for i in 0..3 {
+            let (a, b) = (t[i], t[(i + 1) % 3]);
+            let (sa, sb) = (sides[i], sides[(i + 1) % 3]);
+            if sa >= 0.0 {
+                ccw.push(a);
+            }
+            if sa <= 0.0 {
+                cw.push(a);
+            }
+            if sa * sb < 0.0 {
+                let key = (a.min(b), a.max(b));
+                let crossing = *crossings.entry(key).or_insert_with(|| {
+                    let (p, q) = (points[key.0 as usize], points[key.1 as usize]);
+                    let (sp, sq) = (side(p), side(q));
+                    points.push(p + (q - p) * (sp / (sp - sq)));
+                    points.len() as u32 - 1
+                });
+                ccw.push(crossing);
+                cw.push(crossing);
+            }
+        }
+        for (piece, is_ccw) in [(ccw, true), (cw, false)] {
+            for i in 1..piece.len() - 1 {
+                tagged.push(([piece[0], piece[i], piece[i + 1]], is_ccw));
+            }
+        }
+    }
+
+    // Points counter-clockwise of the line have offsets up to `0.5` and those clockwise of it
+    // have offsets from `0.5`, so any offset on the wrong side of the seam belongs on the other.
+    let kind = Kind::Conic { center, angle };
+    let mut vertices: HashMap<(u32, bool), u32> = HashMap::new();
+    let mut split_points = Vec::with_capacity(points.len());
+    let mut offsets = Vec::with_capacity(points.len());
+    indices.clear();
+    for (tri, ccw) in tagged {
+        for &v in &tri {
+            let ix = *vertices.entry((v, ccw)).or_insert_with(|| {
+                let p = points[v as usize];
+                let offset = kind.offset_at(p);
+                let offset = match ccw {
+                    true if offset > 0.75 => offset - 1.0,
+                    false if offset < 0.25 => offset + 1.0,
+                    _ => offset,
+                };
+                split_points.push(p);
+                offsets.push(offset);
+                split_points.len() as u32 - 1
+            });
+            indices.push(ix);
+        }
+    }
+    *points = split_points;
+    offsets
+}
@@ -4,6 +4,7 @@
 
 use crate::geom::{self, Point2};
 use crate::math::{deg_to_rad, turns_to_rad, BaseFloat, Matrix4, SquareMatrix};
+use crate::serde_derive::{Deserialize, Serialize};
 use crate::wgpu;
 use lyon::path::PathEvent;
 use std::cell::RefCell;
@@ -16,6 +17,8 @@ pub use self::drawing::{Drawing, DrawingContext};
 use self::mesh::vertex::Color;
 pub use self::mesh::Mesh;
 use self::primitive::Primitive;
+pub use self::properties::dash::Dash;
+pub use self::properties::gradient::Gradient;
 pub use self::renderer::{Builder as RendererBuilder, Renderer};
 pub use self::theme::Theme;
 
@@ -24,8 +27,11 @@ mod drawing;
 pub mod mesh;
 pub mod primitive;
 pub mod properties;
+pub mod recording;
 pub mod renderer;
+pub mod svg;
 pub mod theme;
+mod wgpu_serde;
 
 /// A simple API for drawing 2D and 3D graphics.
 ///
@@ -61,13 +67,19 @@ where
 }
 
 /// The current **Transform**, alpha **BlendDescriptor** and **Scissor** of a **Draw** instance.
-#[derive(Clone, Debug, PartialEq)]
+///
+/// The sampler's debug `label` is not serialized.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Context<S = geom::scalar::Default> {
     pub transform: Matrix4<S>,
+    #[serde(with = "wgpu_serde::BlendDescriptorDef")]
     pub alpha_blend: wgpu::BlendDescriptor,
+    #[serde(with = "wgpu_serde::BlendDescriptorDef")]
     pub color_blend: wgpu::BlendDescriptor,
     pub scissor: Scissor<S>,
+    #[serde(with = "wgpu_serde::PrimitiveTopologyDef")]
     pub topology: wgpu::PrimitiveTopology,
+    #[serde(with = "wgpu_serde::sampler_descriptor")]
     pub sampler: wgpu::SamplerDescriptor<'static>,
 }
 
@@ -75,7 +87,10 @@ pub struct Context<S = geom::scalar::Default> {
 ///
 /// During rendering, the list of **DrawCommand**s are converted into a list of **RenderCommands**
 /// that are directly associated with encodable render pass commands.
-#[derive(Clone, Debug)]
+///
+/// Textures referenced by primitives are not serialized. See the [**recording**](./recording/index.html)
+/// module for recording and replaying draw commands along with references to their textures.
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum DrawCommand<S = geom::scalar::Default> {
     /// Draw a primitive.
     Primitive(Primitive<S>),
@@ -84,7 +99,7 @@ pub enum DrawCommand<S = geom::scalar::Default> {
 }
 
 /// The scissor for a **Draw**'s render context.
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Scissor<S = geom::scalar::Default> {
     /// The extent of the scissor matches the bounds of the target texture.
     Full,
@@ -590,6 +605,17 @@ where
         self.a(text)
     }
 
+    /// Begin drawing a **TextOnPath**, laying out the text along the given path.
+    pub fn text_on_path(&self, s: &str, path: &geom::Path) -> Drawing<primitive::TextOnPath<S>, S> {
+        let text = {
+            let state = self.state.borrow();
+            let mut intermediary_state = state.intermediary_state.borrow_mut();
+            let ctxt = DrawingContext::from_intermediary_state(&mut *intermediary_state);
+            primitive::text_on_path::TextOnPath::new(ctxt, s, path)
+        };
+        self.a(text)
+    }
+
     /// Begin drawing a **Texture**.
     pub fn texture(&self, view: &dyn wgpu::ToTextureView) -> Drawing<primitive::Texture<S>, S> {
         self.a(primitive::Texture::new(view))
@@ -2,17 +2,22 @@ use crate::color::LinSrgba;
 use crate::draw::primitive::path;
 use crate::draw::primitive::Line;
 use crate::draw::primitive::Primitive;
+use crate::draw::properties::dash::Dash;
+use crate::draw::properties::gradient::{self, Gradient};
 use crate::draw::properties::spatial::{orientation, position};
-use crate::draw::properties::{ColorScalar, SetColor, SetOrientation, SetPosition, SetStroke};
+use crate::draw::properties::{
+    ColorScalar, SetColor, SetDash, SetGradient, SetOrientation, SetPosition, SetStroke,
+};
 use crate::draw::{self, Drawing};
 use crate::geom::{self, pt2, vec2, Point2};
 use crate::math::{BaseFloat, Zero};
+use crate::serde_derive::{Deserialize, Serialize};
 use lyon::tessellation::StrokeOptions;
 
 /// A path containing only two points - a start and end.
 ///
 /// A triangle is drawn on the end to indicate direction.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Arrow<S = geom::scalar::Default> {
     line: Line<S>,
     head_length: Option<S>,
@@ -155,6 +160,18 @@ impl<S> SetColor<ColorScalar> for Arrow<S> {
     }
 }
 
+impl<S> SetGradient for Arrow<S> {
+    fn gradient_mut(&mut self) -> &mut Option<Gradient> {
+        SetGradient::gradient_mut(&mut self.line)
+    }
+}
+
+impl<S> SetDash for Arrow<S> {
+    fn dash_mut(&mut self) -> &mut Option<Dash> {
+        SetDash::dash_mut(&mut self.line)
+    }
+}
+
 impl<S> From<Arrow<S>> for Primitive<S> {
     fn from(prim: Arrow<S>) -> Self {
         Primitive::Arrow(prim)
@@ -170,21 +187,21 @@ impl<S> Into<Option<Arrow<S>>> for Primitive<S> {
     }
 }
 
-impl draw::renderer::RenderPrimitive for Arrow<f32> {
-    fn render_primitive(
-        self,
-        mut ctxt: draw::renderer::RenderContext,
-        mesh: &mut draw::Mesh,
-    ) -> draw::renderer::PrimitiveRender {
+impl Arrow<f32> {
+    // Calculate the points of the arrow head along with the line leading up to it.
+    //
+    // Returns `None` if the start and end of the arrow are the same. The line is `None` if there is
+    // no space left after drawing the head.
+    fn head_and_line(&self) -> Option<([Point2; 3], Option<[Point2; 2]>)> {
         let Arrow {
-            line,
+            ref line,
             head_length,
             head_width,
-        } = self;
+        } = *self;
         let start = line.start.unwrap_or(pt2(0.0, 0.0));
         let end = line.end.unwrap_or(pt2(0.0, 0.0));
         if start == end {
-            return draw::renderer::PrimitiveRender::default();
+            return None;
         }
 
         // Calculate the arrow head points.
@@ -206,6 +223,26 @@ impl draw::renderer::RenderPrimitive for Arrow<f32> {
         let tri_c = tri_start - tri_w_dir;
         // The line should only be drawn if there is space after drawing the triangle.
         let draw_line = line_dir_mag > tri_len;
+        let line_points = if draw_line {
+            Some([line_start, line_end])
+        } else {
+            None
+        };
+        Some(([tri_a, tri_b, tri_c], line_points))
+    }
+}
+
+impl draw::renderer::RenderPrimitive for Arrow<f32> {
+    fn render_primitive(
+        self,
+        mut ctxt: draw::renderer::RenderContext,
+        mesh: &mut draw::Mesh,
+    ) -> draw::renderer::PrimitiveRender {
+        let (tri_points, line_points) = match self.head_and_line() {
+            None => return draw::renderer::PrimitiveRender::default(),
+            Some(points) => points,
+        };
+        let line = self.line;
 
         // Determine the transform to apply to all points.
         let global_transform = ctxt.transform;
@@ -213,13 +250,14 @@ impl draw::renderer::RenderPrimitive for Arrow<f32> {
         let transform = global_transform * local_transform;
 
         // Draw the tri.
-        let tri_points = [tri_a, tri_b, tri_c];
         let tri_points = tri_points.iter().cloned().map(Into::into);
         let close_tri = true;
         let tri_events = lyon::path::iterator::FromPolyline::new(close_tri, tri_points);
         path::render_path_events(
             tri_events,
             line.path.color,
+            line.path.gradient.as_ref(),
+            None,
             transform,
             path::Options::Fill(Default::default()),
             &ctxt.theme,
@@ -230,14 +268,15 @@ impl draw::renderer::RenderPrimitive for Arrow<f32> {
         );
 
         // Draw the line.
-        if draw_line {
-            let line_points = [line_start, line_end];
+        if let Some(line_points) = line_points {
             let line_points = line_points.iter().cloned().map(Into::into);
             let close_line = false;
             let line_events = lyon::path::iterator::FromPolyline::new(close_line, line_points);
             path::render_path_events(
                 line_events,
                 line.path.color,
+                line.path.gradient.as_ref(),
+                line.path.dash.as_ref(),
                 transform,
                 path::Options::Stroke(line.path.opts),
                 &ctxt.theme,
@@ -252,6 +291,34 @@ impl draw::renderer::RenderPrimitive for Arrow<f32> {
     }
 }
 
+impl draw::svg::SvgPrimitive for Arrow<f32> {
+    fn render_svg(self, ctxt: draw::svg::RenderContext, doc: &mut draw::svg::Document) {
+        let (tri_points, line_points) = match self.head_and_line() {
+            None => return,
+            Some(points) => points,
+        };
+        let line = self.line;
+        let local_transform = line.path.position.transform() * line.path.orientation.transform();
+        let transform = *ctxt.transform * local_transform;
+        let theme_prim = &draw::theme::Primitive::Arrow;
+        let color = gradient::flat_color(line.path.gradient.as_ref(), line.path.color);
+
+        // Draw the line beneath the head.
+        if let Some(line_points) = line_points {
+            let color = color.unwrap_or_else(|| ctxt.theme.stroke_lin_srgba(theme_prim));
+            let style = draw::svg::Style {
+                dash: line.path.dash,
+                ..draw::svg::Style::stroke(color, line.path.opts)
+            };
+            doc.polyline(&transform, false, line_points.iter().cloned(), &style);
+        }
+
+        let color = color.unwrap_or_else(|| ctxt.theme.fill_lin_srgba(theme_prim));
+        let style = draw::svg::Style::fill(color);
+        doc.polyline(&transform, true, tri_points.iter().cloned(), &style);
+    }
+}
+
 impl<S> Default for Arrow<S>
 where
     S: Zero,
@@ -2,17 +2,20 @@ use crate::color::conv::IntoLinSrgba;
 use crate::draw;
 use crate::draw::primitive::polygon::{self, PolygonInit, PolygonOptions, SetPolygon};
 use crate::draw::primitive::Primitive;
+use crate::draw::properties::gradient::Gradient;
 use crate::draw::properties::spatial::{dimension, orientation, position};
 use crate::draw::properties::{
-    spatial, ColorScalar, LinSrgba, SetColor, SetDimensions, SetOrientation, SetPosition, SetStroke,
+    spatial, ColorScalar, LinSrgba, SetColor, SetDimensions, SetGradient, SetOrientation,
+    SetPosition, SetStroke,
 };
 use crate::draw::Drawing;
 use crate::geom::{self, Vector2};
 use crate::math::{BaseFloat, Zero};
+use crate::serde_derive::{Deserialize, Serialize};
 use lyon::tessellation::StrokeOptions;
 
 /// Properties related to drawing an **Ellipse**.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Ellipse<S = geom::scalar::Default> {
     dimensions: spatial::dimension::Properties<S>,
     resolution: Option<usize>,
@@ -115,6 +118,40 @@ impl draw::renderer::RenderPrimitive for Ellipse<f32> {
     }
 }
 
+impl draw::svg::SvgPrimitive for Ellipse<f32> {
+    fn render_svg(self, ctxt: draw::svg::RenderContext, doc: &mut draw::svg::Document) {
+        let Ellipse {
+            dimensions,
+            polygon,
+            resolution,
+        } = self;
+        let w = dimensions.x.map(f32::abs).unwrap_or(100.0);
+        let h = dimensions.y.map(f32::abs).unwrap_or(100.0);
+        let rect = geom::Rect::from_wh(Vector2 { x: w, y: h });
+        match resolution {
+            None => {
+                let opts = polygon.opts;
+                let local_transform = opts.position.transform() * opts.orientation.transform();
+                let transform = *ctxt.transform * local_transform;
+                let theme_prim = &draw::theme::Primitive::Ellipse;
+                let style = polygon::svg_style(&opts, ctxt.theme, theme_prim);
+                doc.ellipse(&transform, rect, &style);
+            }
+            Some(resolution) => {
+                let ellipse = geom::Ellipse::new(rect, resolution);
+                let points = ellipse.circumference();
+                polygon::render_svg_points_themed(
+                    polygon.opts,
+                    points,
+                    ctxt,
+                    &draw::theme::Primitive::Ellipse,
+                    doc,
+                );
+            }
+        }
+    }
+}
+
 impl<S> Default for Ellipse<S>
 where
     S: Zero,
@@ -155,6 +192,12 @@ impl<S> SetColor<ColorScalar> for Ellipse<S> {
     }
 }
 
+impl<S> SetGradient for Ellipse<S> {
+    fn gradient_mut(&mut self) -> &mut Option<Gradient> {
+        SetGradient::gradient_mut(&mut self.polygon)
+    }
+}
+
 impl<S> SetStroke for Ellipse<S> {
     fn stroke_options_mut(&mut self) -> &mut StrokeOptions {
         SetStroke::stroke_options_mut(&mut self.polygon)
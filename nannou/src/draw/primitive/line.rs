@@ -1,18 +1,23 @@
 use crate::color::LinSrgba;
 use crate::draw::primitive::path;
 use crate::draw::primitive::{PathStroke, Primitive};
+use crate::draw::properties::dash::Dash;
+use crate::draw::properties::gradient::{self, Gradient};
 use crate::draw::properties::spatial::{orientation, position};
-use crate::draw::properties::{ColorScalar, SetColor, SetOrientation, SetPosition, SetStroke};
+use crate::draw::properties::{
+    ColorScalar, SetColor, SetDash, SetGradient, SetOrientation, SetPosition, SetStroke,
+};
 use crate::draw::{self, Drawing};
 use crate::geom::{self, pt2, Point2};
 use crate::math::{BaseFloat, Zero};
+use crate::serde_derive::{Deserialize, Serialize};
 use lyon::tessellation::StrokeOptions;
 
 /// A path containing only two points - a start and end.
 ///
 /// The usage of this type is almost identical to `PathStroke` but provides `start`, `end` and
 /// `points(a, b)` methods.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Line<S = geom::scalar::Default> {
     pub path: PathStroke<S>,
     pub start: Option<Point2<S>>,
@@ -115,6 +120,18 @@ impl<S> SetColor<ColorScalar> for Line<S> {
     }
 }
 
+impl<S> SetGradient for Line<S> {
+    fn gradient_mut(&mut self) -> &mut Option<Gradient> {
+        SetGradient::gradient_mut(&mut self.path)
+    }
+}
+
+impl<S> SetDash for Line<S> {
+    fn dash_mut(&mut self) -> &mut Option<Dash> {
+        SetDash::dash_mut(&mut self.path)
+    }
+}
+
 impl<S> From<Line<S>> for Primitive<S> {
     fn from(prim: Line<S>) -> Self {
         Primitive::Line(prim)
@@ -155,6 +172,8 @@ impl draw::renderer::RenderPrimitive for Line<f32> {
         path::render_path_events(
             events,
             path.color,
+            path.gradient.as_ref(),
+            path.dash.as_ref(),
             transform,
             path::Options::Stroke(path.opts),
             &ctxt.theme,
@@ -168,6 +187,26 @@ impl draw::renderer::RenderPrimitive for Line<f32> {
     }
 }
 
+impl draw::svg::SvgPrimitive for Line<f32> {
+    fn render_svg(self, ctxt: draw::svg::RenderContext, doc: &mut draw::svg::Document) {
+        let Line { path, start, end } = self;
+        let start = start.unwrap_or(pt2(0.0, 0.0));
+        let end = end.unwrap_or(pt2(0.0, 0.0));
+        if start == end {
+            return;
+        }
+        let local_transform = path.position.transform() * path.orientation.transform();
+        let transform = *ctxt.transform * local_transform;
+        let color = gradient::flat_color(path.gradient.as_ref(), path.color)
+            .unwrap_or_else(|| ctxt.theme.stroke_lin_srgba(&draw::theme::Primitive::Line));
+        let style = draw::svg::Style {
+            dash: path.dash,
+            ..draw::svg::Style::stroke(color, path.opts)
+        };
+        doc.polyline(&transform, false, vec![start, end], &style);
+    }
+}
+
 impl<S> Default for Line<S>
 where
     S: Zero,
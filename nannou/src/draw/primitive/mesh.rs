@@ -6,15 +6,16 @@ use crate::draw::properties::{ColorScalar, LinSrgba, SetColor, SetOrientation, S
 use crate::draw::{self, Drawing};
 use crate::geom;
 use crate::math::BaseFloat;
+use crate::serde_derive::{Deserialize, Serialize};
 use crate::wgpu;
 use std::ops;
 
 /// The mesh type prior to being initialised with vertices or indices.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Vertexless;
 
 /// Properties related to drawing an arbitrary mesh of colours, geometry and texture.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Mesh<S = geom::scalar::Default> {
     position: position::Properties<S>,
     orientation: orientation::Properties<S>,
@@ -22,10 +23,11 @@ pub struct Mesh<S = geom::scalar::Default> {
     index_range: ops::Range<usize>,
     vertex_mode: draw::renderer::VertexMode,
     fill_color: Option<FillColor>,
-    texture_view: Option<wgpu::TextureView>,
+    #[serde(skip)]
+    pub(crate) texture_view: Option<wgpu::TextureView>,
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 struct FillColor(Option<LinSrgba>);
 
 // A simple iterator for flattening a fixed-size array of indices.
@@ -298,6 +300,29 @@ impl Vertexless {
         mesh
     }
 
+    /// Describe the mesh with the triangulation of a polygon, given its outer contour and the
+    /// contours of any holes.
+    ///
+    /// Concave polygons are supported. See `geom::polygon::triangulate_indices` for details.
+    ///
+    /// This method assumes that the entire mesh should be coloured with a single colour. If a
+    /// colour is not specified via one of the builder methods, a default colour will be retrieved
+    /// from the inner `Theme`.
+    pub fn polygon<S, V>(
+        self,
+        inner_mesh: &mut draw::Mesh<S>,
+        contour: &[V],
+        holes: &[&[V]],
+    ) -> Mesh<S>
+    where
+        S: BaseFloat,
+        V: geom::Vertex2d + Into<Point<S>>,
+    {
+        let indices = geom::polygon::triangulate_indices(contour, holes);
+        let points = contour.iter().chain(holes.iter().flat_map(|h| h.iter()));
+        self.indexed(inner_mesh, points.cloned(), indices)
+    }
+
     fn indexed_inner<S, V, I>(
         self,
         inner_mesh: &mut draw::Mesh<S>,
@@ -501,6 +526,21 @@ where
     {
         self.map_ty_with_context(|ty, ctxt| ty.indexed_textured(ctxt.mesh, view, points, indices))
     }
+
+    /// Describe the mesh with the triangulation of a polygon, given its outer contour and the
+    /// contours of any holes.
+    ///
+    /// Concave polygons are supported. See `geom::polygon::triangulate_indices` for details.
+    ///
+    /// This method assumes that the entire mesh should be coloured with a single colour. If a
+    /// colour is not specified via one of the builder methods, a default colour will be retrieved
+    /// from the inner `Theme`.
+    pub fn polygon<V>(self, contour: &[V], holes: &[&[V]]) -> DrawingMesh<'a, S>
+    where
+        V: geom::Vertex2d + Into<Point<S>>,
+    {
+        self.map_ty_with_context(|ty, ctxt| ty.polygon(ctxt.mesh, contour, holes))
+    }
 }
 
 impl draw::renderer::RenderPrimitive for Mesh<f32> {
@@ -566,6 +606,58 @@ impl draw::renderer::RenderPrimitive for Mesh<f32> {
         draw::renderer::PrimitiveRender {
             texture_view,
             vertex_mode,
+            vertex_mode_runs: vec![],
+        }
+    }
+}
+
+impl draw::svg::SvgPrimitive for Mesh<f32> {
+    fn render_svg(self, ctxt: draw::svg::RenderContext, doc: &mut draw::svg::Document) {
+        let Mesh {
+            orientation,
+            position,
+            index_range,
+            vertex_mode,
+            fill_color,
+            ..
+        } = self;
+
+        // Textured meshes cannot be represented without a fill color.
+        if fill_color.is_none() && vertex_mode == draw::renderer::VertexMode::Texture {
+            return;
+        }
+
+        // Determine the transform to apply to vertices.
+        let global_transform = *ctxt.transform;
+        let local_transform = position.transform() * orientation.transform();
+        let transform = global_transform * local_transform;
+        let transform_point = |p: geom::Point3<f32>| -> geom::Point3<f32> {
+            let p = cgmath::Point3::new(p.x, p.y, p.z);
+            let p = cgmath::Transform::transform_point(&transform, p);
+            p.into()
+        };
+
+        // Write each triangle, averaging the vertex colors if no fill color was specified.
+        let fill = fill_color.map(|fill| {
+            let theme_prim = draw::theme::Primitive::Mesh;
+            fill.0
+                .unwrap_or_else(|| ctxt.theme.fill_lin_srgba(&theme_prim))
+        });
+        let indices = &ctxt.intermediary_mesh.indices()[index_range];
+        for tri in indices.chunks_exact(3) {
+            let [a, b, c] = [tri[0] as usize, tri[1] as usize, tri[2] as usize];
+            let points = ctxt.intermediary_mesh.points();
+            let points = [
+                transform_point(points[a]),
+                transform_point(points[b]),
+                transform_point(points[c]),
+            ];
+            let color = fill.unwrap_or_else(|| {
+                let colors = ctxt.intermediary_mesh.colors();
+                let colors = vec![colors[a], colors[b], colors[c]];
+                draw::svg::average_color(colors).expect("three colors")
+            });
+            doc.triangle(points, color);
         }
     }
 }
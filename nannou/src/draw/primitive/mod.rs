@@ -7,10 +7,12 @@ pub mod polygon;
 pub mod quad;
 pub mod rect;
 pub mod text;
+pub mod text_on_path;
 pub mod texture;
 pub mod tri;
 
 use crate::geom;
+use crate::serde_derive::{Deserialize, Serialize};
 
 pub use self::arrow::Arrow;
 pub use self::ellipse::Ellipse;
@@ -21,6 +23,7 @@ pub use self::polygon::{Polygon, PolygonInit};
 pub use self::quad::Quad;
 pub use self::rect::Rect;
 pub use self::text::Text;
+pub use self::text_on_path::TextOnPath;
 pub use self::texture::Texture;
 pub use self::tri::Tri;
 
@@ -29,7 +32,7 @@ pub use self::tri::Tri;
 ///
 /// This also allows us to flush all pending drawings to the mesh if `Draw::to_frame` is called
 /// before their respective **Drawing** types are dropped.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum Primitive<S = geom::scalar::Default> {
     Arrow(Arrow<S>),
     Ellipse(Ellipse<S>),
@@ -45,6 +48,7 @@ pub enum Primitive<S = geom::scalar::Default> {
     Quad(Quad<S>),
     Rect(Rect<S>),
     Text(Text<S>),
+    TextOnPath(TextOnPath<S>),
     Texture(Texture<S>),
     Tri(Tri<S>),
 }
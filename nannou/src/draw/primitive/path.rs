@@ -1,15 +1,20 @@
 use crate::color::conv::IntoLinSrgba;
-use crate::color::LinSrgba;
+use crate::color::{LinSrgba, Mix};
+use crate::draw::mesh::builder::GradientMeshBuilder;
 use crate::draw::mesh::vertex::{Color, TexCoords};
 use crate::draw::primitive::Primitive;
+use crate::draw::properties::dash::Dash;
+use crate::draw::properties::gradient::{self, Gradient};
 use crate::draw::properties::spatial::{orientation, position};
 use crate::draw::properties::{
-    ColorScalar, SetColor, SetFill, SetOrientation, SetPosition, SetStroke,
+    ColorScalar, SetColor, SetDash, SetFill, SetGradient, SetOrientation, SetPosition, SetStroke,
 };
 use crate::draw::{self, Drawing, DrawingContext};
-use crate::geom::{self, Point2};
+use crate::geom::{self, pt2, Point2};
 use crate::math::{BaseFloat, Zero};
+use crate::serde_derive::{Deserialize, Serialize};
 use crate::wgpu;
+use lyon::path::iterator::PathIterator;
 use lyon::path::PathEvent;
 use lyon::tessellation::{FillOptions, FillTessellator, StrokeOptions, StrokeTessellator};
 
@@ -21,7 +26,7 @@ pub trait TessellationOptions {
     fn into_options(self) -> Options;
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub(crate) enum PathEventSource {
     /// Fetch events from `path_events_buffer`.
     Buffered(std::ops::Range<usize>),
@@ -51,14 +56,16 @@ pub(crate) enum PathEventSourceIter<'a> {
 
 /// The beginning of the path building process, prior to choosing the tessellation mode (fill or
 /// stroke).
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PathInit<S = geom::scalar::Default>(std::marker::PhantomData<S>);
 
 /// A path drawing context ready to specify tessellation options.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PathOptions<T, S = geom::scalar::Default> {
     pub(crate) opts: T,
     pub(crate) color: Option<LinSrgba>,
+    pub(crate) gradient: Option<Gradient>,
+    pub(crate) dash: Option<Dash>,
     pub(crate) position: position::Properties<S>,
     pub(crate) orientation: orientation::Properties<S>,
 }
@@ -76,15 +83,18 @@ pub type PathFill<S = geom::scalar::Default> = PathOptions<FillOptions, S>;
 pub type PathStroke<S = geom::scalar::Default> = PathOptions<StrokeOptions, S>;
 
 /// Properties related to drawing a **Path**.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Path<S = geom::scalar::Default> {
     color: Option<LinSrgba>,
+    gradient: Option<Gradient>,
+    dash: Option<Dash>,
     position: position::Properties<S>,
     orientation: orientation::Properties<S>,
     path_event_src: PathEventSource,
     options: Options,
     vertex_mode: draw::renderer::VertexMode,
-    texture_view: Option<wgpu::TextureView>,
+    #[serde(skip)]
+    pub(crate) texture_view: Option<wgpu::TextureView>,
 }
 
 /// The initial drawing context for a path.
@@ -103,7 +113,7 @@ pub type DrawingPathFill<'a, S = geom::scalar::Default> = Drawing<'a, PathFill<S
 pub type DrawingPath<'a, S = geom::scalar::Default> = Drawing<'a, Path<S>, S>;
 
 /// Dynamically distinguish between fill and stroke tessellation options.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum Options {
     Fill(FillOptions),
     Stroke(StrokeOptions),
@@ -142,11 +152,15 @@ where
         let orientation = Default::default();
         let position = Default::default();
         let color = Default::default();
+        let gradient = Default::default();
+        let dash = Default::default();
         PathOptions {
             opts,
             orientation,
             position,
             color,
+            gradient,
+            dash,
         }
     }
 }
@@ -201,6 +215,8 @@ where
             self.position,
             self.orientation,
             self.color,
+            self.gradient,
+            self.dash,
             PathEventSource::Buffered(start..end),
             self.opts.into_options(),
             draw::renderer::VertexMode::Color,
@@ -330,6 +346,8 @@ where
             self.position,
             self.orientation,
             self.color,
+            self.gradient,
+            self.dash,
             path_event_src,
             self.opts.into_options(),
             draw::renderer::VertexMode::Color,
@@ -367,6 +385,8 @@ where
             self.position,
             self.orientation,
             self.color,
+            self.gradient,
+            self.dash,
             path_event_src,
             self.opts.into_options(),
             draw::renderer::VertexMode::Texture,
@@ -375,9 +395,15 @@ where
     }
 }
 
+/// Tessellate the given path events into the mesh.
+///
+/// If a gradient is given, it is used in place of the color. If a dash pattern is given, strokes
+/// are split into dashes along the flattened path.
 pub(crate) fn render_path_events<I>(
     events: I,
     color: Option<LinSrgba>,
+    gradient: Option<&Gradient>,
+    dash: Option<&Dash>,
     transform: cgmath::Matrix4<f32>,
     options: Options,
     theme: &draw::Theme,
@@ -388,13 +414,46 @@ pub(crate) fn render_path_events<I>(
 ) where
     I: IntoIterator<Item = lyon::path::PathEvent>,
 {
-    let res = match options {
-        Options::Fill(options) => {
+    // Dashed strokes are tessellated one dash at a time.
+    if let (Options::Stroke(opts), Some(dash)) = (&options, dash) {
+        if !dash.is_solid() {
+            for (points, opts) in dash_path_events(events, dash, opts) {
+                let points = points.iter().map(|&(p, ())| p.into());
+                let events: Vec<_> =
+                    lyon::path::iterator::FromPolyline::new(false, points).collect();
+                render_path_events(
+                    events,
+                    color,
+                    gradient,
+                    None,
+                    transform,
+                    Options::Stroke(opts),
+                    theme,
+                    theme_prim,
+                    fill_tessellator,
+                    stroke_tessellator,
+                    mesh,
+                );
+            }
+            return;
+        }
+    }
+
+    let res = match (options, gradient) {
+        (Options::Fill(options), Some(gradient)) => {
+            let mut mesh_builder = GradientMeshBuilder::new(mesh, transform, gradient);
+            fill_tessellator.tessellate(events, &options, &mut mesh_builder)
+        }
+        (Options::Stroke(options), Some(gradient)) => {
+            let mut mesh_builder = GradientMeshBuilder::new(mesh, transform, gradient);
+            stroke_tessellator.tessellate(events, &options, &mut mesh_builder)
+        }
+        (Options::Fill(options), None) => {
             let color = color.unwrap_or_else(|| theme.fill_lin_srgba(theme_prim));
             let mut mesh_builder = draw::mesh::MeshBuilder::single_color(mesh, transform, color);
             fill_tessellator.tessellate(events, &options, &mut mesh_builder)
         }
-        Options::Stroke(options) => {
+        (Options::Stroke(options), None) => {
             let color = color.unwrap_or_else(|| theme.stroke_lin_srgba(theme_prim));
             let mut mesh_builder = draw::mesh::MeshBuilder::single_color(mesh, transform, color);
             stroke_tessellator.tessellate(events, &options, &mut mesh_builder)
@@ -408,6 +467,7 @@ pub(crate) fn render_path_events<I>(
 pub(crate) fn render_path_points_colored<I>(
     points_colored: I,
     close: bool,
+    dash: Option<&Dash>,
     transform: cgmath::Matrix4<f32>,
     options: Options,
     fill_tessellator: &mut lyon::tessellation::FillTessellator,
@@ -416,6 +476,27 @@ pub(crate) fn render_path_points_colored<I>(
 ) where
     I: IntoIterator<Item = (Point2, Color)>,
 {
+    // Colors are interpolated at the ends of each dash so that they remain continuous.
+    if let (Options::Stroke(opts), Some(dash)) = (&options, dash) {
+        if !dash.is_solid() {
+            let points: Vec<_> = points_colored.into_iter().collect();
+            let lerp = |a: Color, b: Color, t| a.mix(&b, t);
+            for (points, opts) in dash.stroke(&points, close, opts, lerp) {
+                render_path_points_colored(
+                    points,
+                    false,
+                    None,
+                    transform,
+                    Options::Stroke(opts),
+                    fill_tessellator,
+                    stroke_tessellator,
+                    mesh,
+                );
+            }
+            return;
+        }
+    }
+
     let path = match points_colored_to_lyon_path(points_colored, close) {
         None => return,
         Some(p) => p,
@@ -447,6 +528,7 @@ pub(crate) fn render_path_points_colored<I>(
 pub(crate) fn render_path_points_textured<I>(
     points_textured: I,
     close: bool,
+    dash: Option<&Dash>,
     transform: cgmath::Matrix4<f32>,
     options: Options,
     fill_tessellator: &mut lyon::tessellation::FillTessellator,
@@ -455,6 +537,28 @@ pub(crate) fn render_path_points_textured<I>(
 ) where
     I: IntoIterator<Item = (Point2, TexCoords)>,
 {
+    // Texture coordinates are interpolated at the ends of each dash so that they remain
+    // continuous.
+    if let (Options::Stroke(opts), Some(dash)) = (&options, dash) {
+        if !dash.is_solid() {
+            let points: Vec<_> = points_textured.into_iter().collect();
+            let lerp = |a: TexCoords, b: TexCoords, t| a + (b - a) * t;
+            for (points, opts) in dash.stroke(&points, close, opts, lerp) {
+                render_path_points_textured(
+                    points,
+                    false,
+                    None,
+                    transform,
+                    Options::Stroke(opts),
+                    fill_tessellator,
+                    stroke_tessellator,
+                    mesh,
+                );
+            }
+            return;
+        }
+    }
+
     let path = match points_textured_to_lyon_path(points_textured, close) {
         None => return,
         Some(p) => p,
@@ -483,10 +587,47 @@ pub(crate) fn render_path_points_textured<I>(
     }
 }
 
+// Flatten the given path events and split each sub-path into dashes.
+fn dash_path_events<I>(
+    events: I,
+    dash: &Dash,
+    opts: &StrokeOptions,
+) -> Vec<(Vec<(Point2, ())>, StrokeOptions)>
+where
+    I: IntoIterator<Item = PathEvent>,
+{
+    let mut dashes = vec![];
+    let mut points = vec![];
+    for event in events.into_iter().flattened(opts.tolerance) {
+        match event {
+            PathEvent::Begin { at } => {
+                points.clear();
+                points.push((pt2(at.x, at.y), ()));
+            }
+            PathEvent::Line { to, .. }
+            | PathEvent::Quadratic { to, .. }
+            | PathEvent::Cubic { to, .. } => {
+                points.push((pt2(to.x, to.y), ()));
+            }
+            PathEvent::End { close, .. } => {
+                dashes.extend(dash.stroke(&points, close, opts, |a, _, _| a));
+                points.clear();
+            }
+        }
+    }
+    dashes
+}
+
+/// Tessellate the path described by the given source into the mesh.
+///
+/// Gradients only apply to path events. Colored and textured points retain their own colors and
+/// texture coordinates.
 pub(crate) fn render_path_source(
     // TODO:
     path_src: PathEventSourceIter,
     color: Option<LinSrgba>,
+    gradient: Option<&Gradient>,
+    dash: Option<&Dash>,
     transform: cgmath::Matrix4<f32>,
     options: Options,
     theme: &draw::Theme,
@@ -499,6 +640,8 @@ pub(crate) fn render_path_source(
         PathEventSourceIter::Events(events) => render_path_events(
             events,
             color,
+            gradient,
+            dash,
             transform,
             options,
             theme,
@@ -510,6 +653,7 @@ pub(crate) fn render_path_source(
         PathEventSourceIter::ColoredPoints { points, close } => render_path_points_colored(
             points,
             close,
+            dash,
             transform,
             options,
             fill_tessellator,
@@ -519,6 +663,7 @@ pub(crate) fn render_path_source(
         PathEventSourceIter::TexturedPoints { points, close } => render_path_points_textured(
             points,
             close,
+            dash,
             transform,
             options,
             fill_tessellator,
@@ -536,6 +681,8 @@ impl draw::renderer::RenderPrimitive for Path<f32> {
     ) -> draw::renderer::PrimitiveRender {
         let Path {
             color,
+            gradient,
+            dash,
             position,
             orientation,
             path_event_src,
@@ -558,6 +705,8 @@ impl draw::renderer::RenderPrimitive for Path<f32> {
                 render_path_source(
                     src,
                     color,
+                    gradient.as_ref(),
+                    dash.as_ref(),
                     transform,
                     options,
                     theme,
@@ -610,10 +759,139 @@ impl draw::renderer::RenderPrimitive for Path<f32> {
         draw::renderer::PrimitiveRender {
             texture_view,
             vertex_mode,
+            vertex_mode_runs: vec![],
         }
     }
 }
 
+/// Write the path described by the given source to the SVG document.
+///
+/// Colored points are written with their average color when filled, or as one element per
+/// segment when stroked. Textured points cannot be represented and are skipped.
+pub(crate) fn render_svg_path_source(
+    path_event_src: &PathEventSource,
+    color: Option<LinSrgba>,
+    dash: Option<&Dash>,
+    transform: &cgmath::Matrix4<f32>,
+    options: &Options,
+    ctxt: &draw::svg::RenderContext,
+    theme_prim: &draw::theme::Primitive,
+    doc: &mut draw::svg::Document,
+) {
+    match *path_event_src {
+        PathEventSource::Buffered(ref range) => {
+            let style = match *options {
+                Options::Fill(ref opts) => draw::svg::Style {
+                    fill: Some(color.unwrap_or_else(|| ctxt.theme.fill_lin_srgba(theme_prim))),
+                    fill_rule: Some(opts.fill_rule),
+                    stroke: None,
+                    dash: None,
+                },
+                Options::Stroke(ref opts) => {
+                    let color = color.unwrap_or_else(|| ctxt.theme.stroke_lin_srgba(theme_prim));
+                    let dash = dash.cloned();
+                    draw::svg::Style {
+                        dash,
+                        ..draw::svg::Style::stroke(color, *opts)
+                    }
+                }
+            };
+            let events = ctxt.path_event_buffer[range.clone()].iter().cloned();
+            doc.path(transform, events, &style);
+        }
+        PathEventSource::ColoredPoints { ref range, close } => {
+            let points = &ctxt.path_points_colored_buffer[range.clone()];
+            render_svg_points_colored(points, close, dash, transform, options, doc);
+        }
+        PathEventSource::TexturedPoints { .. } => (),
+    }
+}
+
+/// Write the given colored points to the SVG document.
+///
+/// Dashed strokes are written as one element per dash.
+pub(crate) fn render_svg_points_colored(
+    points: &[(Point2, Color)],
+    close: bool,
+    dash: Option<&Dash>,
+    transform: &cgmath::Matrix4<f32>,
+    options: &Options,
+    doc: &mut draw::svg::Document,
+) {
+    let color = match draw::svg::average_color(points.iter().map(|&(_, c)| c)) {
+        None => return,
+        Some(color) => color,
+    };
+    let positions = points.iter().map(|&(p, _)| p);
+    match *options {
+        Options::Fill(ref opts) => {
+            let style = draw::svg::Style {
+                fill: Some(color),
+                fill_rule: Some(opts.fill_rule),
+                stroke: None,
+                dash: None,
+            };
+            doc.polyline(transform, close, positions, &style);
+        }
+        Options::Stroke(ref opts) => {
+            if let Some(dash) = dash.filter(|dash| !dash.is_solid()) {
+                let lerp = |a: Color, b: Color, t| a.mix(&b, t);
+                for (points, opts) in dash.stroke(points, close, opts, lerp) {
+                    let options = Options::Stroke(opts);
+                    render_svg_points_colored(&points, false, None, transform, &options, doc);
+                }
+                return;
+            }
+            // A single element is enough if all points share the same color.
+            let (_, first) = points[0];
+            if points.iter().all(|&(_, c)| c == first) {
+                let style = draw::svg::Style::stroke(first, *opts);
+                doc.polyline(transform, close, positions, &style);
+                return;
+            }
+            let segments = points.windows(2).map(|w| (w[0], w[1]));
+            let closing = if close && points.len() > 2 {
+                Some((points[points.len() - 1], points[0]))
+            } else {
+                None
+            };
+            for ((a, ca), (b, cb)) in segments.chain(closing) {
+                let color = draw::svg::average_color(vec![ca, cb]).expect("two colors");
+                let style = draw::svg::Style::stroke(color, *opts);
+                doc.polyline(transform, false, vec![a, b], &style);
+            }
+        }
+    }
+}
+
+impl draw::svg::SvgPrimitive for Path<f32> {
+    fn render_svg(self, ctxt: draw::svg::RenderContext, doc: &mut draw::svg::Document) {
+        let Path {
+            color,
+            gradient,
+            dash,
+            position,
+            orientation,
+            path_event_src,
+            options,
+            ..
+        } = self;
+        let local_transform = position.transform() * orientation.transform();
+        let transform = *ctxt.transform * local_transform;
+        let color = gradient::flat_color(gradient.as_ref(), color);
+        render_svg_path_source(
+            &path_event_src,
+            color,
+            dash.as_ref(),
+            &transform,
+            &options,
+            &ctxt,
+            &draw::theme::Primitive::Path,
+            doc,
+        );
+    }
+}
+
 /// Create a lyon path for the given iterator of colored points.
 pub fn points_colored_to_lyon_path<I>(points_colored: I, close: bool) -> Option<lyon::path::Path>
 where
@@ -687,6 +965,8 @@ where
         position: position::Properties<S>,
         orientation: orientation::Properties<S>,
         color: Option<LinSrgba>,
+        gradient: Option<Gradient>,
+        dash: Option<Dash>,
         path_event_src: PathEventSource,
         options: Options,
         vertex_mode: draw::renderer::VertexMode,
@@ -694,6 +974,8 @@ where
     ) -> Self {
         Path {
             color,
+            gradient,
+            dash,
             orientation,
             position,
             path_event_src,
@@ -908,6 +1190,12 @@ impl<T, S> SetColor<ColorScalar> for PathOptions<T, S> {
     }
 }
 
+impl<T, S> SetGradient for PathOptions<T, S> {
+    fn gradient_mut(&mut self) -> &mut Option<Gradient> {
+        SetGradient::gradient_mut(&mut self.gradient)
+    }
+}
+
 impl<S> SetOrientation<S> for Path<S> {
     fn properties(&mut self) -> &mut orientation::Properties<S> {
         SetOrientation::properties(&mut self.orientation)
@@ -926,6 +1214,24 @@ impl<S> SetColor<ColorScalar> for Path<S> {
     }
 }
 
+impl<S> SetGradient for Path<S> {
+    fn gradient_mut(&mut self) -> &mut Option<Gradient> {
+        SetGradient::gradient_mut(&mut self.gradient)
+    }
+}
+
+impl<S> SetDash for PathStroke<S> {
+    fn dash_mut(&mut self) -> &mut Option<Dash> {
+        SetDash::dash_mut(&mut self.dash)
+    }
+}
+
+impl<S> SetDash for Path<S> {
+    fn dash_mut(&mut self) -> &mut Option<Dash> {
+        SetDash::dash_mut(&mut self.dash)
+    }
+}
+
 impl<S> From<PathInit<S>> for Primitive<S> {
     fn from(prim: PathInit<S>) -> Self {
         Primitive::PathInit(prim)
@@ -3,13 +3,15 @@ use crate::draw::drawing::DrawingContext;
 use crate::draw::mesh::vertex::TexCoords;
 use crate::draw::primitive::path::{self, PathEventSource};
 use crate::draw::primitive::Primitive;
+use crate::draw::properties::gradient::{self, Gradient};
 use crate::draw::properties::spatial::{orientation, position};
 use crate::draw::properties::{
-    ColorScalar, LinSrgba, SetColor, SetOrientation, SetPosition, SetStroke,
+    ColorScalar, LinSrgba, SetColor, SetGradient, SetOrientation, SetPosition, SetStroke,
 };
 use crate::draw::{self, Drawing};
 use crate::geom::{self, Point2};
 use crate::math::{BaseFloat, Zero};
+use crate::serde_derive::{Deserialize, Serialize};
 use crate::wgpu;
 use lyon::path::PathEvent;
 use lyon::tessellation::StrokeOptions;
@@ -37,6 +39,17 @@ pub trait SetPolygon<S>: Sized {
         self
     }
 
+    /// Specify a gradient to use for stroke tessellation in place of the stroke color.
+    ///
+    /// Stroke tessellation will only be performed if this method, `stroke_color` or one of the
+    /// `SetStroke` methods are called.
+    fn stroke_gradient(mut self, gradient: Gradient) -> Self {
+        let opts = self.polygon_options_mut();
+        opts.stroke_gradient = Some(gradient);
+        opts.stroke.get_or_insert_with(Default::default);
+        self
+    }
+
     /// Specify the whole set of polygon options.
     fn polygon_options(mut self, opts: PolygonOptions<S>) -> Self {
         *self.polygon_options_mut() = opts;
@@ -45,13 +58,13 @@ pub trait SetPolygon<S>: Sized {
 }
 
 /// State related to drawing a **Polygon**.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PolygonInit<S = geom::scalar::Default> {
     pub(crate) opts: PolygonOptions<S>,
 }
 
 /// The set of options shared by all polygon types.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PolygonOptions<S = geom::scalar::Default> {
     pub position: position::Properties<S>,
     pub orientation: orientation::Properties<S>,
@@ -59,14 +72,17 @@ pub struct PolygonOptions<S = geom::scalar::Default> {
     pub stroke_color: Option<LinSrgba>,
     pub color: Option<LinSrgba>,
     pub stroke: Option<StrokeOptions>,
+    pub gradient: Option<Gradient>,
+    pub stroke_gradient: Option<Gradient>,
 }
 
 /// A polygon with vertices already submitted.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Polygon<S = geom::scalar::Default> {
     opts: PolygonOptions<S>,
     path_event_src: PathEventSource,
-    texture_view: Option<wgpu::TextureView>,
+    #[serde(skip)]
+    pub(crate) texture_view: Option<wgpu::TextureView>,
 }
 
 /// Initialised drawing state for a polygon.
@@ -196,6 +212,8 @@ pub fn render_events_themed<F, I>(
         stroke_color,
         color,
         stroke,
+        gradient,
+        stroke_gradient,
     } = opts;
 
     // Determine the transform to apply to all points.
@@ -207,12 +225,15 @@ pub fn render_events_themed<F, I>(
     let mut render =
         |opts: path::Options,
          color: Option<LinSrgba>,
+         gradient: Option<&Gradient>,
          theme: &draw::Theme,
          fill_tessellator: &mut lyon::tessellation::FillTessellator,
          stroke_tessellator: &mut lyon::tessellation::StrokeTessellator| {
             path::render_path_events(
                 events(),
                 color,
+                gradient,
+                None,
                 transform,
                 opts,
                 theme,
@@ -229,6 +250,7 @@ pub fn render_events_themed<F, I>(
         render(
             opts,
             color,
+            gradient.as_ref(),
             &ctxt.theme,
             &mut ctxt.fill_tessellator,
             &mut ctxt.stroke_tessellator,
@@ -242,6 +264,7 @@ pub fn render_events_themed<F, I>(
         render(
             opts,
             color,
+            stroke_gradient.as_ref(),
             &ctxt.theme,
             &mut ctxt.fill_tessellator,
             &mut ctxt.stroke_tessellator,
@@ -284,6 +307,8 @@ impl Polygon<f32> {
                     stroke_color,
                     color,
                     stroke,
+                    gradient,
+                    stroke_gradient,
                 },
             texture_view,
         } = self;
@@ -308,12 +333,15 @@ impl Polygon<f32> {
             |src: path::PathEventSourceIter,
              opts: path::Options,
              color: Option<LinSrgba>,
+             gradient: Option<&Gradient>,
              theme: &draw::Theme,
              fill_tessellator: &mut lyon::tessellation::FillTessellator,
              stroke_tessellator: &mut lyon::tessellation::StrokeTessellator| {
                 path::render_path_source(
                     src,
                     color,
+                    gradient,
+                    None,
                     transform,
                     opts,
                     theme,
@@ -335,6 +363,7 @@ impl Polygon<f32> {
                         src,
                         opts,
                         color,
+                        gradient.as_ref(),
                         theme,
                         fill_tessellator,
                         stroke_tessellator,
@@ -351,6 +380,7 @@ impl Polygon<f32> {
                         src,
                         opts,
                         color,
+                        gradient.as_ref(),
                         theme,
                         fill_tessellator,
                         stroke_tessellator,
@@ -367,6 +397,7 @@ impl Polygon<f32> {
                         src,
                         opts,
                         color,
+                        gradient.as_ref(),
                         theme,
                         fill_tessellator,
                         stroke_tessellator,
@@ -386,6 +417,7 @@ impl Polygon<f32> {
                         src,
                         opts,
                         stroke_color,
+                        stroke_gradient.as_ref(),
                         theme,
                         fill_tessellator,
                         stroke_tessellator,
@@ -406,6 +438,7 @@ impl Polygon<f32> {
                         src,
                         opts,
                         stroke_color,
+                        stroke_gradient.as_ref(),
                         theme,
                         fill_tessellator,
                         stroke_tessellator,
@@ -421,6 +454,7 @@ impl Polygon<f32> {
                         src,
                         opts,
                         stroke_color,
+                        stroke_gradient.as_ref(),
                         theme,
                         fill_tessellator,
                         stroke_tessellator,
@@ -434,6 +468,7 @@ impl Polygon<f32> {
             Some(texture_view) => draw::renderer::PrimitiveRender {
                 texture_view: Some(texture_view),
                 vertex_mode: draw::renderer::VertexMode::Texture,
+                vertex_mode_runs: vec![],
             },
         }
     }
@@ -449,6 +484,121 @@ impl draw::renderer::RenderPrimitive for Polygon<f32> {
     }
 }
 
+/// The SVG style for the fill and stroke described by the given polygon options.
+pub(crate) fn svg_style(
+    opts: &PolygonOptions,
+    theme: &draw::Theme,
+    theme_primitive: &draw::theme::Primitive,
+) -> draw::svg::Style {
+    let fill = if opts.no_fill {
+        None
+    } else {
+        Some(
+            gradient::flat_color(opts.gradient.as_ref(), opts.color)
+                .unwrap_or_else(|| theme.fill_lin_srgba(theme_primitive)),
+        )
+    };
+    let stroke = opts.stroke.map(|stroke_opts| {
+        let color = gradient::flat_color(opts.stroke_gradient.as_ref(), opts.stroke_color)
+            .unwrap_or_else(|| theme.stroke_lin_srgba(theme_primitive));
+        (color, stroke_opts)
+    });
+    draw::svg::Style {
+        fill,
+        fill_rule: None,
+        stroke,
+        dash: None,
+    }
+}
+
+pub fn render_svg_events_themed<I>(
+    opts: PolygonOptions,
+    events: I,
+    ctxt: draw::svg::RenderContext,
+    theme_primitive: &draw::theme::Primitive,
+    doc: &mut draw::svg::Document,
+) where
+    I: IntoIterator<Item = lyon::path::PathEvent>,
+{
+    let local_transform = opts.position.transform() * opts.orientation.transform();
+    let transform = *ctxt.transform * local_transform;
+    let style = svg_style(&opts, ctxt.theme, theme_primitive);
+    doc.path(&transform, events, &style);
+}
+
+pub fn render_svg_points_themed<I>(
+    opts: PolygonOptions,
+    points: I,
+    ctxt: draw::svg::RenderContext,
+    theme_primitive: &draw::theme::Primitive,
+    doc: &mut draw::svg::Document,
+) where
+    I: IntoIterator<Item = Point2>,
+{
+    let local_transform = opts.position.transform() * opts.orientation.transform();
+    let transform = *ctxt.transform * local_transform;
+    let style = svg_style(&opts, ctxt.theme, theme_primitive);
+    doc.polyline(&transform, true, points, &style);
+}
+
+impl Polygon<f32> {
+    pub(crate) fn render_svg_themed(
+        self,
+        ctxt: draw::svg::RenderContext,
+        doc: &mut draw::svg::Document,
+        theme_primitive: &draw::theme::Primitive,
+    ) {
+        let Polygon {
+            path_event_src,
+            opts,
+            ..
+        } = self;
+        let local_transform = opts.position.transform() * opts.orientation.transform();
+        let transform = *ctxt.transform * local_transform;
+        match path_event_src {
+            PathEventSource::Buffered(range) => {
+                let style = svg_style(&opts, ctxt.theme, theme_primitive);
+                let events = ctxt.path_event_buffer[range].iter().cloned();
+                doc.path(&transform, events, &style);
+            }
+            PathEventSource::ColoredPoints { range, close } => {
+                let points = &ctxt.path_points_colored_buffer[range];
+                if !opts.no_fill {
+                    let fill = path::Options::Fill(Default::default());
+                    path::render_svg_points_colored(points, close, None, &transform, &fill, doc);
+                }
+                if let Some(stroke_opts) = opts.stroke {
+                    let color = opts
+                        .stroke_color
+                        .unwrap_or_else(|| ctxt.theme.stroke_lin_srgba(theme_primitive));
+                    let style = draw::svg::Style::stroke(color, stroke_opts);
+                    let points = points.iter().map(|&(p, _)| p);
+                    doc.polyline(&transform, close, points, &style);
+                }
+            }
+            // Textures cannot be represented, however the stroke may still be drawn.
+            PathEventSource::TexturedPoints { range, close } => {
+                if let Some(stroke_opts) = opts.stroke {
+                    let color = opts
+                        .stroke_color
+                        .unwrap_or_else(|| ctxt.theme.stroke_lin_srgba(theme_primitive));
+                    let style = draw::svg::Style::stroke(color, stroke_opts);
+                    let points = ctxt.path_points_textured_buffer[range]
+                        .iter()
+                        .map(|&(p, _)| p);
+                    doc.polyline(&transform, close, points, &style);
+                }
+            }
+        }
+    }
+}
+
+impl draw::svg::SvgPrimitive for Polygon<f32> {
+    fn render_svg(self, ctxt: draw::svg::RenderContext, doc: &mut draw::svg::Document) {
+        self.render_svg_themed(ctxt, doc, &draw::theme::Primitive::Polygon)
+    }
+}
+
 impl<'a, S, T> Drawing<'a, T, S>
 where
     S: BaseFloat,
@@ -471,6 +621,11 @@ where
         self.map_ty(|ty| ty.stroke_color(color))
     }
 
+    /// Specify a gradient to use for stroke tessellation in place of the stroke color.
+    pub fn stroke_gradient(self, gradient: Gradient) -> Self {
+        self.map_ty(|ty| ty.stroke_gradient(gradient))
+    }
+
     /// Specify the whole set of polygon options.
     pub fn polygon_options(self, opts: PolygonOptions<S>) -> Self {
         self.map_ty(|ty| ty.polygon_options(opts))
@@ -556,6 +711,8 @@ where
         let color = None;
         let stroke_color = None;
         let stroke = None;
+        let gradient = None;
+        let stroke_gradient = None;
         PolygonOptions {
             position,
             orientation,
@@ -563,6 +720,8 @@ where
             color,
             stroke_color,
             stroke,
+            gradient,
+            stroke_gradient,
         }
     }
 }
@@ -591,6 +750,12 @@ impl<S> SetColor<ColorScalar> for PolygonInit<S> {
     }
 }
 
+impl<S> SetGradient for PolygonInit<S> {
+    fn gradient_mut(&mut self) -> &mut Option<Gradient> {
+        SetGradient::gradient_mut(&mut self.opts.gradient)
+    }
+}
+
 impl<S> SetPolygon<S> for PolygonInit<S> {
     fn polygon_options_mut(&mut self) -> &mut PolygonOptions<S> {
         SetPolygon::polygon_options_mut(&mut self.opts)
@@ -621,6 +786,12 @@ impl<S> SetColor<ColorScalar> for Polygon<S> {
     }
 }
 
+impl<S> SetGradient for Polygon<S> {
+    fn gradient_mut(&mut self) -> &mut Option<Gradient> {
+        SetGradient::gradient_mut(&mut self.opts.gradient)
+    }
+}
+
 impl<S> From<PolygonInit<S>> for Primitive<S> {
     fn from(prim: PolygonInit<S>) -> Self {
         Primitive::PolygonInit(prim)
@@ -1,17 +1,20 @@
 use crate::color::conv::IntoLinSrgba;
 use crate::draw::primitive::polygon::{self, PolygonInit, PolygonOptions, SetPolygon};
 use crate::draw::primitive::Primitive;
+use crate::draw::properties::gradient::Gradient;
 use crate::draw::properties::spatial::{dimension, orientation, position};
 use crate::draw::properties::{
-    spatial, ColorScalar, LinSrgba, SetColor, SetDimensions, SetOrientation, SetPosition, SetStroke,
+    spatial, ColorScalar, LinSrgba, SetColor, SetDimensions, SetGradient, SetOrientation,
+    SetPosition, SetStroke,
 };
 use crate::draw::{self, Drawing};
 use crate::geom::{self, Point2, Vector2};
 use crate::math::{BaseFloat, ElementWise};
+use crate::serde_derive::{Deserialize, Serialize};
 use lyon::tessellation::StrokeOptions;
 
 /// Properties related to drawing a **Quad**.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Quad<S = geom::scalar::Default> {
     quad: geom::Quad<Point2<S>>,
     polygon: PolygonInit<S>,
@@ -47,20 +50,13 @@ impl<S> Quad<S> {
 }
 
 // Trait implementations.
-impl draw::renderer::RenderPrimitive for Quad<f32> {
-    fn render_primitive(
-        self,
-        ctxt: draw::renderer::RenderContext,
-        mesh: &mut draw::Mesh,
-    ) -> draw::renderer::PrimitiveRender {
-        let Quad {
-            mut quad,
-            polygon,
-            dimensions,
-        } = self;
-
+impl Quad<f32> {
+    // The quad with the specified dimensions (if any) applied.
+    fn dimensioned_quad(&self) -> geom::Quad<Point2> {
+        let mut quad = self.quad;
         // If dimensions were specified, scale the points to those dimensions.
-        let (maybe_x, maybe_y, _maybe_z) = (dimensions.x, dimensions.y, dimensions.z);
+        let (maybe_x, maybe_y, _maybe_z) =
+            (self.dimensions.x, self.dimensions.y, self.dimensions.z);
         if maybe_x.is_some() || maybe_y.is_some() {
             let cuboid = quad.bounding_rect();
             let centroid = quad.centroid();
@@ -78,10 +74,20 @@ impl draw::renderer::RenderPrimitive for Quad<f32> {
             let new_d = translate(d);
             quad = geom::Quad([new_a, new_b, new_c, new_d]);
         }
+        quad
+    }
+}
 
+impl draw::renderer::RenderPrimitive for Quad<f32> {
+    fn render_primitive(
+        self,
+        ctxt: draw::renderer::RenderContext,
+        mesh: &mut draw::Mesh,
+    ) -> draw::renderer::PrimitiveRender {
+        let quad = self.dimensioned_quad();
         let points = quad.vertices();
         polygon::render_points_themed(
-            polygon.opts,
+            self.polygon.opts,
             points,
             ctxt,
             &draw::theme::Primitive::Quad,
@@ -92,6 +98,19 @@ impl draw::renderer::RenderPrimitive for Quad<f32> {
     }
 }
 
+impl draw::svg::SvgPrimitive for Quad<f32> {
+    fn render_svg(self, ctxt: draw::svg::RenderContext, doc: &mut draw::svg::Document) {
+        let quad = self.dimensioned_quad();
+        polygon::render_svg_points_themed(
+            self.polygon.opts,
+            quad.vertices(),
+            ctxt,
+            &draw::theme::Primitive::Quad,
+            doc,
+        );
+    }
+}
+
 impl<S> From<geom::Quad<Point2<S>>> for Quad<S>
 where
     S: BaseFloat,
@@ -153,6 +172,12 @@ impl<S> SetColor<ColorScalar> for Quad<S> {
     }
 }
 
+impl<S> SetGradient for Quad<S> {
+    fn gradient_mut(&mut self) -> &mut Option<Gradient> {
+        SetGradient::gradient_mut(&mut self.polygon)
+    }
+}
+
 impl<S> SetStroke for Quad<S> {
     fn stroke_options_mut(&mut self) -> &mut StrokeOptions {
         SetStroke::stroke_options_mut(&mut self.polygon)
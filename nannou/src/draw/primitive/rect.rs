@@ -1,17 +1,20 @@
 use crate::color::conv::IntoLinSrgba;
 use crate::draw::primitive::polygon::{self, PolygonInit, PolygonOptions, SetPolygon};
 use crate::draw::primitive::Primitive;
+use crate::draw::properties::gradient::Gradient;
 use crate::draw::properties::spatial::{dimension, orientation, position};
 use crate::draw::properties::{
-    ColorScalar, LinSrgba, SetColor, SetDimensions, SetOrientation, SetPosition, SetStroke,
+    ColorScalar, LinSrgba, SetColor, SetDimensions, SetGradient, SetOrientation, SetPosition,
+    SetStroke,
 };
 use crate::draw::{self, Drawing};
 use crate::geom::{self, Vector2};
 use crate::math::BaseFloat;
+use crate::serde_derive::{Deserialize, Serialize};
 use lyon::tessellation::StrokeOptions;
 
 /// Properties related to drawing a **Rect**.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Rect<S = geom::scalar::Default> {
     dimensions: dimension::Properties<S>,
     polygon: PolygonInit<S>,
@@ -78,6 +81,23 @@ impl draw::renderer::RenderPrimitive for Rect<f32> {
     }
 }
 
+impl draw::svg::SvgPrimitive for Rect<f32> {
+    fn render_svg(self, ctxt: draw::svg::RenderContext, doc: &mut draw::svg::Document) {
+        let Rect {
+            polygon,
+            dimensions,
+        } = self;
+        let w = dimensions.x.unwrap_or(100.0);
+        let h = dimensions.y.unwrap_or(100.0);
+        let rect = geom::Rect::from_wh(Vector2 { x: w, y: h });
+        let opts = polygon.opts;
+        let local_transform = opts.position.transform() * opts.orientation.transform();
+        let transform = *ctxt.transform * local_transform;
+        let style = polygon::svg_style(&opts, ctxt.theme, &draw::theme::Primitive::Rect);
+        doc.rect(&transform, rect, &style);
+    }
+}
+
 impl<S> From<geom::Rect<S>> for Rect<S>
 where
     S: BaseFloat,
@@ -126,6 +146,12 @@ impl<S> SetColor<ColorScalar> for Rect<S> {
     }
 }
 
+impl<S> SetGradient for Rect<S> {
+    fn gradient_mut(&mut self) -> &mut Option<Gradient> {
+        SetGradient::gradient_mut(&mut self.polygon)
+    }
+}
+
 impl<S> SetStroke for Rect<S> {
     fn stroke_options_mut(&mut self) -> &mut StrokeOptions {
         SetStroke::stroke_options_mut(&mut self.polygon)
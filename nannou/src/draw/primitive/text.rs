@@ -1,30 +1,54 @@
+use crate::color::conv::IntoLinSrgba;
 use crate::draw::drawing::DrawingContext;
 use crate::draw::primitive::Primitive;
 use crate::draw::properties::spatial::{self, dimension, orientation, position};
 use crate::draw::properties::{
     ColorScalar, LinSrgba, SetColor, SetDimensions, SetOrientation, SetPosition,
 };
+use crate::draw::renderer::VertexMode;
 use crate::draw::{self, theme, Drawing};
 use crate::geom::{self, Vector2};
 use crate::math::{BaseFloat, Zero};
+use crate::serde_derive::{Deserialize, Serialize};
 use crate::text::{self, Align, Font, FontSize, Justify, Layout, Scalar, Wrap};
 
 /// Properties related to drawing the **Text** primitive.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Text<S = geom::scalar::Default> {
     spatial: spatial::Properties<S>,
-    style: Style,
+    pub(crate) style: Style,
     // The byte range into the `Draw` context's text buffer.
     text: std::ops::Range<usize>,
+    // Styled spans of the text, relative to the start of its byte range.
+    spans: Vec<text::rich::Span>,
+    // The fonts referred to by the styles of the spans. Fonts are not serialized, though the
+    // `draw::recording` module records them by reference.
+    #[serde(skip)]
+    pub(crate) fonts: text::font::Map,
+    // Whether or not the glyphs are drawn via signed distance fields and their effects if so.
+    sdf: Option<Sdf>,
 }
 
 /// Styling properties for the **Text** primitive.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Style {
     pub color: Option<LinSrgba>,
     pub layout: text::layout::Builder,
 }
 
+/// Properties for drawing the glyphs of the **Text** primitive via signed distance fields.
+///
+/// Rather than rasterising each glyph at the size at which it is displayed, glyphs are drawn from
+/// distance fields generated once from their outlines. This keeps edges crisp when text is large
+/// or scaled by the draw transform, and allows for drawing outlines and glows.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Sdf {
+    /// The width of an outline drawn around each glyph, along with its color.
+    pub outline: Option<(Scalar, LinSrgba)>,
+    /// The radius of a glow drawn around each glyph and its outline, along with its color.
+    pub glow: Option<(Scalar, LinSrgba)>,
+}
+
 /// The drawing context for the **Text** primitive.
 pub type DrawingText<'a, S = geom::scalar::Default> = Drawing<'a, Text<S>, S>;
 
@@ -40,13 +64,67 @@ impl<S> Text<S> {
         let text = start..end;
         let spatial = Default::default();
         let style = Default::default();
+        let spans = Default::default();
+        let fonts = Default::default();
+        let sdf = None;
         Text {
             spatial,
             style,
             text,
+            spans,
+            fonts,
+            sdf,
         }
     }
 
+    /// Append a span of text displayed with the given style.
+    ///
+    /// The text given when beginning the drawing is displayed with the default `rich::Style`.
+    pub fn span(mut self, text_buffer: &mut String, text: &str, style: text::rich::Style) -> Self {
+        // Move the existing text to the end of the buffer if other text has since been drawn, so
+        // that the text of the primitive remains contiguous.
+        if self.text.end != text_buffer.len() {
+            let existing = text_buffer[self.text.clone()].to_string();
+            let start = text_buffer.len();
+            text_buffer.push_str(&existing);
+            self.text = start..text_buffer.len();
+        }
+        let start = self.text.len();
+        text_buffer.push_str(text);
+        self.text.end = text_buffer.len();
+        let byte_range = start..self.text.len();
+        self.spans.push(text::rich::Span { byte_range, style });
+        self
+    }
+
+    /// The fonts referred to by the `font::Id` of each span's style.
+    pub fn fonts(mut self, fonts: &text::font::Map) -> Self {
+        self.fonts = fonts.clone();
+        self
+    }
+
+    /// Draw the glyphs via signed distance fields rather than the glyph cache.
+    pub fn sdf(mut self) -> Self {
+        self.sdf.get_or_insert_with(Default::default);
+        self
+    }
+
+    /// Draw an outline of the given width around each glyph.
+    ///
+    /// Implies drawing via signed distance fields.
+    pub fn outline(mut self, width: Scalar, color: LinSrgba) -> Self {
+        self.sdf.get_or_insert_with(Default::default).outline = Some((width, color));
+        self
+    }
+
+    /// Draw a glow of the given radius around each glyph and its outline.
+    ///
+    /// Implies drawing via signed distance fields.
+    pub fn glow(mut self, radius: Scalar, color: LinSrgba) -> Self {
+        self.sdf.get_or_insert_with(Default::default).glow = Some((radius, color));
+        self
+    }
+
     // Apply the given function to the inner text layout.
     fn map_layout<F>(mut self, map: F) -> Self
     where
@@ -94,6 +172,11 @@ impl<S> Text<S> {
         self.map_layout(|l| l.font(font))
     }
 
+    /// Append a font to the chain of fonts used for characters that the `font` has no glyph for.
+    pub fn fallback_font(self, font: Font) -> Self {
+        self.map_layout(|l| l.fallback_font(font))
+    }
+
     /// Describe the end along the *x* axis to which the text should be aligned.
     pub fn justify(self, justify: Justify) -> Self {
         self.map_layout(|l| l.justify(justify))
@@ -182,11 +265,81 @@ where
         self.map_ty(|ty| ty.font(font))
     }
 
+    /// Append a font to the chain of fonts used for characters that the `font` has no glyph for.
+    ///
+    /// Each grapheme cluster is displayed with the first font in the chain that supports it.
+    pub fn fallback_font(self, font: text::Font) -> Self {
+        self.map_ty(|ty| ty.fallback_font(font))
+    }
+
+    /// Append a span of text displayed with the given style.
+    ///
+    /// Spans may vary in font, font size, color and decoration, and are wrapped and justified
+    /// together as a single block of text, e.g.
+    ///
+    /// ```no_run
+    /// # use nannou::prelude::*;
+    /// # use nannou::text::rich::Style;
+    /// # let draw: nannou::Draw = nannou::Draw::new();
+    /// draw.text("Some ")
+    ///     .span("bold", Style::new().font_size(24).underline())
+    ///     .span(" text", Style::new().color(RED));
+    /// ```
+    pub fn span(self, text: &str, style: text::rich::Style) -> Self {
+        self.map_ty_with_context(|ty, ctxt| ty.span(ctxt.text_buffer, text, style))
+    }
+
+    /// The fonts referred to by the `font::Id` of each span's style.
+    pub fn fonts(self, fonts: &text::font::Map) -> Self {
+        self.map_ty(|ty| ty.fonts(fonts))
+    }
+
     /// Build the **Text** with the given **Style**.
     pub fn with_style(self, style: Style) -> Self {
         self.map_ty(|ty| ty.with_style(style))
     }
 
+    /// Draw the glyphs via signed distance fields rather than the glyph cache.
+    ///
+    /// Glyphs remain crisp at any size and under any scaling transform, at the cost of the
+    /// hinting and fine details that the glyph cache's rasteriser preserves at small sizes.
+    pub fn sdf(self) -> Self {
+        self.map_ty(|ty| ty.sdf())
+    }
+
+    /// Draw an outline of the given width around each glyph, e.g.
+    ///
+    /// ```no_run
+    /// # use nannou::prelude::*;
+    /// # let draw: nannou::Draw = nannou::Draw::new();
+    /// draw.text("Outlined")
+    ///     .font_size(96)
+    ///     .color(WHITE)
+    ///     .outline(3.0, BLACK)
+    ///     .glow(8.0, rgba(1.0, 0.8, 0.0, 0.6));
+    /// ```
+    ///
+    /// Implies drawing via signed distance fields. The width of the outline and the radius of any
+    /// glow are limited by the spread of the fields, roughly a sixth of the font size.
+    pub fn outline<C>(self, width: text::Scalar, color: C) -> Self
+    where
+        C: IntoLinSrgba<ColorScalar>,
+    {
+        let color = color.into_lin_srgba();
+        self.map_ty(|ty| ty.outline(width, color))
+    }
+
+    /// Draw a glow of the given radius around each glyph and its outline.
+    ///
+    /// Implies drawing via signed distance fields.
+    pub fn glow<C>(self, radius: text::Scalar, color: C) -> Self
+    where
+        C: IntoLinSrgba<ColorScalar>,
+    {
+        let color = color.into_lin_srgba();
+        self.map_ty(|ty| ty.glow(radius, color))
+    }
+
     /// Describe the end along the *x* axis to which the text should be aligned.
     pub fn justify(self, justify: text::Justify) -> Self {
         self.map_ty(|ty| ty.justify(justify))
@@ -250,6 +403,9 @@ impl draw::renderer::RenderPrimitive for Text<f32> {
             spatial,
             style,
             text,
+            spans,
+            fonts,
+            sdf,
         } = self;
         let Style { color, layout } = style;
         let layout = layout.build();
@@ -271,54 +427,58 @@ impl draw::renderer::RenderPrimitive for Text<f32> {
         let rect: geom::Rect = geom::Rect::from_wh(Vector2 { x: w, y: h });
         let color = color.unwrap_or_else(|| ctxt.theme.fill_lin_srgba(&theme::Primitive::Text));
 
+        // Collect the fonts used by the text, along with each positioned glyph's index into them,
+        // its color and the rect and color of any decorations.
         let text_str = &ctxt.text_buffer[text.clone()];
-        let text = text::text(text_str).layout(&layout).build(rect);
-
-        // Queue the glyphs to be cached
-        let font_id = text::font::id(text.font());
-        let positioned_glyphs: Vec<_> = text
-            .rt_glyphs(
-                ctxt.output_attachment_size,
-                ctxt.output_attachment_scale_factor,
-            )
-            .collect();
-        for glyph in positioned_glyphs.iter() {
-            ctxt.glyph_cache.queue_glyph(font_id.index(), glyph.clone());
+        let (text_fonts, positioned_glyphs, decorations): (Vec<_>, Vec<_>, Vec<_>) =
+            if spans.is_empty() {
+                let text = text::text(text_str).layout(&layout).build(rect);
+                let glyphs = text
+                    .rt_glyphs_with_fonts(
+                        ctxt.output_attachment_size,
+                        ctxt.output_attachment_scale_factor,
+                    )
+                    .map(|(font, g)| (font, color, g))
+                    .collect();
+                (text.fonts().to_vec(), glyphs, vec![])
+            } else {
+                let text = text::rich::Builder::with_spans(text_str, spans)
+                    .layout(&layout)
+                    .build(rect, &fonts);
+                let span_color = |span: usize| text.spans()[span].style.color.unwrap_or(color);
+                // The fonts of all spans are flattened into a single list.
+                let mut text_fonts = vec![];
+                let mut span_offsets = vec![];
+                for span in 0..text.spans().len() {
+                    span_offsets.push(text_fonts.len());
+                    text_fonts.extend(text.span_fonts(span).iter().cloned());
+                }
+                let glyphs = text
+                    .rt_glyphs(
+                        ctxt.output_attachment_size,
+                        ctxt.output_attachment_scale_factor,
+                    )
+                    .map(|(span, font, g)| (span_offsets[span] + font, span_color(span), g))
+                    .collect();
+                let decorations = text
+                    .decorations()
+                    .map(|(span, _, rect)| (span_color(span), rect))
+                    .collect();
+                (text_fonts, glyphs, decorations)
+            };
+        let font_ids: Vec<_> = text_fonts.iter().map(text::font::id).collect();
+
+        // Queue the glyphs to be cached, unless they are drawn via signed distance fields.
+        if sdf.is_none() {
+            for &(font, _, ref glyph) in positioned_glyphs.iter() {
+                ctxt.glyph_cache
+                    .queue_glyph(font_ids[font].index(), glyph.clone());
+            }
         }
 
         // Cache the enqueued glyphs within the pixel buffer.
-        let (glyph_cache_w, _) = ctxt.glyph_cache.dimensions();
-        {
-            let draw::renderer::RenderContext {
-                glyph_cache:
-                    &mut draw::renderer::GlyphCache {
-                        ref mut cache,
-                        ref mut pixel_buffer,
-                        ref mut requires_upload,
-                        ..
-                    },
-                ..
-            } = ctxt;
-            let glyph_cache_w = glyph_cache_w as usize;
-            let res = cache.cache_queued(|rect, data| {
-                let width = (rect.max.x - rect.min.x) as usize;
-                let height = (rect.max.y - rect.min.y) as usize;
-                let mut dst_ix = rect.min.y as usize * glyph_cache_w + rect.min.x as usize;
-                let mut src_ix = 0;
-                for _ in 0..height {
-                    let dst_range = dst_ix..dst_ix + width;
-                    let src_range = src_ix..src_ix + width;
-                    let dst_slice = &mut pixel_buffer[dst_range];
-                    let src_slice = &data[src_range];
-                    dst_slice.copy_from_slice(src_slice);
-                    dst_ix += glyph_cache_w;
-                    src_ix += width;
-                }
-                *requires_upload = true;
-            });
-            if let Err(err) = res {
-                eprintln!("failed to cache queued glyphs: {}", err);
-            }
+        if sdf.is_none() {
+            cache_queued_glyphs(ctxt.glyph_cache);
         }
 
         // Determine the transform to apply to all points.
@@ -338,52 +498,247 @@ impl draw::renderer::RenderPrimitive for Text<f32> {
             geom::Rect::from_corners(geom::pt2(l, b), geom::pt2(r, t))
         };
 
-        // Extend the mesh with a rect for each displayed glyph.
-        for g in positioned_glyphs {
-            if let Ok(Some((uv_rect, screen_rect))) = ctxt.glyph_cache.rect_for(font_id.index(), &g)
-            {
-                let rect = to_nannou_rect(screen_rect);
-
-                // Create a mesh-compatible vertex from the position and tex_coords.
-                let v = |position, tex_coords: [f32; 2]| -> draw::mesh::Vertex {
-                    let p = geom::Point3::from(position);
-                    let p = cgmath::Transform::transform_point(&transform, p.into());
-                    let point = draw::mesh::vertex::Point::from(p);
-                    draw::mesh::vertex::new(point, color, tex_coords.into())
+        // Create a mesh-compatible vertex from the position, color and tex_coords.
+        let v = |position, color: LinSrgba, tex_coords: [f32; 2]| -> draw::mesh::Vertex {
+            let p = geom::Point3::from(position);
+            let p = cgmath::Transform::transform_point(&transform, p.into());
+            let point = draw::mesh::vertex::Point::from(p);
+            draw::mesh::vertex::new(point, color, tex_coords.into())
+        };
+
+        // Extend the mesh with a quad for the given rect and UV rect.
+        let mut push_quad = |rect: geom::Rect, color: LinSrgba, uv_rect: text::rt::Rect<f32>| {
+            // The sides of the UV rect.
+            let uv_l = uv_rect.min.x;
+            let uv_t = uv_rect.min.y;
+            let uv_r = uv_rect.max.x;
+            let uv_b = uv_rect.max.y;
+
+            // Insert the vertices.
+            let bottom_left = v(rect.bottom_left(), color, [uv_l, uv_b]);
+            let bottom_right = v(rect.bottom_right(), color, [uv_r, uv_b]);
+            let top_left = v(rect.top_left(), color, [uv_l, uv_t]);
+            let top_right = v(rect.top_right(), color, [uv_r, uv_t]);
+            let start_ix = mesh.points().len() as u32;
+            mesh.push_vertex(top_left);
+            mesh.push_vertex(bottom_left);
+            mesh.push_vertex(bottom_right);
+            mesh.push_vertex(top_right);
+
+            // Now the indices.
+            let tl_ix = start_ix;
+            let bl_ix = start_ix + 1;
+            let br_ix = start_ix + 2;
+            let tr_ix = start_ix + 3;
+            mesh.push_index(tl_ix);
+            mesh.push_index(bl_ix);
+            mesh.push_index(br_ix);
+            mesh.push_index(tl_ix);
+            mesh.push_index(br_ix);
+            mesh.push_index(tr_ix);
+        };
+
+        // Decorations are submitted first so that they may be coloured without the glyph cache.
+        let no_uv = text::rt::Rect {
+            min: text::rt::point(0.0, 0.0),
+            max: text::rt::point(0.0, 0.0),
+        };
+        for &(color, rect) in &decorations {
+            push_quad(rect, color, no_uv);
+        }
+        let mut vertex_mode_runs = vec![(decorations.len() * 4, VertexMode::Color)];
+
+        let sdf = match sdf {
+            Some(sdf) => sdf,
+            None => {
+                // Extend the mesh with a rect for each displayed glyph.
+                for (font, color, g) in positioned_glyphs {
+                    let glyph_cache = &ctxt.glyph_cache;
+                    if let Ok(Some((uv_rect, screen_rect))) =
+                        glyph_cache.rect_for(font_ids[font].index(), &g)
+                    {
+                        push_quad(to_nannou_rect(screen_rect), color, uv_rect);
+                    }
+                }
+                return draw::renderer::PrimitiveRender {
+                    vertex_mode_runs,
+                    ..draw::renderer::PrimitiveRender::text()
                 };
+            }
+        };
+
+        // Find the field of each glyph, along with the rect of its quad and the scale from atlas
+        // pixels to points.
+        let atlas = &mut ctxt.glyph_cache.sdf_atlas;
+        let entries = sdf_atlas_entries(
+            atlas,
+            positioned_glyphs
+                .iter()
+                .map(|&(font, _, ref g)| (&text_fonts[font], g)),
+        );
+        let (atlas_size, spread) = (atlas.size(), atlas.spread());
+        let glyphs: Vec<_> = positioned_glyphs
+            .iter()
+            .zip(entries)
+            .filter_map(|(&(_, color, ref g), entry)| {
+                let entry = entry?;
+                let k = g.scale().y / (scale_factor * atlas_size);
+                let p = g.position();
+                let x = p.x / scale_factor - half_out_w;
+                let y = -(p.y / scale_factor - half_out_h);
+                let b = entry.bounds;
+                let rect = geom::Rect::from_corners(
+                    geom::pt2(x + b.min.x * k, y - b.max.y * k),
+                    geom::pt2(x + b.max.x * k, y - b.min.y * k),
+                );
+                Some((rect, color, entry.uv_rect, k))
+            })
+            .collect();
+
+        // Distances in points are converted to the range of field values, in which the outline of
+        // the glyph lies at `SDF_OUTLINE`.
+        let outline_edge = VertexMode::SDF_OUTLINE as f32 / 255.0;
+        let offset = |points: Scalar, k: f32| points / (k * 2.0 * spread);
+        let edge = |k: f32| match sdf.outline {
+            Some((width, _)) => outline_edge - offset(width, k),
+            None => outline_edge,
+        };
+        let to_u8 = |value: f32| (value * 255.0).round().max(0.0).min(255.0) as u8;
+        let mut push_run = |mode: VertexMode| match vertex_mode_runs.last_mut() {
+            Some(run) if run.1 == mode => run.0 += 4,
+            _ => vertex_mode_runs.push((4, mode)),
+        };
 
-                // The sides of the UV rect.
-                let uv_l = uv_rect.min.x;
-                let uv_t = uv_rect.min.y;
-                let uv_r = uv_rect.max.x;
-                let uv_b = uv_rect.max.y;
-
-                // Insert the vertices.
-                let bottom_left = v(rect.bottom_left(), [uv_l, uv_b]);
-                let bottom_right = v(rect.bottom_right(), [uv_r, uv_b]);
-                let top_left = v(rect.top_left(), [uv_l, uv_t]);
-                let top_right = v(rect.top_right(), [uv_r, uv_t]);
-                let start_ix = mesh.points().len() as u32;
-                mesh.push_vertex(top_left);
-                mesh.push_vertex(bottom_left);
-                mesh.push_vertex(bottom_right);
-                mesh.push_vertex(top_right);
-
-                // Now the indices.
-                let tl_ix = start_ix;
-                let bl_ix = start_ix + 1;
-                let br_ix = start_ix + 2;
-                let tr_ix = start_ix + 3;
-                mesh.push_index(tl_ix);
-                mesh.push_index(bl_ix);
-                mesh.push_index(br_ix);
-                mesh.push_index(tl_ix);
-                mesh.push_index(br_ix);
-                mesh.push_index(tr_ix);
+        // Submit the glow and outline of every glyph before the glyphs themselves.
+        if let Some((radius, glow_color)) = sdf.glow {
+            for &(rect, _, uv_rect, k) in &glyphs {
+                let half_radius = offset(radius, k) / 2.0;
+                push_quad(rect, glow_color, uv_rect);
+                push_run(VertexMode::Sdf {
+                    edge: to_u8(edge(k) - half_radius),
+                    softness: to_u8(half_radius),
+                });
+            }
+        }
+        if let Some((_, outline_color)) = sdf.outline {
+            for &(rect, _, uv_rect, k) in &glyphs {
+                push_quad(rect, outline_color, uv_rect);
+                push_run(VertexMode::Sdf {
+                    edge: to_u8(edge(k)),
+                    softness: 0,
+                });
             }
         }
+        for &(rect, color, uv_rect, _) in &glyphs {
+            push_quad(rect, color, uv_rect);
+        }
 
-        draw::renderer::PrimitiveRender::text()
+        draw::renderer::PrimitiveRender {
+            vertex_mode_runs,
+            ..draw::renderer::PrimitiveRender::sdf_text()
+        }
+    }
+}
+
+/// Cache the glyphs queued within the glyph cache, writing them to its pixel buffer.
+pub(crate) fn cache_queued_glyphs(glyph_cache: &mut draw::renderer::GlyphCache) {
+    let (glyph_cache_w, _) = glyph_cache.dimensions();
+    let draw::renderer::GlyphCache {
+        ref mut cache,
+        ref mut pixel_buffer,
+        ref mut requires_upload,
+        ..
+    } = *glyph_cache;
+    let glyph_cache_w = glyph_cache_w as usize;
+    let res = cache.cache_queued(|rect, data| {
+        let width = (rect.max.x - rect.min.x) as usize;
+        let height = (rect.max.y - rect.min.y) as usize;
+        let mut dst_ix = rect.min.y as usize * glyph_cache_w + rect.min.x as usize;
+        let mut src_ix = 0;
+        for _ in 0..height {
+            let dst_range = dst_ix..dst_ix + width;
+            let src_range = src_ix..src_ix + width;
+            let dst_slice = &mut pixel_buffer[dst_range];
+            let src_slice = &data[src_range];
+            dst_slice.copy_from_slice(src_slice);
+            dst_ix += glyph_cache_w;
+            src_ix += width;
+        }
+        *requires_upload = true;
+    });
+    if let Err(err) = res {
+        eprintln!("failed to cache queued glyphs: {}", err);
+    }
+}
+
+// Look up the signed distance field of each glyph within the atlas.
+//
+// Glyphs whose fields do not fit within the atlas are skipped. The atlas is not cleared here, as
+// primitives submitted earlier within the frame may already refer to its entries. Instead, the
+// atlas is cleared at the beginning of the following frame.
+fn sdf_atlas_entries<'a, I>(
+    atlas: &mut text::sdf::Atlas,
+    glyphs: I,
+) -> Vec<Option<text::sdf::Entry>>
+where
+    I: Iterator<Item = (&'a text::Font, &'a text::PositionedGlyph)>,
+{
+    glyphs
+        .map(|(font, g)| atlas.glyph(font, g.id()).unwrap_or(None))
+        .collect()
+}
+
+impl draw::svg::SvgPrimitive for Text<f32> {
+    fn render_svg(self, ctxt: draw::svg::RenderContext, doc: &mut draw::svg::Document) {
+        let Text {
+            spatial,
+            style,
+            text,
+            spans,
+            fonts,
+            ..
+        } = self;
+        let Style { color, layout } = style;
+        let layout = layout.build();
+        let w = spatial.dimensions.x.unwrap_or(200.0);
+        let h = spatial.dimensions.y.unwrap_or(200.0);
+        let rect: geom::Rect = geom::Rect::from_wh(Vector2 { x: w, y: h });
+        let color = color.unwrap_or_else(|| ctxt.theme.fill_lin_srgba(&theme::Primitive::Text));
+
+        // Determine the transform to apply to all points.
+        let global_transform = *ctxt.transform;
+        let local_transform = spatial.position.transform() * spatial.orientation.transform();
+        let transform = global_transform * local_transform;
+
+        let text_str = &ctxt.text_buffer[text.clone()];
+        if spans.is_empty() {
+            let text = text::text(text_str).layout(&layout).build(rect);
+
+            // Write the glyph outlines as a single filled path.
+            let style = draw::svg::Style::fill(color);
+            doc.path(&transform, text.path_events(), &style);
+            return;
+        }
+
+        // Write the glyph outlines of each span as a filled path, followed by its decorations.
+        let text = text::rich::Builder::with_spans(text_str, spans)
+            .layout(&layout)
+            .build(rect, &fonts);
+        let span_color = |span: usize| text.spans()[span].style.color.unwrap_or(color);
+        let mut span_events = vec![vec![]; text.spans().len()];
+        for (span, events) in text.path_events() {
+            span_events[span].extend(events);
+        }
+        for (span, events) in span_events.into_iter().enumerate() {
+            if !events.is_empty() {
+                let style = draw::svg::Style::fill(span_color(span));
+                doc.path(&transform, events, &style);
+            }
+        }
+        for (span, _, rect) in text.decorations() {
+            let style = draw::svg::Style::fill(span_color(span));
+            doc.rect(&transform, rect, &style);
+        }
     }
 }
 
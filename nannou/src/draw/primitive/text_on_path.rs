@@ -0,0 +1,442 @@
+use crate::draw::drawing::DrawingContext;
+use crate::draw::primitive::path::{self, render_path_events};
+use crate::draw::primitive::text::cache_queued_glyphs;
+use crate::draw::primitive::Primitive;
+use crate::draw::properties::spatial::{orientation, position};
+use crate::draw::properties::{ColorScalar, LinSrgba, SetColor, SetOrientation, SetPosition};
+use crate::draw::{self, theme, Drawing};
+use crate::geom::{self, path::measure};
+use crate::math::{BaseFloat, Zero};
+use crate::serde_derive::{Deserialize, Serialize};
+use crate::text::{self, on_path, Font, FontSize, Justify, Scalar};
+use lyon::path::PathEvent;
+use lyon::tessellation::FillOptions;
+
+/// Properties related to drawing the **TextOnPath** primitive.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TextOnPath<S = geom::scalar::Default> {
+    position: position::Properties<S>,
+    orientation: orientation::Properties<S>,
+    pub(crate) style: Style,
+    // The byte range into the `Draw` context's text buffer.
+    text: std::ops::Range<usize>,
+    // The range of the path's events within the `Draw` context's path event buffer.
+    path: std::ops::Range<usize>,
+    // Whether glyphs are drawn by tessellating their outlines rather than via the glyph cache.
+    outlines: bool,
+}
+
+/// Styling properties for the **TextOnPath** primitive.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Style {
+    pub color: Option<LinSrgba>,
+    /// Only the font size and fonts of the layout are used. The text is always laid out as a
+    /// single line, aligned along the path according to the `options`.
+    pub layout: text::layout::Builder,
+    pub options: on_path::Options,
+}
+
+// The text shaped at `scale` with the chain of `fonts`, and its glyphs placed along the path.
+struct Layout {
+    fonts: Vec<Font>,
+    line: text::shape::Line,
+    scale: text::Scale,
+    placements: Vec<on_path::Placement>,
+}
+
+/// The drawing context for the **TextOnPath** primitive.
+pub type DrawingTextOnPath<'a, S = geom::scalar::Default> = Drawing<'a, TextOnPath<S>, S>;
+
+impl<S> TextOnPath<S> {
+    /// Begin drawing some text along the given path.
+    ///
+    /// Newlines within the text are displayed as spaces.
+    pub fn new(ctxt: DrawingContext<S>, text: &str, path: &geom::Path) -> Self
+    where
+        S: Zero,
+    {
+        let start = ctxt.text_buffer.len();
+        ctxt.text_buffer
+            .extend(text.chars().map(|c| if c == '\n' { ' ' } else { c }));
+        let end = ctxt.text_buffer.len();
+        let text = start..end;
+        let start = ctxt.path_event_buffer.len();
+        ctxt.path_event_buffer.extend(path.iter());
+        let end = ctxt.path_event_buffer.len();
+        let path = start..end;
+        TextOnPath {
+            position: Default::default(),
+            orientation: Default::default(),
+            style: Default::default(),
+            text,
+            path,
+            outlines: false,
+        }
+    }
+
+    // Apply the given function to the inner text layout.
+    fn map_layout<F>(mut self, map: F) -> Self
+    where
+        F: FnOnce(text::layout::Builder) -> text::layout::Builder,
+    {
+        self.style.layout = map(self.style.layout);
+        self
+    }
+
+    /// The font size to use for the text.
+    pub fn font_size(self, size: FontSize) -> Self {
+        self.map_layout(|l| l.font_size(size))
+    }
+
+    /// A method for specifying the `Font` used for displaying the text.
+    pub fn font(self, font: Font) -> Self {
+        self.map_layout(|l| l.font(font))
+    }
+
+    /// Append a font to the chain of fonts used for characters that the `font` has no glyph for.
+    pub fn fallback_font(self, font: Font) -> Self {
+        self.map_layout(|l| l.fallback_font(font))
+    }
+
+    /// Describe the end of the path to which the text should be aligned.
+    pub fn justify(mut self, justify: Justify) -> Self {
+        self.style.options.justify = justify;
+        self
+    }
+
+    /// Align the text to the start of the path.
+    ///
+    /// This is the default behaviour.
+    pub fn left_justify(self) -> Self {
+        self.justify(Justify::Left)
+    }
+
+    /// Align the middle of the text with the middle of the path.
+    pub fn center_justify(self) -> Self {
+        self.justify(Justify::Center)
+    }
+
+    /// Align the text to the end of the path.
+    pub fn right_justify(self) -> Self {
+        self.justify(Justify::Right)
+    }
+
+    /// Shift the aligned text along the path by the given distance.
+    pub fn offset(mut self, distance: Scalar) -> Self {
+        self.style.options.offset = distance;
+        self
+    }
+
+    /// Insert additional space between each cluster of glyphs.
+    pub fn spacing(mut self, spacing: Scalar) -> Self {
+        self.style.options.spacing = spacing;
+        self
+    }
+
+    /// Whether or not glyphs are rotated to follow the tangent of the path.
+    ///
+    /// By default, glyphs are rotated.
+    pub fn rotate_glyphs(mut self, rotate: bool) -> Self {
+        self.style.options.rotate = rotate;
+        self
+    }
+
+    /// Whether or not glyphs that do not fit entirely along the path are omitted.
+    ///
+    /// By default, glyphs beyond either end of the path continue along its tangent.
+    pub fn clip(mut self, clip: bool) -> Self {
+        self.style.options.clip = clip;
+        self
+    }
+
+    /// Draw the glyphs by tessellating their filled outlines rather than via the glyph cache.
+    pub fn outlines(mut self) -> Self {
+        self.outlines = true;
+        self
+    }
+
+    /// Specify the entire styling for the **TextOnPath**.
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    // Shape the text and place each of its glyphs along the path.
+    fn layout(&self, text_buffer: &str, path_event_buffer: &[PathEvent]) -> Layout {
+        let layout = self.style.layout.clone().build();
+        let font = layout.font.unwrap_or_else(text::default_font);
+        let fonts: Vec<_> = std::iter::once(font).chain(layout.fallback_fonts).collect();
+        let scale = text::pt_to_scale(layout.font_size);
+        let text_str = &text_buffer[self.text.clone()];
+        let line = text::shape::line_with_fallbacks(text_str, &fonts, scale);
+        let events = path_event_buffer[self.path.clone()].iter().cloned();
+        let measure = measure::Measure::new(events, measure::DEFAULT_TOLERANCE);
+        let placements = on_path::place(line.glyphs(), &measure, &self.style.options);
+        Layout {
+            fonts,
+            line,
+            scale,
+            placements,
+        }
+    }
+}
+
+impl<'a, S> DrawingTextOnPath<'a, S>
+where
+    S: BaseFloat,
+{
+    /// The font size to use for the text.
+    pub fn font_size(self, size: text::FontSize) -> Self {
+        self.map_ty(|ty| ty.font_size(size))
+    }
+
+    /// A method for specifying the `Font` used for displaying the text.
+    pub fn font(self, font: text::Font) -> Self {
+        self.map_ty(|ty| ty.font(font))
+    }
+
+    /// Append a font to the chain of fonts used for characters that the `font` has no glyph for.
+    pub fn fallback_font(self, font: text::Font) -> Self {
+        self.map_ty(|ty| ty.fallback_font(font))
+    }
+
+    /// Describe the end of the path to which the text should be aligned.
+    pub fn justify(self, justify: text::Justify) -> Self {
+        self.map_ty(|ty| ty.justify(justify))
+    }
+
+    /// Align the text to the start of the path.
+    ///
+    /// This is the default behaviour.
+    pub fn left_justify(self) -> Self {
+        self.map_ty(|ty| ty.left_justify())
+    }
+
+    /// Align the middle of the text with the middle of the path.
+    pub fn center_justify(self) -> Self {
+        self.map_ty(|ty| ty.center_justify())
+    }
+
+    /// Align the text to the end of the path.
+    pub fn right_justify(self) -> Self {
+        self.map_ty(|ty| ty.right_justify())
+    }
+
+    /// Shift the aligned text along the path by the given distance.
+    pub fn offset(self, distance: text::Scalar) -> Self {
+        self.map_ty(|ty| ty.offset(distance))
+    }
+
+    /// Insert additional space between each cluster of glyphs, e.g.
+    ///
+    /// ```no_run
+    /// # use nannou::prelude::*;
+    /// # let draw: nannou::Draw = nannou::Draw::new();
+    /// let path = geom::path()
+    ///     .move_to(pt2(-200.0, 0.0))
+    ///     .quadratic_bezier_to(pt2(0.0, 200.0), pt2(200.0, 0.0))
+    ///     .build();
+    /// draw.text_on_path("over the hill", &path)
+    ///     .font_size(32)
+    ///     .center_justify()
+    ///     .spacing(4.0)
+    ///     .color(BLACK);
+    /// ```
+    pub fn spacing(self, spacing: text::Scalar) -> Self {
+        self.map_ty(|ty| ty.spacing(spacing))
+    }
+
+    /// Whether or not glyphs are rotated to follow the tangent of the path.
+    ///
+    /// By default, glyphs are rotated.
+    pub fn rotate_glyphs(self, rotate: bool) -> Self {
+        self.map_ty(|ty| ty.rotate_glyphs(rotate))
+    }
+
+    /// Whether or not glyphs that do not fit entirely along the path are omitted.
+    ///
+    /// By default, glyphs beyond either end of the path continue along its tangent.
+    pub fn clip(self, clip: bool) -> Self {
+        self.map_ty(|ty| ty.clip(clip))
+    }
+
+    /// Draw the glyphs by tessellating their filled outlines rather than via the glyph cache.
+    ///
+    /// Outlines remain smooth under any scaling transform, at the cost of the hinting and
+    /// anti-aliasing that the glyph cache's rasteriser provides.
+    pub fn outlines(self) -> Self {
+        self.map_ty(|ty| ty.outlines())
+    }
+
+    /// Build the **TextOnPath** with the given **Style**.
+    pub fn with_style(self, style: Style) -> Self {
+        self.map_ty(|ty| ty.with_style(style))
+    }
+}
+
+impl draw::renderer::RenderPrimitive for TextOnPath<f32> {
+    fn render_primitive(
+        self,
+        ctxt: draw::renderer::RenderContext,
+        mesh: &mut draw::Mesh,
+    ) -> draw::renderer::PrimitiveRender {
+        let Layout {
+            fonts,
+            line,
+            scale,
+            placements,
+        } = self.layout(ctxt.text_buffer, ctxt.path_event_buffer);
+        let glyphs = line.glyphs();
+        let color = self
+            .style
+            .color
+            .unwrap_or_else(|| ctxt.theme.fill_lin_srgba(&theme::Primitive::Text));
+
+        // Determine the transform to apply to all points.
+        let global_transform = ctxt.transform;
+        let local_transform = self.position.transform() * self.orientation.transform();
+        let transform = global_transform * local_transform;
+
+        if self.outlines {
+            let events: Vec<_> = on_path::path_events(glyphs, &fonts, scale, &placements).collect();
+            render_path_events(
+                events,
+                Some(color),
+                None,
+                None,
+                transform,
+                path::Options::Fill(FillOptions::default()),
+                ctxt.theme,
+                &theme::Primitive::Text,
+                ctxt.fill_tessellator,
+                ctxt.stroke_tessellator,
+                mesh,
+            );
+            return draw::renderer::PrimitiveRender::color();
+        }
+
+        // Position each glyph upright at its origin within rusttype's pixel coordinates.
+        let scale_factor = ctxt.output_attachment_scale_factor;
+        let (out_w, out_h) = ctxt.output_attachment_size.into();
+        let [half_out_w, half_out_h] = [out_w / 2.0, out_h / 2.0];
+        let scale = text::Scale::uniform(scale.y * scale_factor);
+        let font_ids: Vec<_> = fonts.iter().map(text::font::id).collect();
+        let positioned_glyphs: Vec<_> = placements
+            .iter()
+            .map(|placement| {
+                let g = &glyphs[placement.index];
+                let o = placement.origin;
+                let point = text::rt::point(
+                    (o.x + half_out_w) * scale_factor,
+                    (half_out_h - o.y) * scale_factor,
+                );
+                let glyph = fonts[g.font].glyph(g.id).scaled(scale).positioned(point);
+                (font_ids[g.font], placement, glyph)
+            })
+            .collect();
+
+        // Cache the glyphs within the pixel buffer.
+        for &(font_id, _, ref glyph) in &positioned_glyphs {
+            ctxt.glyph_cache.queue_glyph(font_id.index(), glyph.clone());
+        }
+        cache_queued_glyphs(ctxt.glyph_cache);
+
+        // Create a mesh-compatible vertex from the position and tex_coords.
+        let v = |position: geom::Point2, tex_coords: [f32; 2]| -> draw::mesh::Vertex {
+            let p = geom::Point3::from(position);
+            let p = cgmath::Transform::transform_point(&transform, p.into());
+            let point = draw::mesh::vertex::Point::from(p);
+            draw::mesh::vertex::new(point, color, tex_coords.into())
+        };
+
+        // Extend the mesh with a quad for each glyph, rotated about the glyph's origin.
+        for (font_id, placement, glyph) in positioned_glyphs {
+            let (uv_rect, screen_rect) = match ctxt.glyph_cache.rect_for(font_id.index(), &glyph) {
+                Ok(Some(rects)) => rects,
+                _ => continue,
+            };
+            let corner = |x: i32, y: i32| {
+                let p = geom::pt2(
+                    x as f32 / scale_factor - half_out_w,
+                    half_out_h - y as f32 / scale_factor,
+                );
+                placement.transform_point(p - placement.origin)
+            };
+            let (min, max) = (screen_rect.min, screen_rect.max);
+            let top_left = v(corner(min.x, min.y), [uv_rect.min.x, uv_rect.min.y]);
+            let bottom_left = v(corner(min.x, max.y), [uv_rect.min.x, uv_rect.max.y]);
+            let bottom_right = v(corner(max.x, max.y), [uv_rect.max.x, uv_rect.max.y]);
+            let top_right = v(corner(max.x, min.y), [uv_rect.max.x, uv_rect.min.y]);
+            let start_ix = mesh.points().len() as u32;
+            mesh.push_vertex(top_left);
+            mesh.push_vertex(bottom_left);
+            mesh.push_vertex(bottom_right);
+            mesh.push_vertex(top_right);
+            for &ix in &[0, 1, 2, 0, 2, 3] {
+                mesh.push_index(start_ix + ix);
+            }
+        }
+
+        draw::renderer::PrimitiveRender::text()
+    }
+}
+
+impl draw::svg::SvgPrimitive for TextOnPath<f32> {
+    fn render_svg(self, ctxt: draw::svg::RenderContext, doc: &mut draw::svg::Document) {
+        let Layout {
+            fonts,
+            line,
+            scale,
+            placements,
+        } = self.layout(ctxt.text_buffer, ctxt.path_event_buffer);
+        let color = self
+            .style
+            .color
+            .unwrap_or_else(|| ctxt.theme.fill_lin_srgba(&theme::Primitive::Text));
+
+        // Determine the transform to apply to all points.
+        let global_transform = *ctxt.transform;
+        let local_transform = self.position.transform() * self.orientation.transform();
+        let transform = global_transform * local_transform;
+
+        // Write the glyph outlines as a single filled path.
+        let events = on_path::path_events(line.glyphs(), &fonts, scale, &placements);
+        let style = draw::svg::Style::fill(color);
+        doc.path(&transform, events, &style);
+    }
+}
+
+impl<S> SetOrientation<S> for TextOnPath<S> {
+    fn properties(&mut self) -> &mut orientation::Properties<S> {
+        SetOrientation::properties(&mut self.orientation)
+    }
+}
+
+impl<S> SetPosition<S> for TextOnPath<S> {
+    fn properties(&mut self) -> &mut position::Properties<S> {
+        SetPosition::properties(&mut self.position)
+    }
+}
+
+impl<S> SetColor<ColorScalar> for TextOnPath<S> {
+    fn rgba_mut(&mut self) -> &mut Option<LinSrgba> {
+        SetColor::rgba_mut(&mut self.style.color)
+    }
+}
+
+// Primitive conversions.
+
+impl<S> From<TextOnPath<S>> for Primitive<S> {
+    fn from(prim: TextOnPath<S>) -> Self {
+        Primitive::TextOnPath(prim)
+    }
+}
+
+impl<S> Into<Option<TextOnPath<S>>> for Primitive<S> {
+    fn into(self) -> Option<TextOnPath<S>> {
+        match self {
+            Primitive::TextOnPath(prim) => Some(prim),
+            _ => None,
+        }
+    }
+}
@@ -5,12 +5,15 @@ use crate::draw::properties::{SetDimensions, SetOrientation, SetPosition};
 use crate::draw::{self, Drawing};
 use crate::geom::{self, Vector2};
 use crate::math::BaseFloat;
+use crate::serde_derive::{Deserialize, Serialize};
 use crate::wgpu;
 
 /// Properties related to drawing a **Rect**.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Texture<S = geom::scalar::Default> {
-    texture_view: wgpu::TextureView,
+    // `None` if the texture could not be resolved when deserializing.
+    #[serde(skip)]
+    pub(crate) texture_view: Option<wgpu::TextureView>,
     spatial: spatial::Properties<S>,
     area: geom::Rect,
 }
@@ -40,7 +43,7 @@ where
         };
         let area = geom::Rect { x, y };
         Self {
-            texture_view,
+            texture_view: Some(texture_view),
             spatial,
             area,
         }
@@ -122,6 +125,7 @@ impl draw::renderer::RenderPrimitive for Texture<f32> {
         path::render_path_points_textured(
             points_textured,
             true,
+            None,
             transform,
             path::Options::Fill(Default::default()),
             &mut ctxt.fill_tessellator,
@@ -129,7 +133,13 @@ impl draw::renderer::RenderPrimitive for Texture<f32> {
             mesh,
         );
 
-        draw::renderer::PrimitiveRender::texture(texture_view)
+        match texture_view {
+            Some(texture_view) => draw::renderer::PrimitiveRender::texture(texture_view),
+            // Sampled from the renderer's default texture, as with textured meshes and paths.
+            None => {
+                draw::renderer::PrimitiveRender::vertex_mode(draw::renderer::VertexMode::Texture)
+            }
+        }
     }
 }
 
@@ -1,17 +1,20 @@
 use crate::color::conv::IntoLinSrgba;
 use crate::draw::primitive::polygon::{self, PolygonInit, PolygonOptions, SetPolygon};
 use crate::draw::primitive::Primitive;
+use crate::draw::properties::gradient::Gradient;
 use crate::draw::properties::spatial::{dimension, orientation, position};
 use crate::draw::properties::{
-    ColorScalar, LinSrgba, SetColor, SetDimensions, SetOrientation, SetPosition, SetStroke,
+    ColorScalar, LinSrgba, SetColor, SetDimensions, SetGradient, SetOrientation, SetPosition,
+    SetStroke,
 };
 use crate::draw::{self, Drawing};
 use crate::geom::{self, Point2, Vector2};
 use crate::math::{BaseFloat, ElementWise};
+use crate::serde_derive::{Deserialize, Serialize};
 use lyon::tessellation::StrokeOptions;
 
 /// Properties related to drawing a **Tri**.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Tri<S = geom::scalar::Default> {
     tri: geom::Tri<Point2<S>>,
     dimensions: dimension::Properties<S>,
@@ -70,18 +73,12 @@ where
 
 // Trait implementations.
 
-impl draw::renderer::RenderPrimitive for Tri<f32> {
-    fn render_primitive(
-        self,
-        ctxt: draw::renderer::RenderContext,
-        mesh: &mut draw::Mesh,
-    ) -> draw::renderer::PrimitiveRender {
-        let Tri {
-            mut tri,
-            dimensions,
-            polygon,
-        } = self;
-        let (maybe_x, maybe_y, _maybe_z) = (dimensions.x, dimensions.y, dimensions.z);
+impl Tri<f32> {
+    // The triangle with the specified dimensions (if any) applied.
+    fn dimensioned_tri(&self) -> geom::Tri<Point2> {
+        let mut tri = self.tri;
+        let (maybe_x, maybe_y, _maybe_z) =
+            (self.dimensions.x, self.dimensions.y, self.dimensions.z);
         // If dimensions were specified, scale the points to those dimensions.
         if maybe_x.is_some() || maybe_y.is_some() {
             let cuboid = tri.bounding_rect();
@@ -99,9 +96,20 @@ impl draw::renderer::RenderPrimitive for Tri<f32> {
             let new_c = translate(c);
             tri = geom::Tri([new_a, new_b, new_c]);
         }
+        tri
+    }
+}
+
+impl draw::renderer::RenderPrimitive for Tri<f32> {
+    fn render_primitive(
+        self,
+        ctxt: draw::renderer::RenderContext,
+        mesh: &mut draw::Mesh,
+    ) -> draw::renderer::PrimitiveRender {
+        let tri = self.dimensioned_tri();
         let points = tri.vertices();
         polygon::render_points_themed(
-            polygon.opts,
+            self.polygon.opts,
             points,
             ctxt,
             &draw::theme::Primitive::Tri,
@@ -112,6 +120,19 @@ impl draw::renderer::RenderPrimitive for Tri<f32> {
     }
 }
 
+impl draw::svg::SvgPrimitive for Tri<f32> {
+    fn render_svg(self, ctxt: draw::svg::RenderContext, doc: &mut draw::svg::Document) {
+        let tri = self.dimensioned_tri();
+        polygon::render_svg_points_themed(
+            self.polygon.opts,
+            tri.vertices(),
+            ctxt,
+            &draw::theme::Primitive::Tri,
+            doc,
+        );
+    }
+}
+
 impl<S> From<geom::Tri<Point2<S>>> for Tri<S>
 where
     S: BaseFloat,
@@ -173,6 +194,12 @@ impl<S> SetColor<ColorScalar> for Tri<S> {
     }
 }
 
+impl<S> SetGradient for Tri<S> {
+    fn gradient_mut(&mut self) -> &mut Option<Gradient> {
+        SetGradient::gradient_mut(&mut self.polygon)
+    }
+}
+
 impl<S> SetStroke for Tri<S> {
     fn stroke_options_mut(&mut self) -> &mut StrokeOptions {
         SetStroke::stroke_options_mut(&mut self.polygon)
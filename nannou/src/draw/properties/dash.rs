@@ -0,0 +1,314 @@
+//! Dash patterns that may be applied to stroked paths.
+
+use crate::geom::Point2;
+use crate::serde_derive::{Deserialize, Serialize};
+use lyon::tessellation::{LineCap, StrokeOptions};
+
+/// A pattern of alternating dashes and gaps applied along the length of a stroke.
+///
+/// Dashes are measured along the flattened path, beginning anew at the start of each sub-path as
+/// described by the SVG `stroke-dasharray` property.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Dash {
+    lengths: Vec<f32>,
+    offset: f32,
+    cap: Option<LineCap>,
+}
+
+/// Nodes that support dashed strokes.
+pub trait SetDash: Sized {
+    /// Provide a mutable reference to the dash field.
+    fn dash_mut(&mut self) -> &mut Option<Dash>;
+
+    /// Specify the whole dash pattern.
+    fn dash_pattern(mut self, dash: Dash) -> Self {
+        *self.dash_mut() = Some(dash);
+        self
+    }
+
+    /// Stroke with dashes of the given length separated by gaps of the given length.
+    fn dash(self, dash: f32, gap: f32) -> Self {
+        self.dashes(vec![dash, gap])
+    }
+
+    /// Stroke with alternating dash and gap lengths, as in the SVG `stroke-dasharray` property.
+    ///
+    /// A list with an odd number of lengths is repeated to produce an even number of lengths.
+    fn dashes<I>(mut self, lengths: I) -> Self
+    where
+        I: IntoIterator<Item = f32>,
+    {
+        let dash = self.dash_mut().get_or_insert_with(Default::default);
+        dash.lengths = lengths.into_iter().collect();
+        self
+    }
+
+    /// The distance into the dash pattern at which the stroke begins.
+    fn dash_offset(mut self, offset: f32) -> Self {
+        self.dash_mut().get_or_insert_with(Default::default).offset = offset;
+        self
+    }
+
+    /// The cap applied to the ends of each dash.
+    ///
+    /// By default, each dash uses the start and end caps of the stroke.
+    fn dash_cap(mut self, cap: LineCap) -> Self {
+        self.dash_mut().get_or_insert_with(Default::default).cap = Some(cap);
+        self
+    }
+
+    /// Stroke with round dots spaced the given distance apart.
+    ///
+    /// The diameter of each dot is the stroke weight.
+    fn dots(self, spacing: f32) -> Self {
+        self.dashes(vec![0.0, spacing]).dash_cap(LineCap::Round)
+    }
+
+    /// Stroke without dashes.
+    fn no_dash(mut self) -> Self {
+        *self.dash_mut() = None;
+        self
+    }
+}
+
+impl Dash {
+    /// A dash pattern with the given alternating dash and gap lengths.
+    pub fn new<I>(lengths: I) -> Self
+    where
+        I: IntoIterator<Item = f32>,
+    {
+        let lengths = lengths.into_iter().collect();
+        Dash {
+            lengths,
+            offset: 0.0,
+            cap: None,
+        }
+    }
+
+    /// The distance into the pattern at which the stroke begins.
+    pub fn offset(mut self, offset: f32) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// The cap applied to the ends of each dash.
+    pub fn cap(mut self, cap: LineCap) -> Self {
+        self.cap = Some(cap);
+        self
+    }
+
+    /// The alternating dash and gap lengths.
+    pub fn lengths(&self) -> &[f32] {
+        &self.lengths
+    }
+
+    /// The distance into the pattern at which the stroke begins.
+    pub fn dash_offset(&self) -> f32 {
+        self.offset
+    }
+
+    /// The cap applied to the ends of each dash, if one was specified.
+    pub fn dash_cap(&self) -> Option<LineCap> {
+        self.cap
+    }
+
+    /// Whether or not the pattern produces a solid stroke.
+    ///
+    /// This is the case for an empty pattern, a pattern with negative lengths or a pattern whose
+    /// lengths sum to zero.
+    pub fn is_solid(&self) -> bool {
+        self.pattern().is_none()
+    }
+
+    /// Split the polyline into dashes.
+    ///
+    /// The `lerp` function is used to interpolate the attributes of each point (e.g. colors or
+    /// texture coordinates) where a dash begins or ends part way along a segment. A dash of zero
+    /// length is produced as two identical points.
+    ///
+    /// If the pattern is solid, the whole polyline is returned as a single dash.
+    pub fn split<A, F>(&self, points: &[(Point2, A)], close: bool, lerp: F) -> Vec<Vec<(Point2, A)>>
+    where
+        A: Copy,
+        F: Fn(A, A, f32) -> A,
+    {
+        self.split_inner(points, close, lerp).dashes
+    }
+
+    /// Split the polyline into dashes, each paired with the options with which it should be
+    /// stroked.
+    pub(crate) fn stroke<A, F>(
+        &self,
+        points: &[(Point2, A)],
+        close: bool,
+        opts: &StrokeOptions,
+        lerp: F,
+    ) -> Vec<(Vec<(Point2, A)>, StrokeOptions)>
+    where
+        A: Copy,
+        F: Fn(A, A, f32) -> A,
+    {
+        let Split {
+            dashes,
+            starts_on,
+            ends_on,
+        } = self.split_inner(points, close, lerp);
+        let last = dashes.len().saturating_sub(1);
+        dashes
+            .into_iter()
+            .enumerate()
+            .map(|(i, dash)| {
+                let mut opts = *opts;
+                if let Some(cap) = self.cap {
+                    // The caps of the stroke itself apply at the ends of an open path, unless the
+                    // dash there is a dot.
+                    let is_dot = dash.windows(2).all(|w| w[0].0 == w[1].0);
+                    let at_start = !close && i == 0 && starts_on && !is_dot;
+                    let at_end = !close && i == last && ends_on && !is_dot;
+                    if !at_start {
+                        opts.start_cap = cap;
+                    }
+                    if !at_end {
+                        opts.end_cap = cap;
+                    }
+                }
+                (dash, opts)
+            })
+            .collect()
+    }
+
+    // The even-length pattern of lengths, or `None` if the pattern is solid.
+    fn pattern(&self) -> Option<Vec<f32>> {
+        if self.lengths.iter().any(|&l| l < 0.0 || !l.is_finite()) {
+            return None;
+        }
+        if self.lengths.iter().sum::<f32>() <= 0.0 {
+            return None;
+        }
+        let mut pattern = self.lengths.clone();
+        if pattern.len() % 2 == 1 {
+            pattern.extend_from_slice(&self.lengths);
+        }
+        Some(pattern)
+    }
+
+    fn split_inner<A, F>(&self, points: &[(Point2, A)], close: bool, lerp: F) -> Split<A>
+    where
+        A: Copy,
+        F: Fn(A, A, f32) -> A,
+    {
+        let mut dashes = vec![];
+        let pattern = match self.pattern() {
+            Some(pattern) => pattern,
+            None => {
+                if !points.is_empty() {
+                    let mut dash = points.to_vec();
+                    if close {
+                        dash.push(points[0]);
+                    }
+                    dashes.push(dash);
+                }
+                let (starts_on, ends_on) = (true, true);
+                return Split {
+                    dashes,
+                    starts_on,
+                    ends_on,
+                };
+            }
+        };
+        let first = match points.first() {
+            Some(&first) => first,
+            None => {
+                let (starts_on, ends_on) = (false, false);
+                return Split {
+                    dashes,
+                    starts_on,
+                    ends_on,
+                };
+            }
+        };
+
+        // Find the position within the pattern at which the stroke begins.
+        let total: f32 = pattern.iter().sum();
+        let mut offset = self.offset % total;
+        if offset < 0.0 {
+            offset += total;
+        }
+        let mut ix = 0;
+        while offset >= pattern[ix] && offset > 0.0 {
+            offset -= pattern[ix];
+            ix = (ix + 1) % pattern.len();
+        }
+        let mut remaining = pattern[ix] - offset;
+        let mut on = ix % 2 == 0;
+        let starts_on = on;
+
+        let mut dash = vec![];
+        if on {
+            dash.push(first);
+        }
+        let closing = if close {
+            Some((points[points.len() - 1], first))
+        } else {
+            None
+        };
+        let segments = points.windows(2).map(|w| (w[0], w[1])).chain(closing);
+        for ((a, a_attr), (b, b_attr)) in segments {
+            let len = (b - a).magnitude();
+            let mut pos = 0.0;
+            while len - pos > remaining {
+                pos += remaining;
+                let t = pos / len;
+                dash.push((a + (b - a) * t, lerp(a_attr, b_attr, t)));
+                if on {
+                    dashes.push(std::mem::replace(&mut dash, vec![]));
+                }
+                on = !on;
+                ix = (ix + 1) % pattern.len();
+                remaining = pattern[ix];
+            }
+            remaining -= len - pos;
+            if on {
+                dash.push((b, b_attr));
+            }
+        }
+        let ends_on = on;
+        if on && !dash.is_empty() {
+            dashes.push(dash);
+        }
+
+        // Join the last and first dashes where a dash continues through the start of a closed path.
+        if close && starts_on && ends_on && dashes.len() > 1 {
+            let first = dashes.remove(0);
+            let last = dashes.last_mut().expect("at least one dash");
+            last.extend(first.into_iter().skip(1));
+        }
+
+        Split {
+            dashes,
+            starts_on,
+            ends_on,
+        }
+    }
+}
+
+// The result of splitting a polyline into dashes.
+struct Split<A> {
+    dashes: Vec<Vec<(Point2, A)>>,
+    // Whether the first dash begins at the start of the polyline.
+    starts_on: bool,
+    // Whether the last dash ends at the end of the polyline.
+    ends_on: bool,
+}
+
+impl Default for Dash {
+    fn default() -> Self {
+        Dash::new(vec![])
+    }
+}
+
+impl SetDash for Option<Dash> {
+    fn dash_mut(&mut self) -> &mut Option<Dash> {
+        self
+    }
+}
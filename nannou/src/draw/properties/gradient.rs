@@ -0,0 +1,329 @@
+//! Gradient paints that may be used in place of a flat color when filling or stroking.
+
+use crate::color::{self, IntoLinSrgba, Limited, Mix};
+use crate::draw::properties::LinSrgba;
+use crate::geom::Point2;
+use crate::serde_derive::{Deserialize, Serialize};
+
+/// A gradient paint described by a set of color stops.
+///
+/// Gradient coordinates are specified in the local space of the primitive to which the gradient
+/// is applied. E.g. for a `rect`, `(0.0, 0.0)` is the centre of the rect, regardless of its
+/// position. Colors are evaluated per tessellated vertex. Geometry is subdivided as necessary so
+/// that the interpolation between vertices closely follows the gradient, and is split along the
+/// seam of conic gradients where the colors wrap around.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Gradient {
+    kind: Kind,
+    stops: Vec<Stop>,
+    spread: Spread,
+    color_space: ColorSpace,
+}
+
+/// The shape of a **Gradient**.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Kind {
+    /// Colors vary along the line from `start` (offset `0.0`) to `end` (offset `1.0`).
+    Linear { start: Point2, end: Point2 },
+    /// Colors vary with the distance from `center`, reaching offset `1.0` at `radius`.
+    Radial { center: Point2, radius: f32 },
+    /// Colors vary with the angle around `center`, starting at `angle` radians
+    /// counter-clockwise from the positive *x* axis.
+    Conic { center: Point2, angle: f32 },
+}
+
+/// A color at some offset along a **Gradient**.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Stop {
+    pub offset: f32,
+    pub color: LinSrgba,
+}
+
+/// Describes how a **Gradient** is painted beyond the offset range `0.0..=1.0`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Spread {
+    /// The colors of the first and last stops extend indefinitely.
+    Pad,
+    /// The gradient repeats.
+    Repeat,
+    /// The gradient repeats, reversing direction every other repetition.
+    Reflect,
+}
+
+/// The color space in which a **Gradient** interpolates between stops.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ColorSpace {
+    /// Linear RGB. This is the default.
+    LinearRgb,
+    /// Gamma encoded sRGB, as used by most web browsers.
+    Srgb,
+    /// HSL, taking the shortest path around the hue circle.
+    Hsl,
+    /// HSV, taking the shortest path around the hue circle.
+    Hsv,
+    /// CIE L\*a\*b\* with a D65 white point.
+    Lab,
+    /// CIE L\*C\*h° with a D65 white point, taking the shortest path around the hue circle.
+    Lch,
+}
+
+/// Nodes that support gradient paints.
+pub trait SetGradient: Sized {
+    /// Provide a mutable reference to the gradient field.
+    fn gradient_mut(&mut self) -> &mut Option<Gradient>;
+
+    /// Paint with the given gradient.
+    ///
+    /// Where both a gradient and a color are specified, the gradient takes precedence.
+    fn gradient(mut self, gradient: Gradient) -> Self {
+        *self.gradient_mut() = Some(gradient);
+        self
+    }
+}
+
+impl Gradient {
+    /// A gradient of the given kind with no stops.
+    pub fn new(kind: Kind) -> Self {
+        Gradient {
+            kind,
+            stops: vec![],
+            spread: Spread::Pad,
+            color_space: ColorSpace::LinearRgb,
+        }
+    }
+
+    /// A linear gradient from `start` to `end`.
+    pub fn linear<P>(start: P, end: P) -> Self
+    where
+        P: Into<Point2>,
+    {
+        let (start, end) = (start.into(), end.into());
+        Self::new(Kind::Linear { start, end })
+    }
+
+    /// A radial gradient around `center`.
+    pub fn radial<P>(center: P, radius: f32) -> Self
+    where
+        P: Into<Point2>,
+    {
+        let center = center.into();
+        Self::new(Kind::Radial { center, radius })
+    }
+
+    /// A conic gradient sweeping counter-clockwise around `center`, beginning at the given angle
+    /// in radians.
+    pub fn conic<P>(center: P, angle: f32) -> Self
+    where
+        P: Into<Point2>,
+    {
+        let center = center.into();
+        Self::new(Kind::Conic { center, angle })
+    }
+
+    /// Add a color stop at the given offset.
+    ///
+    /// Stops are kept ordered by offset. Stops that share an offset are kept in the order in
+    /// which they were added, allowing for hard transitions.
+    pub fn stop<C>(mut self, offset: f32, color: C) -> Self
+    where
+        C: IntoLinSrgba<f32>,
+    {
+        let color = color.into_lin_srgba();
+        let ix = self
+            .stops
+            .iter()
+            .position(|s| s.offset > offset)
+            .unwrap_or(self.stops.len());
+        self.stops.insert(ix, Stop { offset, color });
+        self
+    }
+
+    /// Add the given color stops.
+    pub fn stops<I, C>(self, stops: I) -> Self
+    where
+        I: IntoIterator<Item = (f32, C)>,
+        C: IntoLinSrgba<f32>,
+    {
+        stops
+            .into_iter()
+            .fold(self, |g, (offset, color)| g.stop(offset, color))
+    }
+
+    /// Specify how the gradient is painted beyond its extent.
+    ///
+    /// By default, this is `Spread::Pad`.
+    pub fn spread(mut self, spread: Spread) -> Self {
+        self.spread = spread;
+        self
+    }
+
+    /// Specify the color space in which colors are interpolated between stops.
+    ///
+    /// By default, this is `ColorSpace::LinearRgb`.
+    pub fn color_space(mut self, color_space: ColorSpace) -> Self {
+        self.color_space = color_space;
+        self
+    }
+
+    /// The shape of the gradient.
+    pub fn kind(&self) -> Kind {
+        self.kind
+    }
+
+    /// The color stops, ordered by offset.
+    pub fn color_stops(&self) -> &[Stop] {
+        &self.stops
+    }
+
+    /// The color of the gradient at the given point.
+    pub fn color_at(&self, point: Point2) -> LinSrgba {
+        let offset = self.kind.offset_at(point);
+        self.color_at_offset(self.spread.apply(offset))
+    }
+
+    /// The color at the given offset, ignoring the spread mode.
+    ///
+    /// Offsets before the first stop or after the last stop produce the color of that stop. A
+    /// gradient without stops is transparent.
+    pub fn color_at_offset(&self, offset: f32) -> LinSrgba {
+        let (first, last) = match (self.stops.first(), self.stops.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return LinSrgba::new(0.0, 0.0, 0.0, 0.0),
+        };
+        if offset < first.offset {
+            return first.color;
+        }
+        if offset >= last.offset {
+            return last.color;
+        }
+        let ix = self
+            .stops
+            .iter()
+            .position(|s| s.offset > offset)
+            .expect("offset is before the last stop");
+        let (a, b) = (self.stops[ix - 1], self.stops[ix]);
+        let t = (offset - a.offset) / (b.offset - a.offset);
+        self.color_space.mix(a.color, b.color, t)
+    }
+
+    /// The distance over which the gradient's colors vary, if the gradient has one.
+    ///
+    /// Conic gradients vary by angle and so have no such distance.
+    pub fn extent(&self) -> Option<f32> {
+        match self.kind {
+            Kind::Linear { start, end } => Some((end - start).magnitude()),
+            Kind::Radial { radius, .. } => Some(radius.abs()),
+            Kind::Conic { .. } => None,
+        }
+    }
+}
+
+/// The flat color with which to paint where gradients are unsupported, e.g. when rendering SVG.
+///
+/// Gradients are approximated by their midpoint color, taking precedence over the flat color.
+pub(crate) fn flat_color(gradient: Option<&Gradient>, color: Option<LinSrgba>) -> Option<LinSrgba> {
+    gradient.map(|g| g.color_at_offset(0.5)).or(color)
+}
+
+impl Kind {
+    /// The gradient offset at the given point, prior to applying the spread mode.
+    pub fn offset_at(&self, point: Point2) -> f32 {
+        match *self {
+            Kind::Linear { start, end } => {
+                let dir = end - start;
+                let len2 = dir.magnitude2();
+                if len2 == 0.0 {
+                    return 0.0;
+                }
+                (point - start).dot(dir) / len2
+            }
+            Kind::Radial { center, radius } => {
+                if radius == 0.0 {
+                    return 0.0;
+                }
+                (point - center).magnitude() / radius.abs()
+            }
+            Kind::Conic { center, angle } => {
+                let v = point - center;
+                let turns = (v.y.atan2(v.x) - angle) / (2.0 * std::f32::consts::PI);
+                turns - turns.floor()
+            }
+        }
+    }
+}
+
+impl Spread {
+    /// Map the given offset into the range `0.0..=1.0`.
+    pub fn apply(self, offset: f32) -> f32 {
+        match self {
+            Spread::Pad => offset.max(0.0).min(1.0),
+            Spread::Repeat => offset - offset.floor(),
+            Spread::Reflect => {
+                let t = offset - (offset * 0.5).floor() * 2.0;
+                if t > 1.0 {
+                    2.0 - t
+                } else {
+                    t
+                }
+            }
+        }
+    }
+}
+
+impl ColorSpace {
+    /// Interpolate between the two colors within this color space.
+    ///
+    /// Colors that fall outside of the RGB gamut are clamped.
+    pub fn mix(self, a: LinSrgba, b: LinSrgba, t: f32) -> LinSrgba {
+        type D65 = color::white_point::D65;
+        match self {
+            ColorSpace::LinearRgb => a.mix(&b, t),
+            ColorSpace::Srgb => {
+                let a = color::Srgba::from_linear(a);
+                let b = color::Srgba::from_linear(b);
+                let lerp = |a: f32, b: f32| a + (b - a) * t;
+                let c = color::Srgba::new(
+                    lerp(a.red, b.red),
+                    lerp(a.green, b.green),
+                    lerp(a.blue, b.blue),
+                    lerp(a.alpha, b.alpha),
+                );
+                c.into_linear()
+            }
+            ColorSpace::Hsl => {
+                let (a, b) = (color::Hsla::from(a), color::Hsla::from(b));
+                LinSrgba::from(a.mix(&b, t))
+            }
+            ColorSpace::Hsv => {
+                let (a, b) = (color::Hsva::from(a), color::Hsva::from(b));
+                LinSrgba::from(a.mix(&b, t))
+            }
+            ColorSpace::Lab => {
+                let (a, b) = (color::Laba::<D65>::from(a), color::Laba::<D65>::from(b));
+                LinSrgba::from(a.mix(&b, t)).clamp()
+            }
+            ColorSpace::Lch => {
+                let (a, b) = (color::Lcha::<D65>::from(a), color::Lcha::<D65>::from(b));
+                LinSrgba::from(a.mix(&b, t)).clamp()
+            }
+        }
+    }
+}
+
+impl Default for Spread {
+    fn default() -> Self {
+        Spread::Pad
+    }
+}
+
+impl Default for ColorSpace {
+    fn default() -> Self {
+        ColorSpace::LinearRgb
+    }
+}
+
+impl SetGradient for Option<Gradient> {
+    fn gradient_mut(&mut self) -> &mut Option<Gradient> {
+        self
+    }
+}
@@ -7,12 +7,16 @@
 //! a unique **node::Index** to simplify this.
 
 pub mod color;
+pub mod dash;
 pub mod fill;
+pub mod gradient;
 pub mod spatial;
 pub mod stroke;
 
 pub use self::color::SetColor;
+pub use self::dash::SetDash;
 pub use self::fill::SetFill;
+pub use self::gradient::SetGradient;
 pub use self::spatial::dimension::SetDimensions;
 pub use self::spatial::orientation::SetOrientation;
 pub use self::spatial::position::SetPosition;
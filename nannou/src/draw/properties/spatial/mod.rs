@@ -1,5 +1,6 @@
 use crate::geom;
 use crate::math::Zero;
+use crate::serde_derive::{Deserialize, Serialize};
 
 pub mod dimension;
 pub mod orientation;
@@ -14,7 +15,7 @@ pub trait SetSpatial<S>: SetDimensions<S> + SetPosition<S> + SetOrientation<S> {
 
 impl<S, T> SetSpatial<S> for T where T: SetDimensions<S> + SetPosition<S> + SetOrientation<S> {}
 
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Properties<S = geom::scalar::Default> {
     pub position: position::Properties<S>,
     pub dimensions: dimension::Properties<S>,
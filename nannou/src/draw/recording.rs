@@ -0,0 +1,601 @@
+//! Record the commands submitted to a **Draw** instance to a frame-indexed log and play them back.
+//!
+//! The log is written as [JSON Lines](https://jsonlines.org/), where each line describes the
+//! commands, background and vertex data of a single frame. This is useful for debugging, for
+//! regression tests and for re-rendering a sketch offline, e.g. at a higher resolution.
+//!
+//! GPU textures cannot be serialized. Instead, each texture is given an ID that is unique within
+//! the log, along with the path from which it was loaded if one was registered via
+//! `Recorder::register_texture`. When playing back, textures may be provided via
+//! `Player::set_texture` or loaded from their paths via `Player::load_textures`. Primitives whose
+//! textures are not provided are drawn with the renderer's default texture.
+//!
+//! Fonts used by text primitives are referenced by an ID in the same manner. Each font is written
+//! to the log once, either as the path registered via `Recorder::register_font` or otherwise as
+//! its data. Fonts with a path are loaded via `Player::load_fonts` or provided via
+//! `Player::set_font`, while fonts whose data was written are restored as the log is read. Text
+//! whose fonts are not provided is drawn with the default font.
+
+use crate::draw::mesh::vertex;
+use crate::draw::primitive::Primitive;
+use crate::draw::properties::LinSrgba;
+use crate::draw::{Draw, DrawCommand};
+use crate::geom::Point2;
+use crate::math::BaseFloat;
+use crate::serde_derive::{Deserialize, Serialize};
+use crate::text::{self, Font};
+use crate::wgpu;
+use lyon::path::PathEvent;
+use serde::de::DeserializeOwned;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::{fmt, fs};
+
+/// Writes the commands of a **Draw** instance to a log, one frame at a time.
+pub struct Recorder<W> {
+    writer: W,
+    texture_ids: HashMap<wgpu::TextureViewId, u64>,
+    texture_paths: HashMap<wgpu::TextureViewId, PathBuf>,
+    font_ids: HashMap<FontKey, u64>,
+    font_paths: HashMap<FontKey, PathBuf>,
+}
+
+/// Reads a log written by a **Recorder** and feeds its frames back into a **Draw** instance.
+pub struct Player<S = crate::geom::scalar::Default> {
+    frames: BTreeMap<u64, FrameRecord<S>>,
+    texture_paths: HashMap<u64, PathBuf>,
+    textures: HashMap<u64, wgpu::TextureView>,
+    font_paths: HashMap<u64, PathBuf>,
+    fonts: HashMap<u64, Font>,
+}
+
+/// A reference to a texture used by a recorded primitive.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TextureRef {
+    /// Uniquely identifies the texture within the log.
+    pub id: u64,
+    /// The path from which the texture was loaded, if it was registered with the **Recorder**.
+    pub path: Option<PathBuf>,
+}
+
+/// A reference to a font used by a recorded text primitive.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FontRef {
+    /// Uniquely identifies the font within the log.
+    pub id: u64,
+    /// The path from which the font was loaded, if it was registered with the **Recorder**.
+    pub path: Option<PathBuf>,
+}
+
+/// Errors that might occur while recording or playing back a log.
+#[derive(Debug)]
+pub enum Error {
+    /// An error occurred while reading or writing the log.
+    Io(io::Error),
+    /// A frame could not be serialized or deserialized.
+    Json(serde_json::Error),
+    /// A texture could not be loaded from its path.
+    Image(image::ImageError),
+    /// A font could not be loaded from its path or recorded data.
+    Font(text::font::Error),
+}
+
+// A single line within the log.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct FrameRecord<S> {
+    frame: u64,
+    background: Option<LinSrgba>,
+    commands: Vec<DrawCommand<S>>,
+    // Indices into `commands` of primitives that reference textures.
+    textures: Vec<(usize, TextureRef)>,
+    // Fonts used for the first time within this frame.
+    font_defs: Vec<FontDef>,
+    // Indices into `commands` of text primitives along with the fonts they use.
+    fonts: Vec<(usize, TextFonts)>,
+    mesh: MeshRecord<S>,
+    path_events: Vec<PathEvent>,
+    path_points_colored: Vec<(Point2<S>, vertex::Color)>,
+    path_points_textured: Vec<(Point2<S>, Point2<S>)>,
+    text: String,
+}
+
+// A font written to the log, either by the path from which it was loaded or by its data.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct FontDef {
+    id: u64,
+    path: Option<PathBuf>,
+    data: Option<Vec<u8>>,
+}
+
+// The fonts used by a text primitive by their IDs within the log. These mirror the fields of the
+// primitive's `layout::Builder` and, for rich text, its `font::Map`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct TextFonts {
+    font: Option<Option<u64>>,
+    fallback_fonts: Option<Vec<u64>>,
+    map: Vec<(text::font::Id, u64)>,
+}
+
+// Identifies a font by the location and length of its data, which is shared between clones.
+type FontKey = (usize, usize);
+
+// The intermediary mesh, stored channel by channel.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct MeshRecord<S> {
+    points: Vec<vertex::Point<S>>,
+    indices: Vec<u32>,
+    colors: Vec<vertex::Color>,
+    tex_coords: Vec<vertex::TexCoords<S>>,
+}
+
+impl Recorder<io::BufWriter<fs::File>> {
+    /// Create the file at the given path and record to it.
+    pub fn create<P>(path: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let file = fs::File::create(path)?;
+        Ok(Self::new(io::BufWriter::new(file)))
+    }
+}
+
+impl<W> Recorder<W>
+where
+    W: Write,
+{
+    /// Record to the given writer.
+    pub fn new(writer: W) -> Self {
+        Recorder {
+            writer,
+            texture_ids: Default::default(),
+            texture_paths: Default::default(),
+            font_ids: Default::default(),
+            font_paths: Default::default(),
+        }
+    }
+
+    /// Associate the given font with the path from which it was loaded.
+    ///
+    /// The path is written in place of the font's data so that the **Player** may load it again.
+    pub fn register_font<P>(&mut self, font: &Font, path: P)
+    where
+        P: Into<PathBuf>,
+    {
+        self.font_paths.insert(font_key(font), path.into());
+    }
+
+    // The ID of the given font within the log, writing a definition for it if it is new.
+    fn font_id(&mut self, font: &Font, font_defs: &mut Vec<FontDef>) -> u64 {
+        let key = font_key(font);
+        if let Some(&id) = self.font_ids.get(&key) {
+            return id;
+        }
+        let id = self.font_ids.len() as u64;
+        self.font_ids.insert(key, id);
+        let path = self.font_paths.get(&key).cloned();
+        let data = match path {
+            Some(_) => None,
+            None => Some(font.data().to_vec()),
+        };
+        font_defs.push(FontDef { id, path, data });
+        id
+    }
+
+    /// Associate the given texture with the path from which it was loaded.
+    ///
+    /// The path is written alongside references to the texture so that the **Player** may load
+    /// it again.
+    pub fn register_texture<P>(&mut self, view: &dyn wgpu::ToTextureView, path: P)
+    where
+        P: Into<PathBuf>,
+    {
+        let id = view.to_texture_view().id();
+        self.texture_paths.insert(id, path.into());
+    }
+
+    /// Write the current commands of the given **Draw** instance as the frame with the given
+    /// number.
+    ///
+    /// Any drawings in progress are finished first. The **Draw** instance is otherwise left
+    /// untouched, so this may be called at the end of `view` before the draw is rendered.
+    pub fn record<S>(&mut self, frame: u64, draw: &Draw<S>) -> Result<(), Error>
+    where
+        S: BaseFloat + serde::Serialize,
+    {
+        draw.finish_remaining_drawings();
+        let state = draw.state.borrow();
+        let intermediary_state = state.intermediary_state.borrow();
+        let commands: Vec<_> = state
+            .draw_commands
+            .iter()
+            .filter_map(Clone::clone)
+            .collect();
+
+        let mut textures = vec![];
+        for (ix, cmd) in commands.iter().enumerate() {
+            let view = match *cmd {
+                DrawCommand::Primitive(ref prim) => match texture_view(prim) {
+                    Some(view) => view,
+                    None => continue,
+                },
+                DrawCommand::Context(_) => continue,
+            };
+            let view_id = view.id();
+            let next_id = self.texture_ids.len() as u64;
+            let id = *self.texture_ids.entry(view_id).or_insert(next_id);
+            let path = self.texture_paths.get(&view_id).cloned();
+            textures.push((ix, TextureRef { id, path }));
+        }
+
+        let mut font_defs = vec![];
+        let mut fonts = vec![];
+        for (ix, cmd) in commands.iter().enumerate() {
+            let (layout, map) = match *cmd {
+                DrawCommand::Primitive(ref prim) => match text_fonts(prim) {
+                    Some(text_fonts) => text_fonts,
+                    None => continue,
+                },
+                DrawCommand::Context(_) => continue,
+            };
+            let font = layout
+                .font
+                .as_ref()
+                .map(|font| font.as_ref().map(|font| self.font_id(font, &mut font_defs)));
+            let fallback_fonts = layout.fallback_fonts.as_ref().map(|fallbacks| {
+                fallbacks
+                    .iter()
+                    .map(|font| self.font_id(font, &mut font_defs))
+                    .collect()
+            });
+            let mut map_ids = vec![];
+            if let Some(map) = map {
+                let mut ids: Vec<_> = map.ids().collect();
+                ids.sort();
+                for id in ids {
+                    let font = map.get(id).expect("no font for `font::Id` in map");
+                    map_ids.push((id, self.font_id(font, &mut font_defs)));
+                }
+            }
+            let text_fonts = TextFonts {
+                font,
+                fallback_fonts,
+                map: map_ids,
+            };
+            fonts.push((ix, text_fonts));
+        }
+
+        let mesh = &intermediary_state.intermediary_mesh;
+        let record = FrameRecord {
+            frame,
+            background: state.background_color,
+            commands,
+            textures,
+            font_defs,
+            fonts,
+            mesh: MeshRecord {
+                points: mesh.points().to_vec(),
+                indices: mesh.indices().to_vec(),
+                colors: mesh.colors().to_vec(),
+                tex_coords: mesh.tex_coords().to_vec(),
+            },
+            path_events: intermediary_state.path_event_buffer.clone(),
+            path_points_colored: intermediary_state.path_points_colored_buffer.clone(),
+            path_points_textured: intermediary_state.path_points_textured_buffer.clone(),
+            text: intermediary_state.text_buffer.clone(),
+        };
+        serde_json::to_writer(&mut self.writer, &record)?;
+        self.writer.write_all(b"\n")?;
+        Ok(())
+    }
+
+    /// Flush the log and return the inner writer.
+    pub fn finish(mut self) -> Result<W, Error> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+impl<S> Player<S>
+where
+    S: BaseFloat + DeserializeOwned,
+{
+    /// Read a log from the file at the given path.
+    pub fn open<P>(path: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let file = fs::File::open(path)?;
+        Self::read(io::BufReader::new(file))
+    }
+
+    /// Read a log from the given reader.
+    ///
+    /// If a frame number occurs more than once, the last record is used.
+    pub fn read<R>(reader: R) -> Result<Self, Error>
+    where
+        R: BufRead,
+    {
+        let mut frames = BTreeMap::new();
+        let mut texture_paths = HashMap::new();
+        let mut font_paths = HashMap::new();
+        let mut fonts = HashMap::new();
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record: FrameRecord<S> = serde_json::from_str(&line)?;
+            for (_, tex) in &record.textures {
+                if let Some(ref path) = tex.path {
+                    texture_paths.insert(tex.id, path.clone());
+                }
+            }
+            for def in &record.font_defs {
+                if let Some(ref path) = def.path {
+                    font_paths.insert(def.id, path.clone());
+                }
+                if let Some(ref data) = def.data {
+                    fonts.insert(def.id, text::font::from_bytes(data.clone())?);
+                }
+            }
+            frames.insert(record.frame, record);
+        }
+        Ok(Player {
+            frames,
+            texture_paths,
+            textures: Default::default(),
+            font_paths,
+            fonts,
+        })
+    }
+}
+
+impl<S> Player<S>
+where
+    S: BaseFloat,
+{
+    /// The number of frames within the log.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Whether or not the log contains any frames.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// The numbers of all recorded frames in ascending order.
+    pub fn frames(&self) -> impl Iterator<Item = u64> + '_ {
+        self.frames.keys().cloned()
+    }
+
+    /// All textures referenced within the log, ordered by ID.
+    pub fn texture_refs(&self) -> Vec<TextureRef> {
+        let mut refs: BTreeMap<u64, TextureRef> = BTreeMap::new();
+        for record in self.frames.values() {
+            for (_, tex) in &record.textures {
+                refs.entry(tex.id).or_insert_with(|| TextureRef {
+                    id: tex.id,
+                    path: self.texture_paths.get(&tex.id).cloned(),
+                });
+            }
+        }
+        refs.into_iter().map(|(_, tex)| tex).collect()
+    }
+
+    /// Provide the texture to use for the texture with the given ID.
+    pub fn set_texture(&mut self, id: u64, view: &dyn wgpu::ToTextureView) {
+        self.textures.insert(id, view.to_texture_view());
+    }
+
+    /// Load all textures that have a recorded path and that have not yet been provided.
+    pub fn load_textures(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+    ) -> Result<(), Error> {
+        for (&id, path) in &self.texture_paths {
+            if self.textures.contains_key(&id) {
+                continue;
+            }
+            let texture = wgpu::Texture::from_path((device, queue), path)?;
+            self.textures.insert(id, texture.view().build());
+        }
+        Ok(())
+    }
+
+    /// All fonts that were recorded by path, ordered by ID.
+    ///
+    /// Fonts whose data was written to the log are restored as it is read and are not included.
+    pub fn font_refs(&self) -> Vec<FontRef> {
+        let mut refs: Vec<_> = self
+            .font_paths
+            .iter()
+            .map(|(&id, path)| FontRef {
+                id,
+                path: Some(path.clone()),
+            })
+            .collect();
+        refs.sort_by_key(|font| font.id);
+        refs
+    }
+
+    /// Provide the font to use for the font with the given ID.
+    pub fn set_font(&mut self, id: u64, font: Font) {
+        self.fonts.insert(id, font);
+    }
+
+    /// Load all fonts that have a recorded path and that have not yet been provided.
+    pub fn load_fonts(&mut self) -> Result<(), Error> {
+        for (&id, path) in &self.font_paths {
+            if self.fonts.contains_key(&id) {
+                continue;
+            }
+            let font = text::font::from_file(path)?;
+            self.fonts.insert(id, font);
+        }
+        Ok(())
+    }
+
+    /// Replace the contents of the given **Draw** instance with the recorded frame.
+    ///
+    /// Returns `false` if there is no frame with the given number, in which case the **Draw** is
+    /// left untouched.
+    pub fn replay(&self, frame: u64, draw: &Draw<S>) -> bool {
+        let record = match self.frames.get(&frame) {
+            None => return false,
+            Some(record) => record,
+        };
+        draw.reset();
+        let mut state = draw.state.borrow_mut();
+
+        let mut commands = record.commands.clone();
+        for &(ix, ref tex) in &record.textures {
+            if let Some(DrawCommand::Primitive(ref mut prim)) = commands.get_mut(ix) {
+                if let Some(texture_view) = texture_view_mut(prim) {
+                    *texture_view = self.textures.get(&tex.id).cloned();
+                }
+            }
+        }
+        for &(ix, ref text_fonts) in &record.fonts {
+            if let Some(DrawCommand::Primitive(ref mut prim)) = commands.get_mut(ix) {
+                if let Some((layout, map)) = text_fonts_mut(prim) {
+                    let font = |id: &u64| self.fonts.get(id).cloned();
+                    layout.font = text_fonts.font.map(|id| id.as_ref().and_then(font));
+                    layout.fallback_fonts = text_fonts
+                        .fallback_fonts
+                        .as_ref()
+                        .map(|ids| ids.iter().filter_map(font).collect());
+                    if let Some(map) = map {
+                        for &(font_id, ref id) in &text_fonts.map {
+                            if let Some(font) = font(id) {
+                                map.insert_with_id(font_id, font);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        state.background_color = record.background;
+        state.draw_commands = commands.into_iter().map(Some).collect();
+
+        let mut intermediary_state = state.intermediary_state.borrow_mut();
+        let mesh = &record.mesh;
+        let vertices = mesh
+            .points
+            .iter()
+            .zip(&mesh.colors)
+            .zip(&mesh.tex_coords)
+            .map(|((&p, &c), &t)| vertex::new(p, c, t));
+        intermediary_state
+            .intermediary_mesh
+            .extend(vertices, mesh.indices.iter().cloned());
+        intermediary_state
+            .path_event_buffer
+            .extend(record.path_events.iter().cloned());
+        intermediary_state
+            .path_points_colored_buffer
+            .extend(record.path_points_colored.iter().cloned());
+        intermediary_state
+            .path_points_textured_buffer
+            .extend(record.path_points_textured.iter().cloned());
+        intermediary_state.text_buffer.push_str(&record.text);
+        true
+    }
+}
+
+// The texture used by the given primitive, if any.
+fn texture_view<S>(prim: &Primitive<S>) -> Option<&wgpu::TextureView> {
+    match *prim {
+        Primitive::Mesh(ref p) => p.texture_view.as_ref(),
+        Primitive::Path(ref p) => p.texture_view.as_ref(),
+        Primitive::Polygon(ref p) => p.texture_view.as_ref(),
+        Primitive::Texture(ref p) => p.texture_view.as_ref(),
+        _ => None,
+    }
+}
+
+// The texture field of primitives that may be textured.
+fn texture_view_mut<S>(prim: &mut Primitive<S>) -> Option<&mut Option<wgpu::TextureView>> {
+    match *prim {
+        Primitive::Mesh(ref mut p) => Some(&mut p.texture_view),
+        Primitive::Path(ref mut p) => Some(&mut p.texture_view),
+        Primitive::Polygon(ref mut p) => Some(&mut p.texture_view),
+        Primitive::Texture(ref mut p) => Some(&mut p.texture_view),
+        _ => None,
+    }
+}
+
+// The layout of text primitives along with the font map of rich text, if any.
+fn text_fonts<S>(
+    prim: &Primitive<S>,
+) -> Option<(&text::layout::Builder, Option<&text::font::Map>)> {
+    match *prim {
+        Primitive::Text(ref p) => Some((&p.style.layout, Some(&p.fonts))),
+        Primitive::TextOnPath(ref p) => Some((&p.style.layout, None)),
+        _ => None,
+    }
+}
+
+// The layout of text primitives along with the font map of rich text, if any.
+fn text_fonts_mut<S>(
+    prim: &mut Primitive<S>,
+) -> Option<(&mut text::layout::Builder, Option<&mut text::font::Map>)> {
+    match *prim {
+        Primitive::Text(ref mut p) => Some((&mut p.style.layout, Some(&mut p.fonts))),
+        Primitive::TextOnPath(ref mut p) => Some((&mut p.style.layout, None)),
+        _ => None,
+    }
+}
+
+fn font_key(font: &Font) -> FontKey {
+    let data = font.data();
+    (data.as_ptr() as usize, data.len())
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Json(e)
+    }
+}
+
+impl From<image::ImageError> for Error {
+    fn from(e: image::ImageError) -> Self {
+        Error::Image(e)
+    }
+}
+
+impl From<text::font::Error> for Error {
+    fn from(e: text::font::Error) -> Self {
+        Error::Font(e)
+    }
+}
+
+impl std::error::Error for Error {
+    fn cause(&self) -> Option<&dyn std::error::Error> {
+        match *self {
+            Error::Io(ref e) => Some(e),
+            Error::Json(ref e) => Some(e),
+            Error::Image(ref e) => Some(e),
+            Error::Font(ref e) => Some(e),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Io(ref e) => fmt::Display::fmt(e, f),
+            Error::Json(ref e) => fmt::Display::fmt(e, f),
+            Error::Image(ref e) => fmt::Display::fmt(e, f),
+            Error::Font(ref e) => fmt::Display::fmt(e, f),
+        }
+    }
+}
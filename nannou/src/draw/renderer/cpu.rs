@@ -0,0 +1,1077 @@
+//! A CPU implementation of the **draw::Renderer** for rendering a **Draw** instance's commands
+//! into an **image::RgbaImage** without requiring a wgpu adapter.
+//!
+//! The **cpu::Renderer** tessellates primitives into the same **draw::Mesh** as the GPU renderer
+//! and follows a similar list of render commands. The result is rasterised in a manner that aims
+//! to match the GPU pipeline as closely as possible:
+//!
+//! - Vertices are coloured according to their **VertexMode** (color, texture, text or signed
+//!   distance field text).
+//! - Scissors, blend descriptors, primitive topologies and samplers are honoured.
+//! - Depth testing is performed with the same `LessEqual` comparison as the GPU pipeline.
+//! - Multisample anti-aliasing is emulated using the standard sample positions for sample counts
+//!   of 1, 2, 4, 8 and 16. Like MSAA on the GPU, fragments are shaded once per pixel and the
+//!   result is written to each covered sample.
+//!
+//! Blending is performed in linear space and the result is encoded as sRGB, matching an
+//! `Rgba8UnormSrgb` output attachment.
+//!
+//! As texture data lives on the GPU, the pixels of any textures that are drawn must be provided
+//! via the `Renderer::insert_texture` method. Textures for which no image has been provided are
+//! sampled as transparent black.
+
+use crate::draw;
+use crate::draw::renderer::{GlyphCache, PrimitiveRender, Scissor, VertexMode};
+use crate::geom;
+use crate::image;
+use crate::wgpu;
+use std::collections::HashMap;
+use std::ops;
+
+/// A type aimed at simplifying construction of a `draw::renderer::cpu::Renderer`.
+#[derive(Clone, Debug)]
+pub struct Builder {
+    pub sample_count: u32,
+    pub glyph_cache_size: [u32; 2],
+    pub glyph_cache_scale_tolerance: f32,
+    pub glyph_cache_position_tolerance: f32,
+    pub sdf_atlas_size: [u32; 2],
+}
+
+/// Renders the commands of a **Draw** instance into an image on the CPU.
+#[derive(Debug)]
+pub struct Renderer {
+    glyph_cache: GlyphCache,
+    textures: HashMap<wgpu::TextureViewId, image::RgbaImage>,
+    sample_count: u32,
+    render_commands: Vec<RenderCommand>,
+    mesh: draw::Mesh,
+    vertex_mode_buffer: Vec<VertexMode>,
+    // The color of each sample in linear space.
+    samples: Vec<[f32; 4]>,
+    // The depth of each sample.
+    depths: Vec<f32>,
+}
+
+/// Commands that map to the CPU rasterisation steps.
+#[derive(Debug)]
+enum RenderCommand {
+    /// Change the blend descriptors and topology.
+    SetPipeline(Pipeline),
+    /// Change the texture and sampler.
+    SetTexture {
+        texture: Option<wgpu::TextureViewId>,
+        sampler: Sampler,
+    },
+    /// Set the rectangular scissor.
+    SetScissor(Scissor),
+    /// Draw the given index range.
+    DrawIndexed { index_range: ops::Range<u32> },
+}
+
+// The equivalent of a render pipeline for the CPU.
+#[derive(Clone, Debug, PartialEq)]
+struct Pipeline {
+    color_blend: wgpu::BlendDescriptor,
+    alpha_blend: wgpu::BlendDescriptor,
+    topology: wgpu::PrimitiveTopology,
+}
+
+// The subset of a sampler descriptor supported by the CPU renderer.
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Sampler {
+    address_mode_u: wgpu::AddressMode,
+    address_mode_v: wgpu::AddressMode,
+    mag_filter: wgpu::FilterMode,
+}
+
+// A vertex in pixel coordinates, ready for rasterisation.
+#[derive(Copy, Clone, Debug)]
+struct Vertex {
+    // x and y in pixels from the top left, z as depth in the range 0.0 to 1.0.
+    position: [f32; 3],
+    color: [f32; 4],
+    tex_coords: [f32; 2],
+    mode: VertexMode,
+}
+
+// The state required to shade and blend fragments.
+struct Target<'a> {
+    width: u32,
+    height: u32,
+    sample_offsets: &'a [[f32; 2]],
+    samples: &'a mut [[f32; 4]],
+    depths: &'a mut [f32],
+    scissor: [u32; 4],
+    pipeline: &'a Pipeline,
+    texture: Option<&'a image::RgbaImage>,
+    sampler: Sampler,
+    glyph_cache: &'a GlyphCache,
+}
+
+// The minimum distance over which the edges of signed distance field glyphs are smoothed, matching
+// the fragment shader.
+const SDF_MIN_SMOOTHING: f32 = 0.001;
+
+// The standard sample positions in sixteenths of a pixel relative to the pixel centre.
+const SAMPLE_POSITIONS_1: [[i8; 2]; 1] = [[0, 0]];
+const SAMPLE_POSITIONS_2: [[i8; 2]; 2] = [[4, 4], [-4, -4]];
+const SAMPLE_POSITIONS_4: [[i8; 2]; 4] = [[-2, -6], [6, -2], [-6, 2], [2, 6]];
+const SAMPLE_POSITIONS_8: [[i8; 2]; 8] = [
+    [1, -3],
+    [-1, 3],
+    [5, 1],
+    [-3, -5],
+    [-5, 5],
+    [-7, -1],
+    [3, 7],
+    [7, -7],
+];
+const SAMPLE_POSITIONS_16: [[i8; 2]; 16] = [
+    [1, 1],
+    [-1, -3],
+    [-3, 2],
+    [4, -1],
+    [-5, -2],
+    [2, 5],
+    [5, 3],
+    [3, -5],
+    [-2, 6],
+    [0, -7],
+    [-4, -6],
+    [-6, 4],
+    [-8, 0],
+    [7, -4],
+    [6, 7],
+    [-7, -8],
+];
+
+impl Builder {
+    /// The default number of samples per pixel.
+    pub const DEFAULT_SAMPLE_COUNT: u32 = Renderer::DEFAULT_SAMPLE_COUNT;
+    /// The default size for the inner glyph cache.
+    pub const DEFAULT_GLYPH_CACHE_SIZE: [u32; 2] = draw::Renderer::DEFAULT_GLYPH_CACHE_SIZE;
+    /// The default scale tolerance for the glyph cache.
+    pub const DEFAULT_GLYPH_CACHE_SCALE_TOLERANCE: f32 =
+        draw::Renderer::DEFAULT_GLYPH_CACHE_SCALE_TOLERANCE;
+    /// The default position tolerance for the glyph cache.
+    pub const DEFAULT_GLYPH_CACHE_POSITION_TOLERANCE: f32 =
+        draw::Renderer::DEFAULT_GLYPH_CACHE_POSITION_TOLERANCE;
+    /// The default size for the atlas of signed distance field glyphs.
+    pub const DEFAULT_SDF_ATLAS_SIZE: [u32; 2] = draw::Renderer::DEFAULT_SDF_ATLAS_SIZE;
+
+    /// Begin building a new **cpu::Renderer**.
+    pub fn new() -> Self {
+        Self {
+            sample_count: Self::DEFAULT_SAMPLE_COUNT,
+            glyph_cache_size: Self::DEFAULT_GLYPH_CACHE_SIZE,
+            glyph_cache_scale_tolerance: Self::DEFAULT_GLYPH_CACHE_SCALE_TOLERANCE,
+            glyph_cache_position_tolerance: Self::DEFAULT_GLYPH_CACHE_POSITION_TOLERANCE,
+            sdf_atlas_size: Self::DEFAULT_SDF_ATLAS_SIZE,
+        }
+    }
+
+    /// The number of samples per pixel used for anti-aliasing.
+    ///
+    /// Must be one of 1, 2, 4, 8 or 16.
+    pub fn sample_count(mut self, sample_count: u32) -> Self {
+        self.sample_count = sample_count;
+        self
+    }
+
+    /// The dimensions of the buffer used to cache glyphs.
+    pub fn glyph_cache_size(mut self, size: [u32; 2]) -> Self {
+        self.glyph_cache_size = size;
+        self
+    }
+
+    /// Specifies the tolerances (maximum allowed difference) for judging whether an existing glyph
+    /// in the cache is close enough to the requested glyph in scale to be used in its place.
+    pub fn glyph_cache_scale_tolerance(mut self, tolerance: f32) -> Self {
+        self.glyph_cache_scale_tolerance = tolerance;
+        self
+    }
+
+    /// Specifies the tolerances (maximum allowed difference) for judging whether an existing glyph
+    /// in the cache is close enough to the requested glyph in subpixel offset to be used in its
+    /// place.
+    pub fn glyph_cache_position_tolerance(mut self, tolerance: f32) -> Self {
+        self.glyph_cache_position_tolerance = tolerance;
+        self
+    }
+
+    /// The dimensions of the buffer used to store the signed distance fields of glyphs.
+    pub fn sdf_atlas_size(mut self, size: [u32; 2]) -> Self {
+        self.sdf_atlas_size = size;
+        self
+    }
+
+    /// Build the **cpu::Renderer**.
+    ///
+    /// Panics if the sample count is not one of 1, 2, 4, 8 or 16.
+    pub fn build(self) -> Renderer {
+        assert!(
+            sample_offsets(self.sample_count).is_some(),
+            "unsupported sample count {}, expected one of 1, 2, 4, 8 or 16",
+            self.sample_count,
+        );
+        let glyph_cache = GlyphCache::new(
+            self.glyph_cache_size,
+            self.glyph_cache_scale_tolerance,
+            self.glyph_cache_position_tolerance,
+            self.sdf_atlas_size,
+        );
+        Renderer {
+            glyph_cache,
+            textures: Default::default(),
+            sample_count: self.sample_count,
+            render_commands: vec![],
+            mesh: Default::default(),
+            vertex_mode_buffer: vec![],
+            samples: vec![],
+            depths: vec![],
+        }
+    }
+}
+
+impl Renderer {
+    /// The default number of samples per pixel.
+    pub const DEFAULT_SAMPLE_COUNT: u32 = 4;
+
+    /// Create a new **cpu::Renderer** with the default sample count and glyph cache.
+    pub fn new() -> Self {
+        Builder::new().build()
+    }
+
+    /// The number of samples per pixel used for anti-aliasing.
+    pub fn sample_count(&self) -> u32 {
+        self.sample_count
+    }
+
+    /// Provide the pixels of the given texture for use when rendering.
+    ///
+    /// The image is expected to be encoded as sRGB. Returns the previous image for the texture if
+    /// there was one.
+    pub fn insert_texture(
+        &mut self,
+        texture: &dyn wgpu::ToTextureView,
+        image: image::RgbaImage,
+    ) -> Option<image::RgbaImage> {
+        let id = texture.to_texture_view().id();
+        self.textures.insert(id, image)
+    }
+
+    /// Remove the pixels of the given texture.
+    pub fn remove_texture(
+        &mut self,
+        texture: &dyn wgpu::ToTextureView,
+    ) -> Option<image::RgbaImage> {
+        let id = texture.to_texture_view().id();
+        self.textures.remove(&id)
+    }
+
+    /// Clear all pending render commands vertex data.
+    pub fn clear(&mut self) {
+        self.render_commands.clear();
+        self.mesh.clear();
+        self.vertex_mode_buffer.clear();
+    }
+
+    /// Generate a list of render commands from the given **Draw** instance and prepare any
+    /// necessary vertex data.
+    ///
+    /// Note that the given **Draw** instance will be *drained* of its commands.
+    pub fn fill(&mut self, draw: &draw::Draw, scale_factor: f32, output_size: [u32; 2]) {
+        let mut curr_start_index = 0;
+        let mut curr_pipeline = None;
+        let mut curr_texture = None;
+        let mut curr_scissor = None;
+
+        let Renderer {
+            ref mut glyph_cache,
+            ref mut mesh,
+            ref mut vertex_mode_buffer,
+            ref mut render_commands,
+            ..
+        } = *self;
+        super::tessellate_draw(
+            draw,
+            glyph_cache,
+            mesh,
+            vertex_mode_buffer,
+            scale_factor,
+            output_size,
+            |curr_ctxt, render: PrimitiveRender, prev_index_count| {
+                let new_pipeline = Pipeline {
+                    color_blend: curr_ctxt.color_blend.clone(),
+                    alpha_blend: curr_ctxt.alpha_blend.clone(),
+                    topology: curr_ctxt.topology,
+                };
+                let new_texture = (
+                    render.texture_view.map(|view| view.id()),
+                    Sampler::from(&curr_ctxt.sampler),
+                );
+                let new_scissor = curr_ctxt.scissor;
+
+                let pipeline_changed = Some(&new_pipeline) != curr_pipeline.as_ref();
+                let texture_changed = Some(new_texture) != curr_texture;
+                let scissor_changed = Some(new_scissor) != curr_scissor;
+
+                // Draw whatever pending vertices we have collected so far.
+                if pipeline_changed || texture_changed || scissor_changed {
+                    push_draw_cmd(&mut curr_start_index, prev_index_count, render_commands);
+                }
+                if pipeline_changed {
+                    curr_pipeline = Some(new_pipeline.clone());
+                    render_commands.push(RenderCommand::SetPipeline(new_pipeline));
+                }
+                if texture_changed {
+                    curr_texture = Some(new_texture);
+                    let (texture, sampler) = new_texture;
+                    render_commands.push(RenderCommand::SetTexture { texture, sampler });
+                }
+                if scissor_changed {
+                    curr_scissor = Some(new_scissor);
+                    let scissor = Scissor::from_draw(new_scissor, scale_factor, output_size);
+                    render_commands.push(RenderCommand::SetScissor(scissor));
+                }
+            },
+        );
+
+        // Insert the final draw command if there is still some drawing to be done.
+        push_draw_cmd(
+            &mut curr_start_index,
+            self.mesh.indices().len() as u32,
+            &mut self.render_commands,
+        );
+    }
+
+    /// Render the given **Draw** to a new image of the given size in pixels.
+    ///
+    /// Pixels that are not drawn to and are not covered by the **Draw**'s background color remain
+    /// transparent.
+    pub fn render(
+        &mut self,
+        draw: &draw::Draw,
+        scale_factor: f32,
+        size: [u32; 2],
+    ) -> image::RgbaImage {
+        let [w, h] = size;
+        let mut image = image::RgbaImage::new(w, h);
+        self.render_to_image(draw, scale_factor, &mut image);
+        image
+    }
+
+    /// Render the given **Draw** on top of the given image.
+    ///
+    /// If the **Draw** has a background color, the image is first cleared with that color.
+    /// Otherwise the existing contents of the image are blended with.
+    pub fn render_to_image(
+        &mut self,
+        draw: &draw::Draw,
+        scale_factor: f32,
+        image: &mut image::RgbaImage,
+    ) {
+        let size = [image.width(), image.height()];
+        self.clear();
+        self.fill(draw, scale_factor, size);
+        let bg_color = draw.state.borrow().background_color;
+        self.rasterize(bg_color, scale_factor, image);
+    }
+
+    // Follow the render commands, rasterising the mesh into the given image.
+    fn rasterize(
+        &mut self,
+        bg_color: Option<draw::mesh::vertex::Color>,
+        scale_factor: f32,
+        image: &mut image::RgbaImage,
+    ) {
+        let Renderer {
+            ref glyph_cache,
+            ref textures,
+            sample_count,
+            ref mut render_commands,
+            ref mesh,
+            ref vertex_mode_buffer,
+            ref mut samples,
+            ref mut depths,
+        } = *self;
+
+        let (w, h) = image.dimensions();
+        let sample_offsets = sample_offsets(sample_count).expect("unsupported sample count");
+        let n = sample_offsets.len();
+        let len = w as usize * h as usize * n;
+
+        // Initialise the samples with either the background color or the existing image.
+        samples.clear();
+        match bg_color {
+            Some(color) => {
+                let (r, g, b, a) = color.into();
+                samples.resize(len, [r, g, b, a]);
+            }
+            None => {
+                for pixel in image.pixels() {
+                    let color = srgba_to_linear(pixel.0);
+                    samples.extend((0..n).map(|_| color));
+                }
+            }
+        }
+        depths.clear();
+        depths.resize(len, 1.0);
+
+        // Converts from logical coordinates to pixels.
+        let half_w = w as f32 * 0.5 / scale_factor;
+        let half_h = h as f32 * 0.5 / scale_factor;
+        let far = std::cmp::max(w, h) as f32 / scale_factor;
+        let modes = vertex_mode_buffer;
+        let vertex = |i: u32| -> Vertex {
+            let i = i as usize;
+            let p = mesh.points()[i];
+            let position = [
+                (p.x + half_w) * scale_factor,
+                (half_h - p.y) * scale_factor,
+                0.5 * (1.0 - p.z / far),
+            ];
+            let (r, g, b, a) = mesh.colors()[i].into();
+            let tc = mesh.tex_coords()[i];
+            Vertex {
+                position,
+                color: [r, g, b, a],
+                tex_coords: [tc.x, tc.y],
+                mode: modes[i],
+            }
+        };
+
+        let default_pipeline = Pipeline {
+            color_blend: wgpu::BlendDescriptor::REPLACE,
+            alpha_blend: wgpu::BlendDescriptor::REPLACE,
+            topology: wgpu::PrimitiveTopology::TriangleList,
+        };
+        let mut pipeline = default_pipeline;
+        let mut texture = None;
+        let mut sampler = Sampler::from(&wgpu::SamplerBuilder::new().into_descriptor());
+        let mut scissor = [0, 0, w, h];
+
+        for cmd in render_commands.drain(..) {
+            match cmd {
+                RenderCommand::SetPipeline(new_pipeline) => pipeline = new_pipeline,
+                RenderCommand::SetTexture {
+                    texture: new_texture,
+                    sampler: new_sampler,
+                } => {
+                    texture = new_texture;
+                    sampler = new_sampler;
+                }
+                RenderCommand::SetScissor(Scissor {
+                    left,
+                    bottom,
+                    width,
+                    height,
+                }) => {
+                    // Convert from the bottom-left origin to rows from the top.
+                    let x0 = left.min(w);
+                    let x1 = (left + width).min(w);
+                    let y1 = h.saturating_sub(bottom);
+                    let y0 = h.saturating_sub(bottom + height);
+                    scissor = [x0, y0, x1, y1];
+                }
+                RenderCommand::DrawIndexed { index_range } => {
+                    let indices =
+                        &mesh.indices()[index_range.start as usize..index_range.end as usize];
+                    let mut target = Target {
+                        width: w,
+                        height: h,
+                        sample_offsets: &sample_offsets,
+                        samples: &mut samples[..],
+                        depths: &mut depths[..],
+                        scissor,
+                        pipeline: &pipeline,
+                        texture: texture.and_then(|id| textures.get(&id)),
+                        sampler,
+                        glyph_cache,
+                    };
+                    match pipeline.topology {
+                        wgpu::PrimitiveTopology::PointList => {
+                            for &i in indices {
+                                target.point(vertex(i));
+                            }
+                        }
+                        wgpu::PrimitiveTopology::LineList => {
+                            for l in indices.chunks_exact(2) {
+                                target.line(vertex(l[0]), vertex(l[1]));
+                            }
+                        }
+                        wgpu::PrimitiveTopology::LineStrip => {
+                            for l in indices.windows(2) {
+                                target.line(vertex(l[0]), vertex(l[1]));
+                            }
+                        }
+                        wgpu::PrimitiveTopology::TriangleList => {
+                            for t in indices.chunks_exact(3) {
+                                target.triangle([vertex(t[0]), vertex(t[1]), vertex(t[2])]);
+                            }
+                        }
+                        wgpu::PrimitiveTopology::TriangleStrip => {
+                            for t in indices.windows(3) {
+                                target.triangle([vertex(t[0]), vertex(t[1]), vertex(t[2])]);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // Resolve the samples into the image.
+        for (pixel, samples) in image.pixels_mut().zip(samples.chunks_exact(n)) {
+            let mut avg = [0.0; 4];
+            for s in samples {
+                for (a, c) in avg.iter_mut().zip(s) {
+                    *a += c / n as f32;
+                }
+            }
+            pixel.0 = linear_to_srgba(avg);
+        }
+    }
+}
+
+impl<'a> Target<'a> {
+    // Rasterise a single triangle.
+    fn triangle(&mut self, mut vs: [Vertex; 3]) {
+        let p = |v: &Vertex| geom::pt2(v.position[0], v.position[1]);
+        let mut area = edge(p(&vs[0]), p(&vs[1]), p(&vs[2]));
+        if area == 0.0 || !area.is_finite() {
+            return;
+        }
+        // Ensure a consistent winding so that the same edge rules apply to all triangles.
+        if area < 0.0 {
+            vs.swap(1, 2);
+            area = -area;
+        }
+        let [a, b, c] = [p(&vs[0]), p(&vs[1]), p(&vs[2])];
+
+        // The bounding box of the triangle, clipped to the scissor.
+        let [sx0, sy0, sx1, sy1] = self.scissor;
+        let min_x = a.x.min(b.x).min(c.x).floor().max(sx0 as f32) as u32;
+        let min_y = a.y.min(b.y).min(c.y).floor().max(sy0 as f32) as u32;
+        let max_x = (a.x.max(b.x).max(c.x).ceil().max(0.0) as u32).min(sx1);
+        let max_y = (a.y.max(b.y).max(c.y).ceil().max(0.0) as u32).min(sy1);
+
+        let edges = [(b, c), (c, a), (a, b)];
+        let n = self.sample_offsets.len();
+        let mut covered = vec![None; n];
+        for py in min_y..max_y {
+            for px in min_x..max_x {
+                // Determine the coverage and depth of each sample.
+                let mut any = false;
+                for (s, off) in self.sample_offsets.iter().enumerate() {
+                    let sp = geom::pt2(px as f32 + 0.5 + off[0], py as f32 + 0.5 + off[1]);
+                    covered[s] = barycentric(&edges, area, sp).map(|l| {
+                        any = true;
+                        interpolate(&l, |i| vs[i].position[2])
+                    });
+                }
+                if !any {
+                    continue;
+                }
+
+                // Shade the fragment once at the pixel centre.
+                let centre = geom::pt2(px as f32 + 0.5, py as f32 + 0.5);
+                let l = barycentric_unchecked(&edges, area, centre);
+                let color = [
+                    interpolate(&l, |i| vs[i].color[0]),
+                    interpolate(&l, |i| vs[i].color[1]),
+                    interpolate(&l, |i| vs[i].color[2]),
+                    interpolate(&l, |i| vs[i].color[3]),
+                ];
+                let tex_coords_at = |l: &[f32; 3]| {
+                    [
+                        interpolate(l, |i| vs[i].tex_coords[0]),
+                        interpolate(l, |i| vs[i].tex_coords[1]),
+                    ]
+                };
+                let tex_coords = tex_coords_at(&l);
+                // The change in texture coordinates across the pixel, like `dFdx` and `dFdy`.
+                let tex_coords_derivs = {
+                    let dx = geom::pt2(centre.x + 1.0, centre.y);
+                    let dy = geom::pt2(centre.x, centre.y + 1.0);
+                    let tx = tex_coords_at(&barycentric_unchecked(&edges, area, dx));
+                    let ty = tex_coords_at(&barycentric_unchecked(&edges, area, dy));
+                    [
+                        [tx[0] - tex_coords[0], tx[1] - tex_coords[1]],
+                        [ty[0] - tex_coords[0], ty[1] - tex_coords[1]],
+                    ]
+                };
+                // The vertex mode is flat, taken from the provoking vertex.
+                let frag = self.shade(vs[0].mode, color, tex_coords, tex_coords_derivs);
+                self.write(px, py, &covered, frag);
+            }
+        }
+    }
+
+    // Rasterise a line one pixel wide.
+    fn line(&mut self, a: Vertex, b: Vertex) {
+        let [ax, ay, _] = a.position;
+        let [bx, by, _] = b.position;
+        let steps = (bx - ax).abs().max((by - ay).abs()).ceil().max(1.0) as usize;
+        for step in 0..=steps {
+            let t = step as f32 / steps as f32;
+            let lerp = |x: f32, y: f32| x + (y - x) * t;
+            let v = Vertex {
+                position: [
+                    lerp(ax, bx),
+                    lerp(ay, by),
+                    lerp(a.position[2], b.position[2]),
+                ],
+                color: [
+                    lerp(a.color[0], b.color[0]),
+                    lerp(a.color[1], b.color[1]),
+                    lerp(a.color[2], b.color[2]),
+                    lerp(a.color[3], b.color[3]),
+                ],
+                tex_coords: [
+                    lerp(a.tex_coords[0], b.tex_coords[0]),
+                    lerp(a.tex_coords[1], b.tex_coords[1]),
+                ],
+                mode: a.mode,
+            };
+            self.point(v);
+        }
+    }
+
+    // Rasterise a single pixel covering all of its samples.
+    fn point(&mut self, v: Vertex) {
+        let [x, y, z] = v.position;
+        let [sx0, sy0, sx1, sy1] = self.scissor;
+        if x < sx0 as f32 || y < sy0 as f32 || x >= sx1 as f32 || y >= sy1 as f32 {
+            return;
+        }
+        let covered = vec![Some(z); self.sample_offsets.len()];
+        let frag = self.shade(v.mode, v.color, v.tex_coords, [[0.0; 2]; 2]);
+        self.write(x as u32, y as u32, &covered, frag);
+    }
+
+    // Produce the color of a fragment, matching the fragment shader of the GPU pipeline.
+    //
+    // The derivatives describe the change in texture coordinates along the x and y axes across
+    // the pixel.
+    fn shade(
+        &self,
+        mode: VertexMode,
+        color: [f32; 4],
+        tex_coords: [f32; 2],
+        tex_coords_derivs: [[f32; 2]; 2],
+    ) -> [f32; 4] {
+        match mode {
+            VertexMode::Color => color,
+            VertexMode::Texture => match self.texture {
+                None => [0.0; 4],
+                Some(image) => sample_texture(image, &self.sampler, tex_coords),
+            },
+            VertexMode::Text => {
+                let (w, h) = self.glyph_cache.cache.dimensions();
+                let buffer = &self.glyph_cache.pixel_buffer;
+                let texel = |x: u32, y: u32| buffer[y as usize * w as usize + x as usize];
+                let tex_a = sample_bilinear(w, h, tex_coords, |x, y| {
+                    let x = address(x, w, wgpu::AddressMode::ClampToEdge);
+                    let y = address(y, h, wgpu::AddressMode::ClampToEdge);
+                    let r = texel(x, y) as f32 / 255.0;
+                    [r, r, r, r]
+                })[0];
+                [color[0], color[1], color[2], color[3] * tex_a]
+            }
+            VertexMode::Sdf { edge, softness } => {
+                let atlas = &self.glyph_cache.sdf_atlas;
+                let [w, h] = atlas.dimensions();
+                let buffer = atlas.pixel_buffer();
+                let texel = |x: u32, y: u32| buffer[y as usize * w as usize + x as usize];
+                let distance = |uv: [f32; 2]| {
+                    sample_bilinear(w, h, uv, |x, y| {
+                        let x = address(x, w, wgpu::AddressMode::ClampToEdge);
+                        let y = address(y, h, wgpu::AddressMode::ClampToEdge);
+                        let r = texel(x, y) as f32 / 255.0;
+                        [r, r, r, r]
+                    })[0]
+                };
+                let [[dudx, dvdx], [dudy, dvdy]] = tex_coords_derivs;
+                let [u, v] = tex_coords;
+                let dist = distance(tex_coords);
+                let fwidth = (distance([u + dudx, v + dvdx]) - dist).abs()
+                    + (distance([u + dudy, v + dvdy]) - dist).abs();
+                let edge = edge as f32 / 255.0;
+                let w = (fwidth * 0.5 + softness as f32 / 255.0).max(SDF_MIN_SMOOTHING);
+                let a = smoothstep(edge - w, edge + w, dist);
+                [color[0], color[1], color[2], color[3] * a]
+            }
+        }
+    }
+
+    // Depth test and blend the fragment into each of the covered samples of the pixel.
+    fn write(&mut self, px: u32, py: u32, covered: &[Option<f32>], frag: [f32; 4]) {
+        debug_assert!(px < self.width && py < self.height);
+        let n = self.sample_offsets.len();
+        let start = (py as usize * self.width as usize + px as usize) * n;
+        for (s, depth) in covered.iter().enumerate() {
+            let depth = match *depth {
+                Some(depth) if (0.0..=1.0).contains(&depth) => depth,
+                _ => continue,
+            };
+            let ix = start + s;
+            if depth > self.depths[ix] {
+                continue;
+            }
+            self.depths[ix] = depth;
+            let dst = self.samples[ix];
+            self.samples[ix] = blend(self.pipeline, frag, dst);
+        }
+    }
+}
+
+impl<'a> From<&'a wgpu::SamplerDescriptor<'a>> for Sampler {
+    fn from(desc: &'a wgpu::SamplerDescriptor<'a>) -> Self {
+        Sampler {
+            address_mode_u: desc.address_mode_u,
+            address_mode_v: desc.address_mode_v,
+            mag_filter: desc.mag_filter,
+        }
+    }
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Default for Renderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Pushes a draw command and updates the `curr_start_index`.
+fn push_draw_cmd(
+    curr_start_index: &mut u32,
+    end_index: u32,
+    render_commands: &mut Vec<RenderCommand>,
+) {
+    let index_range = *curr_start_index..end_index;
+    if index_range.len() != 0 {
+        *curr_start_index = index_range.end;
+        render_commands.push(RenderCommand::DrawIndexed { index_range });
+    }
+}
+
+// The sample offsets in pixels for the given sample count.
+fn sample_offsets(sample_count: u32) -> Option<Vec<[f32; 2]>> {
+    let positions: &[[i8; 2]] = match sample_count {
+        1 => &SAMPLE_POSITIONS_1,
+        2 => &SAMPLE_POSITIONS_2,
+        4 => &SAMPLE_POSITIONS_4,
+        8 => &SAMPLE_POSITIONS_8,
+        16 => &SAMPLE_POSITIONS_16,
+        _ => return None,
+    };
+    let offsets = positions
+        .iter()
+        .map(|&[x, y]| [x as f32 / 16.0, y as f32 / 16.0])
+        .collect();
+    Some(offsets)
+}
+
+// Twice the signed area of the triangle `a`, `b`, `p`.
+fn edge(a: geom::Point2, b: geom::Point2, p: geom::Point2) -> f32 {
+    (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x)
+}
+
+// Whether or not the edge is a "top" or "left" edge for a triangle with positive area.
+//
+// Samples lying exactly on an edge are only covered by top and left edges so that pixels shared by
+// adjacent triangles are not drawn twice.
+fn is_top_left(a: geom::Point2, b: geom::Point2) -> bool {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    dy < 0.0 || (dy == 0.0 && dx > 0.0)
+}
+
+// The barycentric coordinates of `p` if it is covered by the triangle with the given edges.
+fn barycentric(
+    edges: &[(geom::Point2, geom::Point2); 3],
+    area: f32,
+    p: geom::Point2,
+) -> Option<[f32; 3]> {
+    let mut l = [0.0; 3];
+    for (i, &(a, b)) in edges.iter().enumerate() {
+        let w = edge(a, b, p);
+        if w < 0.0 || (w == 0.0 && !is_top_left(a, b)) {
+            return None;
+        }
+        l[i] = w / area;
+    }
+    Some(l)
+}
+
+// The barycentric coordinates of `p`, regardless of whether or not it lies within the triangle.
+fn barycentric_unchecked(
+    edges: &[(geom::Point2, geom::Point2); 3],
+    area: f32,
+    p: geom::Point2,
+) -> [f32; 3] {
+    let mut l = [0.0; 3];
+    for (i, &(a, b)) in edges.iter().enumerate() {
+        l[i] = edge(a, b, p) / area;
+    }
+    l
+}
+
+// Interpolate a vertex attribute using the given barycentric coordinates.
+//
+// The attribute is interpolated relative to the first vertex, so that an attribute that is equal
+// at each vertex is reproduced exactly. Otherwise rounding would cause flat primitives drawn at
+// the same depth to intermittently fail the depth test against one another.
+fn interpolate<F>(l: &[f32; 3], attr: F) -> f32
+where
+    F: Fn(usize) -> f32,
+{
+    let a = attr(0);
+    a + l[1] * (attr(1) - a) + l[2] * (attr(2) - a)
+}
+
+// Map the texel coordinate into the range `0..size` using the given address mode.
+fn address(i: i64, size: u32, mode: wgpu::AddressMode) -> u32 {
+    let n = size as i64;
+    let i = match mode {
+        wgpu::AddressMode::ClampToEdge => i.max(0).min(n - 1),
+        wgpu::AddressMode::Repeat => i.rem_euclid(n),
+        wgpu::AddressMode::MirrorRepeat => {
+            let m = i.rem_euclid(2 * n);
+            if m < n {
+                m
+            } else {
+                2 * n - 1 - m
+            }
+        }
+    };
+    i as u32
+}
+
+// Bilinearly sample a texture of the given size using the given texel fetching function.
+fn sample_bilinear<F>(w: u32, h: u32, uv: [f32; 2], texel: F) -> [f32; 4]
+where
+    F: Fn(i64, i64) -> [f32; 4],
+{
+    let x = uv[0] * w as f32 - 0.5;
+    let y = uv[1] * h as f32 - 0.5;
+    let (x0, y0) = (x.floor(), y.floor());
+    let (tx, ty) = (x - x0, y - y0);
+    let (x0, y0) = (x0 as i64, y0 as i64);
+    let tl = texel(x0, y0);
+    let tr = texel(x0 + 1, y0);
+    let bl = texel(x0, y0 + 1);
+    let br = texel(x0 + 1, y0 + 1);
+    let mut out = [0.0; 4];
+    for (c, out) in out.iter_mut().enumerate() {
+        let top = tl[c] + (tr[c] - tl[c]) * tx;
+        let bottom = bl[c] + (br[c] - bl[c]) * tx;
+        *out = top + (bottom - top) * ty;
+    }
+    out
+}
+
+// Sample the given sRGB image, returning a color in linear space.
+fn sample_texture(image: &image::RgbaImage, sampler: &Sampler, uv: [f32; 2]) -> [f32; 4] {
+    let (w, h) = image.dimensions();
+    if w == 0 || h == 0 {
+        return [0.0; 4];
+    }
+    let texel = |x: i64, y: i64| {
+        let x = address(x, w, sampler.address_mode_u);
+        let y = address(y, h, sampler.address_mode_v);
+        srgba_to_linear(image.get_pixel(x, y).0)
+    };
+    match sampler.mag_filter {
+        wgpu::FilterMode::Nearest => {
+            let x = (uv[0] * w as f32).floor() as i64;
+            let y = (uv[1] * h as f32).floor() as i64;
+            texel(x, y)
+        }
+        wgpu::FilterMode::Linear => sample_bilinear(w, h, uv, texel),
+    }
+}
+
+// Hermite interpolation between 0.0 and 1.0 as `x` moves from `edge0` to `edge1`, matching GLSL's
+// `smoothstep`.
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).max(0.0).min(1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+// Blend the source fragment with the destination sample using the given pipeline's blend
+// descriptors. The result is clamped to the range of a unorm output attachment.
+fn blend(pipeline: &Pipeline, src: [f32; 4], dst: [f32; 4]) -> [f32; 4] {
+    let mut out = [0.0; 4];
+    for (c, out) in out.iter_mut().enumerate() {
+        let desc = if c < 3 {
+            &pipeline.color_blend
+        } else {
+            &pipeline.alpha_blend
+        };
+        let s = src[c] * blend_factor(desc.src_factor, src, dst, c);
+        let d = dst[c] * blend_factor(desc.dst_factor, src, dst, c);
+        let v = match desc.operation {
+            wgpu::BlendOperation::Add => s + d,
+            wgpu::BlendOperation::Subtract => s - d,
+            wgpu::BlendOperation::ReverseSubtract => d - s,
+            // Factors are ignored for the min and max operations.
+            wgpu::BlendOperation::Min => src[c].min(dst[c]),
+            wgpu::BlendOperation::Max => src[c].max(dst[c]),
+        };
+        *out = v.max(0.0).min(1.0);
+    }
+    out
+}
+
+// The value of the blend factor for the given channel.
+//
+// The renderer never specifies a blend color, so the wgpu default of transparent black is used.
+fn blend_factor(factor: wgpu::BlendFactor, src: [f32; 4], dst: [f32; 4], c: usize) -> f32 {
+    match factor {
+        wgpu::BlendFactor::Zero => 0.0,
+        wgpu::BlendFactor::One => 1.0,
+        wgpu::BlendFactor::SrcColor => src[c],
+        wgpu::BlendFactor::OneMinusSrcColor => 1.0 - src[c],
+        wgpu::BlendFactor::SrcAlpha => src[3],
+        wgpu::BlendFactor::OneMinusSrcAlpha => 1.0 - src[3],
+        wgpu::BlendFactor::DstColor => dst[c],
+        wgpu::BlendFactor::OneMinusDstColor => 1.0 - dst[c],
+        wgpu::BlendFactor::DstAlpha => dst[3],
+        wgpu::BlendFactor::OneMinusDstAlpha => 1.0 - dst[3],
+        wgpu::BlendFactor::SrcAlphaSaturated => {
+            if c == 3 {
+                1.0
+            } else {
+                src[3].min(1.0 - dst[3])
+            }
+        }
+        wgpu::BlendFactor::BlendColor => 0.0,
+        wgpu::BlendFactor::OneMinusBlendColor => 1.0,
+    }
+}
+
+// Convert an sRGB encoded 8-bit pixel to linear space.
+fn srgba_to_linear([r, g, b, a]: [u8; 4]) -> [f32; 4] {
+    fn channel(c: u8) -> f32 {
+        let c = c as f32 / 255.0;
+        if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    }
+    [channel(r), channel(g), channel(b), a as f32 / 255.0]
+}
+
+// Convert a linear color to an sRGB encoded 8-bit pixel.
+fn linear_to_srgba([r, g, b, a]: [f32; 4]) -> [u8; 4] {
+    fn channel(c: f32) -> u8 {
+        let c = c.max(0.0).min(1.0);
+        let c = if c <= 0.003_130_8 {
+            c * 12.92
+        } else {
+            1.055 * c.powf(1.0 / 2.4) - 0.055
+        };
+        (c * 255.0).round() as u8
+    }
+    let a = (a.max(0.0).min(1.0) * 255.0).round() as u8;
+    [channel(r), channel(g), channel(b), a]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Rasterise a quad covering the whole target, textured with the given sampler and range of
+    // texture coordinates, producing the resolved sRGB pixels.
+    fn textured_quad(texture: &image::RgbaImage, sampler: Sampler, uv_max: f32) -> Vec<[u8; 4]> {
+        let (w, h) = (8, 8);
+        let renderer = Renderer::new();
+        let offsets = sample_offsets(1).unwrap();
+        let mut samples = vec![[0.0; 4]; w as usize * h as usize];
+        let mut depths = vec![1.0; samples.len()];
+        let pipeline = Pipeline {
+            color_blend: wgpu::BlendDescriptor::REPLACE,
+            alpha_blend: wgpu::BlendDescriptor::REPLACE,
+            topology: wgpu::PrimitiveTopology::TriangleList,
+        };
+        let mut target = Target {
+            width: w,
+            height: h,
+            sample_offsets: &offsets,
+            samples: &mut samples,
+            depths: &mut depths,
+            scissor: [0, 0, w, h],
+            pipeline: &pipeline,
+            texture: Some(texture),
+            sampler,
+            glyph_cache: &renderer.glyph_cache,
+        };
+        let vertex = |x: f32, y: f32| Vertex {
+            position: [x * w as f32, y * h as f32, 0.5],
+            color: [1.0; 4],
+            tex_coords: [x * uv_max, y * uv_max],
+            mode: VertexMode::Texture,
+        };
+        let [a, b, c, d] = [
+            vertex(0.0, 0.0),
+            vertex(1.0, 0.0),
+            vertex(1.0, 1.0),
+            vertex(0.0, 1.0),
+        ];
+        target.triangle([a, b, c]);
+        target.triangle([a, c, d]);
+        samples.into_iter().map(linear_to_srgba).collect()
+    }
+
+    #[test]
+    fn textured_quads_sample_their_texture() {
+        let texels = [
+            [255, 0, 0, 255],
+            [0, 255, 0, 255],
+            [0, 0, 255, 255],
+            [255; 4],
+        ];
+        let texture =
+            image::RgbaImage::from_fn(2, 2, |x, y| image::Rgba(texels[(y * 2 + x) as usize]));
+        let pixel = |pixels: &[[u8; 4]], x: usize, y: usize| pixels[y * 8 + x];
+
+        // Each quadrant of the quad is covered by the nearest texel.
+        let nearest = Sampler {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Nearest,
+        };
+        let pixels = textured_quad(&texture, nearest, 1.0);
+        assert_eq!(pixel(&pixels, 1, 1), texels[0]);
+        assert_eq!(pixel(&pixels, 6, 1), texels[1]);
+        assert_eq!(pixel(&pixels, 1, 6), texels[2]);
+        assert_eq!(pixel(&pixels, 6, 6), texels[3]);
+
+        // Texture coordinates beyond the edge wrap when repeating.
+        let repeat = Sampler {
+            address_mode_u: wgpu::AddressMode::Repeat,
+            address_mode_v: wgpu::AddressMode::Repeat,
+            ..nearest
+        };
+        let pixels = textured_quad(&texture, repeat, 2.0);
+        assert_eq!(pixel(&pixels, 0, 0), texels[0]);
+        assert_eq!(pixel(&pixels, 4, 0), texels[0]);
+        assert_eq!(pixel(&pixels, 6, 6), texels[3]);
+
+        // Linear filtering blends neighbouring texels.
+        let linear = Sampler {
+            mag_filter: wgpu::FilterMode::Linear,
+            ..nearest
+        };
+        let pixels = textured_quad(&texture, linear, 1.0);
+        let [r, g, _, _] = pixel(&pixels, 4, 1);
+        assert!(r > 0 && r < 255 && g > 0 && g < 255);
+    }
+}
@@ -3,6 +3,7 @@ use crate::draw::mesh::vertex::Color;
 use crate::frame::Frame;
 use crate::geom::{self, Point2, Rect, Vector2};
 use crate::math::{map_range, Matrix4};
+use crate::serde_derive::{Deserialize, Serialize};
 use crate::text;
 use crate::wgpu;
 use lyon::path::PathEvent;
@@ -13,6 +14,8 @@ use std::hash::{Hash, Hasher};
 use std::ops::{Deref, DerefMut};
 use wgpu::util::{BufferInitDescriptor, DeviceExt};
 
+pub mod cpu;
+
 /// Draw API primitives that may be rendered via the **Renderer** type.
 pub trait RenderPrimitive {
     /// Render self into the given mesh.
@@ -29,6 +32,13 @@ pub struct PrimitiveRender {
     pub texture_view: Option<wgpu::TextureView>,
     /// The way in which vertices should be coloured in the fragment shader.
     pub vertex_mode: VertexMode,
+    /// Runs of vertices, at the start of those submitted by the primitive, that should be coloured
+    /// via a mode other than the `vertex_mode`. Each run is described by its number of vertices and
+    /// its mode.
+    ///
+    /// This allows for text primitives to submit solid underlines, outlines and glows alongside
+    /// their glyphs.
+    pub vertex_mode_runs: Vec<(usize, VertexMode)>,
 }
 
 /// The context provided to primitives to assist with the rendering process.
@@ -54,20 +64,28 @@ pub struct GlyphCache {
     pub pixel_buffer: Vec<u8>,
     /// Will be set to `true` after the cache has been updated if the texture requires re-uploading.
     pub requires_upload: bool,
+    /// The signed distance fields of glyphs drawn via the text primitive's `sdf` mode.
+    pub sdf_atlas: text::sdf::Atlas,
 }
 
 /// A top-level indicator of whether or not
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
-#[repr(u32)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub enum VertexMode {
     /// Use the color values and ignore the texture coordinates.
-    Color = 0,
+    Color,
     /// Use the texture color and ignore the color values.
-    Texture = 1,
+    Texture,
     /// A special mode used by the text primitive.
     ///
     /// Uses the color values, but multiplies the alpha by the glyph cache texture's red value.
-    Text = 2,
+    Text,
+    /// A special mode used by the text primitive when drawing via signed distance fields.
+    ///
+    /// Uses the color values, but multiplies the alpha by the coverage of the shape found by
+    /// thresholding the signed distance field atlas texture's red value about the `edge`. The
+    /// threshold is smoothed over the width of a pixel plus the `softness`. Both are in 255ths of
+    /// the range of the atlas texture's values, within which `128` lies on the outline of a glyph.
+    Sdf { edge: u8, softness: u8 },
 }
 
 /// A helper type aimed at simplifying the rendering of conrod primitives via wgpu.
@@ -79,6 +97,7 @@ pub struct Renderer {
     // One pipeline per unique Pipeline ID (combination of blend, topology and component type).
     pipelines: HashMap<PipelineId, wgpu::RenderPipeline>,
     glyph_cache_texture: wgpu::Texture,
+    sdf_atlas_texture: wgpu::Texture,
     depth_texture: wgpu::Texture,
     depth_texture_view: wgpu::TextureView,
     default_texture: wgpu::Texture,
@@ -106,6 +125,7 @@ pub struct Builder {
     pub glyph_cache_size: [u32; 2],
     pub glyph_cache_scale_tolerance: f32,
     pub glyph_cache_position_tolerance: f32,
+    pub sdf_atlas_size: [u32; 2],
 }
 
 /// Commands that map to wgpu encodable commands.
@@ -187,6 +207,7 @@ impl RenderPrimitive for draw::Primitive {
             draw::Primitive::Rect(prim) => prim.render_primitive(ctxt, mesh),
             draw::Primitive::Line(prim) => prim.render_primitive(ctxt, mesh),
             draw::Primitive::Text(prim) => prim.render_primitive(ctxt, mesh),
+            draw::Primitive::TextOnPath(prim) => prim.render_primitive(ctxt, mesh),
             draw::Primitive::Texture(prim) => prim.render_primitive(ctxt, mesh),
             _ => PrimitiveRender::default(),
         }
@@ -199,16 +220,36 @@ impl fmt::Debug for GlyphCache {
             .field("cache", &self.cache.dimensions())
             .field("pixel_buffer", &self.pixel_buffer.len())
             .field("requires_upload", &self.requires_upload)
+            .field("sdf_atlas", &self.sdf_atlas.dimensions())
             .finish()
     }
 }
 
+impl VertexMode {
+    /// The edge at which the shape described by a signed distance field is thresholded.
+    pub const SDF_OUTLINE: u8 = 128;
+
+    /// The word by which the mode is described to the fragment shader.
+    ///
+    /// The lowest byte identifies the mode, while the second and third bytes contain the `edge`
+    /// and `softness` of the `Sdf` mode.
+    pub fn to_u32(self) -> u32 {
+        match self {
+            VertexMode::Color => 0,
+            VertexMode::Texture => 1,
+            VertexMode::Text => 2,
+            VertexMode::Sdf { edge, softness } => 3 | (edge as u32) << 8 | (softness as u32) << 16,
+        }
+    }
+}
+
 impl PrimitiveRender {
     /// Specify a vertex mode for the primitive render.
     pub fn vertex_mode(vertex_mode: VertexMode) -> Self {
         PrimitiveRender {
             texture_view: None,
             vertex_mode,
+            vertex_mode_runs: vec![],
         }
     }
 
@@ -220,12 +261,20 @@ impl PrimitiveRender {
         PrimitiveRender {
             vertex_mode: VertexMode::Texture,
             texture_view: Some(texture_view),
+            vertex_mode_runs: vec![],
         }
     }
 
     pub fn text() -> Self {
         Self::vertex_mode(VertexMode::Text)
     }
+
+    pub fn sdf_text() -> Self {
+        Self::vertex_mode(VertexMode::Sdf {
+            edge: VertexMode::SDF_OUTLINE,
+            softness: 0,
+        })
+    }
 }
 
 impl Builder {
@@ -239,6 +288,8 @@ impl Builder {
     /// The default position tolerance for the glyph cache.
     pub const DEFAULT_GLYPH_CACHE_POSITION_TOLERANCE: f32 =
         Renderer::DEFAULT_GLYPH_CACHE_POSITION_TOLERANCE;
+    /// The default size for the atlas of signed distance field glyphs.
+    pub const DEFAULT_SDF_ATLAS_SIZE: [u32; 2] = Renderer::DEFAULT_SDF_ATLAS_SIZE;
 
     /// Begin building a new **draw::Renderer**.
     pub fn new() -> Self {
@@ -247,6 +298,7 @@ impl Builder {
             glyph_cache_size: Self::DEFAULT_GLYPH_CACHE_SIZE,
             glyph_cache_scale_tolerance: Self::DEFAULT_GLYPH_CACHE_SCALE_TOLERANCE,
             glyph_cache_position_tolerance: Self::DEFAULT_GLYPH_CACHE_POSITION_TOLERANCE,
+            sdf_atlas_size: Self::DEFAULT_SDF_ATLAS_SIZE,
         }
     }
 
@@ -286,6 +338,16 @@ impl Builder {
         self
     }
 
+    /// The dimensions of the texture used to store the signed distance fields of glyphs drawn via
+    /// the text primitive's `sdf` mode.
+    ///
+    /// Fields are generated once per glyph regardless of the size at which they are drawn. If the
+    /// atlas fills up, it is cleared and refilled with the glyphs of the current text.
+    pub fn sdf_atlas_size(mut self, size: [u32; 2]) -> Self {
+        self.sdf_atlas_size = size;
+        self
+    }
+
     /// Build the **draw::Renderer** ready to target an output attachment of the given descriptor.
     pub fn build_from_texture_descriptor(
         self,
@@ -322,12 +384,18 @@ impl Builder {
             self.glyph_cache_size,
             self.glyph_cache_scale_tolerance,
             self.glyph_cache_position_tolerance,
+            self.sdf_atlas_size,
         )
     }
 }
 
 impl GlyphCache {
-    fn new(size: [u32; 2], scale_tolerance: f32, position_tolerance: f32) -> Self {
+    fn new(
+        size: [u32; 2],
+        scale_tolerance: f32,
+        position_tolerance: f32,
+        sdf_atlas_size: [u32; 2],
+    ) -> Self {
         let [w, h] = size;
         let cache = text::GlyphCache::builder()
             .dimensions(w, h)
@@ -337,10 +405,16 @@ impl GlyphCache {
             .into();
         let pixel_buffer = vec![0u8; w as usize * h as usize];
         let requires_upload = false;
+        let sdf_atlas = text::sdf::Atlas::new(
+            sdf_atlas_size,
+            text::sdf::DEFAULT_SIZE,
+            text::sdf::DEFAULT_SPREAD,
+        );
         GlyphCache {
             cache,
             pixel_buffer,
             requires_upload,
+            sdf_atlas,
         }
     }
 }
@@ -356,6 +430,10 @@ impl Renderer {
     pub const DEFAULT_GLYPH_CACHE_POSITION_TOLERANCE: f32 = 0.1;
     /// The texture format of the inner glyph cache.
     pub const GLYPH_CACHE_TEXTURE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R8Unorm;
+    /// The default size for the atlas of signed distance field glyphs.
+    pub const DEFAULT_SDF_ATLAS_SIZE: [u32; 2] = [1024; 2];
+    /// The texture format of the atlas of signed distance field glyphs.
+    pub const SDF_ATLAS_TEXTURE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R8Unorm;
 
     /// Create a new **Renderer**, ready to target an output attachment with the given size, sample
     /// count and color format.
@@ -367,6 +445,9 @@ impl Renderer {
     ///
     /// The `glyph_cache_size` will be used to create a texture on which glyphs will be stored for
     /// efficient look-up.
+    ///
+    /// The `sdf_atlas_size` will be used to create a texture on which the signed distance fields
+    /// of glyphs will be stored.
     pub fn new(
         device: &wgpu::Device,
         output_attachment_size: [u32; 2],
@@ -377,12 +458,14 @@ impl Renderer {
         glyph_cache_size: [u32; 2],
         glyph_cache_scale_tolerance: f32,
         glyph_cache_position_tolerance: f32,
+        sdf_atlas_size: [u32; 2],
     ) -> Self {
         // Construct the glyph cache.
         let glyph_cache = GlyphCache::new(
             glyph_cache_size,
             glyph_cache_scale_tolerance,
             glyph_cache_position_tolerance,
+            sdf_atlas_size,
         );
 
         // Load shader modules.
@@ -399,6 +482,15 @@ impl Renderer {
         let glyph_cache_texture_view =
             glyph_cache_texture.create_view(&wgpu::TextureViewDescriptor::default());
 
+        // Create the signed distance field atlas texture.
+        let sdf_atlas_texture = wgpu::TextureBuilder::new()
+            .size(sdf_atlas_size)
+            .usage(wgpu::TextureUsage::SAMPLED | wgpu::TextureUsage::COPY_DST)
+            .format(Self::SDF_ATLAS_TEXTURE_FORMAT)
+            .build(device);
+        let sdf_atlas_texture_view =
+            sdf_atlas_texture.create_view(&wgpu::TextureViewDescriptor::default());
+
         // Create the depth texture.
         let depth_texture =
             create_depth_texture(device, output_attachment_size, depth_format, sample_count);
@@ -433,6 +525,7 @@ impl Renderer {
             &text_bind_group_layout,
             &text_sampler,
             &glyph_cache_texture_view,
+            &sdf_atlas_texture_view,
         );
 
         // Initialise the sampler set with the default sampler.
@@ -457,6 +550,7 @@ impl Renderer {
             fs_mod,
             glyph_cache,
             glyph_cache_texture,
+            sdf_atlas_texture,
             depth_texture,
             depth_texture_view,
             default_texture,
@@ -497,49 +591,7 @@ impl Renderer {
         scale_factor: f32,
         output_attachment_size: [u32; 2],
     ) {
-        // Pushes a draw command and updates the `curr_start_index`.
-        //
-        // Returns `true` if the command was added, `false` if there was nothing to
-        // draw.
-        fn push_draw_cmd(
-            curr_start_index: &mut u32,
-            end_index: u32,
-            render_commands: &mut Vec<RenderCommand>,
-        ) -> bool {
-            let index_range = *curr_start_index..end_index;
-            if index_range.len() != 0 {
-                let start_vertex = 0;
-                *curr_start_index = index_range.end;
-                let cmd = RenderCommand::DrawIndexed {
-                    start_vertex,
-                    index_range,
-                };
-                render_commands.push(cmd);
-                true
-            } else {
-                false
-            }
-        }
-
-        let [w_px, h_px] = output_attachment_size;
-
-        // Converting between pixels and points.
-        let px_to_pt = |s: u32| s as f32 / scale_factor;
-        let pt_to_px = |s: f32| (s * scale_factor).round() as u32;
-        let full_rect = Rect::from_w_h(px_to_pt(w_px), px_to_pt(h_px));
-
-        let window_to_scissor = |v: Vector2| -> [u32; 2] {
-            let x = map_range(v.x, full_rect.left(), full_rect.right(), 0u32, w_px);
-            let y = map_range(v.y, full_rect.bottom(), full_rect.top(), 0u32, h_px);
-            [x, y]
-        };
-
-        // TODO: Store these in `Renderer`.
-        let mut fill_tessellator = FillTessellator::new();
-        let mut stroke_tessellator = StrokeTessellator::new();
-
         // Keep track of context changes.
-        let mut curr_ctxt = draw::Context::default();
         let mut new_pipeline_ids = HashMap::new();
         let mut curr_start_index = 0;
         let mut new_tex_views = HashMap::new();
@@ -549,143 +601,93 @@ impl Renderer {
         let mut curr_scissor = None;
         let mut curr_tex_sampler_id = None;
 
-        // Collect all draw commands to avoid borrow errors.
-        let draw_cmds: Vec<_> = draw.drain_commands().collect();
-        let draw_state = draw.state.borrow_mut();
-        let intermediary_state = draw_state.intermediary_state.borrow();
-        for cmd in draw_cmds {
-            match cmd {
-                draw::DrawCommand::Context(ctxt) => curr_ctxt = ctxt,
-                draw::DrawCommand::Primitive(prim) => {
-                    // Track the prev index and vertex counts.
-                    let prev_index_count = self.mesh.indices().len() as u32;
-                    let prev_vert_count = self.mesh.vertex_count();
-
-                    // Info required during rendering.
-                    let ctxt = RenderContext {
-                        intermediary_mesh: &intermediary_state.intermediary_mesh,
-                        path_event_buffer: &intermediary_state.path_event_buffer,
-                        path_points_colored_buffer: &intermediary_state.path_points_colored_buffer,
-                        path_points_textured_buffer: &intermediary_state
-                            .path_points_textured_buffer,
-                        text_buffer: &intermediary_state.text_buffer,
-                        theme: &draw_state.theme,
-                        transform: &curr_ctxt.transform,
-                        fill_tessellator: &mut fill_tessellator,
-                        stroke_tessellator: &mut stroke_tessellator,
-                        glyph_cache: &mut self.glyph_cache,
-                        output_attachment_size: Vector2::new(px_to_pt(w_px), px_to_pt(h_px)),
-                        output_attachment_scale_factor: scale_factor,
-                    };
-
-                    // Render the primitive.
-                    let render = prim.render_primitive(ctxt, &mut self.mesh);
-
-                    // If the mesh indices are unchanged, there's nothing to be drawn.
-                    if prev_index_count == self.mesh.indices().len() as u32 {
-                        assert_eq!(
-                            prev_vert_count,
-                            self.mesh.vertex_count(),
-                            "vertices were submitted during `render` without submitting indices",
-                        );
-                        continue;
-                    }
-
-                    // Retrieve the current texture view and texture view ID. These are necessary
-                    // for producing the curren tpipeline and bind group IDs. Also ensure we have
-                    // an entry for them in our map.
-                    let tex_view = match render.texture_view {
-                        Some(tex_view) => tex_view,
-                        None => self.default_texture_view.clone(),
-                    };
-                    let tex_view_id = tex_view.id();
-                    let texture_component_type = tex_view.component_type();
-                    new_tex_views.insert(tex_view_id, tex_view);
-
-                    // Determine the new current bind group layout ID, pipeline ID, bind group ID
-                    // and scissor required for drawing this primitive.
-                    let new_pipeline_id = {
-                        let color_id = blend_descriptor_hash(&curr_ctxt.color_blend);
-                        let alpha_id = blend_descriptor_hash(&curr_ctxt.alpha_blend);
-                        let topology = curr_ctxt.topology;
-                        PipelineId {
-                            color_id,
-                            alpha_id,
-                            topology,
-                            texture_component_type,
-                        }
-                    };
-                    let new_bind_group_id = {
-                        let sampler_id = sampler_descriptor_hash(&curr_ctxt.sampler);
-                        (sampler_id, tex_view_id)
-                    };
-                    let new_scissor = curr_ctxt.scissor;
-
-                    // Determine which have changed and in turn which require submitting new
-                    // commands.
-                    let pipeline_changed = Some(new_pipeline_id) != curr_pipeline_id;
-                    let bind_group_changed = Some(new_bind_group_id) != curr_tex_sampler_id;
-                    let scissor_changed = Some(new_scissor) != curr_scissor;
-
-                    // If we require submitting a scissor, pipeline or bind group command, first
-                    // draw whatever pending vertices we have collected so far. If there have been
-                    // no graphics yet, this will do nothing.
-                    if scissor_changed || pipeline_changed || bind_group_changed {
-                        push_draw_cmd(
-                            &mut curr_start_index,
-                            prev_index_count,
-                            &mut self.render_commands,
-                        );
-                    }
-
-                    // If necessary, push a new pipeline command.
-                    if pipeline_changed {
-                        curr_pipeline_id = Some(new_pipeline_id);
-                        let color_blend = curr_ctxt.color_blend.clone();
-                        let alpha_blend = curr_ctxt.alpha_blend.clone();
-                        new_pipeline_ids.insert(new_pipeline_id, (color_blend, alpha_blend));
-                        let cmd = RenderCommand::SetPipeline(new_pipeline_id);
-                        self.render_commands.push(cmd);
+        let Renderer {
+            ref mut glyph_cache,
+            ref mut mesh,
+            ref mut vertex_mode_buffer,
+            ref mut render_commands,
+            ref default_texture_view,
+            ..
+        } = *self;
+        let curr_ctxt = tessellate_draw(
+            draw,
+            glyph_cache,
+            mesh,
+            vertex_mode_buffer,
+            scale_factor,
+            output_attachment_size,
+            |curr_ctxt, render, prev_index_count| {
+                // Retrieve the current texture view and texture view ID. These are necessary
+                // for producing the curren tpipeline and bind group IDs. Also ensure we have
+                // an entry for them in our map.
+                let tex_view = match render.texture_view {
+                    Some(tex_view) => tex_view,
+                    None => default_texture_view.clone(),
+                };
+                let tex_view_id = tex_view.id();
+                let texture_component_type = tex_view.component_type();
+                new_tex_views.insert(tex_view_id, tex_view);
+
+                // Determine the new current bind group layout ID, pipeline ID, bind group ID
+                // and scissor required for drawing this primitive.
+                let new_pipeline_id = {
+                    let color_id = blend_descriptor_hash(&curr_ctxt.color_blend);
+                    let alpha_id = blend_descriptor_hash(&curr_ctxt.alpha_blend);
+                    let topology = curr_ctxt.topology;
+                    PipelineId {
+                        color_id,
+                        alpha_id,
+                        topology,
+                        texture_component_type,
                     }
+                };
+                let new_bind_group_id = {
+                    let sampler_id = sampler_descriptor_hash(&curr_ctxt.sampler);
+                    (sampler_id, tex_view_id)
+                };
+                let new_scissor = curr_ctxt.scissor;
+
+                // Determine which have changed and in turn which require submitting new
+                // commands.
+                let pipeline_changed = Some(new_pipeline_id) != curr_pipeline_id;
+                let bind_group_changed = Some(new_bind_group_id) != curr_tex_sampler_id;
+                let scissor_changed = Some(new_scissor) != curr_scissor;
+
+                // If we require submitting a scissor, pipeline or bind group command, first
+                // draw whatever pending vertices we have collected so far. If there have been
+                // no graphics yet, this will do nothing.
+                if scissor_changed || pipeline_changed || bind_group_changed {
+                    push_draw_cmd(&mut curr_start_index, prev_index_count, render_commands);
+                }
 
-                    // If necessary, push a new bind group command.
-                    if bind_group_changed {
-                        curr_tex_sampler_id = Some(new_bind_group_id);
-                        new_tex_sampler_combos.insert(new_bind_group_id, new_pipeline_id);
-                        let cmd = RenderCommand::SetBindGroup(new_bind_group_id);
-                        self.render_commands.push(cmd);
-                    }
+                // If necessary, push a new pipeline command.
+                if pipeline_changed {
+                    curr_pipeline_id = Some(new_pipeline_id);
+                    let color_blend = curr_ctxt.color_blend.clone();
+                    let alpha_blend = curr_ctxt.alpha_blend.clone();
+                    new_pipeline_ids.insert(new_pipeline_id, (color_blend, alpha_blend));
+                    let cmd = RenderCommand::SetPipeline(new_pipeline_id);
+                    render_commands.push(cmd);
+                }
 
-                    // If necessary, push a new scissor command.
-                    if scissor_changed {
-                        curr_scissor = Some(new_scissor);
-                        let rect = match curr_ctxt.scissor {
-                            draw::Scissor::Full => full_rect,
-                            draw::Scissor::Rect(rect) => full_rect
-                                .overlap(rect)
-                                .unwrap_or(geom::Rect::from_w_h(0.0, 0.0)),
-                            draw::Scissor::NoOverlap => geom::Rect::from_w_h(0.0, 0.0),
-                        };
-                        let [left, bottom] = window_to_scissor(rect.bottom_left());
-                        let (width, height) = rect.w_h();
-                        let (width, height) = (pt_to_px(width), pt_to_px(height));
-                        let scissor = Scissor {
-                            left,
-                            bottom,
-                            width,
-                            height,
-                        };
-                        let cmd = RenderCommand::SetScissor(scissor);
-                        self.render_commands.push(cmd);
-                    }
+                // If necessary, push a new bind group command.
+                if bind_group_changed {
+                    curr_tex_sampler_id = Some(new_bind_group_id);
+                    new_tex_sampler_combos.insert(new_bind_group_id, new_pipeline_id);
+                    let cmd = RenderCommand::SetBindGroup(new_bind_group_id);
+                    render_commands.push(cmd);
+                }
 
-                    // Extend the vertex mode channel.
-                    let mode = render.vertex_mode;
-                    let new_vs = self.mesh.points().len() - self.vertex_mode_buffer.len();
-                    self.vertex_mode_buffer.extend((0..new_vs).map(|_| mode));
+                // If necessary, push a new scissor command.
+                if scissor_changed {
+                    curr_scissor = Some(new_scissor);
+                    let scissor =
+                        Scissor::from_draw(new_scissor, scale_factor, output_attachment_size);
+                    let cmd = RenderCommand::SetScissor(scissor);
+                    render_commands.push(cmd);
                 }
-            }
-        }
+            },
+        );
 
         // Insert the final draw command if there is still some drawing to be done.
         push_draw_cmd(
@@ -774,8 +776,9 @@ impl Renderer {
 
         let Renderer {
             ref pipelines,
-            ref glyph_cache,
+            ref mut glyph_cache,
             ref glyph_cache_texture,
+            ref sdf_atlas_texture,
             ref mut depth_texture,
             ref mut depth_texture_view,
             ref uniform_bind_group,
@@ -793,6 +796,11 @@ impl Renderer {
         if glyph_cache.requires_upload {
             glyph_cache_texture.upload_data(device, encoder, &glyph_cache.pixel_buffer);
         }
+        if glyph_cache.sdf_atlas.requires_upload() {
+            let pixels = glyph_cache.sdf_atlas.pixel_buffer();
+            sdf_atlas_texture.upload_data(device, encoder, pixels);
+            glyph_cache.sdf_atlas.uploaded();
+        }
 
         // Resize the depth texture if the output attachment size has changed.
         let depth_size = depth_texture.size();
@@ -835,7 +843,8 @@ impl Renderer {
         let points_bytes = points_as_bytes(mesh.points());
         let colors_bytes = colors_as_bytes(mesh.colors());
         let tex_coords_bytes = tex_coords_as_bytes(mesh.tex_coords());
-        let modes_bytes = vertex_modes_as_bytes(vertex_mode_buffer);
+        let mode_words: Vec<u32> = vertex_mode_buffer.iter().map(|m| m.to_u32()).collect();
+        let modes_bytes = vertex_modes_as_bytes(&mode_words);
         let indices_bytes = indices_as_bytes(mesh.indices());
         let point_buffer = device.create_buffer_init(&BufferInitDescriptor {
             label: Some("nannou Renderer point_buffer"),
@@ -996,6 +1005,165 @@ impl DerefMut for GlyphCache {
     }
 }
 
+impl Scissor {
+    /// Convert the given **draw::Scissor** to a scissor in pixels within an output attachment of
+    /// the given size.
+    pub(crate) fn from_draw(
+        scissor: draw::Scissor,
+        scale_factor: f32,
+        output_attachment_size: [u32; 2],
+    ) -> Self {
+        let [w_px, h_px] = output_attachment_size;
+
+        // Converting between pixels and points.
+        let px_to_pt = |s: u32| s as f32 / scale_factor;
+        let pt_to_px = |s: f32| (s * scale_factor).round() as u32;
+        let full_rect = Rect::from_w_h(px_to_pt(w_px), px_to_pt(h_px));
+
+        let window_to_scissor = |v: Vector2| -> [u32; 2] {
+            let x = map_range(v.x, full_rect.left(), full_rect.right(), 0u32, w_px);
+            let y = map_range(v.y, full_rect.bottom(), full_rect.top(), 0u32, h_px);
+            [x, y]
+        };
+
+        let rect = match scissor {
+            draw::Scissor::Full => full_rect,
+            draw::Scissor::Rect(rect) => full_rect
+                .overlap(rect)
+                .unwrap_or(geom::Rect::from_w_h(0.0, 0.0)),
+            draw::Scissor::NoOverlap => geom::Rect::from_w_h(0.0, 0.0),
+        };
+        let [left, bottom] = window_to_scissor(rect.bottom_left());
+        let (width, height) = rect.w_h();
+        let (width, height) = (pt_to_px(width), pt_to_px(height));
+        Scissor {
+            left,
+            bottom,
+            width,
+            height,
+        }
+    }
+}
+
+// Pushes a draw command and updates the `curr_start_index`.
+//
+// Returns `true` if the command was added, `false` if there was nothing to
+// draw.
+fn push_draw_cmd(
+    curr_start_index: &mut u32,
+    end_index: u32,
+    render_commands: &mut Vec<RenderCommand>,
+) -> bool {
+    let index_range = *curr_start_index..end_index;
+    if index_range.len() != 0 {
+        let start_vertex = 0;
+        *curr_start_index = index_range.end;
+        let cmd = RenderCommand::DrawIndexed {
+            start_vertex,
+            index_range,
+        };
+        render_commands.push(cmd);
+        true
+    } else {
+        false
+    }
+}
+
+// Render each primitive of the given **Draw** into the given mesh, extending the vertex mode
+// buffer as necessary.
+//
+// The `primitive_rendered` function is called for every primitive that submitted at least one
+// index along with the context in which it was drawn, the info about how it was rendered and the
+// length of the mesh's index buffer prior to rendering the primitive.
+//
+// Returns the last context that was set.
+//
+// Note that the given **Draw** instance will be *drained* of its commands.
+fn tessellate_draw<F>(
+    draw: &draw::Draw,
+    glyph_cache: &mut GlyphCache,
+    mesh: &mut draw::Mesh,
+    vertex_mode_buffer: &mut Vec<VertexMode>,
+    scale_factor: f32,
+    output_attachment_size: [u32; 2],
+    mut primitive_rendered: F,
+) -> draw::Context
+where
+    F: FnMut(&draw::Context, PrimitiveRender, u32),
+{
+    let [w_px, h_px] = output_attachment_size;
+    let px_to_pt = |s: u32| s as f32 / scale_factor;
+
+    // TODO: Store these in `Renderer`.
+    let mut fill_tessellator = FillTessellator::new();
+    let mut stroke_tessellator = StrokeTessellator::new();
+
+    // Keep track of context changes.
+    let mut curr_ctxt = draw::Context::default();
+
+    // No primitives refer to the signed distance field atlas yet, so it may be cleared if it
+    // filled up during the previous frame.
+    glyph_cache.sdf_atlas.begin_frame();
+
+    // Collect all draw commands to avoid borrow errors.
+    let draw_cmds: Vec<_> = draw.drain_commands().collect();
+    let draw_state = draw.state.borrow_mut();
+    let intermediary_state = draw_state.intermediary_state.borrow();
+    for cmd in draw_cmds {
+        match cmd {
+            draw::DrawCommand::Context(ctxt) => curr_ctxt = ctxt,
+            draw::DrawCommand::Primitive(prim) => {
+                // Track the prev index and vertex counts.
+                let prev_index_count = mesh.indices().len() as u32;
+                let prev_vert_count = mesh.vertex_count();
+
+                // Info required during rendering.
+                let ctxt = RenderContext {
+                    intermediary_mesh: &intermediary_state.intermediary_mesh,
+                    path_event_buffer: &intermediary_state.path_event_buffer,
+                    path_points_colored_buffer: &intermediary_state.path_points_colored_buffer,
+                    path_points_textured_buffer: &intermediary_state.path_points_textured_buffer,
+                    text_buffer: &intermediary_state.text_buffer,
+                    theme: &draw_state.theme,
+                    transform: &curr_ctxt.transform,
+                    fill_tessellator: &mut fill_tessellator,
+                    stroke_tessellator: &mut stroke_tessellator,
+                    glyph_cache: &mut *glyph_cache,
+                    output_attachment_size: Vector2::new(px_to_pt(w_px), px_to_pt(h_px)),
+                    output_attachment_scale_factor: scale_factor,
+                };
+
+                // Render the primitive.
+                let render = prim.render_primitive(ctxt, mesh);
+
+                // If the mesh indices are unchanged, there's nothing to be drawn.
+                if prev_index_count == mesh.indices().len() as u32 {
+                    assert_eq!(
+                        prev_vert_count,
+                        mesh.vertex_count(),
+                        "vertices were submitted during `render` without submitting indices",
+                    );
+                    continue;
+                }
+
+                // Extend the vertex mode channel.
+                let mode = render.vertex_mode;
+                let mut new_vs = mesh.points().len() - vertex_mode_buffer.len();
+                for &(run_vs, run_mode) in &render.vertex_mode_runs {
+                    let run_vs = std::cmp::min(run_vs, new_vs);
+                    vertex_mode_buffer.extend((0..run_vs).map(|_| run_mode));
+                    new_vs -= run_vs;
+                }
+                vertex_mode_buffer.extend((0..new_vs).map(|_| mode));
+
+                primitive_rendered(&curr_ctxt, render, prev_index_count);
+            }
+        }
+    }
+
+    curr_ctxt
+}
+
 fn create_depth_texture(
     device: &wgpu::Device,
     size: [u32; 2],
@@ -1042,6 +1210,12 @@ fn create_text_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout
             wgpu::TextureViewDimension::D2,
             Renderer::GLYPH_CACHE_TEXTURE_FORMAT.into(),
         )
+        .sampled_texture(
+            wgpu::ShaderStage::FRAGMENT,
+            false,
+            wgpu::TextureViewDimension::D2,
+            Renderer::SDF_ATLAS_TEXTURE_FORMAT.into(),
+        )
         .build(device)
 }
 
@@ -1075,10 +1249,12 @@ fn create_text_bind_group(
     layout: &wgpu::BindGroupLayout,
     sampler: &wgpu::Sampler,
     glyph_cache_texture_view: &wgpu::TextureViewHandle,
+    sdf_atlas_texture_view: &wgpu::TextureViewHandle,
 ) -> wgpu::BindGroup {
     wgpu::BindGroupBuilder::new()
         .sampler(sampler)
         .texture_view(glyph_cache_texture_view)
+        .texture_view(sdf_atlas_texture_view)
         .build(device, layout)
 }
 
@@ -1115,7 +1291,7 @@ fn create_render_pipeline(
         .add_vertex_buffer::<draw::mesh::vertex::Point>(&wgpu::vertex_attr_array![0 => Float3])
         .add_vertex_buffer::<draw::mesh::vertex::Color>(&wgpu::vertex_attr_array![1 => Float4])
         .add_vertex_buffer::<draw::mesh::vertex::TexCoords>(&wgpu::vertex_attr_array![2 => Float2])
-        .add_vertex_buffer::<VertexMode>(&wgpu::vertex_attr_array![3 => Uint])
+        .add_vertex_buffer::<u32>(&wgpu::vertex_attr_array![3 => Uint])
         .depth_format(depth_format)
         .sample_count(sample_count)
         .color_blend(color_blend)
@@ -1164,7 +1340,7 @@ fn tex_coords_as_bytes(data: &[draw::mesh::vertex::TexCoords]) -> &[u8] {
     unsafe { wgpu::bytes::from_slice(data) }
 }
 
-fn vertex_modes_as_bytes(data: &[VertexMode]) -> &[u8] {
+fn vertex_modes_as_bytes(data: &[u32]) -> &[u8] {
     unsafe { wgpu::bytes::from_slice(data) }
 }
 
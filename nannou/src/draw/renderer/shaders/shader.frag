@@ -8,6 +8,7 @@
 
 layout(set = 1, binding = 0) uniform sampler text_sampler;
 layout(set = 1, binding = 1) uniform texture2D text;
+layout(set = 1, binding = 2) uniform texture2D sdf;
 layout(set = 2, binding = 0) uniform sampler tex_sampler;
 layout(set = 2, binding = 1) uniform texture2D tex;
 
@@ -31,6 +32,15 @@ void main() {
         float tex_a = texture(sampler2D(text, text_sampler), v_tex_coords).r;
         f_color = vec4(v_color.rgb, v_color.a * tex_a);
 
+    // Signed distance field text, with the edge and softness in the second and third bytes.
+    } else if ((v_mode & uint(255)) == uint(3)) {
+        float edge = float((v_mode >> 8) & uint(255)) / 255.0;
+        float softness = float((v_mode >> 16) & uint(255)) / 255.0;
+        float dist = texture(sampler2D(sdf, text_sampler), v_tex_coords).r;
+        float w = max(fwidth(dist) * 0.5 + softness, 0.001);
+        float sdf_a = smoothstep(edge - w, edge + w, dist);
+        f_color = vec4(v_color.rgb, v_color.a * sdf_a);
+
     // Unhandled mode - Indicate error with red.
     } else {
         f_color = vec4(1.0, 0.0, 0.0, 1.0);
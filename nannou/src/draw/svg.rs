@@ -0,0 +1,631 @@
+//! Items related to rendering a **Draw** instance's commands to Scalable Vector Graphics.
+//!
+//! Unlike the **draw::Renderer**, the **svg::Renderer** requires no wgpu device. Each primitive is
+//! converted to its nearest SVG equivalent, making it useful for producing artwork for pen
+//! plotters and print, or for testing sketches on machines without a GPU.
+//!
+//! - **Ellipse** and **Rect** produce `<ellipse>` and `<rect>` elements.
+//! - **Path**, **Line**, **Arrow**, **Polygon**, **Quad** and **Tri** produce `<path>` elements.
+//! - **Text** is converted to glyph outlines and produced as a single `<path>` element.
+//! - **Mesh** produces a `<polygon>` element for each triangle.
+//!
+//! The **Context** transform of each drawing is applied via the `transform` attribute, scissors are
+//! applied via `<clipPath>` and blend descriptors are mapped to the nearest `mix-blend-mode`.
+//!
+//! **Texture**s and textured vertices cannot be represented without access to the GPU and are
+//! skipped.
+
+use crate::color::{LinSrgba, Srgba};
+use crate::draw;
+use crate::draw::mesh::vertex::Color;
+use crate::geom::{self, Point2};
+use crate::math::Matrix4;
+use crate::wgpu;
+use lyon::path::PathEvent;
+use lyon::tessellation::{FillRule, LineCap, LineJoin, StrokeOptions};
+use std::fmt::Write as FmtWrite;
+use std::io;
+use std::path::Path;
+
+/// Draw API primitives that may be rendered via the **svg::Renderer** type.
+pub trait SvgPrimitive {
+    /// Render self as SVG elements into the given document.
+    fn render_svg(self, ctxt: RenderContext, doc: &mut Document);
+}
+
+/// The context provided to primitives to assist with the SVG rendering process.
+pub struct RenderContext<'a> {
+    pub transform: &'a Matrix4<f32>,
+    pub intermediary_mesh: &'a draw::Mesh,
+    pub path_event_buffer: &'a [PathEvent],
+    pub path_points_colored_buffer: &'a [(Point2, Color)],
+    pub path_points_textured_buffer: &'a [(Point2, Point2)],
+    pub text_buffer: &'a str,
+    pub theme: &'a draw::Theme,
+}
+
+/// The fill and stroke styling applied to a single SVG element.
+#[derive(Clone, Debug, Default)]
+pub struct Style {
+    /// The color with which the element should be filled, if any.
+    pub fill: Option<LinSrgba>,
+    /// The rule used to determine the inside of a filled path.
+    ///
+    /// If `None`, the SVG default (`nonzero`) is used.
+    pub fill_rule: Option<FillRule>,
+    /// The color and options with which the outline should be stroked, if any.
+    pub stroke: Option<(LinSrgba, StrokeOptions)>,
+    /// The dash pattern applied to the stroke, if any.
+    pub dash: Option<draw::Dash>,
+}
+
+/// An SVG document body under construction.
+///
+/// Primitives write their elements into the document via the methods below. All coordinates are
+/// expected in nannou's logical coordinate space (the origin at the centre, *y* pointing upwards).
+#[derive(Clone, Debug)]
+pub struct Document {
+    body: String,
+    precision: usize,
+    clip_count: usize,
+}
+
+/// A type aimed at simplifying construction of an **svg::Renderer**.
+#[derive(Clone, Debug)]
+pub struct Builder {
+    pub precision: usize,
+}
+
+/// Renders the commands of a **Draw** instance to an SVG document.
+#[derive(Clone, Debug)]
+pub struct Renderer {
+    size: [f32; 2],
+    precision: usize,
+}
+
+// The state shared by a group of elements.
+#[derive(Clone, Debug, PartialEq)]
+struct Group {
+    scissor: draw::Scissor,
+    blend_mode: Option<&'static str>,
+}
+
+impl Style {
+    /// A style that only fills the element with the given color.
+    pub fn fill(color: LinSrgba) -> Self {
+        Style {
+            fill: Some(color),
+            ..Default::default()
+        }
+    }
+
+    /// A style that only strokes the element with the given color and options.
+    pub fn stroke(color: LinSrgba, opts: StrokeOptions) -> Self {
+        Style {
+            stroke: Some((color, opts)),
+            ..Default::default()
+        }
+    }
+}
+
+impl Document {
+    /// Create an empty document writing numbers with the given number of decimal places.
+    pub fn new(precision: usize) -> Self {
+        Document {
+            body: String::new(),
+            precision,
+            clip_count: 0,
+        }
+    }
+
+    /// The elements written so far.
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+
+    /// Write an `<ellipse>` element bounded by the given rectangle.
+    pub fn ellipse(&mut self, transform: &Matrix4<f32>, rect: geom::Rect, style: &Style) {
+        let (x, y, w, h) = rect.x_y_w_h();
+        self.body.push_str("<ellipse");
+        self.attr("cx", x);
+        self.attr("cy", y);
+        self.attr("rx", w.abs() * 0.5);
+        self.attr("ry", h.abs() * 0.5);
+        self.transform_attr(transform);
+        self.style_attrs(style);
+        self.body.push_str("/>\n");
+    }
+
+    /// Write a `<rect>` element.
+    pub fn rect(&mut self, transform: &Matrix4<f32>, rect: geom::Rect, style: &Style) {
+        self.body.push_str("<rect");
+        self.attr("x", rect.left());
+        self.attr("y", rect.bottom());
+        self.attr("width", rect.w());
+        self.attr("height", rect.h());
+        self.transform_attr(transform);
+        self.style_attrs(style);
+        self.body.push_str("/>\n");
+    }
+
+    /// Write a `<path>` element described by the given path events.
+    ///
+    /// Nothing is written if the given iterator yields no events.
+    pub fn path<I>(&mut self, transform: &Matrix4<f32>, events: I, style: &Style)
+    where
+        I: IntoIterator<Item = PathEvent>,
+    {
+        let mut d = String::new();
+        for event in events {
+            match event {
+                PathEvent::Begin { at } => {
+                    d.push('M');
+                    self.push_point(&mut d, at.x, at.y);
+                }
+                PathEvent::Line { to, .. } => {
+                    d.push('L');
+                    self.push_point(&mut d, to.x, to.y);
+                }
+                PathEvent::Quadratic { ctrl, to, .. } => {
+                    d.push('Q');
+                    self.push_point(&mut d, ctrl.x, ctrl.y);
+                    d.push(' ');
+                    self.push_point(&mut d, to.x, to.y);
+                }
+                PathEvent::Cubic {
+                    ctrl1, ctrl2, to, ..
+                } => {
+                    d.push('C');
+                    self.push_point(&mut d, ctrl1.x, ctrl1.y);
+                    d.push(' ');
+                    self.push_point(&mut d, ctrl2.x, ctrl2.y);
+                    d.push(' ');
+                    self.push_point(&mut d, to.x, to.y);
+                }
+                PathEvent::End { close, .. } => {
+                    if close {
+                        d.push('Z');
+                    }
+                }
+            }
+        }
+        if d.is_empty() {
+            return;
+        }
+        self.body.push_str("<path d=\"");
+        self.body.push_str(&d);
+        self.body.push('"');
+        self.transform_attr(transform);
+        self.style_attrs(style);
+        self.body.push_str("/>\n");
+    }
+
+    /// Write a `<path>` element describing a polyline through the given points.
+    pub fn polyline<I>(&mut self, transform: &Matrix4<f32>, close: bool, points: I, style: &Style)
+    where
+        I: IntoIterator<Item = Point2>,
+    {
+        let points = points.into_iter().map(|p| lyon::math::point(p.x, p.y));
+        let events = lyon::path::iterator::FromPolyline::new(close, points);
+        self.path(transform, events, style);
+    }
+
+    /// Write a filled `<polygon>` element for a single triangle.
+    ///
+    /// The points are expected to be in their final position, i.e. no transform is applied.
+    pub fn triangle(&mut self, points: [geom::Point3; 3], color: LinSrgba) {
+        self.body.push_str("<polygon points=\"");
+        for (i, p) in points.iter().enumerate() {
+            if i > 0 {
+                self.body.push(' ');
+            }
+            let mut s = String::new();
+            self.push_point(&mut s, p.x, p.y);
+            self.body.push_str(&s);
+        }
+        self.body.push('"');
+        self.color_attrs("fill", color);
+        self.body.push_str("/>\n");
+    }
+
+    // Open a group for the given scissor and blend mode.
+    fn begin_group(&mut self, group: &Group, full_rect: geom::Rect) {
+        let mut clip = None;
+        if let draw::Scissor::Rect(rect) = group.scissor {
+            let rect = full_rect
+                .overlap(rect)
+                .unwrap_or(geom::Rect::from_w_h(0.0, 0.0));
+            let id = format!("nannou-scissor-{}", self.clip_count);
+            self.clip_count += 1;
+            write!(self.body, "<clipPath id=\"{}\"><rect", id).unwrap();
+            self.attr("x", rect.left());
+            self.attr("y", rect.bottom());
+            self.attr("width", rect.w());
+            self.attr("height", rect.h());
+            self.body.push_str("/></clipPath>\n");
+            clip = Some(id);
+        }
+        self.body.push_str("<g");
+        if let Some(id) = clip {
+            write!(self.body, " clip-path=\"url(#{})\"", id).unwrap();
+        }
+        if let Some(mode) = group.blend_mode {
+            write!(self.body, " style=\"mix-blend-mode:{}\"", mode).unwrap();
+        }
+        self.body.push_str(">\n");
+    }
+
+    fn end_group(&mut self) {
+        self.body.push_str("</g>\n");
+    }
+
+    // Write a number rounded to the document's precision.
+    fn push_num(&self, s: &mut String, v: f32) {
+        let p = 10f32.powi(self.precision as i32);
+        let v = (v * p).round() / p;
+        // Avoid writing `-0`.
+        let v = if v == 0.0 { 0.0 } else { v };
+        write!(s, "{}", v).unwrap();
+    }
+
+    fn push_point(&self, s: &mut String, x: f32, y: f32) {
+        self.push_num(s, x);
+        s.push(',');
+        self.push_num(s, y);
+    }
+
+    fn attr(&mut self, name: &str, v: f32) {
+        let mut s = String::new();
+        self.push_num(&mut s, v);
+        write!(self.body, " {}=\"{}\"", name, s).unwrap();
+    }
+
+    // Write the transform attribute, skipping it if the transform is the identity.
+    fn transform_attr(&mut self, m: &Matrix4<f32>) {
+        let [a, b, c, d, e, f] = [m.x.x, m.x.y, m.y.x, m.y.y, m.w.x, m.w.y];
+        if [a, b, c, d, e, f] == [1.0, 0.0, 0.0, 1.0, 0.0, 0.0] {
+            return;
+        }
+        let mut s = String::new();
+        for (i, v) in [a, b, c, d, e, f].iter().enumerate() {
+            if i > 0 {
+                s.push(' ');
+            }
+            // Use a higher precision for the linear part to avoid visible distortion.
+            let precision = if i < 4 {
+                self.precision + 3
+            } else {
+                self.precision
+            };
+            let p = 10f32.powi(precision as i32);
+            let v = (v * p).round() / p;
+            let v = if v == 0.0 { 0.0 } else { v };
+            write!(s, "{}", v).unwrap();
+        }
+        write!(self.body, " transform=\"matrix({})\"", s).unwrap();
+    }
+
+    fn color_attrs(&mut self, name: &str, color: LinSrgba) {
+        let (r, g, b, a) = srgba_components(color);
+        write!(self.body, " {}=\"rgb({},{},{})\"", name, r, g, b).unwrap();
+        if a < 1.0 {
+            let mut s = String::new();
+            self.push_num(&mut s, a);
+            write!(self.body, " {}-opacity=\"{}\"", name, s).unwrap();
+        }
+    }
+
+    fn style_attrs(&mut self, style: &Style) {
+        match style.fill {
+            None => self.body.push_str(" fill=\"none\""),
+            Some(color) => self.color_attrs("fill", color),
+        }
+        if let Some(FillRule::EvenOdd) = style.fill_rule {
+            self.body.push_str(" fill-rule=\"evenodd\"");
+        }
+        if let Some((color, ref opts)) = style.stroke {
+            self.color_attrs("stroke", color);
+            self.attr("stroke-width", opts.line_width);
+            // SVG does not distinguish between start and end caps, nor between the caps of the
+            // stroke and those of its dashes.
+            let dash = style.dash.as_ref().filter(|dash| !dash.is_solid());
+            let cap = dash
+                .and_then(|dash| dash.dash_cap())
+                .unwrap_or(opts.start_cap);
+            let cap = match cap {
+                LineCap::Butt => None,
+                LineCap::Square => Some("square"),
+                LineCap::Round => Some("round"),
+            };
+            if let Some(cap) = cap {
+                write!(self.body, " stroke-linecap=\"{}\"", cap).unwrap();
+            }
+            let join = match opts.line_join {
+                LineJoin::Miter => None,
+                LineJoin::MiterClip => Some("miter-clip"),
+                LineJoin::Round => Some("round"),
+                LineJoin::Bevel => Some("bevel"),
+            };
+            if let Some(join) = join {
+                write!(self.body, " stroke-linejoin=\"{}\"", join).unwrap();
+            }
+            self.attr("stroke-miterlimit", opts.miter_limit);
+            if let Some(dash) = dash {
+                let mut lengths = String::new();
+                for (i, &length) in dash.lengths().iter().enumerate() {
+                    if i > 0 {
+                        lengths.push(' ');
+                    }
+                    self.push_num(&mut lengths, length);
+                }
+                write!(self.body, " stroke-dasharray=\"{}\"", lengths).unwrap();
+                if dash.dash_offset() != 0.0 {
+                    self.attr("stroke-dashoffset", dash.dash_offset());
+                }
+            }
+        }
+    }
+}
+
+impl Builder {
+    /// The default number of decimal places used when writing numbers.
+    pub const DEFAULT_PRECISION: usize = Renderer::DEFAULT_PRECISION;
+
+    /// Begin building a new **svg::Renderer**.
+    pub fn new() -> Self {
+        Builder {
+            precision: Self::DEFAULT_PRECISION,
+        }
+    }
+
+    /// The number of decimal places used when writing coordinates and other numbers.
+    pub fn precision(mut self, precision: usize) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// Build the **svg::Renderer** for a document of the given size in logical coordinates.
+    pub fn build(self, size: [f32; 2]) -> Renderer {
+        Renderer {
+            size,
+            precision: self.precision,
+        }
+    }
+}
+
+impl Renderer {
+    /// The default number of decimal places used when writing numbers.
+    pub const DEFAULT_PRECISION: usize = 3;
+
+    /// Create a new **svg::Renderer** for a document of the given size in logical coordinates.
+    ///
+    /// The centre of the document corresponds to the origin of the **Draw** coordinate space.
+    pub fn new(size: [f32; 2]) -> Self {
+        Builder::new().build(size)
+    }
+
+    /// The size of the document in logical coordinates.
+    pub fn size(&self) -> [f32; 2] {
+        self.size
+    }
+
+    /// Render the given **Draw** to an SVG document and return it as a `String`.
+    ///
+    /// Note that the given **Draw** instance will be *drained* of its commands.
+    pub fn render(&mut self, draw: &draw::Draw) -> String {
+        let [w, h] = self.size;
+        let full_rect = geom::Rect::from_w_h(w, h);
+        let mut doc = Document::new(self.precision);
+
+        // Collect all draw commands to avoid borrow errors.
+        let draw_cmds: Vec<_> = draw.drain_commands().collect();
+        let draw_state = draw.state.borrow();
+        let intermediary_state = draw_state.intermediary_state.borrow();
+
+        let default_group = Group {
+            scissor: draw::Scissor::Full,
+            blend_mode: None,
+        };
+        let mut curr_ctxt = draw::Context::default();
+        let mut curr_group = default_group.clone();
+        for cmd in draw_cmds {
+            match cmd {
+                draw::DrawCommand::Context(ctxt) => curr_ctxt = ctxt,
+                draw::DrawCommand::Primitive(prim) => {
+                    // Nothing within a non-overlapping scissor is visible.
+                    if let draw::Scissor::NoOverlap = curr_ctxt.scissor {
+                        continue;
+                    }
+
+                    // Open a new group if the scissor or blend mode has changed.
+                    let group = Group {
+                        scissor: curr_ctxt.scissor,
+                        blend_mode: blend_mode(&curr_ctxt.color_blend),
+                    };
+                    if group != curr_group {
+                        if curr_group != default_group {
+                            doc.end_group();
+                        }
+                        if group != default_group {
+                            doc.begin_group(&group, full_rect);
+                        }
+                        curr_group = group;
+                    }
+
+                    let ctxt = RenderContext {
+                        transform: &curr_ctxt.transform,
+                        intermediary_mesh: &intermediary_state.intermediary_mesh,
+                        path_event_buffer: &intermediary_state.path_event_buffer,
+                        path_points_colored_buffer: &intermediary_state.path_points_colored_buffer,
+                        path_points_textured_buffer: &intermediary_state
+                            .path_points_textured_buffer,
+                        text_buffer: &intermediary_state.text_buffer,
+                        theme: &draw_state.theme,
+                    };
+                    prim.render_svg(ctxt, &mut doc);
+                }
+            }
+        }
+        if curr_group != default_group {
+            doc.end_group();
+        }
+
+        // Write the document.
+        let mut svg = String::new();
+        let num = |v: f32| {
+            let mut s = String::new();
+            doc.push_num(&mut s, v);
+            s
+        };
+        let (w_s, h_s, hw_s, hh_s) = (num(w), num(h), num(w * 0.5), num(h * 0.5));
+        svg.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        write!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" \
+             viewBox=\"0 0 {w} {h}\">\n",
+            w = w_s,
+            h = h_s,
+        )
+        .unwrap();
+        if let Some(color) = draw_state.background_color {
+            let (r, g, b, a) = srgba_components(color);
+            write!(
+                svg,
+                "<rect width=\"100%\" height=\"100%\" fill=\"rgb({},{},{})\"",
+                r, g, b
+            )
+            .unwrap();
+            if a < 1.0 {
+                write!(svg, " fill-opacity=\"{}\"", a).unwrap();
+            }
+            svg.push_str("/>\n");
+        }
+        // Flip the y axis and move the origin to the centre to match nannou's coordinate space.
+        write!(
+            svg,
+            "<g transform=\"matrix(1 0 0 -1 {} {})\">\n",
+            hw_s, hh_s
+        )
+        .unwrap();
+        svg.push_str(doc.body());
+        svg.push_str("</g>\n</svg>\n");
+        svg
+    }
+
+    /// Render the given **Draw** to an SVG document and write it to the given writer.
+    pub fn write<W>(&mut self, draw: &draw::Draw, mut writer: W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        let svg = self.render(draw);
+        writer.write_all(svg.as_bytes())
+    }
+
+    /// Render the given **Draw** to an SVG document and save it at the given path.
+    pub fn save<P>(&mut self, draw: &draw::Draw, path: P) -> io::Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let file = std::fs::File::create(path)?;
+        let writer = io::BufWriter::new(file);
+        self.write(draw, writer)
+    }
+}
+
+impl SvgPrimitive for draw::Primitive {
+    fn render_svg(self, ctxt: RenderContext, doc: &mut Document) {
+        match self {
+            draw::Primitive::Arrow(prim) => prim.render_svg(ctxt, doc),
+            draw::Primitive::Mesh(prim) => prim.render_svg(ctxt, doc),
+            draw::Primitive::Path(prim) => prim.render_svg(ctxt, doc),
+            draw::Primitive::Polygon(prim) => prim.render_svg(ctxt, doc),
+            draw::Primitive::Tri(prim) => prim.render_svg(ctxt, doc),
+            draw::Primitive::Ellipse(prim) => prim.render_svg(ctxt, doc),
+            draw::Primitive::Quad(prim) => prim.render_svg(ctxt, doc),
+            draw::Primitive::Rect(prim) => prim.render_svg(ctxt, doc),
+            draw::Primitive::Line(prim) => prim.render_svg(ctxt, doc),
+            draw::Primitive::Text(prim) => prim.render_svg(ctxt, doc),
+            draw::Primitive::TextOnPath(prim) => prim.render_svg(ctxt, doc),
+            _ => (),
+        }
+    }
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Render the given **Draw** to an SVG document of the given size.
+///
+/// This is short-hand for `svg::Renderer::new(size).render(draw)`.
+pub fn render(draw: &draw::Draw, size: [f32; 2]) -> String {
+    Renderer::new(size).render(draw)
+}
+
+/// Render the given **Draw** to an SVG document of the given size and save it at the given path.
+pub fn save<P>(draw: &draw::Draw, size: [f32; 2], path: P) -> io::Result<()>
+where
+    P: AsRef<Path>,
+{
+    Renderer::new(size).save(draw, path)
+}
+
+/// The `mix-blend-mode` that most closely matches the given blend descriptor.
+///
+/// Returns `None` for the default "normal" blending.
+pub fn blend_mode(desc: &wgpu::BlendDescriptor) -> Option<&'static str> {
+    match desc.operation {
+        wgpu::BlendOperation::Min => Some("darken"),
+        wgpu::BlendOperation::Max => Some("lighten"),
+        wgpu::BlendOperation::Subtract | wgpu::BlendOperation::ReverseSubtract => {
+            Some("difference")
+        }
+        wgpu::BlendOperation::Add => match (desc.src_factor, desc.dst_factor) {
+            (wgpu::BlendFactor::SrcAlpha, wgpu::BlendFactor::OneMinusSrcAlpha)
+            | (wgpu::BlendFactor::One, wgpu::BlendFactor::OneMinusSrcAlpha)
+            | (wgpu::BlendFactor::One, wgpu::BlendFactor::Zero) => None,
+            (wgpu::BlendFactor::Zero, wgpu::BlendFactor::SrcColor)
+            | (wgpu::BlendFactor::DstColor, wgpu::BlendFactor::Zero) => Some("multiply"),
+            (wgpu::BlendFactor::One, wgpu::BlendFactor::OneMinusSrcColor) => Some("screen"),
+            _ => Some("plus-lighter"),
+        },
+    }
+}
+
+/// The average of the given colors, or `None` if the iterator is empty.
+///
+/// Useful for approximating vertex colors that vary across a single element.
+pub fn average_color<I>(colors: I) -> Option<LinSrgba>
+where
+    I: IntoIterator<Item = LinSrgba>,
+{
+    let mut count = 0;
+    let mut sum = [0.0; 4];
+    for color in colors {
+        let (r, g, b, a) = color.into();
+        sum[0] += r;
+        sum[1] += g;
+        sum[2] += b;
+        sum[3] += a;
+        count += 1;
+    }
+    if count == 0 {
+        return None;
+    }
+    let n = count as f32;
+    Some(LinSrgba::new(
+        sum[0] / n,
+        sum[1] / n,
+        sum[2] / n,
+        sum[3] / n,
+    ))
+}
+
+// Convert the linear color to 8-bit sRGB components alongside its alpha.
+fn srgba_components(color: LinSrgba) -> (u8, u8, u8, f32) {
+    let (r, g, b, a): (f32, f32, f32, f32) = Srgba::from_linear(color).into();
+    let to_u8 = |c: f32| (c.max(0.0).min(1.0) * 255.0).round() as u8;
+    (to_u8(r), to_u8(g), to_u8(b), a.max(0.0).min(1.0))
+}
@@ -0,0 +1,160 @@
+//! Serialization of the `wgpu` types stored within a draw **Context**.
+//!
+//! These types do not implement the serde traits themselves, so we mirror them here for use with
+//! `#[serde(with = "...")]`.
+
+use crate::serde_derive::{Deserialize, Serialize};
+use crate::wgpu;
+use std::num::NonZeroU8;
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "wgpu::BlendDescriptor")]
+pub struct BlendDescriptorDef {
+    #[serde(with = "BlendFactorDef")]
+    pub src_factor: wgpu::BlendFactor,
+    #[serde(with = "BlendFactorDef")]
+    pub dst_factor: wgpu::BlendFactor,
+    #[serde(with = "BlendOperationDef")]
+    pub operation: wgpu::BlendOperation,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "wgpu::BlendFactor")]
+enum BlendFactorDef {
+    Zero,
+    One,
+    SrcColor,
+    OneMinusSrcColor,
+    SrcAlpha,
+    OneMinusSrcAlpha,
+    DstColor,
+    OneMinusDstColor,
+    DstAlpha,
+    OneMinusDstAlpha,
+    SrcAlphaSaturated,
+    BlendColor,
+    OneMinusBlendColor,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "wgpu::BlendOperation")]
+enum BlendOperationDef {
+    Add,
+    Subtract,
+    ReverseSubtract,
+    Min,
+    Max,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "wgpu::PrimitiveTopology")]
+pub enum PrimitiveTopologyDef {
+    PointList,
+    LineList,
+    LineStrip,
+    TriangleList,
+    TriangleStrip,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "wgpu::AddressMode")]
+enum AddressModeDef {
+    ClampToEdge,
+    Repeat,
+    MirrorRepeat,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "wgpu::FilterMode")]
+enum FilterModeDef {
+    Nearest,
+    Linear,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "wgpu::CompareFunction")]
+enum CompareFunctionDef {
+    Never,
+    Less,
+    Equal,
+    LessEqual,
+    Greater,
+    NotEqual,
+    GreaterEqual,
+    Always,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CompareFunction(#[serde(with = "CompareFunctionDef")] wgpu::CompareFunction);
+
+// The debug label is not serialized.
+#[derive(Serialize, Deserialize)]
+struct SamplerDescriptor {
+    #[serde(with = "AddressModeDef")]
+    address_mode_u: wgpu::AddressMode,
+    #[serde(with = "AddressModeDef")]
+    address_mode_v: wgpu::AddressMode,
+    #[serde(with = "AddressModeDef")]
+    address_mode_w: wgpu::AddressMode,
+    #[serde(with = "FilterModeDef")]
+    mag_filter: wgpu::FilterMode,
+    #[serde(with = "FilterModeDef")]
+    min_filter: wgpu::FilterMode,
+    #[serde(with = "FilterModeDef")]
+    mipmap_filter: wgpu::FilterMode,
+    lod_min_clamp: f32,
+    lod_max_clamp: f32,
+    compare: Option<CompareFunction>,
+    anisotropy_clamp: Option<NonZeroU8>,
+}
+
+/// For use with `#[serde(with = "sampler_descriptor")]`.
+pub mod sampler_descriptor {
+    use super::{CompareFunction, SamplerDescriptor};
+    use crate::wgpu;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S>(
+        desc: &wgpu::SamplerDescriptor<'static>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let desc = SamplerDescriptor {
+            address_mode_u: desc.address_mode_u,
+            address_mode_v: desc.address_mode_v,
+            address_mode_w: desc.address_mode_w,
+            mag_filter: desc.mag_filter,
+            min_filter: desc.min_filter,
+            mipmap_filter: desc.mipmap_filter,
+            lod_min_clamp: desc.lod_min_clamp,
+            lod_max_clamp: desc.lod_max_clamp,
+            compare: desc.compare.map(CompareFunction),
+            anisotropy_clamp: desc.anisotropy_clamp,
+        };
+        desc.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(
+        deserializer: D,
+    ) -> Result<wgpu::SamplerDescriptor<'static>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let desc = SamplerDescriptor::deserialize(deserializer)?;
+        Ok(wgpu::SamplerDescriptor {
+            label: None,
+            address_mode_u: desc.address_mode_u,
+            address_mode_v: desc.address_mode_v,
+            address_mode_w: desc.address_mode_w,
+            mag_filter: desc.mag_filter,
+            min_filter: desc.min_filter,
+            mipmap_filter: desc.mipmap_filter,
+            lod_min_clamp: desc.lod_min_clamp,
+            lod_max_clamp: desc.lod_max_clamp,
+            compare: desc.compare.map(|CompareFunction(f)| f),
+            anisotropy_clamp: desc.anisotropy_clamp,
+        })
+    }
+}
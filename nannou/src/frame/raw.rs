@@ -91,6 +91,8 @@ impl<'swap_chain> RawFrame<'swap_chain> {
     }
 
     /// The `Id` of the window whose wgpu surface is associated with this frame.
+    ///
+    /// When running headless, this is the `Id` of the stand-in window for the offscreen texture.
     pub fn window_id(&self) -> window::Id {
         self.window_id
     }
@@ -0,0 +1,372 @@
+//! Convex hulls and Delaunay triangulations of sets of points.
+//!
+//! The Delaunay triangulation of a set of points is the triangulation in which no point lies
+//! within the circumcircle of any triangle. It avoids long, thin triangles where possible and is
+//! the dual of the Voronoi diagram (see the `geom::voronoi` module).
+
+use crate::geom::{pt2, Point2, Tri, Vector2};
+use std::collections::HashMap;
+
+/// The Delaunay triangulation of a set of points.
+///
+/// Triangles are described by indices into the original list of points and wind
+/// counter-clockwise. Points that duplicate an earlier point are not part of any triangle.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Triangulation {
+    points: Vec<Point2>,
+    triangles: Vec<[usize; 3]>,
+    neighbours: Vec<[Option<usize>; 3]>,
+}
+
+// All geometry is processed in double precision.
+type Point = Vector2<f64>;
+
+// A point that may lie infinitely far away, at `p + d * m` for an infinitely large `m`.
+#[derive(Copy, Clone, Debug)]
+struct SymbolicPoint {
+    p: Point,
+    d: Point,
+}
+
+// The coefficients of a polynomial in `m`, from the constant term upwards.
+type Poly = [f64; 5];
+
+// The directions in which the points of the super triangle lie infinitely far away.
+const SUPER_DIRECTIONS: [[f64; 2]; 3] = [[-1.0, -1.0], [1.0, -1.0], [0.0, 1.0]];
+
+/// The indices of the points that lie on the convex hull of the given points.
+///
+/// The indices are ordered counter-clockwise around the hull. Points that lie along an edge of
+/// the hull, rather than at a corner, are not included.
+pub fn convex_hull(points: &[Point2]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..points.len()).collect();
+    order.sort_by(|&a, &b| {
+        let (a, b) = (points[a], points[b]);
+        (a.x, a.y).partial_cmp(&(b.x, b.y)).expect("point was NaN")
+    });
+    order.dedup_by(|a, b| points[*a] == points[*b]);
+    if order.len() < 3 {
+        return order;
+    }
+
+    // Andrew's monotone chain, building the lower hull and then the upper hull.
+    let turns_left = |hull: &[usize], i: usize| {
+        let (a, b) = (points[hull[hull.len() - 2]], points[hull[hull.len() - 1]]);
+        (b - a).perp_dot(points[i] - a) > 0.0
+    };
+    let mut hull: Vec<usize> = vec![];
+    for &i in &order {
+        while hull.len() >= 2 && !turns_left(&hull, i) {
+            hull.pop();
+        }
+        hull.push(i);
+    }
+    let lower_len = hull.len();
+    for &i in order.iter().rev().skip(1) {
+        while hull.len() > lower_len && !turns_left(&hull, i) {
+            hull.pop();
+        }
+        hull.push(i);
+    }
+    // The last point is the first point, reached again by the upper hull.
+    hull.pop();
+    hull
+}
+
+impl Triangulation {
+    /// Produce the Delaunay triangulation of the given points.
+    ///
+    /// If fewer than three of the points are distinct or all points lie along a single line, the
+    /// triangulation is empty.
+    ///
+    /// Uses the Bowyer-Watson algorithm. Each point is located via a linear search of the
+    /// triangles inserted so far, so the cost grows quadratically with the number of points.
+    pub fn new<I>(points: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<Point2>,
+    {
+        let points: Vec<Point2> = points.into_iter().map(Into::into).collect();
+        let triangles = bowyer_watson(&points);
+        let neighbours = neighbours(&triangles);
+        Triangulation {
+            points,
+            triangles,
+            neighbours,
+        }
+    }
+
+    /// The points that were triangulated.
+    pub fn points(&self) -> &[Point2] {
+        &self.points
+    }
+
+    /// The indices of the points of each triangle.
+    pub fn triangle_indices(&self) -> &[[usize; 3]] {
+        &self.triangles
+    }
+
+    /// An iterator yielding each triangle.
+    pub fn triangles<'a>(&'a self) -> impl 'a + Iterator<Item = Tri<Point2>> {
+        self.triangles
+            .iter()
+            .map(move |t| Tri([self.points[t[0]], self.points[t[1]], self.points[t[2]]]))
+    }
+
+    /// The number of triangles.
+    pub fn len(&self) -> usize {
+        self.triangles.len()
+    }
+
+    /// Whether or not the triangulation contains no triangles.
+    pub fn is_empty(&self) -> bool {
+        self.triangles.is_empty()
+    }
+
+    /// The indices of the triangles adjacent to the triangle at the given index.
+    ///
+    /// The `i`th neighbour shares the edge opposite the triangle's `i`th point, and is `None`
+    /// where that edge lies on the convex hull.
+    pub fn neighbours(&self, triangle: usize) -> [Option<usize>; 3] {
+        self.neighbours[triangle]
+    }
+
+    /// An iterator yielding each edge of the triangulation once, as a pair of point indices.
+    pub fn edges<'a>(&'a self) -> impl 'a + Iterator<Item = (usize, usize)> {
+        self.triangles.iter().enumerate().flat_map(move |(t, tri)| {
+            (0..3).filter_map(move |i| {
+                let (a, b) = (tri[(i + 1) % 3], tri[(i + 2) % 3]);
+                // Interior edges are shared by two triangles, so yield them from one only.
+                match self.neighbours[t][i] {
+                    Some(n) if n < t => None,
+                    _ => Some((a, b)),
+                }
+            })
+        })
+    }
+
+    /// The indices of the points joined to the point at the given index by an edge.
+    pub fn point_neighbours(&self, point: usize) -> Vec<usize> {
+        let mut neighbours: Vec<usize> = self
+            .edges()
+            .filter_map(|(a, b)| match (a == point, b == point) {
+                (true, _) => Some(b),
+                (_, true) => Some(a),
+                _ => None,
+            })
+            .collect();
+        neighbours.sort();
+        neighbours
+    }
+
+    /// The indices of the points on the convex hull of the triangulated points.
+    ///
+    /// See the `convex_hull` function.
+    pub fn hull(&self) -> Vec<usize> {
+        convex_hull(&self.points)
+    }
+}
+
+// Triangulate the points by inserting them one at a time into a triangulation of a triangle large
+// enough to contain them all, re-triangulating the region whose circumcircles contain each point.
+//
+// The points of the super triangle are treated as lying infinitely far away, so that they never
+// fall within the circumcircle of a triangle of the input points. Otherwise triangles along the
+// hull whose circumcircles are very large, e.g. due to nearly collinear points, would be lost.
+fn bowyer_watson(points: &[Point2]) -> Vec<[usize; 3]> {
+    let n = points.len();
+    if n < 3 {
+        return vec![];
+    }
+
+    // The input points followed by the super triangle.
+    let zero = pt2(0.0, 0.0);
+    let pts: Vec<SymbolicPoint> = points
+        .iter()
+        .map(|p| SymbolicPoint {
+            p: pt2(p.x as f64, p.y as f64),
+            d: zero,
+        })
+        .chain(SUPER_DIRECTIONS.iter().map(|&[x, y]| SymbolicPoint {
+            p: zero,
+            d: pt2(x, y),
+        }))
+        .collect();
+
+    // Triangles, with removed triangles left as `None`, along with each directed edge's triangle.
+    let super_tri = [n, n + 1, n + 2];
+    let mut triangles: Vec<Option<[usize; 3]>> = vec![Some(super_tri)];
+    let mut edges: HashMap<(usize, usize), usize> = HashMap::new();
+    for i in 0..3 {
+        edges.insert((super_tri[i], super_tri[(i + 1) % 3]), 0);
+    }
+
+    let mut cavity = vec![];
+    let mut boundary = vec![];
+    for (i, &p) in pts[..n].iter().enumerate() {
+        let p = p.p;
+        // Find the triangle containing the point, skipping duplicates.
+        let containing = triangles.iter().position(|t| match *t {
+            Some([a, b, c]) => in_triangle(pts[a], pts[b], pts[c], p),
+            None => false,
+        });
+        let start = match containing {
+            Some(t) => t,
+            None => continue,
+        };
+        let tri = triangles[start].expect("triangle was removed");
+        if tri.iter().any(|&v| v < n && pts[v].p == p) {
+            continue;
+        }
+
+        // Grow the cavity of connected triangles whose circumcircles contain the point.
+        cavity.clear();
+        cavity.push(start);
+        let mut ix = 0;
+        while ix < cavity.len() {
+            let [a, b, c] = triangles[cavity[ix]].expect("triangle was removed");
+            for &(from, to) in &[(a, b), (b, c), (c, a)] {
+                if let Some(&t) = edges.get(&(to, from)) {
+                    if cavity.contains(&t) {
+                        continue;
+                    }
+                    let [d, e, f] = triangles[t].expect("triangle was removed");
+                    if in_circumcircle(pts[d], pts[e], pts[f], p) {
+                        cavity.push(t);
+                    }
+                }
+            }
+            ix += 1;
+        }
+
+        // Find the edges bounding the cavity, then remove the cavity and fill it with a fan of
+        // triangles from the new point.
+        boundary.clear();
+        for &t in &cavity {
+            let [a, b, c] = triangles[t].expect("triangle was removed");
+            for &(from, to) in &[(a, b), (b, c), (c, a)] {
+                let interior = edges.get(&(to, from)).map_or(false, |t| cavity.contains(t));
+                if !interior {
+                    boundary.push((from, to));
+                }
+            }
+        }
+        for &t in &cavity {
+            let [a, b, c] = triangles[t].take().expect("triangle was removed");
+            for edge in &[(a, b), (b, c), (c, a)] {
+                edges.remove(edge);
+            }
+        }
+        for &(from, to) in &boundary {
+            let t = triangles.len();
+            triangles.push(Some([from, to, i]));
+            edges.insert((from, to), t);
+            edges.insert((to, i), t);
+            edges.insert((i, from), t);
+        }
+    }
+
+    // Remove all triangles that share a point with the super triangle.
+    triangles
+        .into_iter()
+        .flatten()
+        .filter(|t| t.iter().all(|&v| v < n))
+        .collect()
+}
+
+// The neighbours of each triangle, across the edge opposite each of its points.
+fn neighbours(triangles: &[[usize; 3]]) -> Vec<[Option<usize>; 3]> {
+    let mut edges = HashMap::new();
+    for (t, tri) in triangles.iter().enumerate() {
+        for i in 0..3 {
+            edges.insert((tri[i], tri[(i + 1) % 3]), t);
+        }
+    }
+    triangles
+        .iter()
+        .map(|tri| {
+            let mut neighbours = [None; 3];
+            for i in 0..3 {
+                let (a, b) = (tri[(i + 1) % 3], tri[(i + 2) % 3]);
+                neighbours[i] = edges.get(&(b, a)).cloned();
+            }
+            neighbours
+        })
+        .collect()
+}
+
+// Whether `p` lies inside or on the edge of the counter-clockwise triangle.
+fn in_triangle(a: SymbolicPoint, b: SymbolicPoint, c: SymbolicPoint, p: Point) -> bool {
+    let p = SymbolicPoint {
+        p,
+        d: pt2(0.0, 0.0),
+    };
+    sign(&orient(a, b, p)) >= 0.0 && sign(&orient(b, c, p)) >= 0.0 && sign(&orient(c, a, p)) >= 0.0
+}
+
+// Whether `p` lies strictly inside the circumcircle of the counter-clockwise triangle.
+fn in_circumcircle(a: SymbolicPoint, b: SymbolicPoint, c: SymbolicPoint, p: Point) -> bool {
+    let rel = |v: SymbolicPoint| (v.p - p, v.d);
+    let (a, b, c) = (rel(a), rel(b), rel(c));
+    let magnitude2 = |(p, d): (Point, Point)| [p.magnitude2(), 2.0 * p.dot(d), d.magnitude2()];
+    let det = add(
+        &sub(
+            &mul(&magnitude2(a), &perp_dot(b, c)),
+            &mul(&magnitude2(b), &perp_dot(a, c)),
+        ),
+        &mul(&magnitude2(c), &perp_dot(a, b)),
+    );
+    sign(&det) > 0.0
+}
+
+// Twice the signed area of the triangle, positive if it winds counter-clockwise.
+fn orient(a: SymbolicPoint, b: SymbolicPoint, c: SymbolicPoint) -> Poly {
+    let u = (b.p - a.p, b.d - a.d);
+    let v = (c.p - a.p, c.d - a.d);
+    let cross = perp_dot(u, v);
+    [cross[0], cross[1], cross[2], 0.0, 0.0]
+}
+
+// The perp dot product of two symbolic vectors, as a quadratic in `m`.
+fn perp_dot((u, ud): (Point, Point), (v, vd): (Point, Point)) -> [f64; 3] {
+    [
+        u.perp_dot(v),
+        u.perp_dot(vd) + ud.perp_dot(v),
+        ud.perp_dot(vd),
+    ]
+}
+
+// The product of two quadratics.
+fn mul(a: &[f64; 3], b: &[f64; 3]) -> Poly {
+    let mut out = [0.0; 5];
+    for (i, a) in a.iter().enumerate() {
+        for (j, b) in b.iter().enumerate() {
+            out[i + j] += a * b;
+        }
+    }
+    out
+}
+
+fn add(a: &Poly, b: &Poly) -> Poly {
+    let mut out = *a;
+    for (o, b) in out.iter_mut().zip(b) {
+        *o += b;
+    }
+    out
+}
+
+fn sub(a: &Poly, b: &Poly) -> Poly {
+    let mut out = *a;
+    for (o, b) in out.iter_mut().zip(b) {
+        *o -= b;
+    }
+    out
+}
+
+// The sign of the polynomial as `m` grows infinitely large, or `0.0` if it is zero.
+fn sign(poly: &Poly) -> f64 {
+    poly.iter()
+        .rev()
+        .find(|&&c| c != 0.0)
+        .map_or(0.0, |c| c.signum())
+}
@@ -0,0 +1,741 @@
+//! Intersection and distance queries between geometric primitives.
+//!
+//! Includes:
+//!
+//! - Intersection of line segments.
+//! - Ray casting against segments, circles, rects and polygons in 2D, and against triangles,
+//!   spheres and cuboids in 3D. Each produces a `Hit` describing the point, surface normal and
+//!   distance at which the ray first meets the shape.
+//! - Overlap tests between circles, rects and convex polygons. Each produces a `Contact` describing
+//!   how the shapes may be separated.
+//! - Closest point and signed distance queries.
+
+use crate::geom::{scalar, vec2, Cuboid, Point2, Point3, Rect, Tri, Vector2, Vector3};
+use crate::math::{BaseFloat, Float, InnerSpace, One, Zero};
+use std::ops::Neg;
+
+/// A ray in two dimensions, beginning at an origin and extending infinitely in a direction.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Ray2<S = scalar::Default> {
+    /// The point at which the ray begins.
+    pub origin: Point2<S>,
+    /// The direction in which the ray extends. Need not be normalized, though rays with a zero
+    /// direction intersect nothing.
+    pub direction: Vector2<S>,
+}
+
+/// A ray in three dimensions, beginning at an origin and extending infinitely in a direction.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Ray3<S = scalar::Default> {
+    /// The point at which the ray begins.
+    pub origin: Point3<S>,
+    /// The direction in which the ray extends. Need not be normalized, though rays with a zero
+    /// direction intersect nothing.
+    pub direction: Vector3<S>,
+}
+
+/// The first intersection of a `Ray2` with a shape.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Hit2<S = scalar::Default> {
+    /// The point at which the ray meets the shape.
+    pub point: Point2<S>,
+    /// The unit normal of the shape's surface at the point, facing back towards the ray's origin.
+    pub normal: Vector2<S>,
+    /// The distance from the ray's origin to the point.
+    pub distance: S,
+}
+
+/// The first intersection of a `Ray3` with a shape.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Hit3<S = scalar::Default> {
+    /// The point at which the ray meets the shape.
+    pub point: Point3<S>,
+    /// The unit normal of the shape's surface at the point, facing back towards the ray's origin.
+    pub normal: Vector3<S>,
+    /// The distance from the ray's origin to the point.
+    pub distance: S,
+}
+
+/// Describes the overlap of two shapes.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Contact<S = scalar::Default> {
+    /// The point of the first shape that reaches deepest into the second along the normal.
+    pub point: Point2<S>,
+    /// The unit direction in which the first shape must move to separate the shapes.
+    pub normal: Vector2<S>,
+    /// The distance the first shape must move along the normal to separate the shapes.
+    pub depth: S,
+}
+
+impl<S> Ray2<S>
+where
+    S: BaseFloat,
+{
+    /// A ray beginning at the given origin and extending in the given direction.
+    pub fn new(origin: Point2<S>, direction: Vector2<S>) -> Self {
+        Ray2 { origin, direction }
+    }
+
+    /// The ray extending from the origin through the given point.
+    pub fn through(origin: Point2<S>, point: Point2<S>) -> Self {
+        Ray2::new(origin, point - origin)
+    }
+
+    /// The point at the given distance along the ray.
+    pub fn point_at(&self, distance: S) -> Point2<S> {
+        self.origin + self.direction.normalize() * distance
+    }
+
+    // The normalized direction of the ray, or `None` if the direction is zero.
+    fn unit_direction(&self) -> Option<Vector2<S>> {
+        if self.direction.is_zero() {
+            None
+        } else {
+            Some(self.direction.normalize())
+        }
+    }
+}
+
+impl<S> Ray3<S>
+where
+    S: BaseFloat,
+{
+    /// A ray beginning at the given origin and extending in the given direction.
+    pub fn new(origin: Point3<S>, direction: Vector3<S>) -> Self {
+        Ray3 { origin, direction }
+    }
+
+    /// The ray extending from the origin through the given point.
+    pub fn through(origin: Point3<S>, point: Point3<S>) -> Self {
+        Ray3::new(origin, point - origin)
+    }
+
+    /// The point at the given distance along the ray.
+    pub fn point_at(&self, distance: S) -> Point3<S> {
+        self.origin + self.direction.normalize() * distance
+    }
+
+    // The normalized direction of the ray, or `None` if the direction is zero.
+    fn unit_direction(&self) -> Option<Vector3<S>> {
+        if self.direction.is_zero() {
+            None
+        } else {
+            Some(self.direction.normalize())
+        }
+    }
+}
+
+/// The point at which the segment from `a_start` to `a_end` intersects the segment from
+/// `b_start` to `b_end`.
+///
+/// Where collinear segments overlap, the point of the overlap nearest to `a_start` is returned.
+pub fn segment_segment<S>(
+    a_start: Point2<S>,
+    a_end: Point2<S>,
+    b_start: Point2<S>,
+    b_end: Point2<S>,
+) -> Option<Point2<S>>
+where
+    S: BaseFloat,
+{
+    let (r, s, q) = (a_end - a_start, b_end - b_start, b_start - a_start);
+    let denom = r.perp_dot(s);
+    let (zero, one) = (S::zero(), S::one());
+    if denom != zero {
+        let t = q.perp_dot(s) / denom;
+        let u = q.perp_dot(r) / denom;
+        return if t >= zero && t <= one && u >= zero && u <= one {
+            Some(a_start + r * t)
+        } else {
+            None
+        };
+    }
+
+    // The segments are parallel, so only intersect if they are collinear.
+    if q.perp_dot(r) != zero || q.perp_dot(s) != zero {
+        return None;
+    }
+    let r2 = r.magnitude2();
+    if r2 == zero {
+        return if closest_point_on_segment(a_start, b_start, b_end) == a_start {
+            Some(a_start)
+        } else {
+            None
+        };
+    }
+    let t0 = q.dot(r) / r2;
+    let t1 = t0 + s.dot(r) / r2;
+    let lo = t0.min(t1).max(zero);
+    let hi = t0.max(t1).min(one);
+    if lo <= hi {
+        Some(a_start + r * lo)
+    } else {
+        None
+    }
+}
+
+/// The first intersection of the ray with the segment from `start` to `end`.
+///
+/// Rays running parallel to the segment do not intersect it.
+pub fn ray_segment<S>(ray: &Ray2<S>, start: Point2<S>, end: Point2<S>) -> Option<Hit2<S>>
+where
+    S: BaseFloat,
+{
+    let dir = ray.unit_direction()?;
+    let s = end - start;
+    let denom = dir.perp_dot(s);
+    if denom == S::zero() {
+        return None;
+    }
+    let q = start - ray.origin;
+    let t = q.perp_dot(s) / denom;
+    let u = q.perp_dot(dir) / denom;
+    if t < S::zero() || u < S::zero() || u > S::one() {
+        return None;
+    }
+    let normal = facing(vec2(-s.y, s.x).normalize(), dir);
+    Some(Hit2 {
+        point: ray.origin + dir * t,
+        normal,
+        distance: t,
+    })
+}
+
+/// The first intersection of the ray with the circle.
+///
+/// Rays beginning within the circle hit its edge on the way out.
+pub fn ray_circle<S>(ray: &Ray2<S>, centre: Point2<S>, radius: S) -> Option<Hit2<S>>
+where
+    S: BaseFloat,
+{
+    let dir = ray.unit_direction()?;
+    let t = ray_ball(ray.origin, dir, centre, radius)?;
+    let point = ray.origin + dir * t;
+    Some(Hit2 {
+        point,
+        normal: facing((point - centre).normalize(), dir),
+        distance: t,
+    })
+}
+
+/// The first intersection of the ray with the edges of the rect.
+///
+/// Rays beginning within the rect hit its edge on the way out.
+pub fn ray_rect<S>(ray: &Ray2<S>, rect: &Rect<S>) -> Option<Hit2<S>>
+where
+    S: BaseFloat,
+{
+    let dir = ray.unit_direction()?;
+    let o = ray.origin;
+    let min = [rect.left(), rect.bottom()];
+    let max = [rect.right(), rect.top()];
+    let (t, axis) = ray_slabs(&[o.x, o.y], &[dir.x, dir.y], &min, &max)?;
+    let mut normal = Vector2::zero();
+    normal[axis] = -dir[axis].signum();
+    Some(Hit2 {
+        point: o + dir * t,
+        normal,
+        distance: t,
+    })
+}
+
+/// The first intersection of the ray with the edges of the polygon described by the given points.
+///
+/// The polygon is closed by an edge from the last point to the first.
+pub fn ray_polygon<S, I>(ray: &Ray2<S>, points: I) -> Option<Hit2<S>>
+where
+    S: BaseFloat,
+    I: IntoIterator<Item = Point2<S>>,
+{
+    let points: Vec<_> = points.into_iter().collect();
+    let n = points.len();
+    (0..n)
+        .filter_map(|i| ray_segment(ray, points[i], points[(i + 1) % n]))
+        .fold(None, |nearest: Option<Hit2<S>>, hit| match nearest {
+            Some(nearest) if nearest.distance <= hit.distance => Some(nearest),
+            _ => Some(hit),
+        })
+}
+
+/// The intersection of the ray with the triangle.
+///
+/// Triangles are hit from either side.
+pub fn ray_triangle<S>(ray: &Ray3<S>, tri: Tri<Point3<S>>) -> Option<Hit3<S>>
+where
+    S: BaseFloat,
+{
+    // The Möller-Trumbore algorithm.
+    let dir = ray.unit_direction()?;
+    let [a, b, c] = tri.0;
+    let (e1, e2) = (b - a, c - a);
+    let p = dir.cross(e2);
+    let det = e1.dot(p);
+    if det == S::zero() {
+        return None;
+    }
+    let s = ray.origin - a;
+    let u = s.dot(p) / det;
+    if u < S::zero() || u > S::one() {
+        return None;
+    }
+    let q = s.cross(e1);
+    let v = dir.dot(q) / det;
+    if v < S::zero() || u + v > S::one() {
+        return None;
+    }
+    let t = e2.dot(q) / det;
+    if t < S::zero() {
+        return None;
+    }
+    Some(Hit3 {
+        point: ray.origin + dir * t,
+        normal: facing(e1.cross(e2).normalize(), dir),
+        distance: t,
+    })
+}
+
+/// The first intersection of the ray with the sphere.
+///
+/// Rays beginning within the sphere hit its surface on the way out.
+pub fn ray_sphere<S>(ray: &Ray3<S>, centre: Point3<S>, radius: S) -> Option<Hit3<S>>
+where
+    S: BaseFloat,
+{
+    let dir = ray.unit_direction()?;
+    let t = ray_ball(ray.origin, dir, centre, radius)?;
+    let point = ray.origin + dir * t;
+    Some(Hit3 {
+        point,
+        normal: facing((point - centre).normalize(), dir),
+        distance: t,
+    })
+}
+
+/// The first intersection of the ray with the faces of the cuboid.
+///
+/// Rays beginning within the cuboid hit its faces on the way out.
+pub fn ray_cuboid<S>(ray: &Ray3<S>, cuboid: &Cuboid<S>) -> Option<Hit3<S>>
+where
+    S: BaseFloat,
+{
+    let dir = ray.unit_direction()?;
+    let o = ray.origin;
+    let c = cuboid.absolute();
+    let min = [c.x.start, c.y.start, c.z.start];
+    let max = [c.x.end, c.y.end, c.z.end];
+    let (t, axis) = ray_slabs(&[o.x, o.y, o.z], &[dir.x, dir.y, dir.z], &min, &max)?;
+    let mut normal = Vector3::zero();
+    normal[axis] = -dir[axis].signum();
+    Some(Hit3 {
+        point: o + dir * t,
+        normal,
+        distance: t,
+    })
+}
+
+/// The overlap of two circles, if any.
+pub fn circle_circle<S>(
+    a_centre: Point2<S>,
+    a_radius: S,
+    b_centre: Point2<S>,
+    b_radius: S,
+) -> Option<Contact<S>>
+where
+    S: BaseFloat,
+{
+    let v = a_centre - b_centre;
+    let dist = v.magnitude();
+    if dist > a_radius + b_radius {
+        return None;
+    }
+    // Concentric circles may be separated in any direction.
+    let normal = if dist == S::zero() {
+        vec2(S::one(), S::zero())
+    } else {
+        v / dist
+    };
+    Some(Contact {
+        point: a_centre - normal * a_radius,
+        normal,
+        depth: a_radius + b_radius - dist,
+    })
+}
+
+/// The overlap of a circle and a rect, if any.
+pub fn circle_rect<S>(centre: Point2<S>, radius: S, rect: &Rect<S>) -> Option<Contact<S>>
+where
+    S: BaseFloat,
+{
+    let (left, right, bottom, top) = (rect.left(), rect.right(), rect.bottom(), rect.top());
+    let (normal, depth) = if rect.contains(centre) {
+        // Separate the circle through the nearest edge.
+        let edges = [
+            (centre.x - left, vec2(-S::one(), S::zero())),
+            (right - centre.x, vec2(S::one(), S::zero())),
+            (centre.y - bottom, vec2(S::zero(), -S::one())),
+            (top - centre.y, vec2(S::zero(), S::one())),
+        ];
+        let (d, normal) = edges
+            .iter()
+            .cloned()
+            .fold(edges[0], |a, b| if b.0 < a.0 { b } else { a });
+        (normal, radius + d)
+    } else {
+        let closest = vec2(centre.x.max(left).min(right), centre.y.max(bottom).min(top));
+        let v = centre - closest;
+        let dist = v.magnitude();
+        if dist > radius {
+            return None;
+        }
+        (v / dist, radius - dist)
+    };
+    Some(Contact {
+        point: centre - normal * radius,
+        normal,
+        depth,
+    })
+}
+
+/// The overlap of two convex polygons, if any.
+///
+/// Uses the separating axis theorem, testing each edge normal of both polygons. The result is
+/// undefined for concave polygons.
+pub fn polygon_polygon<S>(a: &[Point2<S>], b: &[Point2<S>]) -> Option<Contact<S>>
+where
+    S: BaseFloat,
+{
+    if a.is_empty() || b.is_empty() {
+        return None;
+    }
+    // Find the axis along which the polygons overlap least.
+    let mut least: Option<(S, Vector2<S>)> = None;
+    for axis in edge_normals(a).chain(edge_normals(b)) {
+        let (a_lo, a_hi) = project(a, axis);
+        let (b_lo, b_hi) = project(b, axis);
+        let overlap = a_hi.min(b_hi) - a_lo.max(b_lo);
+        if overlap < S::zero() {
+            return None;
+        }
+        // Move `a` in whichever direction along the axis separates it soonest.
+        let (depth, normal) = if a_hi - b_lo < b_hi - a_lo {
+            (a_hi - b_lo, -axis)
+        } else {
+            (b_hi - a_lo, axis)
+        };
+        match least {
+            Some((d, _)) if d <= depth => (),
+            _ => least = Some((depth, normal)),
+        }
+    }
+    let (depth, normal) = least?;
+    let point = a.iter().cloned().fold(
+        a[0],
+        |p, q| if q.dot(normal) < p.dot(normal) { q } else { p },
+    );
+    Some(Contact {
+        point,
+        normal,
+        depth,
+    })
+}
+
+/// The point on the segment from `start` to `end` nearest to the given point.
+///
+/// Applies to both `Point2` and `Point3`.
+pub fn closest_point_on_segment<V>(point: V, start: V, end: V) -> V
+where
+    V: InnerSpace,
+    V::Scalar: BaseFloat,
+{
+    let s = end - start;
+    let s2 = s.magnitude2();
+    if s2 == V::Scalar::zero() {
+        return start;
+    }
+    let t = (point - start).dot(s) / s2;
+    start + s * t.max(V::Scalar::zero()).min(V::Scalar::one())
+}
+
+/// The point on the edge of the circle nearest to the given point.
+pub fn closest_point_on_circle<S>(point: Point2<S>, centre: Point2<S>, radius: S) -> Point2<S>
+where
+    S: BaseFloat,
+{
+    let v = point - centre;
+    let v = if v.is_zero() {
+        vec2(S::one(), S::zero())
+    } else {
+        v.normalize()
+    };
+    centre + v * radius
+}
+
+/// The point on the edges of the rect nearest to the given point.
+pub fn closest_point_on_rect<S>(point: Point2<S>, rect: &Rect<S>) -> Point2<S>
+where
+    S: BaseFloat,
+{
+    let corners = [
+        rect.bottom_left(),
+        rect.bottom_right(),
+        rect.top_right(),
+        rect.top_left(),
+    ];
+    closest_point_on_polygon(point, corners.iter().cloned())
+}
+
+/// The point on the edges of the polygon described by the given points nearest to the given
+/// point.
+///
+/// The polygon is closed by an edge from the last point to the first. Returns the given point if
+/// the polygon has no points.
+pub fn closest_point_on_polygon<S, I>(point: Point2<S>, points: I) -> Point2<S>
+where
+    S: BaseFloat,
+    I: IntoIterator<Item = Point2<S>>,
+{
+    let points: Vec<_> = points.into_iter().collect();
+    let n = points.len();
+    (0..n)
+        .map(|i| closest_point_on_segment(point, points[i], points[(i + 1) % n]))
+        .fold(None, |nearest: Option<Point2<S>>, p| match nearest {
+            Some(q) if (q - point).magnitude2() <= (p - point).magnitude2() => Some(q),
+            _ => Some(p),
+        })
+        .unwrap_or(point)
+}
+
+/// The point on the triangle nearest to the given point.
+pub fn closest_point_on_triangle<S>(point: Point3<S>, tri: Tri<Point3<S>>) -> Point3<S>
+where
+    S: BaseFloat,
+{
+    let [a, b, c] = tri.0;
+    let edges = [(a, b), (b, c), (c, a)];
+
+    // If the point's projection onto the triangle's plane lies within the triangle it is nearest,
+    // otherwise the nearest point lies on one of the edges.
+    let n = (b - a).cross(c - a);
+    if !n.is_zero() {
+        let projected = point - n * ((point - a).dot(n) / n.magnitude2());
+        let inside = edges
+            .iter()
+            .all(|&(s, e)| (e - s).cross(projected - s).dot(n) >= S::zero());
+        if inside {
+            return projected;
+        }
+    }
+    edges
+        .iter()
+        .map(|&(s, e)| closest_point_on_segment(point, s, e))
+        .fold(a, |q, p| {
+            if (p - point).magnitude2() < (q - point).magnitude2() {
+                p
+            } else {
+                q
+            }
+        })
+}
+
+/// The point on the faces of the cuboid nearest to the given point.
+pub fn closest_point_on_cuboid<S>(point: Point3<S>, cuboid: &Cuboid<S>) -> Point3<S>
+where
+    S: BaseFloat,
+{
+    let c = cuboid.absolute();
+    let ranges = [c.x, c.y, c.z];
+    let mut closest = point;
+    if c.contains(point) {
+        // Move the point onto the nearest face.
+        let mut nearest = (S::infinity(), 0, S::zero());
+        for (axis, range) in ranges.iter().enumerate() {
+            for &face in &[range.start, range.end] {
+                let d = (point[axis] - face).abs();
+                if d < nearest.0 {
+                    nearest = (d, axis, face);
+                }
+            }
+        }
+        closest[nearest.1] = nearest.2;
+    } else {
+        for (axis, range) in ranges.iter().enumerate() {
+            closest[axis] = point[axis].max(range.start).min(range.end);
+        }
+    }
+    closest
+}
+
+/// The signed distance from the point to the edge of the circle, negative within the circle.
+pub fn signed_distance_circle<S>(point: Point2<S>, centre: Point2<S>, radius: S) -> S
+where
+    S: BaseFloat,
+{
+    (point - centre).magnitude() - radius
+}
+
+/// The signed distance from the point to the edges of the rect, negative within the rect.
+pub fn signed_distance_rect<S>(point: Point2<S>, rect: &Rect<S>) -> S
+where
+    S: BaseFloat,
+{
+    let half = vec2(rect.w().abs(), rect.h().abs()) / (S::one() + S::one());
+    let v = point - rect.xy();
+    let d = vec2(v.x.abs() - half.x, v.y.abs() - half.y);
+    let outside = vec2(d.x.max(S::zero()), d.y.max(S::zero())).magnitude();
+    let inside = d.x.max(d.y).min(S::zero());
+    outside + inside
+}
+
+/// The signed distance from the point to the edges of the polygon described by the given points,
+/// negative within the polygon.
+///
+/// The polygon is closed by an edge from the last point to the first. Whether the point lies
+/// within a self-intersecting polygon is determined by the even-odd rule.
+pub fn signed_distance_polygon<S, I>(point: Point2<S>, points: I) -> S
+where
+    S: BaseFloat,
+    I: IntoIterator<Item = Point2<S>>,
+{
+    let points: Vec<_> = points.into_iter().collect();
+    let n = points.len();
+    let mut inside = false;
+    for i in 0..n {
+        let (a, b) = (points[i], points[(i + 1) % n]);
+        if (a.y > point.y) != (b.y > point.y) {
+            let x = a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x);
+            if point.x < x {
+                inside = !inside;
+            }
+        }
+    }
+    let dist = (closest_point_on_polygon(point, points) - point).magnitude();
+    if inside {
+        -dist
+    } else {
+        dist
+    }
+}
+
+/// The signed distance from the point to the faces of the cuboid, negative within the cuboid.
+pub fn signed_distance_cuboid<S>(point: Point3<S>, cuboid: &Cuboid<S>) -> S
+where
+    S: BaseFloat,
+{
+    let c = cuboid.absolute();
+    let two = S::one() + S::one();
+    let ranges = [c.x, c.y, c.z];
+    let mut d = Vector3::zero();
+    for (axis, range) in ranges.iter().enumerate() {
+        let middle = (range.start + range.end) / two;
+        let half = (range.end - range.start) / two;
+        d[axis] = (point[axis] - middle).abs() - half;
+    }
+    let outside = d.map(|s| s.max(S::zero())).magnitude();
+    let inside = d.x.max(d.y).max(d.z).min(S::zero());
+    outside + inside
+}
+
+// The range covered by the points when projected onto the axis.
+fn project<S>(points: &[Point2<S>], axis: Vector2<S>) -> (S, S)
+where
+    S: BaseFloat,
+{
+    points
+        .iter()
+        .fold((S::infinity(), S::neg_infinity()), |(lo, hi), p| {
+            let d = p.dot(axis);
+            (lo.min(d), hi.max(d))
+        })
+}
+
+// The unit normal of each edge of the polygon, skipping edges of zero length.
+fn edge_normals<S>(points: &[Point2<S>]) -> impl Iterator<Item = Vector2<S>> + '_
+where
+    S: BaseFloat,
+{
+    let n = points.len();
+    (0..n).filter_map(move |i| {
+        let e = points[(i + 1) % n] - points[i];
+        if e.is_zero() {
+            None
+        } else {
+            Some(vec2(-e.y, e.x).normalize())
+        }
+    })
+}
+
+// Flip the normal if necessary so that it faces against the direction.
+fn facing<V>(normal: V, direction: V) -> V
+where
+    V: InnerSpace + Neg<Output = V>,
+    V::Scalar: BaseFloat,
+{
+    if normal.dot(direction) > V::Scalar::zero() {
+        -normal
+    } else {
+        normal
+    }
+}
+
+// The distance along the normalized direction from the origin at which a ray first meets the
+// circle or sphere.
+fn ray_ball<V>(origin: V, dir: V, centre: V, radius: V::Scalar) -> Option<V::Scalar>
+where
+    V: InnerSpace,
+    V::Scalar: BaseFloat,
+{
+    let zero = V::Scalar::zero();
+    let m = origin - centre;
+    let b = m.dot(dir);
+    let c = m.magnitude2() - radius * radius;
+    // The ray begins outside of the ball and points away from it.
+    if c > zero && b > zero {
+        return None;
+    }
+    let discriminant = b * b - c;
+    if discriminant < zero {
+        return None;
+    }
+    let t = -b - discriminant.sqrt();
+    if t >= zero {
+        Some(t)
+    } else {
+        Some(-b + discriminant.sqrt())
+    }
+}
+
+// The distance along the normalized direction from the origin at which a ray first meets the
+// boundary of the axis-aligned box, along with the axis of the face that it meets.
+fn ray_slabs<S>(origin: &[S], dir: &[S], min: &[S], max: &[S]) -> Option<(S, usize)>
+where
+    S: BaseFloat,
+{
+    let (mut enter, mut enter_axis) = (S::neg_infinity(), 0);
+    let (mut exit, mut exit_axis) = (S::infinity(), 0);
+    for axis in 0..origin.len() {
+        if dir[axis] == S::zero() {
+            if origin[axis] < min[axis] || origin[axis] > max[axis] {
+                return None;
+            }
+            continue;
+        }
+        let t1 = (min[axis] - origin[axis]) / dir[axis];
+        let t2 = (max[axis] - origin[axis]) / dir[axis];
+        let (near, far) = if t1 < t2 { (t1, t2) } else { (t2, t1) };
+        if near > enter {
+            enter = near;
+            enter_axis = axis;
+        }
+        if far < exit {
+            exit = far;
+            exit_axis = axis;
+        }
+    }
+    if exit < enter || exit < S::zero() {
+        None
+    } else if enter >= S::zero() {
+        Some((enter, enter_axis))
+    } else {
+        Some((exit, exit_axis))
+    }
+}
@@ -14,8 +14,10 @@ use crate::math::{BaseFloat, EuclideanSpace};
 use std::ops;
 
 pub mod cuboid;
+pub mod delaunay;
 pub mod ellipse;
 pub mod graph;
+pub mod intersect;
 pub mod path;
 pub mod point;
 pub mod polygon;
@@ -23,9 +25,12 @@ pub mod quad;
 pub mod range;
 pub mod rect;
 pub mod scalar;
+pub mod sdf;
+pub mod spatial;
 pub mod tri;
 pub mod vector;
 pub mod vertex;
+pub mod voronoi;
 
 pub use self::cuboid::Cuboid;
 pub use self::ellipse::Ellipse;
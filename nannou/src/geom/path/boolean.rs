@@ -0,0 +1,476 @@
+//! Boolean operations between paths, e.g. union, intersection, difference and xor.
+//!
+//! Curves are flattened to line segments before the operation is applied, so the resulting path
+//! consists only of straight edges. Each operand's interior is determined by its own fill rule.
+//! The resulting path winds counter-clockwise around filled regions and clockwise around holes,
+//! and describes the same area under both the even-odd and non-zero fill rules.
+//!
+//! The cost of an operation grows quadratically with the number of flattened edges.
+
+use crate::geom::path::Path;
+use crate::geom::{pt2, Vector2};
+use lyon::path::iterator::PathIterator;
+use lyon::path::PathEvent;
+use lyon::tessellation::FillRule;
+use std::collections::HashMap;
+
+/// The boolean operations that may be applied to a pair of paths.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Op {
+    /// The area covered by either path.
+    Union,
+    /// The area covered by both paths.
+    Intersection,
+    /// The area covered by the first path but not by the second.
+    Difference,
+    /// The area covered by exactly one of the paths.
+    Xor,
+}
+
+/// Parameters for a boolean operation.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Options {
+    /// The maximum distance between a curve and the line segments used to approximate it.
+    pub tolerance: f32,
+    /// The rule used to determine the interior of the first path.
+    pub fill_rule: FillRule,
+    /// The rule used to determine the interior of the second path.
+    pub other_fill_rule: FillRule,
+}
+
+// All geometry is processed in double precision.
+pub(super) type Point = Vector2<f64>;
+
+// An edge between two vertices, belonging to one of the two operands.
+#[derive(Copy, Clone, Debug)]
+struct Edge {
+    from: usize,
+    to: usize,
+    operand: usize,
+}
+
+// A set of vertices in which points within some small distance of one another are merged.
+struct Vertices {
+    points: Vec<Point>,
+    cells: HashMap<(i64, i64), Vec<usize>>,
+    epsilon: f64,
+}
+
+impl Op {
+    /// Whether or not a region inside the first and/or second operand is inside the result.
+    pub fn apply(self, a: bool, b: bool) -> bool {
+        match self {
+            Op::Union => a || b,
+            Op::Intersection => a && b,
+            Op::Difference => a && !b,
+            Op::Xor => a != b,
+        }
+    }
+}
+
+impl Options {
+    /// The default flattening tolerance.
+    pub const DEFAULT_TOLERANCE: f32 = 0.1;
+
+    /// Specify the maximum distance between a curve and the line segments used to approximate it.
+    pub fn tolerance(mut self, tolerance: f32) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Specify the rule used to determine the interior of the first path.
+    pub fn fill_rule(mut self, rule: FillRule) -> Self {
+        self.fill_rule = rule;
+        self
+    }
+
+    /// Specify the rule used to determine the interior of the second path.
+    pub fn other_fill_rule(mut self, rule: FillRule) -> Self {
+        self.other_fill_rule = rule;
+        self
+    }
+}
+
+impl Vertices {
+    fn new(epsilon: f64) -> Self {
+        Vertices {
+            points: vec![],
+            cells: HashMap::new(),
+            epsilon,
+        }
+    }
+
+    // Insert the point, returning the index of the vertex with which it was merged, if any.
+    fn insert(&mut self, p: Point) -> usize {
+        let cell = |v: f64| (v / self.epsilon).floor() as i64;
+        let (cx, cy) = (cell(p.x), cell(p.y));
+        for x in cx - 1..=cx + 1 {
+            for y in cy - 1..=cy + 1 {
+                if let Some(ixs) = self.cells.get(&(x, y)) {
+                    for &ix in ixs {
+                        if (self.points[ix] - p).magnitude() <= self.epsilon {
+                            return ix;
+                        }
+                    }
+                }
+            }
+        }
+        let ix = self.points.len();
+        self.points.push(p);
+        self.cells.entry((cx, cy)).or_insert_with(Vec::new).push(ix);
+        ix
+    }
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            tolerance: Self::DEFAULT_TOLERANCE,
+            fill_rule: FillRule::EvenOdd,
+            other_fill_rule: FillRule::EvenOdd,
+        }
+    }
+}
+
+/// Apply the boolean operation to the paths described by the two sequences of path events.
+///
+/// All sub-paths are treated as closed.
+pub fn boolean<A, B>(a: A, b: B, op: Op, options: &Options) -> Path
+where
+    A: IntoIterator<Item = PathEvent>,
+    B: IntoIterator<Item = PathEvent>,
+{
+    let rings = [flatten(a, options.tolerance), flatten(b, options.tolerance)];
+    let rules = [options.fill_rule, options.other_fill_rule];
+    let inside = |w: [i32; 2]| op.apply(is_inside(w[0], rules[0]), is_inside(w[1], rules[1]));
+    rings_to_path(apply(&rings, inside))
+}
+
+// Build a path with a closed sub-path for each ring.
+pub(super) fn rings_to_path(rings: Vec<Vec<Point>>) -> Path {
+    let mut builder = Path::builder();
+    for ring in rings {
+        let mut ring = ring.into_iter().map(|p| pt2(p.x as f32, p.y as f32));
+        if let Some(first) = ring.next() {
+            builder = builder.move_to(first);
+            for p in ring {
+                builder = builder.line_to(p);
+            }
+            builder = builder.close();
+        }
+    }
+    builder.build()
+}
+
+// Produce the rings bounding the regions in which the given function returns `true` for the
+// winding numbers of the two sets of rings.
+//
+// The resulting rings wind counter-clockwise around the regions and clockwise around holes.
+pub(super) fn apply<F>(rings: &[Vec<Vec<Point>>; 2], inside: F) -> Vec<Vec<Point>>
+where
+    F: Fn([i32; 2]) -> bool,
+{
+    // Vertices closer than this distance are considered coincident.
+    let (min, max) = rings
+        .iter()
+        .flat_map(|rs| rs.iter().flat_map(|r| r.iter()))
+        .fold(
+            (
+                pt2(std::f64::MAX, std::f64::MAX),
+                pt2(std::f64::MIN, std::f64::MIN),
+            ),
+            |(min, max), p| {
+                let min = pt2(min.x.min(p.x), min.y.min(p.y));
+                let max = pt2(max.x.max(p.x), max.y.max(p.y));
+                (min, max)
+            },
+        );
+    if min.x > max.x {
+        return vec![];
+    }
+    let epsilon = ((max - min).magnitude() * 1e-9).max(std::f64::MIN_POSITIVE);
+
+    let (points, edges) = split_edges(rings, epsilon);
+
+    // Group coincident edges, tracking the net number of times each operand's boundary passes
+    // along the edge in the direction of its first occurrence.
+    let mut groups: HashMap<(usize, usize), ((usize, usize), [i32; 2])> = HashMap::new();
+    let mut order = vec![];
+    for e in &edges {
+        let key = edge_key(e);
+        let group = groups.entry(key).or_insert_with(|| {
+            order.push(key);
+            ((e.from, e.to), [0, 0])
+        });
+        let dir = if (e.from, e.to) == group.0 { 1 } else { -1 };
+        group.1[e.operand] += dir;
+    }
+
+    // Keep the edges that separate the inside of the result from the outside, oriented so that
+    // the inside is on the left.
+    let mut kept = vec![];
+    for key in order {
+        let ((from, to), net) = groups[&key];
+        let (a, b) = (points[from], points[to]);
+        let d = b - a;
+        let mid = a + d * 0.5;
+        let right_normal = pt2(d.y, -d.x);
+        let right = ray_winding(&points, &edges, key, mid, right_normal);
+        let left = [right[0] + net[0], right[1] + net[1]];
+        match (inside(left), inside(right)) {
+            (true, false) => kept.push((from, to)),
+            (false, true) => kept.push((to, from)),
+            _ => (),
+        }
+    }
+
+    link_loops(&points, &kept)
+}
+
+// Whether the given winding number lies inside the shape under the given fill rule.
+pub(super) fn is_inside(winding: i32, rule: FillRule) -> bool {
+    match rule {
+        FillRule::EvenOdd => winding % 2 != 0,
+        FillRule::NonZero => winding != 0,
+    }
+}
+
+// The key shared by an edge and all edges coincident with it.
+fn edge_key(e: &Edge) -> (usize, usize) {
+    (e.from.min(e.to), e.from.max(e.to))
+}
+
+// Flatten the path events into closed rings of points.
+pub(super) fn flatten<I>(events: I, tolerance: f32) -> Vec<Vec<Point>>
+where
+    I: IntoIterator<Item = PathEvent>,
+{
+    let mut rings = vec![];
+    let mut ring: Vec<Point> = vec![];
+    let mut finish = |ring: &mut Vec<Point>| {
+        let mut r = std::mem::replace(ring, vec![]);
+        r.dedup();
+        while r.len() > 1 && r.first() == r.last() {
+            r.pop();
+        }
+        if r.len() > 2 {
+            rings.push(r);
+        }
+    };
+    let to_point = |p: lyon::math::Point| pt2(p.x as f64, p.y as f64);
+    for event in events.into_iter().flattened(tolerance) {
+        match event {
+            PathEvent::Begin { at } => {
+                finish(&mut ring);
+                ring.push(to_point(at));
+            }
+            PathEvent::Line { to, .. }
+            | PathEvent::Quadratic { to, .. }
+            | PathEvent::Cubic { to, .. } => ring.push(to_point(to)),
+            PathEvent::End { .. } => finish(&mut ring),
+        }
+    }
+    finish(&mut ring);
+    rings
+}
+
+// Split the edges of all rings at every point at which they touch another edge.
+fn split_edges(rings: &[Vec<Vec<Point>>; 2], epsilon: f64) -> (Vec<Point>, Vec<Edge>) {
+    let mut segments = vec![];
+    for (operand, rings) in rings.iter().enumerate() {
+        for ring in rings {
+            for (i, &a) in ring.iter().enumerate() {
+                let b = ring[(i + 1) % ring.len()];
+                segments.push((a, b, operand));
+            }
+        }
+    }
+
+    // Find the parameters along each segment at which it must be split.
+    let mut splits: Vec<Vec<f64>> = vec![vec![0.0, 1.0]; segments.len()];
+    for i in 0..segments.len() {
+        for j in i + 1..segments.len() {
+            let (a0, a1, _) = segments[i];
+            let (b0, b1, _) = segments[j];
+            intersect(a0, a1, b0, b1, epsilon, &mut |ta, tb| {
+                if let Some(t) = ta {
+                    splits[i].push(t);
+                }
+                if let Some(t) = tb {
+                    splits[j].push(t);
+                }
+            });
+        }
+    }
+
+    let mut vertices = Vertices::new(epsilon);
+    let mut edges = vec![];
+    for (&(a, b, operand), ts) in segments.iter().zip(&mut splits) {
+        ts.sort_by(|a, b| a.partial_cmp(b).expect("split parameter was NaN"));
+        let ixs: Vec<usize> = ts
+            .iter()
+            .map(|&t| vertices.insert(a + (b - a) * t))
+            .collect();
+        for w in ixs.windows(2) {
+            if w[0] != w[1] {
+                let (from, to) = (w[0], w[1]);
+                edges.push(Edge { from, to, operand });
+            }
+        }
+    }
+    (vertices.points, edges)
+}
+
+// Call `f` with the parameters at which each of the two segments should be split in order to
+// meet the other.
+fn intersect<F>(a0: Point, a1: Point, b0: Point, b1: Point, epsilon: f64, f: &mut F)
+where
+    F: FnMut(Option<f64>, Option<f64>),
+{
+    let (r, s) = (a1 - a0, b1 - b0);
+    let (r_len, s_len) = (r.magnitude(), s.magnitude());
+    if r_len <= epsilon || s_len <= epsilon {
+        return;
+    }
+
+    // Skip segments whose bounding boxes do not overlap.
+    if a0.x.max(a1.x) + epsilon < b0.x.min(b1.x)
+        || b0.x.max(b1.x) + epsilon < a0.x.min(a1.x)
+        || a0.y.max(a1.y) + epsilon < b0.y.min(b1.y)
+        || b0.y.max(b1.y) + epsilon < a0.y.min(a1.y)
+    {
+        return;
+    }
+
+    // The parameter along a segment if it lies strictly between the ends.
+    let interior = |t: f64, len: f64| {
+        if t * len > epsilon && (1.0 - t) * len > epsilon {
+            Some(t)
+        } else {
+            None
+        }
+    };
+
+    let qp = b0 - a0;
+    let denom = r.perp_dot(s);
+    if denom.abs() <= 1e-12 * r_len * s_len {
+        // Parallel segments only touch if they are collinear.
+        if (qp.perp_dot(r) / r_len).abs() > epsilon {
+            return;
+        }
+        let project_a = |p: Point| (p - a0).dot(r) / (r_len * r_len);
+        let project_b = |p: Point| (p - b0).dot(s) / (s_len * s_len);
+        f(interior(project_a(b0), r_len), None);
+        f(interior(project_a(b1), r_len), None);
+        f(None, interior(project_b(a0), s_len));
+        f(None, interior(project_b(a1), s_len));
+        return;
+    }
+
+    let t = qp.perp_dot(s) / denom;
+    let u = qp.perp_dot(r) / denom;
+    let (t_tol, u_tol) = (epsilon / r_len, epsilon / s_len);
+    if t < -t_tol || t > 1.0 + t_tol || u < -u_tol || u > 1.0 + u_tol {
+        return;
+    }
+    f(interior(t, r_len), interior(u, s_len));
+}
+
+// The winding number of each operand along a ray cast from `origin` in the given direction.
+//
+// Edges with the given key are ignored, allowing the ray to begin on those edges. The result is
+// the winding number of the region immediately to the side of those edges in which the ray is
+// cast.
+fn ray_winding(
+    points: &[Point],
+    edges: &[Edge],
+    exclude: (usize, usize),
+    origin: Point,
+    dir: Point,
+) -> [i32; 2] {
+    let mut winding = [0, 0];
+    for e in edges {
+        if edge_key(e) == exclude {
+            continue;
+        }
+        // Transform the edge so that the ray lies along the positive x axis.
+        let local = |p: Point| {
+            let p = p - origin;
+            pt2(p.dot(dir), dir.perp_dot(p))
+        };
+        let (a, b) = (local(points[e.from]), local(points[e.to]));
+        let up = a.y <= 0.0 && b.y > 0.0;
+        let down = b.y <= 0.0 && a.y > 0.0;
+        if !up && !down {
+            continue;
+        }
+        let x = a.x + (b.x - a.x) * (-a.y / (b.y - a.y));
+        if x > 0.0 {
+            winding[e.operand] += if up { 1 } else { -1 };
+        }
+    }
+    winding
+}
+
+// Link the directed edges into closed loops, dropping collinear vertices.
+fn link_loops(points: &[Point], edges: &[(usize, usize)]) -> Vec<Vec<Point>> {
+    let mut outgoing: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (i, &(from, _)) in edges.iter().enumerate() {
+        outgoing.entry(from).or_insert_with(Vec::new).push(i);
+    }
+    let mut used = vec![false; edges.len()];
+    let mut loops = vec![];
+    for start in 0..edges.len() {
+        if used[start] {
+            continue;
+        }
+        let mut ring = vec![];
+        let mut current = start;
+        loop {
+            used[current] = true;
+            let (from, to) = edges[current];
+            ring.push(points[from]);
+            if to == edges[start].0 {
+                break;
+            }
+            // Take the sharpest left turn so that loops touching at a vertex remain separate.
+            let d_in = points[to] - points[from];
+            let turn = |e: usize| {
+                let d_out = points[edges[e].1] - points[to];
+                d_in.perp_dot(d_out).atan2(d_in.dot(d_out))
+            };
+            let next = outgoing.get(&to).and_then(|es| {
+                es.iter()
+                    .cloned()
+                    .filter(|&e| !used[e])
+                    .max_by(|&a, &b| turn(a).partial_cmp(&turn(b)).expect("turn angle was NaN"))
+            });
+            match next {
+                Some(next) => current = next,
+                None => break,
+            }
+        }
+        remove_collinear(&mut ring);
+        if ring.len() > 2 {
+            loops.push(ring);
+        }
+    }
+    loops
+}
+
+// Remove vertices that lie on the straight line between their neighbours.
+fn remove_collinear(ring: &mut Vec<Point>) {
+    let mut i = 0;
+    while ring.len() > 2 && i < ring.len() {
+        let n = ring.len();
+        let prev = ring[(i + n - 1) % n];
+        let next = ring[(i + 1) % n];
+        let (a, b) = (ring[i] - prev, next - ring[i]);
+        let straight = a.perp_dot(b).abs() <= 1e-12 * a.magnitude() * b.magnitude();
+        if straight && a.dot(b) > 0.0 {
+            ring.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+}
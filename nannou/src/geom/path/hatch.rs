@@ -0,0 +1,348 @@
+//! Filling the interior of paths and polygons with strokes rather than triangles.
+//!
+//! Hatching is useful for pen plotters, lasers and other devices that can only draw lines. The
+//! interior of a shape is filled with parallel lines, crossed lines or contours that follow the
+//! outline of the shape. Curves are flattened before hatching.
+//!
+//! The result is a list of polylines, each of which may be drawn with `draw.polyline()` or
+//! converted to laser points and added to a frame with `nannou_laser::Frame::add_lines`. By
+//! default the polylines are ordered and oriented so as to reduce the distance travelled between
+//! the end of one polyline and the start of the next.
+
+use crate::geom::path::boolean::{self, Point};
+use crate::geom::path::offset;
+use crate::geom::{pt2, Point2};
+use lyon::path::PathEvent;
+use lyon::tessellation::FillRule;
+
+/// The patterns with which the interior of a shape may be filled.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Pattern {
+    /// Parallel lines at the hatch angle.
+    Lines,
+    /// Parallel lines at the hatch angle crossed by parallel lines at a right angle to it.
+    CrossHatch,
+    /// Contours following the outline of the shape, each inset from the last by the spacing.
+    Concentric,
+}
+
+/// Parameters for a hatch fill.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Options {
+    /// The pattern with which the shape is filled.
+    pub pattern: Pattern,
+    /// The angle of the hatch lines in radians, measured counter-clockwise from the x axis.
+    ///
+    /// Ignored by the `Concentric` pattern.
+    pub angle: f32,
+    /// The distance between adjacent lines or contours.
+    pub spacing: f32,
+    /// The maximum distance between a curve and the line segments used to approximate it.
+    pub tolerance: f32,
+    /// The rule used to determine the interior of the shape.
+    pub fill_rule: FillRule,
+    /// Whether or not to reorder and reverse the polylines to reduce travel between them.
+    pub optimize_travel: bool,
+}
+
+// A polyline produced by hatching. Closed polylines repeat their first point at the end.
+struct Line {
+    points: Vec<Point>,
+    closed: bool,
+}
+
+impl Options {
+    /// The default distance between adjacent lines.
+    pub const DEFAULT_SPACING: f32 = 5.0;
+
+    /// The default flattening tolerance.
+    pub const DEFAULT_TOLERANCE: f32 = 0.1;
+
+    /// Specify the pattern with which the shape is filled.
+    pub fn pattern(mut self, pattern: Pattern) -> Self {
+        self.pattern = pattern;
+        self
+    }
+
+    /// Fill the shape with parallel lines.
+    pub fn lines(self) -> Self {
+        self.pattern(Pattern::Lines)
+    }
+
+    /// Fill the shape with two sets of parallel lines crossing at a right angle.
+    pub fn cross_hatch(self) -> Self {
+        self.pattern(Pattern::CrossHatch)
+    }
+
+    /// Fill the shape with contours following its outline.
+    pub fn concentric(self) -> Self {
+        self.pattern(Pattern::Concentric)
+    }
+
+    /// Specify the angle of the hatch lines in radians.
+    pub fn angle(mut self, angle: f32) -> Self {
+        self.angle = angle;
+        self
+    }
+
+    /// Specify the distance between adjacent lines or contours.
+    ///
+    /// Must be greater than `0.0`, otherwise no lines are produced. The `Concentric` pattern
+    /// produces no contours where the spacing is less than the tolerance.
+    pub fn spacing(mut self, spacing: f32) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    /// Specify the maximum distance between a curve and its approximation.
+    pub fn tolerance(mut self, tolerance: f32) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Specify the rule used to determine the interior of the shape.
+    pub fn fill_rule(mut self, rule: FillRule) -> Self {
+        self.fill_rule = rule;
+        self
+    }
+
+    /// Specify whether or not to reorder and reverse the polylines to reduce travel between them.
+    pub fn optimize_travel(mut self, optimize: bool) -> Self {
+        self.optimize_travel = optimize;
+        self
+    }
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            pattern: Pattern::Lines,
+            angle: 0.0,
+            spacing: Self::DEFAULT_SPACING,
+            tolerance: Self::DEFAULT_TOLERANCE,
+            fill_rule: FillRule::EvenOdd,
+            optimize_travel: true,
+        }
+    }
+}
+
+/// Hatch the interior of the shape described by the given path events.
+///
+/// All sub-paths are treated as closed. Returns the polylines of the hatch. The polylines of the
+/// `Concentric` pattern are closed, repeating their first point at the end.
+pub fn hatch<I>(events: I, options: &Options) -> Vec<Vec<Point2>>
+where
+    I: IntoIterator<Item = PathEvent>,
+{
+    let rings = boolean::flatten(events, options.tolerance);
+    hatch_rings(rings, options)
+}
+
+/// Hatch the interior of the polygon described by the given points.
+///
+/// Returns the polylines of the hatch.
+pub fn hatch_polygon<I>(points: I, options: &Options) -> Vec<Vec<Point2>>
+where
+    I: IntoIterator,
+    I::Item: Into<Point2>,
+{
+    let ring = points
+        .into_iter()
+        .map(|p| {
+            let p = p.into();
+            pt2(p.x as f64, p.y as f64)
+        })
+        .collect();
+    hatch_rings(vec![ring], options)
+}
+
+// Hatch the interior of the closed rings.
+fn hatch_rings(rings: Vec<Vec<Point>>, options: &Options) -> Vec<Vec<Point2>> {
+    let spacing = options.spacing as f64;
+    if spacing.is_nan() || spacing <= 0.0 || rings.is_empty() {
+        return vec![];
+    }
+    let angle = options.angle as f64;
+    let rule = options.fill_rule;
+    let lines = match options.pattern {
+        Pattern::Lines => hatch_lines(&rings, angle, spacing, rule),
+        Pattern::CrossHatch => {
+            let mut lines = hatch_lines(&rings, angle, spacing, rule);
+            let right_angle = angle + std::f64::consts::FRAC_PI_2;
+            lines.extend(hatch_lines(&rings, right_angle, spacing, rule));
+            lines
+        }
+        Pattern::Concentric => hatch_contours(rings, spacing, options),
+    };
+    let lines = if options.optimize_travel {
+        order_for_travel(lines)
+    } else {
+        lines
+    };
+    lines
+        .into_iter()
+        .map(|line| {
+            line.points
+                .into_iter()
+                .map(|p| pt2(p.x as f32, p.y as f32))
+                .collect()
+        })
+        .collect()
+}
+
+// Produce the segments of the parallel lines at the given angle that lie inside the rings.
+//
+// Lines are placed at multiples of the spacing from the origin, so that the hatching of adjacent
+// shapes lines up.
+fn hatch_lines(rings: &[Vec<Point>], angle: f64, spacing: f64, rule: FillRule) -> Vec<Line> {
+    // Rotate the rings so that the hatch lines are horizontal.
+    let (sin, cos) = angle.sin_cos();
+    let rings: Vec<Vec<Point>> = rings
+        .iter()
+        .map(|ring| {
+            ring.iter()
+                .map(|p| pt2(p.x * cos + p.y * sin, p.y * cos - p.x * sin))
+                .collect()
+        })
+        .collect();
+    let unrotate = |x: f64, y: f64| pt2(x * cos - y * sin, x * sin + y * cos);
+
+    let (min_y, max_y) = rings
+        .iter()
+        .flat_map(|ring| ring.iter())
+        .fold((std::f64::MAX, std::f64::MIN), |(min, max), p| {
+            (min.min(p.y), max.max(p.y))
+        });
+    let first = (min_y / spacing).ceil() as i64;
+    let last = (max_y / spacing).floor() as i64;
+
+    let mut lines = vec![];
+    let mut crossings = vec![];
+    for k in first..=last {
+        let y = k as f64 * spacing;
+
+        // Find where each edge crosses the line along with the direction in which it crosses.
+        crossings.clear();
+        for ring in &rings {
+            for (i, &a) in ring.iter().enumerate() {
+                let b = ring[(i + 1) % ring.len()];
+                if (a.y <= y) != (b.y <= y) {
+                    let x = a.x + (y - a.y) / (b.y - a.y) * (b.x - a.x);
+                    let dir = if b.y > a.y { 1 } else { -1 };
+                    crossings.push((x, dir));
+                }
+            }
+        }
+        crossings.sort_by(|a, b| a.0.partial_cmp(&b.0).expect("crossing was NaN"));
+
+        // Emit the spans along which the line is inside the shape.
+        let mut winding = 0;
+        let mut start = None;
+        for &(x, dir) in &crossings {
+            winding += dir;
+            match (start, boolean::is_inside(winding, rule)) {
+                (None, true) => start = Some(x),
+                (Some(x0), false) => {
+                    if x > x0 {
+                        let points = vec![unrotate(x0, y), unrotate(x, y)];
+                        let closed = false;
+                        lines.push(Line { points, closed });
+                    }
+                    start = None;
+                }
+                _ => (),
+            }
+        }
+    }
+    lines
+}
+
+// Produce contours inset from the outline of the rings by successive multiples of the spacing.
+//
+// The first contour is inset by half of the spacing, so that the contours of adjacent shapes are
+// evenly spaced.
+fn hatch_contours(rings: Vec<Vec<Point>>, spacing: f64, options: &Options) -> Vec<Line> {
+    // Contours closer together than the tolerance could not be told apart.
+    if spacing < options.tolerance as f64 {
+        return vec![];
+    }
+    let offset_options = offset::Options::default()
+        .tolerance(options.tolerance)
+        .fill_rule(options.fill_rule);
+
+    // Nothing remains once inset by half of the narrower side of the bounds, which limits the
+    // number of contours even where offsetting does not erode the shape entirely.
+    let (min, max) = rings.iter().flat_map(|ring| ring.iter()).fold(
+        (
+            pt2(std::f64::MAX, std::f64::MAX),
+            pt2(std::f64::MIN, std::f64::MIN),
+        ),
+        |(min, max), p| {
+            let min = pt2(min.x.min(p.x), min.y.min(p.y));
+            let max = pt2(max.x.max(p.x), max.y.max(p.y));
+            (min, max)
+        },
+    );
+    let max_inset = (max.x - min.x).min(max.y - min.y) * 0.5;
+
+    let mut lines = vec![];
+    for k in 0.. {
+        let inset = (k as f64 + 0.5) * spacing;
+        if inset >= max_inset {
+            break;
+        }
+        let contours = offset::offset_rings(rings.clone(), -inset as f32, &offset_options);
+        if contours.is_empty() {
+            break;
+        }
+        for mut points in contours {
+            points.push(points[0]);
+            let closed = true;
+            lines.push(Line { points, closed });
+        }
+    }
+    lines
+}
+
+// Greedily order the lines so that each begins at the entry point nearest to the end of the last.
+//
+// Open lines may be entered from either end, while closed lines may be entered at any vertex.
+fn order_for_travel(mut lines: Vec<Line>) -> Vec<Line> {
+    let mut ordered = Vec::with_capacity(lines.len());
+    let mut pen = match lines.first() {
+        Some(line) => line.points[0],
+        None => return ordered,
+    };
+    while !lines.is_empty() {
+        // The index of the line, the index of its entry point and the distance to it.
+        let mut nearest = (0, 0, std::f64::MAX);
+        for (i, line) in lines.iter().enumerate() {
+            let last = line.points.len() - 1;
+            let mut consider = |j: usize| {
+                let dist = (line.points[j] - pen).magnitude2();
+                if dist < nearest.2 {
+                    nearest = (i, j, dist);
+                }
+            };
+            if line.closed {
+                (0..last).for_each(&mut consider);
+            } else {
+                consider(0);
+                consider(last);
+            }
+        }
+
+        let (i, j, _) = nearest;
+        let mut line = lines.swap_remove(i);
+        if line.closed {
+            line.points.pop();
+            line.points.rotate_left(j);
+            line.points.push(line.points[0]);
+        } else if j > 0 {
+            line.points.reverse();
+        }
+        pen = line.points[line.points.len() - 1];
+        ordered.push(line);
+    }
+    ordered
+}
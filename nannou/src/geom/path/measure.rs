@@ -0,0 +1,223 @@
+//! Measuring and sampling paths by distance along their length.
+//!
+//! Curves and arcs are flattened to line segments within a given tolerance, after which all
+//! distances are measured along the flattened path. Sub-paths are measured one after another,
+//! with no distance between the end of one sub-path and the beginning of the next.
+
+use crate::geom::path::{Builder, Path};
+use crate::geom::{pt2, Point2, Vector2};
+use lyon::path::iterator::PathIterator;
+use lyon::path::PathEvent;
+
+/// The default maximum distance between a curve and the line segments used to approximate it.
+pub const DEFAULT_TOLERANCE: f32 = 0.1;
+
+/// A flattened path prepared for queries by distance along its length.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Measure {
+    subpaths: Vec<Subpath>,
+    length: f32,
+}
+
+// A flattened sub-path.
+#[derive(Clone, Debug, PartialEq)]
+struct Subpath {
+    // For closed sub-paths, the first point is repeated at the end.
+    points: Vec<Point2>,
+    // The distance along the whole path at which each point lies.
+    distances: Vec<f32>,
+    closed: bool,
+}
+
+impl Measure {
+    /// Flatten the given path events to the given tolerance and measure the result.
+    pub fn new<I>(events: I, tolerance: f32) -> Self
+    where
+        I: IntoIterator<Item = PathEvent>,
+    {
+        let mut subpaths = vec![];
+        let mut length = 0.0;
+        let mut points: Vec<Point2> = vec![];
+        let mut distances = vec![];
+        for event in events.into_iter().flattened(tolerance) {
+            let to = match event {
+                PathEvent::Begin { at } => {
+                    points.clear();
+                    distances.clear();
+                    points.push(pt2(at.x, at.y));
+                    distances.push(length);
+                    continue;
+                }
+                PathEvent::Line { to, .. }
+                | PathEvent::Quadratic { to, .. }
+                | PathEvent::Cubic { to, .. } => pt2(to.x, to.y),
+                PathEvent::End { first, close, .. } => {
+                    if close {
+                        let first = pt2(first.x, first.y);
+                        let last = *points.last().expect("sub-path has no points");
+                        length += (first - last).magnitude();
+                        points.push(first);
+                        distances.push(length);
+                    }
+                    let points = std::mem::replace(&mut points, vec![]);
+                    let distances = std::mem::replace(&mut distances, vec![]);
+                    subpaths.push(Subpath {
+                        points,
+                        distances,
+                        closed: close,
+                    });
+                    continue;
+                }
+            };
+            let last = *points.last().expect("line event before the sub-path began");
+            length += (to - last).magnitude();
+            points.push(to);
+            distances.push(length);
+        }
+        Measure { subpaths, length }
+    }
+
+    /// The total length of the path.
+    pub fn length(&self) -> f32 {
+        self.length
+    }
+
+    /// The point at the given normalised distance along the path, where `0.0` is the start and
+    /// `1.0` is the end.
+    ///
+    /// Returns `None` if the path is empty.
+    pub fn point_at(&self, t: f32) -> Option<Point2> {
+        self.point_at_distance(t * self.length)
+    }
+
+    /// The unit tangent of the path at the given normalised distance along the path.
+    ///
+    /// Returns `None` if the path has no length.
+    pub fn tangent_at(&self, t: f32) -> Option<Vector2> {
+        self.tangent_at_distance(t * self.length)
+    }
+
+    /// The point at the given distance along the path.
+    ///
+    /// Distances outside of the path's length are clamped to its start or end.
+    pub fn point_at_distance(&self, distance: f32) -> Option<Point2> {
+        self.locate(distance).map(|(sp, ix, d)| {
+            let (a, b) = (sp.points[ix - 1], sp.points[ix]);
+            let (da, db) = (sp.distances[ix - 1], sp.distances[ix]);
+            if db > da {
+                a + (b - a) * ((d - da) / (db - da))
+            } else {
+                a
+            }
+        })
+    }
+
+    /// The unit tangent of the path at the given distance along the path.
+    ///
+    /// Where the distance lies exactly on a vertex, the tangent of the following segment is
+    /// produced.
+    pub fn tangent_at_distance(&self, distance: f32) -> Option<Vector2> {
+        let (sp, ix, _) = self.locate(distance)?;
+        // Search forwards, then backwards, for a segment of non-zero length.
+        let segments = (ix..sp.points.len()).chain((1..ix).rev());
+        segments
+            .map(|i| sp.points[i] - sp.points[i - 1])
+            .find(|v| v.magnitude2() > 0.0)
+            .map(|v| v.normalize())
+    }
+
+    /// Split the path into the section before and the section after the given distance.
+    pub fn split_at_distance(&self, distance: f32) -> (Path, Path) {
+        let d = distance.max(0.0).min(self.length);
+        let (mut before, mut after) = (Path::builder(), Path::builder());
+        for sp in &self.subpaths {
+            let (start, end) = (sp.distances[0], sp.distances[sp.distances.len() - 1]);
+            if end <= d {
+                before = sp.add_to(before);
+            } else if start >= d {
+                after = sp.add_to(after);
+            } else {
+                let split = self.point_at_distance(d).expect("path is not empty");
+                let is_before = |&(_, &pd): &(&Point2, &f32)| pd < d;
+                let is_after = |&(_, &pd): &(&Point2, &f32)| pd > d;
+                let pts = || sp.points.iter().zip(&sp.distances);
+                let head = pts().filter(is_before).map(|(&p, _)| p);
+                let tail = pts().filter(is_after).map(|(&p, _)| p);
+                before = add_polyline(before, head.chain(Some(split)), false);
+                after = add_polyline(after, Some(split).into_iter().chain(tail), false);
+            }
+        }
+        (before.build(), after.build())
+    }
+
+    /// Split the path at the given normalised distance along the path.
+    pub fn split_at(&self, t: f32) -> (Path, Path) {
+        self.split_at_distance(t * self.length)
+    }
+
+    /// Produce `n` points spaced evenly along the length of the path, including the start and end
+    /// points.
+    ///
+    /// For a closed path, the first and last points are the same.
+    pub fn resample(&self, n: usize) -> Vec<Point2> {
+        match n {
+            0 => vec![],
+            1 => self.point_at(0.0).into_iter().collect(),
+            _ => {
+                let step = 1.0 / (n - 1) as f32;
+                (0..n)
+                    .filter_map(|i| self.point_at(i as f32 * step))
+                    .collect()
+            }
+        }
+    }
+
+    // Find the sub-path and the index of the end of the segment at the given distance, along with
+    // the clamped distance.
+    fn locate(&self, distance: f32) -> Option<(&Subpath, usize, f32)> {
+        let d = distance.max(0.0).min(self.length);
+        let sp = self
+            .subpaths
+            .iter()
+            .filter(|sp| sp.points.len() > 1)
+            .find(|sp| sp.distances[sp.distances.len() - 1] >= d)?;
+        let ix = match sp
+            .distances
+            .binary_search_by(|x| x.partial_cmp(&d).expect("distance was NaN"))
+        {
+            Ok(ix) => ix + 1,
+            Err(ix) => ix,
+        };
+        let ix = ix.max(1).min(sp.points.len() - 1);
+        Some((sp, ix, d))
+    }
+}
+
+impl Subpath {
+    fn add_to(&self, builder: Builder) -> Builder {
+        let n = if self.closed {
+            self.points.len() - 1
+        } else {
+            self.points.len()
+        };
+        add_polyline(builder, self.points[..n].iter().cloned(), self.closed)
+    }
+}
+
+// Add the polyline to the builder as a new sub-path.
+fn add_polyline<I>(mut builder: Builder, points: I, close: bool) -> Builder
+where
+    I: IntoIterator<Item = Point2>,
+{
+    let mut points = points.into_iter();
+    if let Some(first) = points.next() {
+        builder = builder.move_to(first);
+        for p in points {
+            builder = builder.line_to(p);
+        }
+        if close {
+            builder = builder.close();
+        }
+    }
+    builder
+}
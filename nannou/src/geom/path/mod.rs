@@ -6,6 +6,11 @@
 
 use crate::geom::{Point2, Vector2};
 
+pub mod boolean;
+pub mod hatch;
+pub mod measure;
+pub mod offset;
+
 /// A wrapper around a 2D lyon path exposing a nannou-friendly API.
 pub struct Path {
     path: lyon::path::Path,
@@ -70,6 +75,61 @@ impl Path {
     pub fn merge(&self, other: &Self) -> Self {
         self.path.merge(&other.path).into()
     }
+
+    /// Apply the given boolean operation to this path and the other.
+    ///
+    /// See the `boolean` module for details.
+    pub fn boolean(&self, other: &Self, op: boolean::Op, options: &boolean::Options) -> Self {
+        boolean::boolean(self, other, op, options)
+    }
+
+    /// The area covered by either this path or the other, using the default boolean options.
+    pub fn union(&self, other: &Self) -> Self {
+        self.boolean(other, boolean::Op::Union, &Default::default())
+    }
+
+    /// The area covered by both this path and the other, using the default boolean options.
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.boolean(other, boolean::Op::Intersection, &Default::default())
+    }
+
+    /// The area covered by this path but not the other, using the default boolean options.
+    pub fn difference(&self, other: &Self) -> Self {
+        self.boolean(other, boolean::Op::Difference, &Default::default())
+    }
+
+    /// The area covered by exactly one of this path and the other, using the default boolean
+    /// options.
+    pub fn xor(&self, other: &Self) -> Self {
+        self.boolean(other, boolean::Op::Xor, &Default::default())
+    }
+
+    /// Offset the outline of the path by the given distance.
+    ///
+    /// A positive distance grows the filled area while a negative distance shrinks it. See the
+    /// `offset` module for details.
+    pub fn offset(&self, distance: f32, options: &offset::Options) -> Self {
+        offset::offset(self, distance, options)
+    }
+
+    /// Fill the interior of the path with strokes, e.g. for pen plotters or lasers.
+    ///
+    /// Returns the polylines of the hatch. See the `hatch` module for details.
+    pub fn hatch(&self, options: &hatch::Options) -> Vec<Vec<Point2>> {
+        hatch::hatch(self, options)
+    }
+
+    /// The length of the path, with curves flattened to within the given tolerance.
+    pub fn length(&self, tolerance: f32) -> f32 {
+        self.measure(tolerance).length()
+    }
+
+    /// Prepare the path for measuring and sampling by distance along its length.
+    ///
+    /// Curves are flattened to within the given tolerance. See the `measure` module for details.
+    pub fn measure(&self, tolerance: f32) -> measure::Measure {
+        measure::Measure::new(self, tolerance)
+    }
 }
 
 impl Builder {
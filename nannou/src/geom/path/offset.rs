@@ -0,0 +1,221 @@
+//! Offsetting (also known as buffering) the outlines of paths and polygons.
+//!
+//! A positive distance grows the filled area of a shape while a negative distance shrinks it.
+//! Curves are flattened before offsetting and all sub-paths are treated as closed. Any overlaps
+//! produced by the offset are resolved, so the result may contain more or fewer contours than the
+//! original, e.g. when a narrow section of a shape is inset until it splits in two.
+
+use crate::geom::path::boolean::{self, Point};
+use crate::geom::path::Path;
+use crate::geom::{pt2, Point2};
+use lyon::path::PathEvent;
+use lyon::tessellation::{FillRule, LineJoin};
+
+/// Parameters for an offset operation.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Options {
+    /// The shape used to join offset edges around the outside of each corner.
+    pub join: LineJoin,
+    /// The limit, as a multiple of the offset distance, beyond which miter joins are bevelled (for
+    /// `LineJoin::Miter`) or clipped (for `LineJoin::MiterClip`).
+    pub miter_limit: f32,
+    /// The maximum distance between a curve or round join and the line segments used to
+    /// approximate it.
+    pub tolerance: f32,
+    /// The rule used to determine the interior of the shape being offset.
+    pub fill_rule: FillRule,
+}
+
+impl Options {
+    /// The default miter limit, matching that of SVG.
+    pub const DEFAULT_MITER_LIMIT: f32 = 4.0;
+
+    /// The default flattening tolerance.
+    pub const DEFAULT_TOLERANCE: f32 = 0.1;
+
+    /// Specify the shape used to join offset edges.
+    pub fn join(mut self, join: LineJoin) -> Self {
+        self.join = join;
+        self
+    }
+
+    /// Join offset edges with a sharp corner.
+    pub fn join_miter(self) -> Self {
+        self.join(LineJoin::Miter)
+    }
+
+    /// Join offset edges with a sharp corner that is clipped at the miter limit.
+    pub fn join_miter_clip(self) -> Self {
+        self.join(LineJoin::MiterClip)
+    }
+
+    /// Join offset edges with a circular arc.
+    pub fn join_round(self) -> Self {
+        self.join(LineJoin::Round)
+    }
+
+    /// Join offset edges with a straight line.
+    pub fn join_bevel(self) -> Self {
+        self.join(LineJoin::Bevel)
+    }
+
+    /// Specify the miter limit as a multiple of the offset distance.
+    ///
+    /// Must be greater than or equal to `1.0`.
+    pub fn miter_limit(mut self, limit: f32) -> Self {
+        self.miter_limit = limit;
+        self
+    }
+
+    /// Specify the maximum distance between a curve and its approximation.
+    pub fn tolerance(mut self, tolerance: f32) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Specify the rule used to determine the interior of the shape being offset.
+    pub fn fill_rule(mut self, rule: FillRule) -> Self {
+        self.fill_rule = rule;
+        self
+    }
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            join: LineJoin::Miter,
+            miter_limit: Self::DEFAULT_MITER_LIMIT,
+            tolerance: Self::DEFAULT_TOLERANCE,
+            fill_rule: FillRule::EvenOdd,
+        }
+    }
+}
+
+/// Offset the shape described by the given path events by the given distance.
+pub fn offset<I>(events: I, distance: f32, options: &Options) -> Path
+where
+    I: IntoIterator<Item = PathEvent>,
+{
+    let rings = boolean::flatten(events, options.tolerance);
+    boolean::rings_to_path(offset_rings(rings, distance, options))
+}
+
+/// Offset the polygon described by the given points by the given distance.
+///
+/// Returns the resulting contours. Contours wind counter-clockwise around filled regions and
+/// clockwise around holes.
+pub fn offset_polygon<I>(points: I, distance: f32, options: &Options) -> Vec<Vec<Point2>>
+where
+    I: IntoIterator,
+    I::Item: Into<Point2>,
+{
+    let ring = points
+        .into_iter()
+        .map(|p| {
+            let p = p.into();
+            pt2(p.x as f64, p.y as f64)
+        })
+        .collect();
+    offset_rings(vec![ring], distance, options)
+        .into_iter()
+        .map(|ring| {
+            ring.into_iter()
+                .map(|p| pt2(p.x as f32, p.y as f32))
+                .collect()
+        })
+        .collect()
+}
+
+// Offset the closed rings, returning the contours of the result.
+pub(super) fn offset_rings(
+    rings: Vec<Vec<Point>>,
+    distance: f32,
+    options: &Options,
+) -> Vec<Vec<Point>> {
+    // Normalise the rings so that they wind counter-clockwise around the shape's interior, in
+    // turn ensuring that the right-hand side of every edge faces away from the interior.
+    let rule = options.fill_rule;
+    let rings = boolean::apply(&[rings, vec![]], |w| boolean::is_inside(w[0], rule));
+    if distance == 0.0 {
+        return rings;
+    }
+
+    // Offset every edge, joining consecutive edges around each corner. The resulting rings may
+    // overlap themselves and one another, so the final shape is the area they wind around
+    // positively.
+    let raw = rings
+        .iter()
+        .map(|ring| offset_ring(ring, distance as f64, options))
+        .collect();
+    boolean::apply(&[raw, vec![]], |w| w[0] > 0)
+}
+
+// Produce the raw offset of a single ring.
+fn offset_ring(ring: &[Point], delta: f64, options: &Options) -> Vec<Point> {
+    let n = ring.len();
+    let normal = |i: usize| {
+        let d = ring[(i + 1) % n] - ring[i];
+        pt2(d.y, -d.x) / d.magnitude()
+    };
+    let mut points = vec![];
+    for i in 0..n {
+        let p = ring[i];
+        let n0 = normal((i + n - 1) % n);
+        let n1 = normal(i);
+        let (a, b) = (p + n0 * delta, p + n1 * delta);
+
+        // Skip vertices that lie along a straight edge.
+        let turn = n0.perp_dot(n1);
+        let cos = n0.dot(n1);
+        if turn == 0.0 && cos > 0.0 {
+            points.push(a);
+            continue;
+        }
+
+        // On the inside of a corner, the offset edges are left to cross one another. The
+        // overlapping section winds negatively and is removed.
+        if turn * delta <= 0.0 {
+            points.push(a);
+            points.push(p);
+            points.push(b);
+            continue;
+        }
+
+        points.push(a);
+        let bisector = (n0 + n1) / (n0 + n1).magnitude();
+        let miter_len = delta.abs() / bisector.dot(n0);
+        let limit = options.miter_limit as f64 * delta.abs();
+        match options.join {
+            LineJoin::Miter if miter_len <= limit => {
+                points.push(p + (n0 + n1) * (delta / (1.0 + cos)));
+            }
+            LineJoin::MiterClip if miter_len > limit => {
+                // Clip the miter perpendicular to the bisector at the limit.
+                let miter = p + (n0 + n1) * (delta / (1.0 + cos));
+                let h = |q: Point| (q - p).dot(bisector) * delta.signum();
+                let clip = |q: Point| q + (miter - q) * ((limit - h(q)) / (h(miter) - h(q)));
+                points.push(clip(a));
+                points.push(clip(b));
+            }
+            LineJoin::MiterClip => {
+                points.push(p + (n0 + n1) * (delta / (1.0 + cos)));
+            }
+            LineJoin::Round => {
+                let r = delta.abs();
+                let tolerance = (options.tolerance as f64).min(r);
+                let angle = turn.atan2(cos);
+                let step = 2.0 * (1.0 - tolerance / r).acos();
+                let steps = (angle.abs() / step.max(1e-3)).ceil() as usize;
+                for s in 1..steps {
+                    let t = angle * s as f64 / steps as f64;
+                    let (sin, cos) = t.sin_cos();
+                    let rotated = pt2(n0.x * cos - n0.y * sin, n0.x * sin + n0.y * cos);
+                    points.push(p + rotated * delta);
+                }
+            }
+            _ => (),
+        }
+        points.push(b);
+    }
+    points
+}
@@ -0,0 +1,245 @@
+//! Triangulation of polygons with holes by ear clipping.
+//!
+//! Holes are first joined to the outer contour by a pair of coincident "bridge" edges, producing
+//! a single contour that is then triangulated by repeatedly clipping "ears" - triangles formed by
+//! three consecutive vertices that contain no other vertex. Vertices that do not contribute any
+//! area (duplicates, collinear vertices and spikes) are removed along the way.
+//!
+//! The cost of triangulation grows quadratically with the number of vertices.
+
+use crate::geom::{pt2, Point2};
+
+// All geometry is processed in double precision.
+pub(super) type Point = Point2<f64>;
+
+// Triangulate the given outer contour and holes, producing three indices per triangle into the
+// list formed by concatenating all contours.
+//
+// Triangles wind counter-clockwise. Self-intersecting input produces a best-effort result.
+pub(super) fn triangulate(contours: &[Vec<Point>]) -> Vec<usize> {
+    let points: Vec<Point> = contours.iter().flat_map(|c| c.iter().cloned()).collect();
+    let mut start = 0;
+    let mut rings = vec![];
+    for contour in contours {
+        rings.push(clean_ring(&points, start..start + contour.len()));
+        start += contour.len();
+    }
+    if rings.is_empty() {
+        return vec![];
+    }
+
+    // Wind the outer contour counter-clockwise and the holes clockwise.
+    let mut holes = rings.split_off(1);
+    let mut ring = rings.pop().expect("no outer contour");
+    if ring.len() < 3 {
+        return vec![];
+    }
+    if signed_area(&points, &ring) < 0.0 {
+        ring.reverse();
+    }
+    holes.retain(|hole| hole.len() >= 3);
+    for hole in &mut holes {
+        if signed_area(&points, hole) > 0.0 {
+            hole.reverse();
+        }
+    }
+
+    // Bridge the holes in order of their rightmost points, from right to left.
+    let max_x = |hole: &Vec<usize>| {
+        hole.iter()
+            .map(|&i| points[i].x)
+            .fold(std::f64::MIN, f64::max)
+    };
+    holes.sort_by(|a, b| max_x(b).partial_cmp(&max_x(a)).expect("coordinate was NaN"));
+    for hole in holes {
+        bridge_hole(&points, &mut ring, &hole);
+    }
+
+    clip_ears(&points, ring)
+}
+
+// The indices in the given range with consecutive duplicate points removed.
+fn clean_ring(points: &[Point], range: std::ops::Range<usize>) -> Vec<usize> {
+    let mut ring: Vec<usize> = range.collect();
+    ring.dedup_by(|a, b| points[*a] == points[*b]);
+    while ring.len() > 1 && points[ring[0]] == points[ring[ring.len() - 1]] {
+        ring.pop();
+    }
+    ring
+}
+
+// Twice the signed area of the ring, positive for counter-clockwise rings.
+fn signed_area(points: &[Point], ring: &[usize]) -> f64 {
+    let n = ring.len();
+    (0..n)
+        .map(|i| points[ring[i]].perp_dot(points[ring[(i + 1) % n]]))
+        .sum()
+}
+
+// Twice the signed area of the triangle, positive if it winds counter-clockwise.
+fn area(a: Point, b: Point, c: Point) -> f64 {
+    (b - a).perp_dot(c - a)
+}
+
+// Whether `p` lies inside or on the edge of the counter-clockwise triangle.
+fn in_triangle(a: Point, b: Point, c: Point, p: Point) -> bool {
+    area(a, b, p) >= 0.0 && area(b, c, p) >= 0.0 && area(c, a, p) >= 0.0
+}
+
+// Whether `p` lies within the interior angle of the ring at vertex `v`.
+fn in_sector(prev: Point, v: Point, next: Point, p: Point) -> bool {
+    let (left_in, left_out) = (area(prev, v, p) > 0.0, area(v, next, p) > 0.0);
+    if area(prev, v, next) >= 0.0 {
+        left_in && left_out
+    } else {
+        left_in || left_out
+    }
+}
+
+// Join the clockwise hole to the counter-clockwise ring with a pair of bridge edges between the
+// hole's rightmost vertex and a vertex of the ring visible from it.
+fn bridge_hole(points: &[Point], ring: &mut Vec<usize>, hole: &[usize]) {
+    let m_ix = (0..hole.len())
+        .max_by(|&a, &b| {
+            let (a, b) = (points[hole[a]], points[hole[b]]);
+            (a.x, -a.y)
+                .partial_cmp(&(b.x, -b.y))
+                .expect("coordinate was NaN")
+        })
+        .expect("hole has no vertices");
+    let m = points[hole[m_ix]];
+    let n = ring.len();
+    let prev = |i: usize| points[ring[(i + n - 1) % n]];
+    let next = |i: usize| points[ring[(i + 1) % n]];
+
+    // Cast a ray from `m` in the positive x direction, finding the nearest point at which it hits
+    // the ring along with the ring vertex to which a bridge would be visible.
+    let mut hit: Option<(f64, usize, bool)> = None;
+    for i in 0..n {
+        let (a, b) = (points[ring[i]], next(i));
+        let (x, candidate, at_vertex) = if a.y == m.y && a.x >= m.x {
+            (a.x, i, true)
+        } else if (a.y < m.y) != (b.y < m.y) && a.y != m.y && b.y != m.y {
+            let x = a.x + (m.y - a.y) / (b.y - a.y) * (b.x - a.x);
+            if x < m.x {
+                continue;
+            }
+            let candidate = if a.x > b.x { i } else { (i + 1) % n };
+            (x, candidate, false)
+        } else {
+            continue;
+        };
+        let nearer = hit.map(|(hx, _, _)| x < hx).unwrap_or(true);
+        if nearer {
+            hit = Some((x, candidate, at_vertex));
+        }
+    }
+    let (x, mut p_ix, at_vertex) = match hit {
+        Some(hit) => hit,
+        // The hole lies outside of the ring.
+        None => return,
+    };
+
+    // If the ray hit an edge, a vertex inside the triangle between `m`, the hit and the candidate
+    // may obstruct the bridge, in which case the one nearest in angle to the ray is used instead.
+    if !at_vertex {
+        let hit_point = pt2(x, m.y);
+        let p = points[ring[p_ix]];
+        let (a, b, c) = if area(m, hit_point, p) >= 0.0 {
+            (m, hit_point, p)
+        } else {
+            (m, p, hit_point)
+        };
+        let mut best = None;
+        for i in 0..n {
+            let v = points[ring[i]];
+            if v == p || v == m || !in_triangle(a, b, c, v) {
+                continue;
+            }
+            let d = v - m;
+            let key = ((d.y / d.x).abs(), d.magnitude2());
+            if best.map(|(k, _)| key < k).unwrap_or(true) {
+                best = Some((key, i));
+            }
+        }
+        if let Some((_, i)) = best {
+            p_ix = i;
+        }
+    }
+
+    // Where the ring passes through the chosen point more than once, use the occurrence whose
+    // interior faces `m`.
+    let p = points[ring[p_ix]];
+    if let Some(i) = (0..n).find(|&i| points[ring[i]] == p && in_sector(prev(i), p, next(i), m)) {
+        p_ix = i;
+    }
+
+    // Splice the hole into the ring: ..., p, m, <hole>, m, p, ...
+    let p = ring[p_ix];
+    let mut spliced = Vec::with_capacity(hole.len() + 2);
+    spliced.extend(hole[m_ix..].iter().chain(&hole[..m_ix]).cloned());
+    spliced.push(hole[m_ix]);
+    spliced.push(p);
+    ring.splice(p_ix + 1..p_ix + 1, spliced);
+}
+
+// Triangulate the counter-clockwise ring by clipping ears.
+fn clip_ears(points: &[Point], mut ring: Vec<usize>) -> Vec<usize> {
+    let mut indices = vec![];
+    let mut i = 0;
+    // The number of vertices visited since the last one was removed.
+    let mut stalled = 0;
+    // Whether ears may contain other vertices, used once no true ears remain.
+    let mut relaxed = false;
+    while ring.len() > 3 {
+        let n = ring.len();
+        let (ia, ib, ic) = (ring[(i + n - 1) % n], ring[i], ring[(i + 1) % n]);
+        let (a, b, c) = (points[ia], points[ib], points[ic]);
+        let abc = area(a, b, c);
+        let is_ear = abc > 0.0 && (relaxed || !contains_vertex(points, &ring, i));
+        if abc == 0.0 || is_ear {
+            // Vertices without area are removed without producing a triangle.
+            if is_ear {
+                indices.extend_from_slice(&[ia, ib, ic]);
+            }
+            ring.remove(i);
+            i = (i + ring.len() - 1) % ring.len();
+            stalled = 0;
+            relaxed = false;
+            continue;
+        }
+        i = (i + 1) % n;
+        stalled += 1;
+        if stalled >= n {
+            if relaxed {
+                // Only reflex vertices remain, so the input must intersect itself.
+                ring.remove(i);
+                i %= ring.len();
+            }
+            relaxed = !relaxed;
+            stalled = 0;
+        }
+    }
+    if ring.len() == 3 {
+        let (ia, ib, ic) = (ring[0], ring[1], ring[2]);
+        if area(points[ia], points[ib], points[ic]) > 0.0 {
+            indices.extend_from_slice(&[ia, ib, ic]);
+        }
+    }
+    indices
+}
+
+// Whether any other vertex of the ring lies within the triangle formed at index `i`.
+fn contains_vertex(points: &[Point], ring: &[usize], i: usize) -> bool {
+    let n = ring.len();
+    let (a, b, c) = (
+        points[ring[(i + n - 1) % n]],
+        points[ring[i]],
+        points[ring[(i + 1) % n]],
+    );
+    (0..n)
+        .filter(|&j| j != i && j != (i + 1) % n && j != (i + n - 1) % n)
+        .map(|j| points[ring[j]])
+        .filter(|&p| p != a && p != b && p != c)
+        .any(|p| in_triangle(a, b, c, p))
+}
@@ -1,5 +1,9 @@
+use crate::geom::path::{hatch, offset};
 use crate::geom::tri::{self, Tri};
-use crate::geom::{Cuboid, Rect, Vertex, Vertex2d, Vertex3d};
+use crate::geom::{pt2, Cuboid, Point2, Rect, Vertex, Vertex2d, Vertex3d};
+use crate::math::num_traits::ToPrimitive;
+
+mod earcut;
 
 /// A simple type wrapper around a list of points that describe a polygon.
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -30,15 +34,29 @@ where
 
     /// Triangulate the polygon given as a list of `Point`s describing its sides.
     ///
+    /// This produces a fan of triangles from the first point and is only correct for convex
+    /// polygons. See `triangulate` for concave polygons.
+    ///
     /// Returns `None` if the polygon's iterator yields less than two points.
     pub fn triangles(self) -> Option<Triangles<I>> {
         triangles(self.points)
     }
 
+    /// Triangulate the polygon by ear clipping, supporting concave polygons.
+    ///
+    /// See the `triangulate` function for details.
+    pub fn triangulate(self) -> Vec<Tri<I::Item>>
+    where
+        I::Item: Vertex2d,
+    {
+        let points: Vec<_> = self.points.collect();
+        triangulate(&points, &[])
+    }
+
     /// Returns `Some` with the touched triangle if the given `Point` is over the polygon described
     /// by the given series of points.
     ///
-    /// This uses the `triangles` function internally.
+    /// This uses the `triangulate` function internally.
     pub fn contains(self, p: &I::Item) -> Option<Tri<I::Item>>
     where
         I::Item: Vertex2d,
@@ -65,6 +83,29 @@ where
     {
         super::bounding_cuboid(self.points)
     }
+
+    /// Offset the outline of the polygon by the given distance.
+    ///
+    /// A positive distance grows the polygon while a negative distance shrinks it. Returns the
+    /// resulting contours, of which there may be many if the polygon splits.
+    ///
+    /// See the `geom::path::offset` module for details.
+    pub fn offset(self, distance: f32, options: &offset::Options) -> Vec<Vec<Point2>>
+    where
+        I::Item: Into<Point2>,
+    {
+        offset::offset_polygon(self.points, distance, options)
+    }
+
+    /// Fill the interior of the polygon with strokes, e.g. for pen plotters or lasers.
+    ///
+    /// Returns the polylines of the hatch. See the `geom::path::hatch` module for details.
+    pub fn hatch(self, options: &hatch::Options) -> Vec<Vec<Point2>>
+    where
+        I::Item: Into<Point2>,
+    {
+        hatch::hatch_polygon(self.points, options)
+    }
 }
 
 /// An iterator that triangulates a polygon represented by a sequence of points describing its
@@ -103,21 +144,73 @@ where
 }
 
 /// An iterator yielding indices into a polygon's vertices required to triangulate the polygon.
+///
+/// Like `triangles`, this produces a fan and is only correct for convex polygons.
 pub fn triangle_indices(n_points: usize) -> TriangleIndices {
     let index = 0;
     TriangleIndices { index, n_points }
 }
 
+/// Triangulate the polygon described by the given outer contour and hole contours by ear clipping.
+///
+/// Unlike `triangle_indices`, this is correct for concave polygons. Contours may wind in either
+/// direction. Duplicate and collinear points are tolerated, while self-intersecting contours
+/// produce a best-effort result.
+///
+/// Returns three indices for each triangle into the list of vertices formed by the outer contour
+/// followed by each of the holes in order, e.g. for use with `draw.mesh().indexed(..)`. Triangles
+/// wind counter-clockwise. Coordinates that cannot be represented as `f64` are treated as zero.
+///
+/// `draw.mesh().polygon(..)` draws the result of this triangulation. Other `draw` primitives are
+/// tessellated by lyon instead, which already supports concave polygons with holes.
+pub fn triangulate_indices<V>(contour: &[V], holes: &[&[V]]) -> Vec<usize>
+where
+    V: Vertex2d,
+{
+    let to_point = |v: &V| {
+        let p = v.point2();
+        let x = p.x.to_f64().unwrap_or(0.0);
+        let y = p.y.to_f64().unwrap_or(0.0);
+        pt2(x, y)
+    };
+    let contours: Vec<Vec<earcut::Point>> = Some(contour)
+        .into_iter()
+        .chain(holes.iter().cloned())
+        .map(|c| c.iter().map(to_point).collect())
+        .collect();
+    earcut::triangulate(&contours)
+}
+
+/// Triangulate the polygon described by the given outer contour and hole contours by ear clipping.
+///
+/// See `triangulate_indices` for details.
+pub fn triangulate<V>(contour: &[V], holes: &[&[V]]) -> Vec<Tri<V>>
+where
+    V: Vertex2d,
+{
+    let vertices: Vec<V> = Some(contour)
+        .into_iter()
+        .chain(holes.iter().cloned())
+        .flat_map(|c| c.iter().cloned())
+        .collect();
+    let indices = triangulate_indices(contour, holes);
+    indices
+        .chunks(3)
+        .map(|t| Tri([vertices[t[0]], vertices[t[1]], vertices[t[2]]]))
+        .collect()
+}
+
 /// Returns `Some` with the touched triangle if the given `Point` is over the polygon described by
 /// the given series of points.
 ///
-/// This uses the `triangles` function internally.
+/// This uses the `triangulate` function internally.
 pub fn contains<I>(points: I, point: &I::Item) -> Option<Tri<I::Item>>
 where
     I: IntoIterator,
     I::Item: Vertex2d,
 {
-    triangles(points).and_then(|ts| tri::iter_contains(ts, &point))
+    let points: Vec<_> = points.into_iter().collect();
+    tri::iter_contains(triangulate(&points, &[]), point)
 }
 
 impl<I> Iterator for Triangles<I>
@@ -1,5 +1,6 @@
 use crate::geom::{tri, vertex, Cuboid, Range, Rect, Tri, Vertex, Vertex2d, Vertex3d};
 use crate::math::EuclideanSpace;
+use crate::serde_derive::{Deserialize, Serialize};
 use std::ops::{Deref, Index};
 
 /// The number of vertices in a quad.
@@ -19,7 +20,7 @@ pub const NUM_TRIANGLE_INDICES: u8 = 6;
 pub const TRIANGLE_INDICES: [usize; NUM_TRIANGLE_INDICES as usize] = [0, 1, 2, 0, 2, 3];
 
 /// A quad represented by its four vertices.
-#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
 pub struct Quad<V = vertex::Default>(pub [V; NUM_VERTICES as usize]);
 
 /// An `Iterator` yielding the two triangles that make up a quad.
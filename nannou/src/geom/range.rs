@@ -3,13 +3,14 @@
 use crate::geom::scalar;
 use crate::math::num_traits::{Float, One, Zero};
 use crate::math::{self, two, BaseNum};
+use crate::serde_derive::{Deserialize, Serialize};
 use std::ops::Neg;
 
 /// Some start and end position along a single axis.
 ///
 /// As an example, a **Rect** is made up of two **Range**s; one along the *x* axis, and one along
 /// the *y* axis.
-#[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Serialize, Deserialize)]
 pub struct Range<S = scalar::Default> {
     /// The start of some `Range` along an axis.
     pub start: S,
@@ -1,10 +1,11 @@
 use crate::geom::{quad, scalar, Align, Edge, Point2, Quad, Range, Tri, Vector2};
 use crate::math::num_traits::Float;
 use crate::math::{self, BaseNum};
+use crate::serde_derive::{Deserialize, Serialize};
 use std::ops::Neg;
 
 /// Defines a Rectangle's bounds across the x and y axes.
-#[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Serialize, Deserialize)]
 pub struct Rect<S = scalar::Default> {
     /// The start and end positions of the Rectangle on the x axis.
     pub x: Range<S>,
@@ -0,0 +1,234 @@
+//! Sampling fields over a grid and extracting iso-contours with marching squares.
+
+use super::Sdf;
+use crate::geom::{pt2, Path, Point2, Rect};
+use std::collections::{HashMap, HashSet};
+
+/// The values of a field sampled at the points of a regular grid covering a `Rect`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Field {
+    rect: Rect,
+    cols: usize,
+    rows: usize,
+    values: Vec<f32>,
+}
+
+// Identifies the edge between two adjacent grid points, by the lower of the two points and whether
+// the edge runs along the x axis.
+type EdgeKey = (usize, usize, bool);
+
+impl Field {
+    /// Sample the field over the given rect at intervals of roughly the given cell size.
+    ///
+    /// The rect is divided into the number of cells that brings their size nearest to the given
+    /// size, with at least one cell along each axis. The field is sampled at the corners of every
+    /// cell.
+    ///
+    /// **Panics** if the cell size is not greater than zero.
+    pub fn sample<F>(sdf: &F, rect: Rect, cell_size: f32) -> Self
+    where
+        F: Sdf + ?Sized,
+    {
+        assert!(cell_size > 0.0, "cell size must be positive");
+        let rect = rect.absolute();
+        let cells = |len: f32| ((len / cell_size).round() as usize).max(1);
+        let (cols, rows) = (cells(rect.w()) + 1, cells(rect.h()) + 1);
+        Self::from_fn(rect, cols, rows, |p| sdf.distance(p))
+    }
+
+    /// Sample the given function at each point of a grid of `cols` by `rows` points evenly
+    /// distributed across the rect, including its edges.
+    ///
+    /// **Panics** if there are fewer than two columns or rows.
+    pub fn from_fn<F>(rect: Rect, cols: usize, rows: usize, f: F) -> Self
+    where
+        F: Fn(Point2) -> f32,
+    {
+        assert!(
+            cols >= 2 && rows >= 2,
+            "a field requires at least two columns and rows"
+        );
+        let rect = rect.absolute();
+        let mut field = Field {
+            rect,
+            cols,
+            rows,
+            values: vec![],
+        };
+        field.values = (0..rows)
+            .flat_map(|row| (0..cols).map(move |col| (col, row)))
+            .map(|(col, row)| f(field.point(col, row)))
+            .collect();
+        field
+    }
+
+    /// The rect covered by the field.
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    /// The number of sampled points along the x axis.
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// The number of sampled points along the y axis.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// The sampled values, ordered by row from the bottom of the rect and then by column from the
+    /// left.
+    pub fn values(&self) -> &[f32] {
+        &self.values
+    }
+
+    /// The value sampled at the given column and row.
+    pub fn value(&self, col: usize, row: usize) -> f32 {
+        self.values[row * self.cols + col]
+    }
+
+    /// The position of the point sampled at the given column and row.
+    pub fn point(&self, col: usize, row: usize) -> Point2 {
+        let x = self.rect.left() + self.rect.w() * col as f32 / (self.cols - 1) as f32;
+        let y = self.rect.bottom() + self.rect.h() * row as f32 / (self.rows - 1) as f32;
+        pt2(x, y)
+    }
+
+    /// Extract the contours along which the field is equal to the given value.
+    ///
+    /// Contours wind counter-clockwise around regions in which the field is less than the value,
+    /// and clockwise around regions in which it is greater. Closed contours end with their first
+    /// point. Contours that reach the edge of the field's rect are left open.
+    pub fn contours(&self, value: f32) -> Vec<Vec<Point2>> {
+        // Each segment crosses a cell, joining the points at which the contour crosses two of its
+        // edges.
+        let mut segments: HashMap<EdgeKey, EdgeKey> = HashMap::new();
+        for row in 0..self.rows - 1 {
+            for col in 0..self.cols - 1 {
+                self.cell_segments(col, row, value, &mut segments);
+            }
+        }
+
+        // Chain the segments into contours, beginning with those that begin at the edge of the
+        // rect and so are not the end of any other segment.
+        let ends: HashSet<EdgeKey> = segments.values().cloned().collect();
+        let mut starts: Vec<EdgeKey> = segments
+            .keys()
+            .filter(|key| !ends.contains(key))
+            .cloned()
+            .collect();
+        let mut rings: Vec<EdgeKey> = segments
+            .keys()
+            .filter(|key| ends.contains(key))
+            .cloned()
+            .collect();
+        starts.sort();
+        rings.sort();
+        let mut contours = vec![];
+        for start in starts.into_iter().chain(rings) {
+            if !segments.contains_key(&start) {
+                continue;
+            }
+            let mut contour = vec![self.crossing(start, value)];
+            let mut key = start;
+            while let Some(next) = segments.remove(&key) {
+                contour.push(self.crossing(next, value));
+                key = next;
+            }
+            contours.push(contour);
+        }
+        contours
+    }
+
+    /// Extract the contours along which the field is equal to the given value as a `Path`.
+    ///
+    /// Closed contours are closed within the path. See the `contours` method for details.
+    pub fn contour_path(&self, value: f32) -> Path {
+        let mut builder = Path::builder();
+        for mut contour in self.contours(value) {
+            let closed = contour.len() > 2 && contour.first() == contour.last();
+            if closed {
+                contour.pop();
+            }
+            let mut points = contour.into_iter();
+            if let Some(first) = points.next() {
+                builder = builder.move_to(first);
+                for p in points {
+                    builder = builder.line_to(p);
+                }
+                if closed {
+                    builder = builder.close();
+                }
+            }
+        }
+        builder.build()
+    }
+
+    // Add the segments of the contour crossing the cell at the given column and row, each directed
+    // so that values less than the contour's lie to its left.
+    fn cell_segments(
+        &self,
+        col: usize,
+        row: usize,
+        value: f32,
+        segments: &mut HashMap<EdgeKey, EdgeKey>,
+    ) {
+        // The corners of the cell counter-clockwise from the bottom left, along with the edges
+        // from each corner to the next.
+        let corners = [
+            (col, row),
+            (col + 1, row),
+            (col + 1, row + 1),
+            (col, row + 1),
+        ];
+        let edges = [
+            (col, row, true),
+            (col + 1, row, false),
+            (col, row + 1, true),
+            (col, row, false),
+        ];
+        let inside: Vec<bool> = corners
+            .iter()
+            .map(|&(c, r)| self.value(c, r) < value)
+            .collect();
+
+        // Find where the contour crosses the edges and whether it leaves or enters the region
+        // inside of the contour as it does.
+        let crossings: Vec<(EdgeKey, bool)> = (0..4)
+            .filter(|&i| inside[i] != inside[(i + 1) % 4])
+            .map(|i| (edges[i], inside[i]))
+            .collect();
+
+        // Each crossing leaving the region is joined to an adjacent crossing entering it. Where the
+        // contour crosses all four edges, the average of the corners decides whether the regions
+        // inside of the contour are joined through the middle of the cell.
+        let n = crossings.len();
+        let middle_inside =
+            corners.iter().map(|&(c, r)| self.value(c, r)).sum::<f32>() / 4.0 < value;
+        for (i, &(edge, leaving)) in crossings.iter().enumerate() {
+            if !leaving {
+                continue;
+            }
+            let entering = if n == 4 && !middle_inside {
+                crossings[(i + n - 1) % n].0
+            } else {
+                crossings[(i + 1) % n].0
+            };
+            segments.insert(edge, entering);
+        }
+    }
+
+    // The point along the edge at which the field is equal to the given value.
+    fn crossing(&self, (col, row, along_x): EdgeKey, value: f32) -> Point2 {
+        let (col_b, row_b) = if along_x {
+            (col + 1, row)
+        } else {
+            (col, row + 1)
+        };
+        let (a, b) = (self.value(col, row), self.value(col_b, row_b));
+        let t = if a == b { 0.5 } else { (value - a) / (b - a) };
+        let (pa, pb) = (self.point(col, row), self.point(col_b, row_b));
+        pa + (pb - pa) * t
+    }
+}
@@ -0,0 +1,492 @@
+//! Signed distance fields for describing shapes implicitly.
+//!
+//! A signed distance field (SDF) maps each point of the plane to its distance from the edge of a
+//! shape, negative within the shape and positive outside of it. Shapes described this way are
+//! easily blended together, repeated and distorted.
+//!
+//! Any type implementing the `Sdf` trait may be used as a field, including closures of the form
+//! `Fn(Point2) -> f32`. This allows for fields described by noise functions, e.g.
+//!
+//! ```
+//! # use nannou::prelude::*;
+//! # use nannou::geom::sdf::{self, Sdf};
+//! # use nannou::noise::{NoiseFn, Perlin};
+//! let perlin = Perlin::new();
+//! let noise = move |p: Point2| perlin.get([p.x as f64 * 0.01, p.y as f64 * 0.01]) as f32 * 20.0;
+//! let shape = sdf::circle(pt2(0.0, 0.0), 100.0)
+//!     .smooth_union(sdf::circle(pt2(120.0, 0.0), 60.0), 30.0)
+//!     .displace(noise);
+//! let field = shape.sample(geom::Rect::from_w_h(400.0, 400.0), 4.0);
+//! let outline = field.contour_path(0.0);
+//! ```
+//!
+//! Fields are sampled over a grid producing a `Field`, from which iso-contours may be extracted
+//! with marching squares. Note that blending and distorting operations produce fields whose
+//! values are only an approximation of the true distance.
+
+use crate::geom::intersect;
+use crate::geom::{pt2, Point2, Rect, Vector2};
+
+pub mod field;
+
+pub use self::field::Field;
+
+/// Types that describe a shape by the signed distance from any point to its edge.
+pub trait Sdf {
+    /// The signed distance from the point to the edge of the shape, negative within the shape.
+    fn distance(&self, point: Point2) -> f32;
+
+    /// The union of this shape and the other.
+    fn union<B>(self, other: B) -> Union<Self, B>
+    where
+        Self: Sized,
+        B: Sdf,
+    {
+        self.smooth_union(other, 0.0)
+    }
+
+    /// The union of this shape and the other, blended together where they are within the given
+    /// distance of one another.
+    fn smooth_union<B>(self, other: B, smoothness: f32) -> Union<Self, B>
+    where
+        Self: Sized,
+        B: Sdf,
+    {
+        Union {
+            a: self,
+            b: other,
+            smoothness,
+        }
+    }
+
+    /// This shape with the other removed from it.
+    fn subtract<B>(self, other: B) -> Subtract<Self, B>
+    where
+        Self: Sized,
+        B: Sdf,
+    {
+        self.smooth_subtract(other, 0.0)
+    }
+
+    /// This shape with the other removed from it, with the edges of the cut blended over the
+    /// given distance.
+    fn smooth_subtract<B>(self, other: B, smoothness: f32) -> Subtract<Self, B>
+    where
+        Self: Sized,
+        B: Sdf,
+    {
+        Subtract {
+            a: self,
+            b: other,
+            smoothness,
+        }
+    }
+
+    /// The region covered by both this shape and the other.
+    fn intersect<B>(self, other: B) -> Intersect<Self, B>
+    where
+        Self: Sized,
+        B: Sdf,
+    {
+        self.smooth_intersect(other, 0.0)
+    }
+
+    /// The region covered by both this shape and the other, with the edges of the region blended
+    /// over the given distance.
+    fn smooth_intersect<B>(self, other: B, smoothness: f32) -> Intersect<Self, B>
+    where
+        Self: Sized,
+        B: Sdf,
+    {
+        Intersect {
+            a: self,
+            b: other,
+            smoothness,
+        }
+    }
+
+    /// The shape moved by the given vector.
+    fn translate(self, v: Vector2) -> Translate<Self>
+    where
+        Self: Sized,
+    {
+        Translate { sdf: self, v }
+    }
+
+    /// The shape rotated about the origin by the given angle in radians.
+    fn rotate(self, radians: f32) -> Rotate<Self>
+    where
+        Self: Sized,
+    {
+        Rotate { sdf: self, radians }
+    }
+
+    /// The shape scaled about the origin by the given factor.
+    fn scale(self, scale: f32) -> Scale<Self>
+    where
+        Self: Sized,
+    {
+        Scale { sdf: self, scale }
+    }
+
+    /// The shape repeated infinitely across the plane, once per the given period along each axis.
+    ///
+    /// An axis with a period of zero is not repeated. The shape should fit within a single
+    /// period of the origin to avoid clipping.
+    fn repeat(self, period: Vector2) -> Repeat<Self>
+    where
+        Self: Sized,
+    {
+        Repeat { sdf: self, period }
+    }
+
+    /// The shape twisted about the origin, with each point rotated by the given angle in radians
+    /// per unit of distance from the origin.
+    fn twist(self, radians_per_unit: f32) -> Twist<Self>
+    where
+        Self: Sized,
+    {
+        Twist {
+            sdf: self,
+            radians_per_unit,
+        }
+    }
+
+    /// The shape grown outwards by the given distance, rounding its corners.
+    fn round(self, radius: f32) -> Round<Self>
+    where
+        Self: Sized,
+    {
+        Round { sdf: self, radius }
+    }
+
+    /// A ring of the given thickness following the edge of the shape.
+    fn onion(self, thickness: f32) -> Onion<Self>
+    where
+        Self: Sized,
+    {
+        Onion {
+            sdf: self,
+            thickness,
+        }
+    }
+
+    /// The shape with the given function's value added to its distance at every point, e.g. to
+    /// distort its edges with noise.
+    fn displace<F>(self, displacement: F) -> Displace<Self, F>
+    where
+        Self: Sized,
+        F: Fn(Point2) -> f32,
+    {
+        Displace {
+            sdf: self,
+            displacement,
+        }
+    }
+
+    /// Sample the field over the given rect at intervals of roughly the given cell size.
+    ///
+    /// See the `Field::sample` constructor.
+    fn sample(&self, rect: Rect, cell_size: f32) -> Field {
+        Field::sample(self, rect, cell_size)
+    }
+}
+
+/// A circle.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Circle {
+    pub centre: Point2,
+    pub radius: f32,
+}
+
+/// An axis-aligned box.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Rectangle {
+    pub rect: Rect,
+}
+
+/// A line segment with no thickness. Use `round` to give it thickness.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Segment {
+    pub start: Point2,
+    pub end: Point2,
+}
+
+/// A polygon, closed by an edge from the last point to the first.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Polygon {
+    pub points: Vec<Point2>,
+}
+
+/// The union of two shapes. See `Sdf::union`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Union<A, B> {
+    a: A,
+    b: B,
+    smoothness: f32,
+}
+
+/// One shape with another removed from it. See `Sdf::subtract`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Subtract<A, B> {
+    a: A,
+    b: B,
+    smoothness: f32,
+}
+
+/// The intersection of two shapes. See `Sdf::intersect`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Intersect<A, B> {
+    a: A,
+    b: B,
+    smoothness: f32,
+}
+
+/// A translated shape. See `Sdf::translate`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Translate<T> {
+    sdf: T,
+    v: Vector2,
+}
+
+/// A rotated shape. See `Sdf::rotate`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Rotate<T> {
+    sdf: T,
+    radians: f32,
+}
+
+/// A scaled shape. See `Sdf::scale`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Scale<T> {
+    sdf: T,
+    scale: f32,
+}
+
+/// A repeated shape. See `Sdf::repeat`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Repeat<T> {
+    sdf: T,
+    period: Vector2,
+}
+
+/// A twisted shape. See `Sdf::twist`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Twist<T> {
+    sdf: T,
+    radians_per_unit: f32,
+}
+
+/// A shape with rounded corners. See `Sdf::round`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Round<T> {
+    sdf: T,
+    radius: f32,
+}
+
+/// A ring following the edge of a shape. See `Sdf::onion`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Onion<T> {
+    sdf: T,
+    thickness: f32,
+}
+
+/// A shape with displaced edges. See `Sdf::displace`.
+#[derive(Copy, Clone, Debug)]
+pub struct Displace<T, F> {
+    sdf: T,
+    displacement: F,
+}
+
+/// A circle with the given centre and radius.
+pub fn circle(centre: Point2, radius: f32) -> Circle {
+    Circle { centre, radius }
+}
+
+/// An axis-aligned box covering the given rect.
+pub fn rect(rect: Rect) -> Rectangle {
+    Rectangle { rect }
+}
+
+/// An axis-aligned box covering the given rect, with corners rounded by the given radius.
+pub fn rounded_rect(rect: Rect, radius: f32) -> Round<Rectangle> {
+    let rect = rect.absolute();
+    let radius = radius.min(rect.w() * 0.5).min(rect.h() * 0.5);
+    Rectangle {
+        rect: rect.pad(radius),
+    }
+    .round(radius)
+}
+
+/// A line segment from `start` to `end` with the given thickness and rounded ends.
+pub fn segment(start: Point2, end: Point2, thickness: f32) -> Round<Segment> {
+    Segment { start, end }.round(thickness * 0.5)
+}
+
+/// A polygon described by the given points.
+pub fn polygon<I>(points: I) -> Polygon
+where
+    I: IntoIterator,
+    I::Item: Into<Point2>,
+{
+    let points = points.into_iter().map(Into::into).collect();
+    Polygon { points }
+}
+
+impl<F> Sdf for F
+where
+    F: Fn(Point2) -> f32,
+{
+    fn distance(&self, point: Point2) -> f32 {
+        (*self)(point)
+    }
+}
+
+impl Sdf for Circle {
+    fn distance(&self, point: Point2) -> f32 {
+        intersect::signed_distance_circle(point, self.centre, self.radius)
+    }
+}
+
+impl Sdf for Rectangle {
+    fn distance(&self, point: Point2) -> f32 {
+        intersect::signed_distance_rect(point, &self.rect)
+    }
+}
+
+impl Sdf for Segment {
+    fn distance(&self, point: Point2) -> f32 {
+        let closest = intersect::closest_point_on_segment(point, self.start, self.end);
+        (point - closest).magnitude()
+    }
+}
+
+impl Sdf for Polygon {
+    fn distance(&self, point: Point2) -> f32 {
+        intersect::signed_distance_polygon(point, self.points.iter().cloned())
+    }
+}
+
+impl<A, B> Sdf for Union<A, B>
+where
+    A: Sdf,
+    B: Sdf,
+{
+    fn distance(&self, point: Point2) -> f32 {
+        let (a, b) = (self.a.distance(point), self.b.distance(point));
+        smooth_min(a, b, self.smoothness)
+    }
+}
+
+impl<A, B> Sdf for Subtract<A, B>
+where
+    A: Sdf,
+    B: Sdf,
+{
+    fn distance(&self, point: Point2) -> f32 {
+        let (a, b) = (self.a.distance(point), self.b.distance(point));
+        -smooth_min(-a, b, self.smoothness)
+    }
+}
+
+impl<A, B> Sdf for Intersect<A, B>
+where
+    A: Sdf,
+    B: Sdf,
+{
+    fn distance(&self, point: Point2) -> f32 {
+        let (a, b) = (self.a.distance(point), self.b.distance(point));
+        -smooth_min(-a, -b, self.smoothness)
+    }
+}
+
+impl<T> Sdf for Translate<T>
+where
+    T: Sdf,
+{
+    fn distance(&self, point: Point2) -> f32 {
+        self.sdf.distance(point - self.v)
+    }
+}
+
+impl<T> Sdf for Rotate<T>
+where
+    T: Sdf,
+{
+    fn distance(&self, point: Point2) -> f32 {
+        self.sdf.distance(point.rotate(-self.radians))
+    }
+}
+
+impl<T> Sdf for Scale<T>
+where
+    T: Sdf,
+{
+    fn distance(&self, point: Point2) -> f32 {
+        self.sdf.distance(point / self.scale) * self.scale
+    }
+}
+
+impl<T> Sdf for Repeat<T>
+where
+    T: Sdf,
+{
+    fn distance(&self, point: Point2) -> f32 {
+        let wrap = |c: f32, period: f32| {
+            if period == 0.0 {
+                c
+            } else {
+                c - period * (c / period).round()
+            }
+        };
+        let p = pt2(wrap(point.x, self.period.x), wrap(point.y, self.period.y));
+        self.sdf.distance(p)
+    }
+}
+
+impl<T> Sdf for Twist<T>
+where
+    T: Sdf,
+{
+    fn distance(&self, point: Point2) -> f32 {
+        let radians = point.magnitude() * self.radians_per_unit;
+        self.sdf.distance(point.rotate(-radians))
+    }
+}
+
+impl<T> Sdf for Round<T>
+where
+    T: Sdf,
+{
+    fn distance(&self, point: Point2) -> f32 {
+        self.sdf.distance(point) - self.radius
+    }
+}
+
+impl<T> Sdf for Onion<T>
+where
+    T: Sdf,
+{
+    fn distance(&self, point: Point2) -> f32 {
+        self.sdf.distance(point).abs() - self.thickness * 0.5
+    }
+}
+
+impl<T, F> Sdf for Displace<T, F>
+where
+    T: Sdf,
+    F: Fn(Point2) -> f32,
+{
+    fn distance(&self, point: Point2) -> f32 {
+        self.sdf.distance(point) + (self.displacement)(point)
+    }
+}
+
+// The minimum of the two distances, blended where they are within `k` of one another.
+fn smooth_min(a: f32, b: f32, k: f32) -> f32 {
+    if k <= 0.0 {
+        return a.min(b);
+    }
+    let h = (k - (a - b).abs()).max(0.0) / k;
+    a.min(b) - h * h * k * 0.25
+}
@@ -0,0 +1,217 @@
+//! A uniform grid of cells over fixed bounds.
+
+use super::{bounds_contain, distance2, push_nearest, Entries, Id, Position};
+use crate::math::{Float, NumCast, Zero};
+
+/// A spatial index dividing fixed bounds into a uniform grid of cells.
+///
+/// Values positioned outside of the bounds are stored in the nearest cell along the edge of the
+/// grid. They are still found by all queries, though many such values will slow queries down.
+#[derive(Clone, Debug)]
+pub struct Grid<P, T>
+where
+    P: Position,
+{
+    min: P,
+    cell_size: P::Scalar,
+    counts: [usize; 3],
+    cells: Vec<Vec<Id>>,
+    entries: Entries<P, T>,
+}
+
+impl<P, T> Grid<P, T>
+where
+    P: Position,
+{
+    /// Create an empty grid over the given bounds, divided into cells of the given size.
+    ///
+    /// **Panics** if the cell size is not greater than zero.
+    pub fn new(bounds: P::Bounds, cell_size: P::Scalar) -> Self {
+        assert!(cell_size > P::Scalar::zero(), "cell size must be positive");
+        let min = P::bounds_min(&bounds);
+        let max = P::bounds_max(&bounds);
+        let mut counts = [1; 3];
+        for (axis, count) in counts.iter_mut().enumerate().take(P::DIMENSIONS) {
+            let n = ((max.coord(axis) - min.coord(axis)) / cell_size).ceil();
+            *count = NumCast::from(n).unwrap_or(0).max(1);
+        }
+        let cells = vec![vec![]; counts.iter().product()];
+        let entries = Entries::new();
+        Grid {
+            min,
+            cell_size,
+            counts,
+            cells,
+            entries,
+        }
+    }
+
+    /// The number of values in the grid.
+    pub fn len(&self) -> usize {
+        self.entries.len
+    }
+
+    /// Whether or not the grid contains no values.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Insert a value at the given position, returning its unique `Id`.
+    pub fn insert(&mut self, position: P, value: T) -> Id {
+        let id = self.entries.insert(position, value);
+        let cell = self.cell_index(self.cell_coords(&position));
+        self.cells[cell].push(id);
+        id
+    }
+
+    /// Remove the value with the given `Id`, returning its position and value.
+    pub fn remove(&mut self, id: Id) -> Option<(P, T)> {
+        let (position, value) = self.entries.remove(id)?;
+        let cell = self.cell_index(self.cell_coords(&position));
+        self.cells[cell].retain(|&other| other != id);
+        Some((position, value))
+    }
+
+    /// The position of the value with the given `Id`.
+    pub fn position(&self, id: Id) -> Option<P> {
+        self.entries.get(id).map(|&(p, _)| p)
+    }
+
+    /// A reference to the value with the given `Id`.
+    pub fn get(&self, id: Id) -> Option<&T> {
+        self.entries.get(id).map(|(_, t)| t)
+    }
+
+    /// A mutable reference to the value with the given `Id`.
+    pub fn get_mut(&mut self, id: Id) -> Option<&mut T> {
+        self.entries.get_mut(id).map(|(_, t)| t)
+    }
+
+    /// An iterator yielding the `Id`, position and value of every value in the grid.
+    pub fn iter(&self) -> impl Iterator<Item = (Id, P, &T)> + '_ {
+        self.entries.iter().map(|(id, &p, t)| (id, p, t))
+    }
+
+    /// Remove all values from the grid.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.cells.iter_mut().for_each(Vec::clear);
+    }
+
+    /// All values positioned within the given bounds.
+    pub fn within_bounds(&self, bounds: &P::Bounds) -> Vec<(Id, &T)> {
+        let min = P::bounds_min(bounds);
+        let max = P::bounds_max(bounds);
+        let cells = cell_range(self.cell_coords(&min), self.cell_coords(&max));
+        self.ids_in_cells(cells)
+            .filter_map(|id| {
+                let (p, t) = self.entries.get(id).expect("no entry for id");
+                if bounds_contain(&min, &max, p) {
+                    Some((id, t))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// All values positioned within the given radius of the given point.
+    pub fn within_radius(&self, point: P, radius: P::Scalar) -> Vec<(Id, &T)> {
+        let (mut min, mut max) = (point, point);
+        for axis in 0..P::DIMENSIONS {
+            min = min.with_coord(axis, point.coord(axis) - radius);
+            max = max.with_coord(axis, point.coord(axis) + radius);
+        }
+        let cells = cell_range(self.cell_coords(&min), self.cell_coords(&max));
+        let radius2 = radius * radius;
+        self.ids_in_cells(cells)
+            .filter_map(|id| {
+                let (p, t) = self.entries.get(id).expect("no entry for id");
+                if distance2(p, &point) <= radius2 {
+                    Some((id, t))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// The `k` values nearest to the given point, ordered from nearest to furthest.
+    pub fn nearest(&self, point: P, k: usize) -> Vec<(Id, &T)> {
+        let center = self.cell_coords(&point);
+        let max_ring = *self.counts.iter().max().expect("no counts");
+        let mut nearest: Vec<(P::Scalar, Id)> = vec![];
+        for ring in 0..max_ring {
+            if k == 0 {
+                break;
+            }
+
+            // Visit the cells at the given ring's distance from the center cell.
+            let ring_cells = cell_range(
+                self.offset_coords(center, -(ring as i64)),
+                self.offset_coords(center, ring as i64),
+            )
+            .filter(|coords| {
+                (0..P::DIMENSIONS).any(|axis| {
+                    let d = coords[axis] as i64 - center[axis] as i64;
+                    d.abs() == ring as i64
+                })
+            });
+            for id in self.ids_in_cells(ring_cells) {
+                let (p, _) = self.entries.get(id).expect("no entry for id");
+                push_nearest(&mut nearest, k, distance2(p, &point), id);
+            }
+
+            // Values in further rings lie at least this far away.
+            let ring_dist = self.cell_size * NumCast::from(ring).expect("ring out of range");
+            if nearest.len() == k && nearest[k - 1].0 <= ring_dist * ring_dist {
+                break;
+            }
+        }
+        nearest
+            .into_iter()
+            .map(|(_, id)| (id, self.get(id).expect("no entry for id")))
+            .collect()
+    }
+
+    // The coordinates of the cell containing the given position, clamped to the grid.
+    fn cell_coords(&self, p: &P) -> [usize; 3] {
+        let mut coords = [0; 3];
+        for (axis, coord) in coords.iter_mut().enumerate().take(P::DIMENSIONS) {
+            let c = ((p.coord(axis) - self.min.coord(axis)) / self.cell_size).floor();
+            let c: i64 = NumCast::from(c).unwrap_or(0);
+            *coord = c.max(0).min(self.counts[axis] as i64 - 1) as usize;
+        }
+        coords
+    }
+
+    // The cell coordinates offset by the given number of cells along every axis, clamped to the
+    // grid.
+    fn offset_coords(&self, coords: [usize; 3], offset: i64) -> [usize; 3] {
+        let mut offset_coords = coords;
+        for (axis, coord) in offset_coords.iter_mut().enumerate().take(P::DIMENSIONS) {
+            let c = *coord as i64 + offset;
+            *coord = c.max(0).min(self.counts[axis] as i64 - 1) as usize;
+        }
+        offset_coords
+    }
+
+    fn cell_index(&self, coords: [usize; 3]) -> usize {
+        coords[0] + self.counts[0] * (coords[1] + self.counts[1] * coords[2])
+    }
+
+    // The ids of all values within the given cells.
+    fn ids_in_cells<'a, I>(&'a self, cells: I) -> impl 'a + Iterator<Item = Id>
+    where
+        I: 'a + Iterator<Item = [usize; 3]>,
+    {
+        cells.flat_map(move |coords| self.cells[self.cell_index(coords)].iter().cloned())
+    }
+}
+
+// An iterator yielding the coordinates of every cell within the given inclusive range.
+fn cell_range(min: [usize; 3], max: [usize; 3]) -> impl Iterator<Item = [usize; 3]> {
+    (min[2]..=max[2]).flat_map(move |z| {
+        (min[1]..=max[1]).flat_map(move |y| (min[0]..=max[0]).map(move |x| [x, y, z]))
+    })
+}
@@ -0,0 +1,406 @@
+//! A k-d tree, recursively splitting space at the positions of its values.
+
+use super::{bounds_contain, distance2, push_nearest, Entries, Id, Position};
+use crate::math::Zero;
+use std::cmp::Ordering;
+use std::iter::FromIterator;
+
+/// The largest fraction of a subtree's nodes that may lie on one side of its root before the
+/// subtree is rebuilt.
+const BALANCE: f64 = 0.7;
+
+/// A spatial index that recursively splits space at the positions of its values, alternating
+/// between axes at each level of the tree.
+///
+/// Unlike the `Grid` and `QuadTree`, no bounds are required. Subtrees are rebuilt as insertions
+/// unbalance them. Removed values are marked as removed rather than restructuring the tree, which
+/// is rebuilt once removed values outnumber the values that remain.
+#[derive(Clone, Debug)]
+pub struct KdTree<P, T> {
+    nodes: Vec<Node<P>>,
+    root: Option<usize>,
+    entries: Entries<P, T>,
+    // The number of nodes no longer representing a value.
+    removed: usize,
+}
+
+// A node of the tree. Values with a coordinate along the node's axis less than that of the node
+// lie to the left, and those with a greater coordinate lie to the right. Values with an equal
+// coordinate are ordered by their ids, so that trees of many equal coordinates remain balanced.
+#[derive(Clone, Debug)]
+struct Node<P> {
+    position: P,
+    id: Id,
+    axis: usize,
+    left: Option<usize>,
+    right: Option<usize>,
+    removed: bool,
+}
+
+impl<P, T> KdTree<P, T>
+where
+    P: Position,
+{
+    /// Create an empty tree.
+    pub fn new() -> Self {
+        KdTree {
+            nodes: vec![],
+            root: None,
+            entries: Entries::new(),
+            removed: 0,
+        }
+    }
+
+    /// The number of values in the tree.
+    pub fn len(&self) -> usize {
+        self.entries.len
+    }
+
+    /// Whether or not the tree contains no values.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Insert a value at the given position, returning its unique `Id`.
+    pub fn insert(&mut self, position: P, value: T) -> Id {
+        let id = self.entries.insert(position, value);
+
+        // Find the new node's ancestors.
+        let mut path = vec![];
+        let mut axis = 0;
+        let mut next = self.root;
+        while let Some(ix) = next {
+            let node = &self.nodes[ix];
+            path.push(ix);
+            axis = (node.axis + 1) % P::DIMENSIONS;
+            next = if node.goes_left(&position, id) {
+                node.left
+            } else {
+                node.right
+            };
+        }
+
+        let ix = self.nodes.len();
+        self.nodes.push(Node::new(position, id, axis));
+        match path.last() {
+            None => self.root = Some(ix),
+            Some(&parent) => {
+                let parent = &mut self.nodes[parent];
+                if parent.goes_left(&position, id) {
+                    parent.left = Some(ix);
+                } else {
+                    parent.right = Some(ix);
+                }
+            }
+        }
+
+        // A node deeper than a balanced tree allows has an unbalanced ancestor.
+        let max_depth = (self.nodes.len() as f64).ln() / (1.0 / BALANCE).ln();
+        if path.len() as f64 > max_depth {
+            self.rebalance(&path, ix);
+        }
+        id
+    }
+
+    /// Remove the value with the given `Id`, returning its position and value.
+    pub fn remove(&mut self, id: Id) -> Option<(P, T)> {
+        let (position, value) = self.entries.remove(id)?;
+        let mut next = self.root;
+        while let Some(ix) = next {
+            let node = &mut self.nodes[ix];
+            if node.id == id && !node.removed {
+                node.removed = true;
+                self.removed += 1;
+                break;
+            }
+            next = if node.goes_left(&position, id) {
+                node.left
+            } else {
+                node.right
+            };
+        }
+        if self.removed > self.len() {
+            self.rebuild();
+        }
+        Some((position, value))
+    }
+
+    /// The position of the value with the given `Id`.
+    pub fn position(&self, id: Id) -> Option<P> {
+        self.entries.get(id).map(|&(p, _)| p)
+    }
+
+    /// A reference to the value with the given `Id`.
+    pub fn get(&self, id: Id) -> Option<&T> {
+        self.entries.get(id).map(|(_, t)| t)
+    }
+
+    /// A mutable reference to the value with the given `Id`.
+    pub fn get_mut(&mut self, id: Id) -> Option<&mut T> {
+        self.entries.get_mut(id).map(|(_, t)| t)
+    }
+
+    /// An iterator yielding the `Id`, position and value of every value in the tree.
+    pub fn iter(&self) -> impl Iterator<Item = (Id, P, &T)> + '_ {
+        self.entries.iter().map(|(id, &p, t)| (id, p, t))
+    }
+
+    /// Remove all values from the tree.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.nodes.clear();
+        self.root = None;
+        self.removed = 0;
+    }
+
+    /// Rebuild the tree so that it is balanced, discarding the nodes of removed values.
+    ///
+    /// This is done automatically once removed values outnumber the values that remain.
+    pub fn rebuild(&mut self) {
+        let mut positions: Vec<(P, Id)> = self.entries.iter().map(|(id, &p, _)| (p, id)).collect();
+        self.nodes.clear();
+        self.root = build(&mut self.nodes, &mut vec![], &mut positions, 0);
+        self.removed = 0;
+    }
+
+    /// All values positioned within the given bounds.
+    pub fn within_bounds(&self, bounds: &P::Bounds) -> Vec<(Id, &T)> {
+        let min = P::bounds_min(bounds);
+        let max = P::bounds_max(bounds);
+        let mut found = vec![];
+        let mut stack: Vec<usize> = self.root.into_iter().collect();
+        while let Some(ix) = stack.pop() {
+            let node = &self.nodes[ix];
+            if !node.removed && bounds_contain(&min, &max, &node.position) {
+                found.push(node.id);
+            }
+            let split = node.position.coord(node.axis);
+            if min.coord(node.axis) <= split {
+                stack.extend(node.left);
+            }
+            if max.coord(node.axis) >= split {
+                stack.extend(node.right);
+            }
+        }
+        self.values(found)
+    }
+
+    /// All values positioned within the given radius of the given point.
+    pub fn within_radius(&self, point: P, radius: P::Scalar) -> Vec<(Id, &T)> {
+        let radius2 = radius * radius;
+        let mut found = vec![];
+        let mut stack: Vec<usize> = self.root.into_iter().collect();
+        while let Some(ix) = stack.pop() {
+            let node = &self.nodes[ix];
+            if !node.removed && distance2(&node.position, &point) <= radius2 {
+                found.push(node.id);
+            }
+            let split = node.position.coord(node.axis);
+            let c = point.coord(node.axis);
+            if c - radius <= split {
+                stack.extend(node.left);
+            }
+            if c + radius >= split {
+                stack.extend(node.right);
+            }
+        }
+        self.values(found)
+    }
+
+    /// The `k` values nearest to the given point, ordered from nearest to furthest.
+    pub fn nearest(&self, point: P, k: usize) -> Vec<(Id, &T)> {
+        if k == 0 {
+            return vec![];
+        }
+        let mut nearest: Vec<(P::Scalar, Id)> = vec![];
+        // Nodes to visit along with the squared distance to the region of space they split.
+        let mut stack: Vec<(usize, P::Scalar)> = self
+            .root
+            .into_iter()
+            .map(|ix| (ix, P::Scalar::zero()))
+            .collect();
+        while let Some((ix, region_dist)) = stack.pop() {
+            if nearest.len() == k && region_dist > nearest[k - 1].0 {
+                continue;
+            }
+            let node = &self.nodes[ix];
+            if !node.removed {
+                let dist = distance2(&node.position, &point);
+                push_nearest(&mut nearest, k, dist, node.id);
+            }
+
+            // Visit the side containing the point first, as it is the most likely to be nearest.
+            let d = point.coord(node.axis) - node.position.coord(node.axis);
+            let (near, far) = if d < P::Scalar::zero() {
+                (node.left, node.right)
+            } else {
+                (node.right, node.left)
+            };
+            let far_dist = if d * d > region_dist {
+                d * d
+            } else {
+                region_dist
+            };
+            stack.extend(far.map(|ix| (ix, far_dist)));
+            stack.extend(near.map(|ix| (ix, region_dist)));
+        }
+        self.values(nearest.into_iter().map(|(_, id)| id).collect())
+    }
+
+    fn values(&self, ids: Vec<Id>) -> Vec<(Id, &T)> {
+        ids.into_iter()
+            .map(|id| (id, self.get(id).expect("no entry for id")))
+            .collect()
+    }
+
+    // Rebuild the subtree of the deepest ancestor of the node whose subtree is unbalanced, given
+    // the node's ancestors from the root down.
+    fn rebalance(&mut self, path: &[usize], ix: usize) {
+        let (mut child, mut child_size) = (ix, 1);
+        for (depth, &ancestor) in path.iter().enumerate().rev() {
+            let node = &self.nodes[ancestor];
+            let sibling = if node.left == Some(child) {
+                node.right
+            } else {
+                node.left
+            };
+            let size = 1 + child_size + self.subtree_nodes(sibling).count();
+            if child_size as f64 > BALANCE * size as f64 {
+                let root = self.rebuild_subtree(ancestor);
+                match depth.checked_sub(1) {
+                    None => self.root = root,
+                    Some(d) => {
+                        let parent = &mut self.nodes[path[d]];
+                        if parent.left == Some(ancestor) {
+                            parent.left = root;
+                        } else {
+                            parent.right = root;
+                        }
+                    }
+                }
+                return;
+            }
+            child = ancestor;
+            child_size = size;
+        }
+    }
+
+    // Rebuild the subtree so that it is balanced, reusing its nodes and returning its new root.
+    fn rebuild_subtree(&mut self, root: usize) -> Option<usize> {
+        let mut slots: Vec<usize> = self.subtree_nodes(Some(root)).collect();
+        let mut positions: Vec<(P, Id)> = slots
+            .iter()
+            .map(|&ix| &self.nodes[ix])
+            .filter(|node| !node.removed)
+            .map(|node| (node.position, node.id))
+            .collect();
+        let axis = self.nodes[root].axis;
+        build(&mut self.nodes, &mut slots, &mut positions, axis)
+    }
+
+    // The indices of all nodes within the subtree.
+    fn subtree_nodes(&self, root: Option<usize>) -> impl Iterator<Item = usize> + '_ {
+        let mut stack: Vec<usize> = root.into_iter().collect();
+        std::iter::from_fn(move || {
+            let ix = stack.pop()?;
+            stack.extend(self.nodes[ix].left);
+            stack.extend(self.nodes[ix].right);
+            Some(ix)
+        })
+    }
+}
+
+impl<P> Node<P> {
+    fn new(position: P, id: Id, axis: usize) -> Self {
+        Node {
+            position,
+            id,
+            axis,
+            left: None,
+            right: None,
+            removed: false,
+        }
+    }
+
+    // Whether the value with the given position and id lies to the left of the node.
+    fn goes_left(&self, p: &P, id: Id) -> bool
+    where
+        P: Position,
+    {
+        match p
+            .coord(self.axis)
+            .partial_cmp(&self.position.coord(self.axis))
+        {
+            Some(Ordering::Less) => true,
+            Some(Ordering::Equal) => id < self.id,
+            _ => false,
+        }
+    }
+}
+
+impl<P, T> Default for KdTree<P, T>
+where
+    P: Position,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P, T> FromIterator<(P, T)> for KdTree<P, T>
+where
+    P: Position,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (P, T)>,
+    {
+        let mut tree = Self::new();
+        for (position, value) in iter {
+            tree.entries.insert(position, value);
+        }
+        tree.rebuild();
+        tree
+    }
+}
+
+// Build a balanced tree of the given positions, split at the median along the given axis. Nodes
+// are written to the given slots where available, or otherwise pushed onto the list of nodes.
+fn build<P>(
+    nodes: &mut Vec<Node<P>>,
+    slots: &mut Vec<usize>,
+    positions: &mut [(P, Id)],
+    axis: usize,
+) -> Option<usize>
+where
+    P: Position,
+{
+    if positions.is_empty() {
+        return None;
+    }
+    positions.sort_by(|a, b| {
+        a.0.coord(axis)
+            .partial_cmp(&b.0.coord(axis))
+            .expect("coordinate was NaN")
+            .then(a.1.cmp(&b.1))
+    });
+    let mid = positions.len() / 2;
+
+    let (position, id) = positions[mid];
+    let node = Node::new(position, id, axis);
+    let ix = match slots.pop() {
+        Some(ix) => {
+            nodes[ix] = node;
+            ix
+        }
+        None => {
+            nodes.push(node);
+            nodes.len() - 1
+        }
+    };
+    let next_axis = (axis + 1) % P::DIMENSIONS;
+    let (left, right) = positions.split_at_mut(mid);
+    nodes[ix].left = build(nodes, slots, left, next_axis);
+    nodes[ix].right = build(nodes, slots, &mut right[1..], next_axis);
+    Some(ix)
+}
@@ -0,0 +1,229 @@
+//! Spatial indexes for efficiently finding values by their position.
+//!
+//! Three indexes are provided, each supporting insertion, removal, bounds queries, radius queries
+//! and k-nearest-neighbour queries:
+//!
+//! - `Grid`: a uniform grid of cells over fixed bounds. Fast for evenly distributed points that
+//!   are queried over distances similar to the cell size, e.g. flocking.
+//! - `QuadTree`: adaptively subdivides fixed bounds where points are dense. The same type keyed
+//!   on `Cuboid` bounds is an octree, available as `Octree`.
+//! - `KdTree`: splits space at the points themselves and requires no bounds.
+//!
+//! Each index stores values of any type alongside a position, either a `Point2<S>` (bounded by a
+//! `Rect<S>`) or a `Point3<S>` (bounded by a `Cuboid<S>`). Inserting a value produces an `Id`
+//! that may later be used to retrieve or remove it.
+
+use crate::geom::{pt2, pt3, Cuboid, Point2, Point3, Range, Rect};
+use crate::math::{BaseFloat, Zero};
+
+pub mod grid;
+pub mod kdtree;
+pub mod quadtree;
+
+pub use self::grid::Grid;
+pub use self::kdtree::KdTree;
+pub use self::quadtree::{Octree, QuadTree};
+
+/// Uniquely identifies a value within a spatial index.
+///
+/// Ids of removed values may be reused by later insertions.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Id(usize);
+
+/// Positions by which values may be indexed.
+pub trait Position: Copy + PartialEq {
+    /// The scalar type of each coordinate.
+    type Scalar: BaseFloat;
+    /// The axis-aligned bounds of a region of positions.
+    type Bounds: Copy;
+    /// The number of coordinates describing a position.
+    const DIMENSIONS: usize;
+
+    /// The coordinate along the given axis.
+    fn coord(&self, axis: usize) -> Self::Scalar;
+
+    /// The position with the coordinate along the given axis replaced.
+    fn with_coord(self, axis: usize, value: Self::Scalar) -> Self;
+
+    /// The corner of the bounds with the lowest coordinates.
+    fn bounds_min(bounds: &Self::Bounds) -> Self;
+
+    /// The corner of the bounds with the highest coordinates.
+    fn bounds_max(bounds: &Self::Bounds) -> Self;
+
+    /// The bounds with the given lowest and highest corners.
+    fn bounds_from_min_max(min: Self, max: Self) -> Self::Bounds;
+}
+
+// The positions and values of an index, stored in slots addressed by `Id`.
+#[derive(Clone, Debug)]
+struct Entries<P, T> {
+    slots: Vec<Option<(P, T)>>,
+    free: Vec<usize>,
+    len: usize,
+}
+
+impl<S> Position for Point2<S>
+where
+    S: BaseFloat,
+{
+    type Scalar = S;
+    type Bounds = Rect<S>;
+    const DIMENSIONS: usize = 2;
+
+    fn coord(&self, axis: usize) -> S {
+        self[axis]
+    }
+
+    fn with_coord(mut self, axis: usize, value: S) -> Self {
+        self[axis] = value;
+        self
+    }
+
+    fn bounds_min(bounds: &Rect<S>) -> Self {
+        pt2(bounds.x.absolute().start, bounds.y.absolute().start)
+    }
+
+    fn bounds_max(bounds: &Rect<S>) -> Self {
+        pt2(bounds.x.absolute().end, bounds.y.absolute().end)
+    }
+
+    fn bounds_from_min_max(min: Self, max: Self) -> Rect<S> {
+        Rect {
+            x: Range::new(min.x, max.x),
+            y: Range::new(min.y, max.y),
+        }
+    }
+}
+
+impl<S> Position for Point3<S>
+where
+    S: BaseFloat,
+{
+    type Scalar = S;
+    type Bounds = Cuboid<S>;
+    const DIMENSIONS: usize = 3;
+
+    fn coord(&self, axis: usize) -> S {
+        self[axis]
+    }
+
+    fn with_coord(mut self, axis: usize, value: S) -> Self {
+        self[axis] = value;
+        self
+    }
+
+    fn bounds_min(bounds: &Cuboid<S>) -> Self {
+        let b = bounds.absolute();
+        pt3(b.x.start, b.y.start, b.z.start)
+    }
+
+    fn bounds_max(bounds: &Cuboid<S>) -> Self {
+        let b = bounds.absolute();
+        pt3(b.x.end, b.y.end, b.z.end)
+    }
+
+    fn bounds_from_min_max(min: Self, max: Self) -> Cuboid<S> {
+        let x = Range::new(min.x, max.x);
+        let y = Range::new(min.y, max.y);
+        let z = Range::new(min.z, max.z);
+        Cuboid::from_ranges(x, y, z)
+    }
+}
+
+impl<P, T> Entries<P, T> {
+    fn new() -> Self {
+        Entries {
+            slots: vec![],
+            free: vec![],
+            len: 0,
+        }
+    }
+
+    fn insert(&mut self, position: P, value: T) -> Id {
+        self.len += 1;
+        match self.free.pop() {
+            Some(ix) => {
+                self.slots[ix] = Some((position, value));
+                Id(ix)
+            }
+            None => {
+                self.slots.push(Some((position, value)));
+                Id(self.slots.len() - 1)
+            }
+        }
+    }
+
+    fn remove(&mut self, id: Id) -> Option<(P, T)> {
+        let entry = self.slots.get_mut(id.0).and_then(Option::take)?;
+        self.free.push(id.0);
+        self.len -= 1;
+        Some(entry)
+    }
+
+    fn get(&self, id: Id) -> Option<&(P, T)> {
+        self.slots.get(id.0).and_then(Option::as_ref)
+    }
+
+    fn get_mut(&mut self, id: Id) -> Option<&mut (P, T)> {
+        self.slots.get_mut(id.0).and_then(Option::as_mut)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (Id, &P, &T)> + '_ {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(ix, slot)| slot.as_ref().map(|(p, t)| (Id(ix), p, t)))
+    }
+
+    fn clear(&mut self) {
+        self.slots.clear();
+        self.free.clear();
+        self.len = 0;
+    }
+}
+
+// The squared distance between two positions.
+fn distance2<P: Position>(a: &P, b: &P) -> P::Scalar {
+    (0..P::DIMENSIONS).fold(P::Scalar::zero(), |acc, axis| {
+        let d = a.coord(axis) - b.coord(axis);
+        acc + d * d
+    })
+}
+
+// The squared distance from the position to the nearest point within the bounds.
+fn bounds_distance2<P: Position>(min: &P, max: &P, p: &P) -> P::Scalar {
+    (0..P::DIMENSIONS).fold(P::Scalar::zero(), |acc, axis| {
+        let c = p.coord(axis);
+        let d = if c < min.coord(axis) {
+            min.coord(axis) - c
+        } else if c > max.coord(axis) {
+            c - max.coord(axis)
+        } else {
+            P::Scalar::zero()
+        };
+        acc + d * d
+    })
+}
+
+// Whether the position lies within or on the edge of the bounds.
+fn bounds_contain<P: Position>(min: &P, max: &P, p: &P) -> bool {
+    (0..P::DIMENSIONS).all(|axis| {
+        let c = p.coord(axis);
+        c >= min.coord(axis) && c <= max.coord(axis)
+    })
+}
+
+// Insert the candidate into the list of nearest candidates, which is sorted by distance and
+// limited to `k` in length.
+fn push_nearest<S: Copy + PartialOrd>(nearest: &mut Vec<(S, Id)>, k: usize, dist: S, id: Id) {
+    if k == 0 || (nearest.len() == k && dist >= nearest[k - 1].0) {
+        return;
+    }
+    let ix = nearest
+        .iter()
+        .position(|&(d, _)| dist < d)
+        .unwrap_or(nearest.len());
+    nearest.insert(ix, (dist, id));
+    nearest.truncate(k);
+}
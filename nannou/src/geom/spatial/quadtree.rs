@@ -0,0 +1,342 @@
+//! A tree recursively dividing fixed bounds into quadrants, or octants in three dimensions.
+
+use super::{bounds_contain, bounds_distance2, distance2, push_nearest, Entries, Id, Position};
+use crate::geom::Point3;
+use crate::math::One;
+use std::cmp::Ordering;
+
+/// The maximum number of values stored within a node before it is divided.
+pub const NODE_CAPACITY: usize = 8;
+
+/// The depth beyond which nodes are no longer divided, regardless of how many values they store.
+pub const MAX_DEPTH: usize = 16;
+
+/// A spatial index that recursively divides fixed bounds wherever values are densely positioned.
+///
+/// Indexing `Point2` positions within a `Rect` produces a quadtree, while indexing `Point3`
+/// positions within a `Cuboid` produces an octree.
+///
+/// Values positioned outside of the bounds are stored in a separate list that is checked by every
+/// query. They are still found by all queries, though many such values will slow queries down.
+#[derive(Clone, Debug)]
+pub struct QuadTree<P, T>
+where
+    P: Position,
+{
+    root: Node<P>,
+    outside: Vec<Id>,
+    entries: Entries<P, T>,
+}
+
+/// A `QuadTree` indexing three-dimensional positions, dividing its bounds into octants.
+pub type Octree<T, S = f32> = QuadTree<Point3<S>, T>;
+
+#[derive(Clone, Debug)]
+struct Node<P> {
+    min: P,
+    max: P,
+    kind: Kind<P>,
+}
+
+#[derive(Clone, Debug)]
+enum Kind<P> {
+    Leaf(Vec<Id>),
+    Branch(Vec<Node<P>>),
+}
+
+impl<P, T> QuadTree<P, T>
+where
+    P: Position,
+{
+    /// Create an empty tree over the given bounds.
+    pub fn new(bounds: P::Bounds) -> Self {
+        let root = Node::leaf(P::bounds_min(&bounds), P::bounds_max(&bounds));
+        QuadTree {
+            root,
+            outside: vec![],
+            entries: Entries::new(),
+        }
+    }
+
+    /// The bounds over which the tree was created.
+    pub fn bounds(&self) -> P::Bounds {
+        P::bounds_from_min_max(self.root.min, self.root.max)
+    }
+
+    /// The number of values in the tree.
+    pub fn len(&self) -> usize {
+        self.entries.len
+    }
+
+    /// Whether or not the tree contains no values.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Insert a value at the given position, returning its unique `Id`.
+    pub fn insert(&mut self, position: P, value: T) -> Id {
+        let id = self.entries.insert(position, value);
+        if self.root.contains(&position) {
+            self.root.insert(id, position, &self.entries, 0);
+        } else {
+            self.outside.push(id);
+        }
+        id
+    }
+
+    /// Remove the value with the given `Id`, returning its position and value.
+    pub fn remove(&mut self, id: Id) -> Option<(P, T)> {
+        let (position, value) = self.entries.remove(id)?;
+        if self.root.contains(&position) {
+            self.root.remove(id, &position);
+        } else {
+            self.outside.retain(|&other| other != id);
+        }
+        Some((position, value))
+    }
+
+    /// The position of the value with the given `Id`.
+    pub fn position(&self, id: Id) -> Option<P> {
+        self.entries.get(id).map(|&(p, _)| p)
+    }
+
+    /// A reference to the value with the given `Id`.
+    pub fn get(&self, id: Id) -> Option<&T> {
+        self.entries.get(id).map(|(_, t)| t)
+    }
+
+    /// A mutable reference to the value with the given `Id`.
+    pub fn get_mut(&mut self, id: Id) -> Option<&mut T> {
+        self.entries.get_mut(id).map(|(_, t)| t)
+    }
+
+    /// An iterator yielding the `Id`, position and value of every value in the tree.
+    pub fn iter(&self) -> impl Iterator<Item = (Id, P, &T)> + '_ {
+        self.entries.iter().map(|(id, &p, t)| (id, p, t))
+    }
+
+    /// Remove all values from the tree.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.outside.clear();
+        self.root = Node::leaf(self.root.min, self.root.max);
+    }
+
+    /// All values positioned within the given bounds.
+    pub fn within_bounds(&self, bounds: &P::Bounds) -> Vec<(Id, &T)> {
+        let min = P::bounds_min(bounds);
+        let max = P::bounds_max(bounds);
+        let mut ids = self.outside.clone();
+        self.root.collect(&mut ids, &|node| {
+            (0..P::DIMENSIONS).all(|axis| {
+                node.min.coord(axis) <= max.coord(axis) && node.max.coord(axis) >= min.coord(axis)
+            })
+        });
+        self.filter_ids(ids, |p| bounds_contain(&min, &max, p))
+    }
+
+    /// All values positioned within the given radius of the given point.
+    pub fn within_radius(&self, point: P, radius: P::Scalar) -> Vec<(Id, &T)> {
+        let radius2 = radius * radius;
+        let mut ids = self.outside.clone();
+        self.root.collect(&mut ids, &|node| {
+            bounds_distance2(&node.min, &node.max, &point) <= radius2
+        });
+        self.filter_ids(ids, |p| distance2(p, &point) <= radius2)
+    }
+
+    /// The `k` values nearest to the given point, ordered from nearest to furthest.
+    pub fn nearest(&self, point: P, k: usize) -> Vec<(Id, &T)> {
+        if k == 0 {
+            return vec![];
+        }
+        let mut nearest: Vec<(P::Scalar, Id)> = vec![];
+        for &id in &self.outside {
+            let (p, _) = self.entries.get(id).expect("no entry for id");
+            push_nearest(&mut nearest, k, distance2(p, &point), id);
+        }
+        self.root.nearest(&point, k, &self.entries, &mut nearest);
+        nearest
+            .into_iter()
+            .map(|(_, id)| (id, self.get(id).expect("no entry for id")))
+            .collect()
+    }
+
+    // The values of the given ids whose positions satisfy the predicate.
+    fn filter_ids<F>(&self, ids: Vec<Id>, predicate: F) -> Vec<(Id, &T)>
+    where
+        F: Fn(&P) -> bool,
+    {
+        ids.into_iter()
+            .filter_map(|id| {
+                let (p, t) = self.entries.get(id).expect("no entry for id");
+                if predicate(p) {
+                    Some((id, t))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+impl<P> Node<P>
+where
+    P: Position,
+{
+    fn leaf(min: P, max: P) -> Self {
+        Node {
+            min,
+            max,
+            kind: Kind::Leaf(vec![]),
+        }
+    }
+
+    fn contains(&self, p: &P) -> bool {
+        bounds_contain(&self.min, &self.max, p)
+    }
+
+    fn mid(&self) -> P {
+        let two = P::Scalar::one() + P::Scalar::one();
+        (0..P::DIMENSIONS).fold(self.min, |mid, axis| {
+            let c = (self.min.coord(axis) + self.max.coord(axis)) / two;
+            mid.with_coord(axis, c)
+        })
+    }
+
+    // The index of the child containing the given position, with one bit per axis that is set
+    // where the position lies within the upper half of the node.
+    fn child_index(&self, p: &P) -> usize {
+        let mid = self.mid();
+        (0..P::DIMENSIONS)
+            .filter(|&axis| p.coord(axis) >= mid.coord(axis))
+            .fold(0, |ix, axis| ix | (1 << axis))
+    }
+
+    fn insert<T>(&mut self, id: Id, position: P, entries: &Entries<P, T>, depth: usize) {
+        let ix = self.child_index(&position);
+        let divide = match self.kind {
+            Kind::Branch(ref mut children) => {
+                children[ix].insert(id, position, entries, depth + 1);
+                false
+            }
+            Kind::Leaf(ref mut ids) => {
+                ids.push(id);
+                ids.len() > NODE_CAPACITY && depth < MAX_DEPTH
+            }
+        };
+        if divide {
+            self.divide(entries, depth);
+        }
+    }
+
+    // Turn the leaf into a branch, moving its values into its new children.
+    fn divide<T>(&mut self, entries: &Entries<P, T>, depth: usize) {
+        let mid = self.mid();
+        let children = (0..1 << P::DIMENSIONS)
+            .map(|ix| {
+                let (mut min, mut max) = (self.min, self.max);
+                for axis in 0..P::DIMENSIONS {
+                    if ix & (1 << axis) != 0 {
+                        min = min.with_coord(axis, mid.coord(axis));
+                    } else {
+                        max = max.with_coord(axis, mid.coord(axis));
+                    }
+                }
+                Node::leaf(min, max)
+            })
+            .collect();
+        if let Kind::Leaf(ids) = std::mem::replace(&mut self.kind, Kind::Branch(children)) {
+            for id in ids {
+                let &(p, _) = entries.get(id).expect("no entry for id");
+                self.insert(id, p, entries, depth);
+            }
+        }
+    }
+
+    fn remove(&mut self, id: Id, position: &P) -> bool {
+        let ix = self.child_index(position);
+        let removed = match self.kind {
+            Kind::Leaf(ref mut ids) => match ids.iter().position(|&other| other == id) {
+                Some(i) => {
+                    ids.swap_remove(i);
+                    true
+                }
+                None => false,
+            },
+            Kind::Branch(ref mut children) => children[ix].remove(id, position),
+        };
+
+        // Merge the children back into a single leaf once they store few enough values.
+        if let Kind::Branch(ref children) = self.kind {
+            let len: usize = children
+                .iter()
+                .map(|child| match child.kind {
+                    Kind::Leaf(ref ids) => ids.len(),
+                    Kind::Branch(_) => NODE_CAPACITY + 1,
+                })
+                .sum();
+            if removed && len <= NODE_CAPACITY {
+                let ids = children
+                    .iter()
+                    .flat_map(|child| match child.kind {
+                        Kind::Leaf(ref ids) => ids.clone(),
+                        Kind::Branch(_) => vec![],
+                    })
+                    .collect();
+                self.kind = Kind::Leaf(ids);
+            }
+        }
+        removed
+    }
+
+    // Collect the ids stored within all leaves whose nodes satisfy the predicate.
+    fn collect<F>(&self, ids: &mut Vec<Id>, predicate: &F)
+    where
+        F: Fn(&Node<P>) -> bool,
+    {
+        if !predicate(self) {
+            return;
+        }
+        match self.kind {
+            Kind::Leaf(ref leaf_ids) => ids.extend_from_slice(leaf_ids),
+            Kind::Branch(ref children) => {
+                for child in children {
+                    child.collect(ids, predicate);
+                }
+            }
+        }
+    }
+
+    fn nearest<T>(
+        &self,
+        point: &P,
+        k: usize,
+        entries: &Entries<P, T>,
+        nearest: &mut Vec<(P::Scalar, Id)>,
+    ) {
+        let dist = bounds_distance2(&self.min, &self.max, point);
+        if nearest.len() == k && dist > nearest[k - 1].0 {
+            return;
+        }
+        match self.kind {
+            Kind::Leaf(ref ids) => {
+                for &id in ids {
+                    let (p, _) = entries.get(id).expect("no entry for id");
+                    push_nearest(nearest, k, distance2(p, point), id);
+                }
+            }
+            Kind::Branch(ref children) => {
+                // Visit the nearest children first so that further children may be skipped.
+                let mut order: Vec<_> = children
+                    .iter()
+                    .map(|child| (bounds_distance2(&child.min, &child.max, point), child))
+                    .collect();
+                order.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+                for (_, child) in order {
+                    child.nearest(point, k, entries, nearest);
+                }
+            }
+        }
+    }
+}
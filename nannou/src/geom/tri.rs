@@ -1,12 +1,13 @@
 use crate::geom::{vertex, Cuboid, Point2, Range, Rect, Vertex, Vertex2d, Vertex3d};
 use crate::math::{BaseNum, EuclideanSpace, Zero};
+use crate::serde_derive::{Deserialize, Serialize};
 use std::ops::Deref;
 
 /// The number of vertices in a triangle.
 pub const NUM_VERTICES: u8 = 3;
 
 /// A triangle as three vertices.
-#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
 pub struct Tri<V = vertex::Default>(pub [V; NUM_VERTICES as usize]);
 
 /// An iterator yielding each of the vertices of the triangle.
@@ -0,0 +1,94 @@
+//! Voronoi diagrams of sets of points.
+//!
+//! The Voronoi cell of a point is the region of the plane that is closer to that point than to
+//! any other. Cells are found by clipping a bounding `Rect` by the perpendicular bisector between
+//! each point and its neighbours within the Delaunay triangulation.
+
+use crate::geom::delaunay::Triangulation;
+use crate::geom::{pt2, Point2, Rect, Vector2};
+
+// All geometry is processed in double precision.
+type Point = Vector2<f64>;
+
+/// Produce the Voronoi cell of each of the given points, clipped to the given bounds.
+///
+/// The cells are returned in the same order as the points. Each cell is a convex polygon whose
+/// points wind counter-clockwise, ready to be drawn with `draw.polygon()`. Cells of points that
+/// lie outside of the bounds may be empty. Duplicate points share the same cell.
+pub fn cells<I>(points: I, bounds: Rect) -> Vec<Vec<Point2>>
+where
+    I: IntoIterator,
+    I::Item: Into<Point2>,
+{
+    let triangulation = Triangulation::new(points);
+    cells_from_triangulation(&triangulation, bounds)
+}
+
+/// Produce the Voronoi cell of each of the triangulated points, clipped to the given bounds.
+///
+/// See the `cells` function for details.
+pub fn cells_from_triangulation(triangulation: &Triangulation, bounds: Rect) -> Vec<Vec<Point2>> {
+    let points = triangulation.points();
+    let to_point = |p: Point2| pt2(p.x as f64, p.y as f64);
+
+    // The indices of the points neighbouring each point within the triangulation.
+    let mut neighbours = vec![vec![]; points.len()];
+    for (a, b) in triangulation.edges() {
+        neighbours[a].push(b);
+        neighbours[b].push(a);
+    }
+
+    let corners = [
+        bounds.bottom_left(),
+        bounds.bottom_right(),
+        bounds.top_right(),
+        bounds.top_left(),
+    ];
+    (0..points.len())
+        .map(|i| {
+            // Duplicate points are not triangulated, so use the neighbours of the first.
+            let ix = if neighbours[i].is_empty() {
+                points.iter().position(|&q| q == points[i]).unwrap_or(i)
+            } else {
+                i
+            };
+            let p = to_point(points[ix]);
+            let mut cell: Vec<Point> = corners.iter().map(|&c| to_point(c)).collect();
+            let mut clip = |other: Point2| {
+                let q = to_point(other);
+                if q != p {
+                    cell = clip_half_plane(&cell, p, q);
+                }
+            };
+            // Points without any neighbours, e.g. when all points are collinear, are compared
+            // with every other point.
+            if neighbours[ix].is_empty() {
+                points.iter().for_each(|&q| clip(q));
+            } else {
+                neighbours[ix].iter().for_each(|&j| clip(points[j]));
+            }
+            cell.into_iter()
+                .map(|p| pt2(p.x as f32, p.y as f32))
+                .collect()
+        })
+        .collect()
+}
+
+// Clip the convex polygon to the half of the plane nearer to `p` than to `q`.
+fn clip_half_plane(polygon: &[Point], p: Point, q: Point) -> Vec<Point> {
+    let mid = (p + q) * 0.5;
+    let normal = q - p;
+    let side = |v: Point| (v - mid).dot(normal);
+    let mut clipped = vec![];
+    for (i, &a) in polygon.iter().enumerate() {
+        let b = polygon[(i + 1) % polygon.len()];
+        let (sa, sb) = (side(a), side(b));
+        if sa <= 0.0 {
+            clipped.push(a);
+        }
+        if (sa < 0.0 && sb > 0.0) || (sa > 0.0 && sb < 0.0) {
+            clipped.push(a + (b - a) * (sa / (sa - sb)));
+        }
+    }
+    clipped
+}
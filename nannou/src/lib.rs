@@ -43,9 +43,11 @@ pub mod noise;
 pub mod prelude;
 pub mod rand;
 pub mod state;
+pub mod testing;
 pub mod text;
 pub mod time;
 pub mod ui;
+pub mod video;
 pub mod wgpu;
 pub mod window;
 
@@ -0,0 +1,267 @@
+//! Reading and writing meshes from and to Wavefront OBJ, PLY and STL files.
+//!
+//! Each format is loaded into a `MeshData`, a triangle mesh whose points, indices, normals,
+//! texture coordinates and colors are stored within separate channels. These channels may be
+//! composed into a mesh using the adaptors within the `mesh` module, e.g.
+//!
+//! ```no_run
+//! # use nannou::mesh;
+//! let data = mesh::io::load("teapot.obj").unwrap();
+//! let teapot = mesh::with_indices(mesh::from_points(data.points), data.indices);
+//! let teapot = mesh::with_normals(teapot, data.normals);
+//! ```
+//!
+//! or converted into a `draw::Mesh` via `MeshData::into_draw_mesh`.
+//!
+//! The `load` and `save` functions determine the format from the file extension. Use the `read`
+//! and `write` functions within each format's module to work with other sources and
+//! destinations.
+
+use crate::color::{self, LinSrgba};
+use crate::draw;
+use crate::geom::{pt2, Point2, Point3, Vector3};
+use crate::mesh::{self, MeshPoints, WithIndices};
+use std::ffi::OsStr;
+use std::io::{self, BufReader};
+use std::path::Path;
+use std::{fmt, fs};
+
+pub mod obj;
+pub mod ply;
+pub mod stl;
+
+/// A triangle mesh loaded from or to be written to a file.
+///
+/// The `normals`, `tex_coords` and `colors` channels are either empty, where the file does not
+/// provide them, or contain one element per point.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MeshData {
+    /// The position of each vertex.
+    pub points: Vec<Point3>,
+    /// Indices into the vertex channels, with every three describing a triangle.
+    pub indices: Vec<u32>,
+    /// The normal of each vertex.
+    pub normals: Vec<Vector3>,
+    /// The texture coordinates of each vertex.
+    pub tex_coords: Vec<Point2>,
+    /// The color of each vertex.
+    pub colors: Vec<LinSrgba>,
+}
+
+/// Whether a format that supports both should be written as text or as binary.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    Ascii,
+    Binary,
+}
+
+/// Errors that might occur while loading or saving a mesh.
+#[derive(Debug)]
+pub enum Error {
+    /// An error occurred while reading or writing the file.
+    Io(io::Error),
+    /// The file's contents do not describe a valid mesh.
+    Parse(String),
+    /// The format could not be determined from the path's extension.
+    UnknownFormat,
+}
+
+impl MeshData {
+    /// The number of triangles within the mesh.
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    /// Replace the normals with those produced by averaging the normals of the triangles around
+    /// each point, weighted by area.
+    ///
+    /// Points that are only part of triangles with no area are given a zero normal.
+    ///
+    /// **Panics** if any of the indices are out of range of the points.
+    pub fn compute_normals(&mut self) {
+        let mut normals = vec![Vector3::new(0.0, 0.0, 0.0); self.points.len()];
+        for tri in self.indices.chunks(3).filter(|tri| tri.len() == 3) {
+            let [a, b, c] = [tri[0] as usize, tri[1] as usize, tri[2] as usize];
+            let normal = triangle_normal(self.points[a], self.points[b], self.points[c]);
+            for &ix in &[a, b, c] {
+                normals[ix] += normal;
+            }
+        }
+        self.normals = normals.into_iter().map(normalize_or_zero).collect();
+    }
+
+    /// Combine the points and indices into a mesh.
+    ///
+    /// Other channels may be added to the mesh with the `mesh::with_*` functions.
+    pub fn into_mesh(self) -> WithIndices<MeshPoints<Vec<Point3>>, Vec<u32>> {
+        mesh::with_indices(mesh::from_points(self.points), self.indices)
+    }
+
+    /// Convert into a mesh that may be drawn with `Draw::mesh`.
+    ///
+    /// Vertices are white where the mesh has no colors, and have zeroed texture coordinates where
+    /// it has no texture coordinates. The `draw::Mesh` type has no normals.
+    pub fn into_draw_mesh(self) -> draw::Mesh {
+        let MeshData {
+            points,
+            indices,
+            tex_coords,
+            colors,
+            ..
+        } = self;
+        let vertices = points.into_iter().enumerate().map(|(i, point)| {
+            let color = colors
+                .get(i)
+                .cloned()
+                .unwrap_or(draw::mesh::vertex::DEFAULT_VERTEX_COLOR);
+            let tex_coords = tex_coords.get(i).cloned().unwrap_or(pt2(0.0, 0.0));
+            draw::mesh::vertex::new(point, color, tex_coords)
+        });
+        let mut mesh = draw::Mesh::default();
+        mesh.extend(vertices, indices);
+        mesh
+    }
+
+    // Check that all indices are within range of the points and that all channels are the same
+    // length.
+    fn validate(&self) -> Result<(), Error> {
+        let n = self.points.len();
+        if let Some(&ix) = self.indices.iter().find(|&&ix| ix as usize >= n) {
+            let msg = format!("index {} is out of range of the {} vertices", ix, n);
+            return Err(Error::Parse(msg));
+        }
+        let lens = [self.normals.len(), self.tex_coords.len(), self.colors.len()];
+        if lens.iter().any(|&len| len != 0 && len != n) {
+            let msg = "vertex channels differ in length".to_string();
+            return Err(Error::Parse(msg));
+        }
+        Ok(())
+    }
+}
+
+impl<'a> From<&'a draw::Mesh> for MeshData {
+    fn from(mesh: &'a draw::Mesh) -> Self {
+        MeshData {
+            points: mesh.points().to_vec(),
+            indices: mesh.indices().to_vec(),
+            normals: vec![],
+            tex_coords: mesh.tex_coords().to_vec(),
+            colors: mesh.colors().to_vec(),
+        }
+    }
+}
+
+/// Load a mesh from the file at the given path.
+///
+/// The format is determined by the file's extension, which must be one of `obj`, `ply` or `stl`.
+pub fn load<P>(path: P) -> Result<MeshData, Error>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let read = match &extension(path)?[..] {
+        "obj" => obj::read::<BufReader<fs::File>>,
+        "ply" => ply::read,
+        "stl" => stl::read,
+        _ => return Err(Error::UnknownFormat),
+    };
+    let reader = BufReader::new(fs::File::open(path)?);
+    read(reader)
+}
+
+/// Save the mesh to a file at the given path.
+///
+/// The format is determined by the file's extension, which must be one of `obj`, `ply` or `stl`.
+/// PLY and STL files are written in binary. Use `ply::write` or `stl::write` to write them as
+/// text.
+///
+/// The file is saved via `nannou::io::safe_file_save`.
+pub fn save<P>(path: P, mesh: &MeshData) -> Result<(), Error>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let format = extension(path)?;
+    let mut bytes = vec![];
+    match &format[..] {
+        "obj" => obj::write(mesh, &mut bytes)?,
+        "ply" => ply::write(mesh, &mut bytes, Encoding::Binary)?,
+        "stl" => stl::write(mesh, &mut bytes, Encoding::Binary)?,
+        _ => return Err(Error::UnknownFormat),
+    }
+    crate::io::safe_file_save(path, &bytes)?;
+    Ok(())
+}
+
+// The lowercase extension of the path.
+fn extension(path: &Path) -> Result<String, Error> {
+    path.extension()
+        .and_then(OsStr::to_str)
+        .map(str::to_lowercase)
+        .ok_or(Error::UnknownFormat)
+}
+
+// The unit normal of the triangle, scaled by its area.
+fn triangle_normal(a: Point3, b: Point3, c: Point3) -> Vector3 {
+    (b - a).cross(c - a) * 0.5
+}
+
+// The unit normal of the triangle, or a zero normal if the triangle has no area.
+fn unit_normal(a: Point3, b: Point3, c: Point3) -> Vector3 {
+    normalize_or_zero(triangle_normal(a, b, c))
+}
+
+// Normalize the vector, or return a zero vector if it has no length.
+fn normalize_or_zero(v: Vector3) -> Vector3 {
+    let len = v.magnitude();
+    if len > 0.0 {
+        v / len
+    } else {
+        Vector3::new(0.0, 0.0, 0.0)
+    }
+}
+
+// Convert a color from the sRGB components stored within files.
+fn color_from_srgba(r: f32, g: f32, b: f32, a: f32) -> LinSrgba {
+    color::Srgba::new(r, g, b, a).into_linear()
+}
+
+// Convert a color to the sRGB components stored within files.
+fn color_to_srgba(color: LinSrgba) -> [f32; 4] {
+    let (r, g, b, a) = color::Srgba::from_linear(color).into();
+    [r, g, b, a]
+}
+
+// Parse a number from a word within a text file.
+fn parse_number<T>(word: &str) -> Result<T, Error>
+where
+    T: std::str::FromStr,
+{
+    word.parse()
+        .map_err(|_| Error::Parse(format!("invalid number `{}`", word)))
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl std::error::Error for Error {
+    fn cause(&self) -> Option<&dyn std::error::Error> {
+        match *self {
+            Error::Io(ref e) => Some(e),
+            Error::Parse(_) | Error::UnknownFormat => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Io(ref e) => fmt::Display::fmt(e, f),
+            Error::Parse(ref msg) => write!(f, "invalid mesh file: {}", msg),
+            Error::UnknownFormat => write!(f, "expected a path ending in `.obj`, `.ply` or `.stl`"),
+        }
+    }
+}
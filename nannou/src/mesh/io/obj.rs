@@ -0,0 +1,188 @@
+//! Reading and writing Wavefront OBJ files.
+//!
+//! Positions, texture coordinates and normals are supported, along with the common extension of
+//! listing an RGB color after each vertex position. Faces with more than three vertices are split
+//! into triangles. Materials, groups, lines and curves are ignored.
+
+use super::{color_from_srgba, color_to_srgba, parse_number, Error, MeshData};
+use crate::draw::mesh::vertex::DEFAULT_VERTEX_COLOR;
+use crate::geom::{pt2, pt3, vec3};
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+
+// The position, texture coordinate and normal indices of a face's vertex.
+type VertexKey = (usize, Option<usize>, Option<usize>);
+
+/// Read a mesh from the given OBJ source.
+///
+/// OBJ files index positions, texture coordinates and normals separately. A vertex is produced for
+/// each unique combination referenced by the faces.
+pub fn read<R>(reader: R) -> Result<MeshData, Error>
+where
+    R: BufRead,
+{
+    let mut positions = vec![];
+    let mut colors = vec![];
+    let mut tex_coords = vec![];
+    let mut normals = vec![];
+    let mut vertices: HashMap<VertexKey, u32> = HashMap::new();
+    let mut keys = vec![];
+    let mut indices = vec![];
+
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        let with_line = |e: Error| match e {
+            Error::Parse(msg) => Error::Parse(format!("line {}: {}", i + 1, msg)),
+            e => e,
+        };
+        let line = line.split('#').next().unwrap_or("");
+        let mut words = line.split_whitespace();
+        let numbers = |words: std::str::SplitWhitespace| -> Result<Vec<f32>, Error> {
+            words.map(parse_number::<f32>).collect()
+        };
+        match words.next() {
+            Some("v") => {
+                let v = numbers(words).map_err(with_line)?;
+                if v.len() < 3 {
+                    return Err(with_line(Error::Parse("expected 3 coordinates".into())));
+                }
+                positions.push(pt3(v[0], v[1], v[2]));
+                if v.len() >= 6 {
+                    colors.push(Some(color_from_srgba(v[3], v[4], v[5], 1.0)));
+                } else {
+                    colors.push(None);
+                }
+            }
+            Some("vt") => {
+                let v = numbers(words).map_err(with_line)?;
+                if v.is_empty() {
+                    return Err(with_line(Error::Parse("expected coordinates".into())));
+                }
+                tex_coords.push(pt2(v[0], v.get(1).cloned().unwrap_or(0.0)));
+            }
+            Some("vn") => {
+                let v = numbers(words).map_err(with_line)?;
+                if v.len() < 3 {
+                    return Err(with_line(Error::Parse("expected 3 coordinates".into())));
+                }
+                normals.push(vec3(v[0], v[1], v[2]));
+            }
+            Some("f") => {
+                let mut face = vec![];
+                for word in words {
+                    let mut parts = word.split('/');
+                    let lens = [positions.len(), tex_coords.len(), normals.len()];
+                    let mut index = |ix: usize| -> Result<Option<usize>, Error> {
+                        match parts.next() {
+                            None | Some("") => Ok(None),
+                            Some(s) => resolve_index(s, lens[ix]).map(Some).map_err(with_line),
+                        }
+                    };
+                    let position = match index(0)? {
+                        Some(position) => position,
+                        None => {
+                            let msg = format!("missing vertex index in `{}`", word);
+                            return Err(with_line(Error::Parse(msg)));
+                        }
+                    };
+                    let key = (position, index(1)?, index(2)?);
+                    let next = keys.len() as u32;
+                    let ix = *vertices.entry(key).or_insert(next);
+                    if ix == next {
+                        keys.push(key);
+                    }
+                    face.push(ix);
+                }
+                if face.len() < 3 {
+                    return Err(with_line(Error::Parse("expected 3 vertices".into())));
+                }
+                for j in 1..face.len() - 1 {
+                    indices.extend_from_slice(&[face[0], face[j], face[j + 1]]);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    // Only include channels that are referenced by the faces.
+    let mut mesh = MeshData {
+        points: keys.iter().map(|&(p, _, _)| positions[p]).collect(),
+        indices,
+        ..Default::default()
+    };
+    if keys.iter().any(|&(p, _, _)| colors[p].is_some()) {
+        mesh.colors = keys
+            .iter()
+            .map(|&(p, _, _)| colors[p].unwrap_or(DEFAULT_VERTEX_COLOR))
+            .collect();
+    }
+    if keys.iter().any(|&(_, t, _)| t.is_some()) {
+        mesh.tex_coords = keys
+            .iter()
+            .map(|&(_, t, _)| t.map(|t| tex_coords[t]).unwrap_or(pt2(0.0, 0.0)))
+            .collect();
+    }
+    if keys.iter().any(|&(_, _, n)| n.is_some()) {
+        mesh.normals = keys
+            .iter()
+            .map(|&(_, _, n)| n.map(|n| normals[n]).unwrap_or(vec3(0.0, 0.0, 0.0)))
+            .collect();
+    }
+    Ok(mesh)
+}
+
+/// Write the mesh to the given destination in the OBJ format.
+///
+/// Colors are written after each vertex position. Their alpha is discarded.
+pub fn write<W>(mesh: &MeshData, mut writer: W) -> io::Result<()>
+where
+    W: Write,
+{
+    let n = mesh.points.len();
+    let has_colors = mesh.colors.len() == n && n > 0;
+    let has_tex_coords = mesh.tex_coords.len() == n && n > 0;
+    let has_normals = mesh.normals.len() == n && n > 0;
+    for (i, p) in mesh.points.iter().enumerate() {
+        write!(writer, "v {} {} {}", p.x, p.y, p.z)?;
+        if has_colors {
+            let [r, g, b, _] = color_to_srgba(mesh.colors[i]);
+            write!(writer, " {} {} {}", r, g, b)?;
+        }
+        writeln!(writer)?;
+    }
+    if has_tex_coords {
+        for t in &mesh.tex_coords {
+            writeln!(writer, "vt {} {}", t.x, t.y)?;
+        }
+    }
+    if has_normals {
+        for n in &mesh.normals {
+            writeln!(writer, "vn {} {} {}", n.x, n.y, n.z)?;
+        }
+    }
+    for tri in mesh.indices.chunks(3).filter(|tri| tri.len() == 3) {
+        write!(writer, "f")?;
+        for &ix in tri {
+            let ix = ix + 1;
+            match (has_tex_coords, has_normals) {
+                (false, false) => write!(writer, " {}", ix)?,
+                (true, false) => write!(writer, " {}/{}", ix, ix)?,
+                (false, true) => write!(writer, " {}//{}", ix, ix)?,
+                (true, true) => write!(writer, " {}/{}/{}", ix, ix, ix)?,
+            }
+        }
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+// Resolve a one-based index, or a negative index relative to the end of the list.
+fn resolve_index(word: &str, len: usize) -> Result<usize, Error> {
+    let ix: i64 = parse_number(word)?;
+    let resolved = if ix < 0 { len as i64 + ix } else { ix - 1 };
+    if resolved < 0 || resolved >= len as i64 {
+        let msg = format!("index {} is out of range of the {} elements", ix, len);
+        return Err(Error::Parse(msg));
+    }
+    Ok(resolved as usize)
+}
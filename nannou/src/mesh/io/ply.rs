@@ -0,0 +1,409 @@
+//! Reading and writing PLY files.
+//!
+//! Both the ASCII and binary encodings are supported. Vertex positions, normals, texture
+//! coordinates and colors are read from the `vertex` element, and faces from the `vertex_indices`
+//! list of the `face` element. Faces with more than three vertices are split into triangles. All
+//! other elements and properties are ignored.
+
+use super::{color_from_srgba, color_to_srgba, parse_number, Encoding, Error, MeshData};
+use crate::geom::{pt2, pt3, vec3};
+use std::io::{self, BufRead, Write};
+
+// The encoding of the body of the file.
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Format {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+// The types of which properties may be composed.
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Scalar {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+#[derive(Clone, Debug)]
+enum Property {
+    Scalar(Scalar),
+    List { count: Scalar, item: Scalar },
+}
+
+#[derive(Clone, Debug)]
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<(String, Property)>,
+}
+
+// Reads the values of the properties from the body of the file.
+struct Body<R> {
+    reader: R,
+    format: Format,
+    words: std::vec::IntoIter<String>,
+}
+
+// The slots of a vertex into which properties are read.
+const X: usize = 0;
+const NX: usize = 3;
+const U: usize = 6;
+const RED: usize = 8;
+const ALPHA: usize = 11;
+const SLOTS: usize = 12;
+
+/// Read a mesh from the given PLY source.
+pub fn read<R>(mut reader: R) -> Result<MeshData, Error>
+where
+    R: BufRead,
+{
+    let (format, elements) = read_header(&mut reader)?;
+    let mut body = Body {
+        reader,
+        format,
+        words: vec![].into_iter(),
+    };
+
+    let mut mesh = MeshData::default();
+    for element in &elements {
+        let slots: Vec<Option<usize>> = element
+            .properties
+            .iter()
+            .map(|(name, _)| vertex_slot(name))
+            .collect();
+        let has = |slot: usize| slots.contains(&Some(slot));
+        for _ in 0..element.count {
+            match &element.name[..] {
+                "vertex" => {
+                    let mut v = [0.0; SLOTS];
+                    v[ALPHA] = 1.0;
+                    for ((_, property), &slot) in element.properties.iter().zip(&slots) {
+                        match (property, slot) {
+                            (&Property::Scalar(ty), Some(slot)) => {
+                                let value = body.read(ty)?;
+                                v[slot] = if slot >= RED {
+                                    value / ty.max_intensity()
+                                } else {
+                                    value
+                                };
+                            }
+                            _ => body.skip(property)?,
+                        }
+                    }
+                    let v: Vec<f32> = v.iter().map(|&f| f as f32).collect();
+                    mesh.points.push(pt3(v[X], v[X + 1], v[X + 2]));
+                    if has(NX) {
+                        mesh.normals.push(vec3(v[NX], v[NX + 1], v[NX + 2]));
+                    }
+                    if has(U) {
+                        mesh.tex_coords.push(pt2(v[U], v[U + 1]));
+                    }
+                    if has(RED) {
+                        let color = color_from_srgba(v[RED], v[RED + 1], v[RED + 2], v[ALPHA]);
+                        mesh.colors.push(color);
+                    }
+                }
+                "face" => {
+                    for (name, property) in &element.properties {
+                        match (&name[..], property) {
+                            ("vertex_indices", &Property::List { count, item })
+                            | ("vertex_index", &Property::List { count, item }) => {
+                                let len = body.read(count)? as usize;
+                                let face = (0..len)
+                                    .map(|_| body.read(item).map(|ix| ix as u32))
+                                    .collect::<Result<Vec<_>, _>>()?;
+                                for j in 2..len {
+                                    let tri = [face[0], face[j - 1], face[j]];
+                                    mesh.indices.extend_from_slice(&tri);
+                                }
+                            }
+                            _ => body.skip(property)?,
+                        }
+                    }
+                }
+                _ => {
+                    for (_, property) in &element.properties {
+                        body.skip(property)?;
+                    }
+                }
+            }
+        }
+    }
+
+    mesh.validate()?;
+    Ok(mesh)
+}
+
+/// Write the mesh to the given destination in the PLY format with the given encoding.
+///
+/// Binary files are written in little-endian byte order. Texture coordinates are written as the
+/// `s` and `t` properties and colors as 8-bit `red`, `green`, `blue` and `alpha` properties.
+pub fn write<W>(mesh: &MeshData, mut writer: W, encoding: Encoding) -> io::Result<()>
+where
+    W: Write,
+{
+    let n = mesh.points.len();
+    let has_normals = mesh.normals.len() == n && n > 0;
+    let has_tex_coords = mesh.tex_coords.len() == n && n > 0;
+    let has_colors = mesh.colors.len() == n && n > 0;
+    let format = match encoding {
+        Encoding::Ascii => "ascii",
+        Encoding::Binary => "binary_little_endian",
+    };
+
+    writeln!(writer, "ply")?;
+    writeln!(writer, "format {} 1.0", format)?;
+    writeln!(writer, "element vertex {}", n)?;
+    let mut float_properties = vec!["x", "y", "z"];
+    if has_normals {
+        float_properties.extend_from_slice(&["nx", "ny", "nz"]);
+    }
+    if has_tex_coords {
+        float_properties.extend_from_slice(&["s", "t"]);
+    }
+    for name in float_properties {
+        writeln!(writer, "property float {}", name)?;
+    }
+    if has_colors {
+        for name in &["red", "green", "blue", "alpha"] {
+            writeln!(writer, "property uchar {}", name)?;
+        }
+    }
+    writeln!(writer, "element face {}", mesh.triangle_count())?;
+    writeln!(writer, "property list uchar uint vertex_indices")?;
+    writeln!(writer, "end_header")?;
+
+    for i in 0..n {
+        let p = mesh.points[i];
+        let mut floats = vec![p.x, p.y, p.z];
+        if has_normals {
+            let n = mesh.normals[i];
+            floats.extend_from_slice(&[n.x, n.y, n.z]);
+        }
+        if has_tex_coords {
+            let t = mesh.tex_coords[i];
+            floats.extend_from_slice(&[t.x, t.y]);
+        }
+        let mut bytes = vec![];
+        if has_colors {
+            let to_u8 = |c: f32| (c * 255.0).round() as u8;
+            bytes.extend(color_to_srgba(mesh.colors[i]).iter().map(|&c| to_u8(c)));
+        }
+        match encoding {
+            Encoding::Ascii => {
+                let words = floats
+                    .iter()
+                    .map(|f| f.to_string())
+                    .chain(bytes.iter().map(|b| b.to_string()))
+                    .collect::<Vec<_>>();
+                writeln!(writer, "{}", words.join(" "))?;
+            }
+            Encoding::Binary => {
+                for f in floats {
+                    writer.write_all(&f.to_le_bytes())?;
+                }
+                writer.write_all(&bytes)?;
+            }
+        }
+    }
+
+    for tri in mesh.indices.chunks(3).filter(|tri| tri.len() == 3) {
+        match encoding {
+            Encoding::Ascii => writeln!(writer, "3 {} {} {}", tri[0], tri[1], tri[2])?,
+            Encoding::Binary => {
+                writer.write_all(&[3])?;
+                for ix in tri {
+                    writer.write_all(&ix.to_le_bytes())?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+// Read the header, returning the encoding of the body and the elements it contains.
+fn read_header<R>(reader: &mut R) -> Result<(Format, Vec<Element>), Error>
+where
+    R: BufRead,
+{
+    let mut format = None;
+    let mut elements: Vec<Element> = vec![];
+    let mut line = String::new();
+    for i in 0.. {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(Error::Parse("missing `end_header`".into()));
+        }
+        let words: Vec<&str> = line.split_whitespace().collect();
+        if i == 0 {
+            if words != ["ply"] {
+                return Err(Error::Parse("missing `ply` magic number".into()));
+            }
+            continue;
+        }
+        let invalid = || Error::Parse(format!("invalid header line `{}`", line.trim()));
+        match &words[..] {
+            ["format", f, _version] => {
+                format = Some(match *f {
+                    "ascii" => Format::Ascii,
+                    "binary_little_endian" => Format::BinaryLittleEndian,
+                    "binary_big_endian" => Format::BinaryBigEndian,
+                    _ => return Err(invalid()),
+                });
+            }
+            ["element", name, count] => elements.push(Element {
+                name: name.to_string(),
+                count: parse_number(count)?,
+                properties: vec![],
+            }),
+            ["property", "list", count, item, name] => {
+                let count = Scalar::from_name(count).ok_or_else(invalid)?;
+                let item = Scalar::from_name(item).ok_or_else(invalid)?;
+                let property = Property::List { count, item };
+                let element = elements.last_mut().ok_or_else(invalid)?;
+                element.properties.push((name.to_string(), property));
+            }
+            ["property", ty, name] => {
+                let ty = Scalar::from_name(ty).ok_or_else(invalid)?;
+                let element = elements.last_mut().ok_or_else(invalid)?;
+                element
+                    .properties
+                    .push((name.to_string(), Property::Scalar(ty)));
+            }
+            ["end_header"] => break,
+            ["comment", ..] | ["obj_info", ..] | [] => (),
+            _ => return Err(invalid()),
+        }
+    }
+    let format = format.ok_or_else(|| Error::Parse("missing `format`".into()))?;
+    Ok((format, elements))
+}
+
+// The slot into which the vertex property with the given name is read.
+fn vertex_slot(name: &str) -> Option<usize> {
+    let slot = match name {
+        "x" => X,
+        "y" => X + 1,
+        "z" => X + 2,
+        "nx" => NX,
+        "ny" => NX + 1,
+        "nz" => NX + 2,
+        "u" | "s" | "texture_u" | "texture_s" => U,
+        "v" | "t" | "texture_v" | "texture_t" => U + 1,
+        "red" => RED,
+        "green" => RED + 1,
+        "blue" => RED + 2,
+        "alpha" => ALPHA,
+        _ => return None,
+    };
+    Some(slot)
+}
+
+impl Scalar {
+    fn from_name(name: &str) -> Option<Self> {
+        let scalar = match name {
+            "char" | "int8" => Scalar::I8,
+            "uchar" | "uint8" => Scalar::U8,
+            "short" | "int16" => Scalar::I16,
+            "ushort" | "uint16" => Scalar::U16,
+            "int" | "int32" => Scalar::I32,
+            "uint" | "uint32" => Scalar::U32,
+            "float" | "float32" => Scalar::F32,
+            "double" | "float64" => Scalar::F64,
+            _ => return None,
+        };
+        Some(scalar)
+    }
+
+    fn size(self) -> usize {
+        match self {
+            Scalar::I8 | Scalar::U8 => 1,
+            Scalar::I16 | Scalar::U16 => 2,
+            Scalar::I32 | Scalar::U32 | Scalar::F32 => 4,
+            Scalar::F64 => 8,
+        }
+    }
+
+    // The value representing full intensity for color components of this type.
+    fn max_intensity(self) -> f64 {
+        match self {
+            Scalar::F32 | Scalar::F64 => 1.0,
+            Scalar::I16 | Scalar::U16 => 65535.0,
+            _ => 255.0,
+        }
+    }
+
+    // Decode a value from its little-endian bytes.
+    fn decode(self, b: [u8; 8]) -> f64 {
+        match self {
+            Scalar::I8 => b[0] as i8 as f64,
+            Scalar::U8 => b[0] as f64,
+            Scalar::I16 => i16::from_le_bytes([b[0], b[1]]) as f64,
+            Scalar::U16 => u16::from_le_bytes([b[0], b[1]]) as f64,
+            Scalar::I32 => i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+            Scalar::U32 => u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+            Scalar::F32 => f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+            Scalar::F64 => f64::from_le_bytes(b),
+        }
+    }
+}
+
+impl<R> Body<R>
+where
+    R: BufRead,
+{
+    // Read the next value of the given type.
+    fn read(&mut self, ty: Scalar) -> Result<f64, Error> {
+        let mut bytes = [0u8; 8];
+        let size = ty.size();
+        match self.format {
+            Format::Ascii => {
+                let word = self.next_word()?;
+                return parse_number(&word);
+            }
+            Format::BinaryLittleEndian => self.reader.read_exact(&mut bytes[..size])?,
+            Format::BinaryBigEndian => {
+                self.reader.read_exact(&mut bytes[..size])?;
+                bytes[..size].reverse();
+            }
+        }
+        Ok(ty.decode(bytes))
+    }
+
+    // Read and discard the value of the given property.
+    fn skip(&mut self, property: &Property) -> Result<(), Error> {
+        match *property {
+            Property::Scalar(ty) => {
+                self.read(ty)?;
+            }
+            Property::List { count, item } => {
+                let len = self.read(count)? as usize;
+                for _ in 0..len {
+                    self.read(item)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // The next whitespace-separated word of an ASCII body.
+    fn next_word(&mut self) -> Result<String, Error> {
+        loop {
+            if let Some(word) = self.words.next() {
+                return Ok(word);
+            }
+            let mut line = String::new();
+            if self.reader.read_line(&mut line)? == 0 {
+                return Err(Error::Parse("unexpected end of file".into()));
+            }
+            let words: Vec<String> = line.split_whitespace().map(str::to_string).collect();
+            self.words = words.into_iter();
+        }
+    }
+}
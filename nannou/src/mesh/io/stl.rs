@@ -0,0 +1,173 @@
+//! Reading and writing STL files.
+//!
+//! Both the ASCII and binary encodings are supported. STL files describe each triangle
+//! independently, so each is read as three vertices sharing the triangle's normal. Triangles whose
+//! normal is missing are given the normal implied by their winding order.
+
+use super::{parse_number, unit_normal, Encoding, Error, MeshData};
+use crate::geom::{vec3, Point3, Vector3};
+use std::io::{self, Read, Write};
+use std::str::SplitWhitespace;
+
+// The size of the header of a binary file, followed by the number of triangles.
+const HEADER_LEN: usize = 80;
+// The size of each triangle within a binary file.
+const TRIANGLE_LEN: usize = 50;
+
+/// Read a mesh from the given STL source, detecting whether it is encoded as text or binary.
+pub fn read<R>(mut reader: R) -> Result<MeshData, Error>
+where
+    R: Read,
+{
+    let mut bytes = vec![];
+    reader.read_to_end(&mut bytes)?;
+
+    // Binary files may also begin with `solid`, so check whether the size matches the number of
+    // triangles described by the header.
+    let is_binary = bytes.len() >= HEADER_LEN + 4
+        && HEADER_LEN + 4 + binary_count(&bytes) * TRIANGLE_LEN == bytes.len();
+    let is_ascii = String::from_utf8_lossy(&bytes[..bytes.len().min(HEADER_LEN)])
+        .trim_start()
+        .starts_with("solid");
+    if is_binary || !is_ascii {
+        read_binary(&bytes)
+    } else {
+        read_ascii(&String::from_utf8_lossy(&bytes))
+    }
+}
+
+/// Write the mesh to the given destination in the STL format with the given encoding.
+///
+/// Only the points and indices are written. The normal of each triangle is determined by its
+/// winding order, or is zero for triangles with no area.
+///
+/// Returns an error of kind `InvalidInput` if the mesh is invalid, e.g. if any of its indices are
+/// out of range of its points.
+pub fn write<W>(mesh: &MeshData, mut writer: W, encoding: Encoding) -> io::Result<()>
+where
+    W: Write,
+{
+    mesh.validate()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.to_string()))?;
+    let triangles = mesh
+        .indices
+        .chunks(3)
+        .filter(|tri| tri.len() == 3)
+        .map(|tri| {
+            let points = [
+                mesh.points[tri[0] as usize],
+                mesh.points[tri[1] as usize],
+                mesh.points[tri[2] as usize],
+            ];
+            let normal = unit_normal(points[0], points[1], points[2]);
+            (normal, points)
+        });
+    match encoding {
+        Encoding::Ascii => {
+            writeln!(writer, "solid nannou")?;
+            for (n, points) in triangles {
+                writeln!(writer, "  facet normal {} {} {}", n.x, n.y, n.z)?;
+                writeln!(writer, "    outer loop")?;
+                for p in &points {
+                    writeln!(writer, "      vertex {} {} {}", p.x, p.y, p.z)?;
+                }
+                writeln!(writer, "    endloop")?;
+                writeln!(writer, "  endfacet")?;
+            }
+            writeln!(writer, "endsolid nannou")?;
+        }
+        Encoding::Binary => {
+            writer.write_all(&[0; HEADER_LEN])?;
+            writer.write_all(&(mesh.triangle_count() as u32).to_le_bytes())?;
+            for (n, points) in triangles {
+                let vectors = [n, points[0], points[1], points[2]];
+                for v in &vectors {
+                    for &c in &[v.x, v.y, v.z] {
+                        writer.write_all(&c.to_le_bytes())?;
+                    }
+                }
+                writer.write_all(&[0, 0])?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn read_binary(bytes: &[u8]) -> Result<MeshData, Error> {
+    if bytes.len() < HEADER_LEN + 4 {
+        return Err(Error::Parse("missing binary header".into()));
+    }
+    let count = binary_count(bytes);
+    let body = &bytes[HEADER_LEN + 4..];
+    if body.len() < count * TRIANGLE_LEN {
+        let msg = format!("expected {} triangles", count);
+        return Err(Error::Parse(msg));
+    }
+    let mut mesh = MeshData::default();
+    for triangle in body.chunks(TRIANGLE_LEN).take(count) {
+        let mut vectors = triangle[..48].chunks(12).map(|b| {
+            let c = |i: usize| f32::from_le_bytes([b[i], b[i + 1], b[i + 2], b[i + 3]]);
+            vec3(c(0), c(4), c(8))
+        });
+        let normal = vectors.next().expect("no normal");
+        let points: Vec<Point3> = vectors.collect();
+        push_triangle(&mut mesh, normal, &points);
+    }
+    Ok(mesh)
+}
+
+fn read_ascii(text: &str) -> Result<MeshData, Error> {
+    let mut mesh = MeshData::default();
+    let mut words = text.split_whitespace();
+    let mut normal = vec3(0.0, 0.0, 0.0);
+    let mut points = vec![];
+    while let Some(word) = words.next() {
+        match word {
+            "normal" => normal = read_vector(&mut words)?,
+            "vertex" => points.push(read_vector(&mut words)?),
+            "endfacet" => {
+                if points.len() < 3 {
+                    return Err(Error::Parse("expected 3 vertices".into()));
+                }
+                push_triangle(&mut mesh, normal, &points);
+                normal = vec3(0.0, 0.0, 0.0);
+                points.clear();
+            }
+            _ => (),
+        }
+    }
+    Ok(mesh)
+}
+
+// The number of triangles stated by the header of a binary file.
+fn binary_count(bytes: &[u8]) -> usize {
+    u32::from_le_bytes([bytes[80], bytes[81], bytes[82], bytes[83]]) as usize
+}
+
+// Read the three coordinates following a `normal` or `vertex` keyword.
+fn read_vector(words: &mut SplitWhitespace) -> Result<Vector3, Error> {
+    let mut coord = || {
+        let word = words
+            .next()
+            .ok_or_else(|| Error::Parse("unexpected end of file".into()))?;
+        parse_number::<f32>(word)
+    };
+    Ok(vec3(coord()?, coord()?, coord()?))
+}
+
+// Push the facet onto the mesh, splitting it into triangles that each have their own vertices.
+fn push_triangle(mesh: &mut MeshData, normal: Vector3, points: &[Point3]) {
+    for j in 2..points.len() {
+        let tri = [points[0], points[j - 1], points[j]];
+        let normal = if normal == vec3(0.0, 0.0, 0.0) {
+            unit_normal(tri[0], tri[1], tri[2])
+        } else {
+            normal
+        };
+        for &p in &tri {
+            mesh.indices.push(mesh.points.len() as u32);
+            mesh.points.push(p);
+            mesh.normals.push(normal);
+        }
+    }
+}
@@ -9,6 +9,7 @@ use std::convert::{TryFrom, TryInto};
 use std::ops::{self, Deref, DerefMut};
 
 pub mod channel;
+pub mod io;
 pub mod vertex;
 
 pub use self::channel::{Channel, ChannelMut};
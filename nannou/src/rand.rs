@@ -4,11 +4,73 @@
 //! Helper functions include [**random_f32()**](./fn.random_f32.html),
 //! [**random_f64()**](./fn.random_f64.html) and [**random_range(min,
 //! max)**](./fn.random_range.html).
+//!
+//! The helper functions use the thread-local random number generator by default. Use
+//! [**with_seed**](./fn.with_seed.html) to seed them for the duration of a closure, e.g. to produce
+//! the same output for every run of a sketch.
 
 pub use rand;
 
 pub use self::rand::*;
 
+use std::cell::RefCell;
+
+thread_local! {
+    // The seeded generator used by the helper functions in place of `thread_rng`, if any.
+    static SEEDED_RNG: RefCell<Option<rngs::StdRng>> = const { RefCell::new(None) };
+}
+
+/// Call the given function with the helper functions of this module seeded with `seed`.
+///
+/// While `f` runs, [**random**](./fn.random.html), [**random_f32**](./fn.random_f32.html),
+/// [**random_f64**](./fn.random_f64.html), [**random_range**](./fn.random_range.html) and
+/// [**random_ascii**](./fn.random_ascii.html) on the current thread draw from a generator seeded
+/// with `seed` rather than the thread-local default generator. The previous generator is restored
+/// once `f` returns or panics.
+///
+/// Note that this does not affect `rand::thread_rng` itself.
+pub fn with_seed<F, R>(seed: u64, f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    // Restores the previously seeded generator (if any) on drop.
+    struct Restore(Option<rngs::StdRng>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let prev = self.0.take();
+            SEEDED_RNG.with(|rng| *rng.borrow_mut() = prev);
+        }
+    }
+
+    let rng = rngs::StdRng::seed_from_u64(seed);
+    let prev = SEEDED_RNG.with(|seeded| seeded.borrow_mut().replace(rng));
+    let _restore = Restore(prev);
+    f()
+}
+
+// Call `f` with the seeded generator if one is active, or the thread-local generator otherwise.
+fn with_rng<F, T>(f: F) -> T
+where
+    F: FnOnce(&mut dyn RngCore) -> T,
+{
+    SEEDED_RNG.with(|seeded| match *seeded.borrow_mut() {
+        Some(ref mut rng) => f(rng),
+        None => f(&mut thread_rng()),
+    })
+}
+
+/// Generates a random value using the thread-local random number generator, or the generator
+/// seeded via [**with_seed**](./fn.with_seed.html) if any.
+///
+/// This is a drop-in replacement for `rand::random`.
+pub fn random<T>() -> T
+where
+    distributions::Standard: distributions::Distribution<T>,
+{
+    with_rng(|rng| rng.gen())
+}
+
 /// A wrapper function around the `random` function that avoids the need for specifying a type in
 /// the case that it cannot be inferred. The primary purpose for this is to simplify the random API
 /// for new rust users.
@@ -31,14 +93,14 @@ pub fn random_f64() -> f64 {
 /// If the given `min` is greater than the given `max`, they will be swapped before calling
 /// `gen_range` internally to avoid triggering a `panic!`.
 ///
-/// This calls `rand::thread_rng().gen_range(min, max)` internally, in turn using the thread-local
-/// default random number generator.
+/// This calls `gen_range(min, max)` on the thread-local default random number generator, or the
+/// generator seeded via [**with_seed**](./fn.with_seed.html) if any.
 pub fn random_range<T>(min: T, max: T) -> T
 where
     T: PartialOrd + distributions::uniform::SampleUniform,
 {
     let (min, max) = if min <= max { (min, max) } else { (max, min) };
-    rand::thread_rng().gen_range(min, max)
+    with_rng(|rng| rng.gen_range(min, max))
 }
 
 /// Generates and returns a random ascii character.
@@ -53,6 +115,6 @@ pub fn random_ascii() -> char {
                             abcdefghijklmnopqrstuvwxyz\
                             0123456789)(*&^%$#@!~. ";
 
-    let idx = rand::thread_rng().gen_range(0, ASCIISET.len());
+    let idx = with_rng(|rng| rng.gen_range(0, ASCIISET.len()));
     ASCIISET[idx] as char
 }
@@ -0,0 +1,729 @@
+//! Golden-image snapshot testing for sketches.
+//!
+//! The items in this module allow for running a sketch for a fixed number of frames, capturing
+//! each frame to an image and comparing the result against a set of stored reference PNGs.
+//!
+//! The `Update` clock advances at a fixed rate and both the RNG provided via the
+//! [**Context**](./struct.Context.html) and the [**random_*](../rand/index.html) functions are
+//! seeded with a fixed value, so that the output for a given sketch is the same for every run.
+//!
+//! Snapshots built via [**sketch**](./fn.sketch.html) or [**app**](./fn.app.html) are rendered via
+//! the CPU [**draw::renderer::cpu::Renderer**](../draw/renderer/cpu/struct.Renderer.html) and
+//! require neither a window nor a GPU.
+//!
+//! ```no_run
+//! use nannou::prelude::*;
+//! use nannou::testing;
+//!
+//! fn view(ctxt: &mut testing::Context, draw: &Draw) {
+//!     draw.background().color(BLACK);
+//!     draw.ellipse().x(ctxt.time().sin() * 100.0).radius(30.0).color(WHITE);
+//! }
+//!
+//! #[test]
+//! fn moving_ellipse() {
+//!     testing::sketch("moving_ellipse", view).frames(4).assert();
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! Existing sketches may be tested without modification via [**headless**](./fn.headless.html),
+//! which runs the real `App` using its headless mode and captures the frames produced by its
+//! `view` function. This requires a wgpu adapter.
+//!
+//! ```no_run
+//! use nannou::prelude::*;
+//! use nannou::testing;
+//!
+//! fn view(app: &App, frame: Frame) {
+//!     let draw = app.draw();
+//!     draw.background().color(BLACK);
+//!     draw.ellipse().x(app.time.sin() * 100.0).radius(30.0).color(WHITE);
+//!     draw.to_frame(app, &frame).unwrap();
+//! }
+//!
+//! #[test]
+//! fn moving_ellipse_app() {
+//!     testing::headless("moving_ellipse_app", nannou::sketch(view))
+//!         .frames(4)
+//!         .assert();
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! References are stored as `<reference_dir>/<name>/frame-<n>.png`. When a frame does not match
+//! its reference, the rendered frame is written alongside it as `frame-<n>.actual.png` along with
+//! a `frame-<n>.diff.png` highlighting the pixels that differ. Set the `NANNOU_UPDATE_SNAPSHOTS`
+//! environment variable to write the rendered frames as the new references instead.
+
+use crate::draw::{self, Draw};
+use crate::event::{LoopEvent, Update};
+use crate::geom;
+use crate::rand::{self, rngs::StdRng, SeedableRng};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{self, AtomicUsize};
+use std::time::Duration;
+use std::{env, fmt, fs, io, process};
+
+/// The user function type for producing the model for the snapshot.
+pub type ModelFn<Model> = fn(&mut Context) -> Model;
+
+/// The user function type for updating the model once per frame.
+pub type UpdateFn<Model> = fn(&mut Context, &mut Model, Update);
+
+/// The user function type for drawing the model for the current frame.
+pub type ViewFn<Model> = fn(&mut Context, &Model, &Draw);
+
+/// A shorthand version of `ViewFn` for sketches that do not require a model.
+pub type SketchViewFn = fn(&mut Context, &Draw);
+
+// Runs a real app headlessly for the given size, scale factor, frame rate and number of frames,
+// capturing each frame to the given directory.
+type RunAppFn = Box<dyn Fn([u32; 2], f32, f64, u64, &Path)>;
+
+/// The environment variable that, when set, causes rendered frames to be written as the new
+/// reference images rather than compared against the existing ones.
+pub const UPDATE_SNAPSHOTS_ENV_VAR: &str = "NANNOU_UPDATE_SNAPSHOTS";
+
+/// The name of the directory, relative to the crate root, in which references are stored by
+/// default.
+pub const DEFAULT_REFERENCE_DIRECTORY: &str = "tests/snapshots";
+
+/// The deterministic state passed to each of the user's functions in place of the `App`.
+#[derive(Debug)]
+pub struct Context {
+    rect: geom::Rect,
+    scale_factor: f32,
+    elapsed_frames: u64,
+    update: Update,
+    rng: StdRng,
+}
+
+/// The snapshot's view function.
+enum View<Model> {
+    /// A view function allows for viewing the user's model.
+    WithModel(ViewFn<Model>),
+    /// A **Sketch** view function does not require a user **Model**.
+    Sketch(SketchViewFn),
+}
+
+/// Describes a snapshot test and how it should be run.
+pub struct Builder<Model = ()> {
+    name: String,
+    model: ModelFn<Model>,
+    update: Option<UpdateFn<Model>>,
+    view: Option<View<Model>>,
+    app: Option<RunAppFn>,
+    size: [u32; 2],
+    scale_factor: f32,
+    frames: u64,
+    frame_rate: f64,
+    seed: u64,
+    sample_count: u32,
+    tolerance: u8,
+    max_differing_pixels: usize,
+    reference_dir: Option<PathBuf>,
+}
+
+/// The result of comparing a rendered frame against its reference image.
+#[derive(Clone, Debug)]
+pub struct Comparison {
+    /// The number of pixels with at least one channel differing by more than the tolerance.
+    pub differing_pixels: usize,
+    /// The greatest difference found between any two channels.
+    pub max_difference: u8,
+    /// An image highlighting differing pixels in red over a faded version of the actual image.
+    pub diff: image::RgbaImage,
+}
+
+/// Describes a single frame that did not match its reference image.
+#[derive(Clone, Debug)]
+pub struct Mismatch {
+    /// The index of the frame that failed to match.
+    pub frame: u64,
+    /// The path to the reference image.
+    pub reference: PathBuf,
+    /// The path to which the rendered frame was written.
+    pub actual: PathBuf,
+    /// The path to which the diff image was written.
+    pub diff: PathBuf,
+    /// The number of pixels that differed by more than the tolerance.
+    pub differing_pixels: usize,
+    /// The greatest difference found between any two channels.
+    pub max_difference: u8,
+}
+
+/// Errors that might occur while running a snapshot test.
+#[derive(Debug)]
+pub enum Error {
+    /// An error occurred while reading or writing an image or directory.
+    Io(io::Error),
+    /// An error occurred while encoding or decoding an image.
+    Image(image::ImageError),
+    /// No reference image exists for the frame. The rendered frame was written to `actual`.
+    MissingReference { reference: PathBuf, actual: PathBuf },
+    /// The reference image's dimensions differ from those of the rendered frame.
+    SizeMismatch {
+        reference: PathBuf,
+        expected: [u32; 2],
+        actual: [u32; 2],
+    },
+    /// One or more frames differed from their references by more than the tolerance.
+    Mismatch(Vec<Mismatch>),
+}
+
+impl Context {
+    /// The default seed used for the RNG.
+    pub const DEFAULT_SEED: u64 = 0;
+
+    /// Create a new context for a frame of the given size in points.
+    pub fn new(rect: geom::Rect, scale_factor: f32, seed: u64) -> Self {
+        Context {
+            rect,
+            scale_factor,
+            elapsed_frames: 0,
+            update: Update {
+                since_last: Duration::default(),
+                since_start: Duration::default(),
+            },
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// The bounding rectangle of the frame in points, analogous to `App::window_rect`.
+    pub fn window_rect(&self) -> geom::Rect {
+        self.rect
+    }
+
+    /// The number of pixels per point.
+    pub fn scale_factor(&self) -> f32 {
+        self.scale_factor
+    }
+
+    /// The number of frames that have been rendered so far, analogous to `App::elapsed_frames`.
+    pub fn elapsed_frames(&self) -> u64 {
+        self.elapsed_frames
+    }
+
+    /// The most recent update, produced by the fixed-rate clock.
+    pub fn update(&self) -> Update {
+        self.update
+    }
+
+    /// The number of seconds since the start of the snapshot, analogous to `App::time`.
+    pub fn time(&self) -> f32 {
+        self.update.since_start.as_secs_f32()
+    }
+
+    /// The seeded random number generator.
+    ///
+    /// The `random_*` functions are also seeded for the duration of the snapshot, though this RNG
+    /// is independent of them and so unaffected by calls from elsewhere.
+    pub fn rng(&mut self) -> &mut StdRng {
+        &mut self.rng
+    }
+}
+
+impl<M> Builder<M> {
+    /// The default size of the frame in points.
+    pub const DEFAULT_SIZE: [u32; 2] = [256, 256];
+
+    /// The default number of frames that are rendered.
+    pub const DEFAULT_FRAMES: u64 = 1;
+
+    /// The default rate at which the update clock advances, in frames per second.
+    pub const DEFAULT_FRAME_RATE: f64 = 60.0;
+
+    /// The default maximum difference allowed between two channels of a pixel.
+    pub const DEFAULT_TOLERANCE: u8 = 2;
+
+    /// The default number of pixels allowed to exceed the tolerance.
+    pub const DEFAULT_MAX_DIFFERING_PIXELS: usize = 0;
+
+    /// Begin building a snapshot test with the given name and model function.
+    ///
+    /// The name is used as the name of the directory in which the reference images are stored.
+    pub fn new(name: impl Into<String>, model: ModelFn<M>) -> Self {
+        Builder {
+            name: name.into(),
+            model,
+            update: None,
+            view: None,
+            app: None,
+            size: Self::DEFAULT_SIZE,
+            scale_factor: 1.0,
+            frames: Self::DEFAULT_FRAMES,
+            frame_rate: Self::DEFAULT_FRAME_RATE,
+            seed: Context::DEFAULT_SEED,
+            sample_count: draw::renderer::cpu::Renderer::DEFAULT_SAMPLE_COUNT,
+            tolerance: Self::DEFAULT_TOLERANCE,
+            max_differing_pixels: Self::DEFAULT_MAX_DIFFERING_PIXELS,
+            reference_dir: None,
+        }
+    }
+
+    /// The function called once per frame, before `view`, to update the model.
+    pub fn update(mut self, update: UpdateFn<M>) -> Self {
+        self.update = Some(update);
+        self
+    }
+
+    /// The function called once per frame to draw the model.
+    pub fn view(mut self, view: ViewFn<M>) -> Self {
+        self.view = Some(View::WithModel(view));
+        self
+    }
+
+    /// The size of the frame in points.
+    pub fn size(mut self, width: u32, height: u32) -> Self {
+        self.size = [width, height];
+        self
+    }
+
+    /// The number of pixels per point. Defaults to `1.0`.
+    pub fn scale_factor(mut self, scale_factor: f32) -> Self {
+        self.scale_factor = scale_factor;
+        self
+    }
+
+    /// The number of frames to render and compare.
+    pub fn frames(mut self, frames: u64) -> Self {
+        self.frames = frames;
+        self
+    }
+
+    /// The rate at which the update clock advances in frames per second.
+    ///
+    /// Each frame advances `Update::since_start` by exactly `1.0 / fps` seconds, regardless of
+    /// how long rendering takes.
+    pub fn frame_rate(mut self, fps: f64) -> Self {
+        assert!(fps > 0.0, "frame rate must be greater than zero");
+        self.frame_rate = fps;
+        self
+    }
+
+    /// The seed for the RNG provided via `Context::rng` and for the `random_*` functions.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// The number of samples per pixel used by the rasterizer.
+    ///
+    /// This has no effect on snapshots built via `headless`, which are rendered by the GPU.
+    pub fn sample_count(mut self, sample_count: u32) -> Self {
+        self.sample_count = sample_count;
+        self
+    }
+
+    /// The maximum difference allowed between each channel of a rendered pixel and its reference.
+    pub fn tolerance(mut self, tolerance: u8) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// The number of pixels per frame that may exceed the tolerance before the frame is considered
+    /// a mismatch.
+    pub fn max_differing_pixels(mut self, max: usize) -> Self {
+        self.max_differing_pixels = max;
+        self
+    }
+
+    /// The directory in which the reference images are stored.
+    ///
+    /// By default this is `tests/snapshots` within the directory specified by the
+    /// `CARGO_MANIFEST_DIR` environment variable, or the current directory if it is not set.
+    pub fn reference_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.reference_dir = Some(dir.into());
+        self
+    }
+
+    /// The directory in which this snapshot's reference images are stored.
+    pub fn snapshot_dir(&self) -> PathBuf {
+        let dir = match self.reference_dir {
+            Some(ref dir) => dir.clone(),
+            None => env::var_os("CARGO_MANIFEST_DIR")
+                .map(PathBuf::from)
+                .unwrap_or_default()
+                .join(DEFAULT_REFERENCE_DIRECTORY),
+        };
+        dir.join(&self.name)
+    }
+
+    /// Render every frame of the snapshot to an image without comparing against references.
+    pub fn render(&self) -> Vec<image::RgbaImage> {
+        let mut frames = Vec::with_capacity(self.frames as usize);
+        self.render_frames(|_, image| frames.push(image));
+        frames
+    }
+
+    /// Render every frame and compare it against its reference image.
+    ///
+    /// If the `NANNOU_UPDATE_SNAPSHOTS` environment variable is set, the rendered frames are
+    /// written as the new references and no comparison takes place.
+    pub fn run(&self) -> Result<(), Error> {
+        let dir = self.snapshot_dir();
+        fs::create_dir_all(&dir)?;
+        let update_refs = env::var_os(UPDATE_SNAPSHOTS_ENV_VAR).is_some();
+        let mut mismatches = vec![];
+        let mut result = Ok(());
+        self.render_frames(|frame, actual| {
+            if result.is_err() {
+                return;
+            }
+            result = self.check_frame(&dir, frame, &actual, update_refs, &mut mismatches);
+        });
+        result?;
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::Mismatch(mismatches))
+        }
+    }
+
+    /// Run the snapshot, panicking with a description of the failure if any frame does not match
+    /// its reference.
+    pub fn assert(&self) {
+        if let Err(err) = self.run() {
+            panic!("snapshot `{}` failed: {}", self.name, err);
+        }
+    }
+
+    // Render each frame with the `random_*` functions seeded.
+    fn render_frames<F>(&self, frame_rendered: F)
+    where
+        F: FnMut(u64, image::RgbaImage),
+    {
+        rand::with_seed(self.seed, || match self.app {
+            Some(ref run_app) => self.render_app_frames(run_app, frame_rendered),
+            None => self.render_cpu_frames(frame_rendered),
+        })
+    }
+
+    // Run the user's app headlessly, capturing each frame to a temporary directory.
+    fn render_app_frames<F>(&self, run_app: &RunAppFn, mut frame_rendered: F)
+    where
+        F: FnMut(u64, image::RgbaImage),
+    {
+        static RUNS: AtomicUsize = AtomicUsize::new(0);
+        let run = RUNS.fetch_add(1, atomic::Ordering::SeqCst);
+        let dir = env::temp_dir().join(format!(
+            "nannou-snapshot-{}-{}-{}",
+            self.name,
+            process::id(),
+            run
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        run_app(
+            self.size,
+            self.scale_factor,
+            self.frame_rate,
+            self.frames,
+            &dir,
+        );
+        for frame in 0..self.frames {
+            let path = dir.join(format!("{:05}.png", frame));
+            let image = image::open(&path)
+                .unwrap_or_else(|err| panic!("failed to read `{}`: {}", path.display(), err))
+                .to_rgba8();
+            frame_rendered(frame, image);
+        }
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    // Drive the user's functions with the fixed-rate clock, rendering each frame.
+    fn render_cpu_frames<F>(&self, mut frame_rendered: F)
+    where
+        F: FnMut(u64, image::RgbaImage),
+    {
+        let [w, h] = self.size;
+        let rect = geom::Rect::from_w_h(w as f32, h as f32);
+        let px_w = (w as f32 * self.scale_factor).round() as u32;
+        let px_h = (h as f32 * self.scale_factor).round() as u32;
+        let interval = Duration::from_secs_f64(1.0 / self.frame_rate);
+        let mut ctxt = Context::new(rect, self.scale_factor, self.seed);
+        let mut model = (self.model)(&mut ctxt);
+        let mut renderer = draw::renderer::cpu::Builder::new()
+            .sample_count(self.sample_count)
+            .build();
+        let draw = Draw::new();
+        for frame in 0..self.frames {
+            let since_last = if frame == 0 {
+                Duration::default()
+            } else {
+                interval
+            };
+            let since_start = Duration::from_secs_f64(frame as f64 / self.frame_rate);
+            ctxt.update = Update {
+                since_last,
+                since_start,
+            };
+            if let Some(update) = self.update {
+                let u = ctxt.update();
+                update(&mut ctxt, &mut model, u);
+            }
+            draw.reset();
+            match self.view {
+                Some(View::WithModel(view)) => view(&mut ctxt, &model, &draw),
+                Some(View::Sketch(view)) => view(&mut ctxt, &draw),
+                None => (),
+            }
+            let image = renderer.render(&draw, self.scale_factor, [px_w, px_h]);
+            frame_rendered(frame, image);
+            ctxt.elapsed_frames += 1;
+        }
+    }
+
+    // Compare a single frame against its reference, or write it as the new reference.
+    fn check_frame(
+        &self,
+        dir: &Path,
+        frame: u64,
+        actual: &image::RgbaImage,
+        update_refs: bool,
+        mismatches: &mut Vec<Mismatch>,
+    ) -> Result<(), Error> {
+        let reference_path = dir.join(format!("frame-{:04}.png", frame));
+        let actual_path = dir.join(format!("frame-{:04}.actual.png", frame));
+        let diff_path = dir.join(format!("frame-{:04}.diff.png", frame));
+
+        if update_refs {
+            actual.save(&reference_path)?;
+            remove_if_exists(&actual_path)?;
+            remove_if_exists(&diff_path)?;
+            return Ok(());
+        }
+
+        if !reference_path.exists() {
+            actual.save(&actual_path)?;
+            return Err(Error::MissingReference {
+                reference: reference_path,
+                actual: actual_path,
+            });
+        }
+
+        let reference = image::open(&reference_path)?.to_rgba8();
+        if reference.dimensions() != actual.dimensions() {
+            actual.save(&actual_path)?;
+            let (rw, rh) = reference.dimensions();
+            let (aw, ah) = actual.dimensions();
+            return Err(Error::SizeMismatch {
+                reference: reference_path,
+                expected: [rw, rh],
+                actual: [aw, ah],
+            });
+        }
+
+        let comparison = compare(&reference, actual, self.tolerance);
+        if comparison.differing_pixels > self.max_differing_pixels {
+            actual.save(&actual_path)?;
+            comparison.diff.save(&diff_path)?;
+            mismatches.push(Mismatch {
+                frame,
+                reference: reference_path,
+                actual: actual_path,
+                diff: diff_path,
+                differing_pixels: comparison.differing_pixels,
+                max_difference: comparison.max_difference,
+            });
+        } else {
+            remove_if_exists(&actual_path)?;
+            remove_if_exists(&diff_path)?;
+        }
+        Ok(())
+    }
+}
+
+impl Builder<()> {
+    /// Begin building a snapshot test for a sketch that requires no model.
+    pub fn sketch(name: impl Into<String>, view: SketchViewFn) -> Self {
+        let mut builder = Builder::new(name, |_| ());
+        builder.view = Some(View::Sketch(view));
+        builder
+    }
+
+    /// Begin building a snapshot test that runs the given app headlessly.
+    ///
+    /// See [**headless**](./fn.headless.html) for details.
+    pub fn headless<M, E>(
+        name: impl Into<String>,
+        app: impl Into<crate::app::Builder<M, E>>,
+    ) -> Self
+    where
+        M: 'static,
+        E: LoopEvent,
+    {
+        let app = app.into();
+        let mut builder = Builder::new(name, |_| ());
+        let run_app = move |[w, h]: [u32; 2], scale_factor: f32, fps, frames, dir: &Path| {
+            app.clone()
+                .headless(w, h)
+                .scale_factor(scale_factor as f64)
+                .fps(fps)
+                .frames(frames)
+                .capture_frames(dir)
+                .run();
+        };
+        builder.app = Some(Box::new(run_app));
+        builder
+    }
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "frame {} differs from `{}` in {} pixels (max channel difference {}), see `{}`",
+            self.frame,
+            self.reference.display(),
+            self.differing_pixels,
+            self.max_difference,
+            self.diff.display(),
+        )
+    }
+}
+
+/// Begin building a snapshot test for a sketch with the given name and view function.
+pub fn sketch(name: impl Into<String>, view: SketchViewFn) -> Builder<()> {
+    Builder::sketch(name, view)
+}
+
+/// Begin building a snapshot test for an app with the given name and model function.
+///
+/// Provide the view function via `Builder::view`.
+pub fn app<M>(name: impl Into<String>, model: ModelFn<M>) -> Builder<M> {
+    Builder::new(name, model)
+}
+
+/// Begin building a snapshot test that runs the given app or sketch without a window.
+///
+/// Accepts the builder returned by `nannou::app` or `nannou::sketch`, so that the sketch's own
+/// `model`, `update` and `view` functions are run unmodified via the `App`'s headless mode. Each
+/// frame is captured from the offscreen texture that the `view` function draws to.
+///
+/// **Panics** when run if no wgpu adapter is available.
+pub fn headless<M, E>(
+    name: impl Into<String>,
+    app: impl Into<crate::app::Builder<M, E>>,
+) -> Builder<()>
+where
+    M: 'static,
+    E: LoopEvent,
+{
+    Builder::headless(name, app)
+}
+
+/// Compare two images of the same size pixel by pixel.
+///
+/// A pixel is considered to differ if the absolute difference of any of its channels exceeds
+/// `tolerance`.
+///
+/// **Panics** if the images have different dimensions.
+pub fn compare(
+    reference: &image::RgbaImage,
+    actual: &image::RgbaImage,
+    tolerance: u8,
+) -> Comparison {
+    assert_eq!(
+        reference.dimensions(),
+        actual.dimensions(),
+        "images must have the same dimensions"
+    );
+    let (w, h) = actual.dimensions();
+    let mut diff = image::RgbaImage::new(w, h);
+    let mut differing_pixels = 0;
+    let mut max_difference = 0;
+    let pixels = reference.pixels().zip(actual.pixels());
+    for ((r, a), d) in pixels.zip(diff.pixels_mut()) {
+        let mut pixel_max = 0;
+        for (&rc, &ac) in r.0.iter().zip(a.0.iter()) {
+            let delta = (rc as i16 - ac as i16).unsigned_abs() as u8;
+            pixel_max = std::cmp::max(pixel_max, delta);
+        }
+        max_difference = std::cmp::max(max_difference, pixel_max);
+        *d = if pixel_max > tolerance {
+            differing_pixels += 1;
+            image::Rgba([255, 0, 0, 255])
+        } else {
+            let [ar, ag, ab, _] = a.0;
+            let luma = (ar as u32 * 299 + ag as u32 * 587 + ab as u32 * 114) / 1000;
+            let faded = (luma / 4) as u8;
+            image::Rgba([faded, faded, faded, 255])
+        };
+    }
+    Comparison {
+        differing_pixels,
+        max_difference,
+        diff,
+    }
+}
+
+fn remove_if_exists(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<image::ImageError> for Error {
+    fn from(e: image::ImageError) -> Self {
+        Error::Image(e)
+    }
+}
+
+impl std::error::Error for Error {
+    fn cause(&self) -> Option<&dyn std::error::Error> {
+        match *self {
+            Error::Io(ref e) => Some(e),
+            Error::Image(ref e) => Some(e),
+            Error::MissingReference { .. } | Error::SizeMismatch { .. } | Error::Mismatch(_) => {
+                None
+            }
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Io(ref e) => fmt::Display::fmt(e, f),
+            Error::Image(ref e) => fmt::Display::fmt(e, f),
+            Error::MissingReference {
+                ref reference,
+                ref actual,
+            } => write!(
+                f,
+                "no reference image at `{}` (rendered frame written to `{}`, set `{}` to accept it)",
+                reference.display(),
+                actual.display(),
+                UPDATE_SNAPSHOTS_ENV_VAR,
+            ),
+            Error::SizeMismatch {
+                ref reference,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "reference image `{}` is {}x{} but the rendered frame is {}x{}",
+                reference.display(),
+                expected[0],
+                expected[1],
+                actual[0],
+                actual[1],
+            ),
+            Error::Mismatch(ref mismatches) => {
+                write!(f, "{} frame(s) did not match:", mismatches.len())?;
+                for mismatch in mismatches {
+                    write!(f, "\n  {}", mismatch)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
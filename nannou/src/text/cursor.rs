@@ -1,7 +1,10 @@
 //! Logic related to the positioning of the cursor within text.
+//!
+//! Cursors are positioned between the grapheme clusters of each line.
 
 use crate::geom::{Range, Rect};
 use crate::text::{self, FontSize, Point, Scalar};
+use unicode_segmentation::UnicodeSegmentation;
 
 /// An index representing the position of a cursor within some text.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
@@ -10,9 +13,9 @@ pub struct Index {
     pub line: usize,
     /// The index within all possible cursor positions for the line.
     ///
-    /// For example, for the line `foo`, a `char` of `1` would indicate the cursor's position
+    /// For example, for the line `foo`, a `cluster` of `1` would indicate the cursor's position
     /// as `f|oo` where `|` is the cursor.
-    pub char: usize,
+    pub cluster: usize,
 }
 
 /// Every possible cursor position within each line of text yielded by the given iterator.
@@ -22,7 +25,7 @@ pub struct Index {
 #[derive(Clone)]
 pub struct XysPerLine<'a, I> {
     lines_with_rects: I,
-    font: &'a text::Font,
+    faces: text::shape::Faces<'a>,
     text: &'a str,
     font_size: FontSize,
 }
@@ -49,10 +52,12 @@ pub struct XysPerLineFromText<'a> {
 
 /// Each possible cursor position along the *x* axis within a line of text.
 ///
+/// Positions are yielded in the order of the clusters within the text. Within bidirectional text
+/// this order may differ from the order along the *x* axis.
+///
 /// `Xs` iterators are produced by the `XysPerLine` iterator.
-pub struct Xs<'a, 'b> {
-    next_x: Option<Scalar>,
-    layout: text::LayoutIter<'a, 'b>,
+pub struct Xs {
+    xs: std::vec::IntoIter<Scalar>,
 }
 
 impl Index {
@@ -69,29 +74,30 @@ impl Index {
     where
         I: Iterator<Item = text::line::Info>,
     {
-        let Index { line, char } = self;
-        if char > 0 {
+        let Index { line, cluster } = self;
+        if cluster > 0 {
             line_infos.nth(line).and_then(|line_info| {
-                let line_count = line_info.char_range().count();
-                let mut chars_rev = (&text[line_info.byte_range()]).chars().rev();
-                if char != line_count {
-                    chars_rev.nth(line_count - char - 1);
+                let line_count = line_info.cluster_range().count();
+                let mut clusters_rev = (&text[line_info.byte_range()]).graphemes(true).rev();
+                if cluster != line_count {
+                    clusters_rev.nth(line_count - cluster - 1);
                 }
-                let mut new_char = 0;
+                let mut new_cluster = 0;
                 let mut hit_non_whitespace = false;
-                for (i, char_) in chars_rev.enumerate() {
+                for (i, cluster_) in clusters_rev.enumerate() {
                     // loop until word starts, then continue until the word ends
-                    if !char_.is_whitespace() {
+                    let is_whitespace = cluster_.chars().all(char::is_whitespace);
+                    if !is_whitespace {
                         hit_non_whitespace = true;
                     }
-                    if char_.is_whitespace() && hit_non_whitespace {
-                        new_char = char - i;
+                    if is_whitespace && hit_non_whitespace {
+                        new_cluster = cluster - i;
                         break;
                     }
                 }
                 Some(Index {
                     line: line,
-                    char: new_char,
+                    cluster: new_cluster,
                 })
             })
         } else {
@@ -114,34 +120,35 @@ impl Index {
     where
         I: Iterator<Item = text::line::Info>,
     {
-        let Index { line, char } = self;
+        let Index { line, cluster } = self;
         line_infos.nth(line).and_then(|line_info| {
-            let line_count = line_info.char_range().count();
-            if char < line_count {
-                let mut chars = (&text[line_info.byte_range()]).chars();
-                let mut new_char = line_count;
+            let line_count = line_info.cluster_range().count();
+            if cluster < line_count {
+                let mut clusters = (&text[line_info.byte_range()]).graphemes(true);
+                let mut new_cluster = line_count;
                 let mut hit_non_whitespace = false;
-                if char != 0 {
-                    chars.nth(char - 1);
+                if cluster != 0 {
+                    clusters.nth(cluster - 1);
                 }
-                for (i, char_) in chars.enumerate() {
+                for (i, cluster_) in clusters.enumerate() {
                     // loop until word starts, then continue until the word ends
-                    if !char_.is_whitespace() {
+                    let is_whitespace = cluster_.chars().all(char::is_whitespace);
+                    if !is_whitespace {
                         hit_non_whitespace = true;
                     }
-                    if char_.is_whitespace() && hit_non_whitespace {
-                        new_char = char + i;
+                    if is_whitespace && hit_non_whitespace {
+                        new_cluster = cluster + i;
                         break;
                     }
                 }
                 Some(Index {
                     line: line,
-                    char: new_char,
+                    cluster: new_cluster,
                 })
             } else {
                 line_infos.next().map(|_| Index {
                     line: line + 1,
-                    char: 0,
+                    cluster: 0,
                 })
             }
         })
@@ -160,14 +167,14 @@ impl Index {
     where
         I: Iterator<Item = text::line::Info>,
     {
-        let Index { line, char } = self;
-        if char > 0 {
-            let new_char = char - 1;
+        let Index { line, cluster } = self;
+        if cluster > 0 {
+            let new_cluster = cluster - 1;
             line_infos.nth(line).and_then(|info| {
-                if new_char <= info.char_range().count() {
+                if new_cluster <= info.cluster_range().count() {
                     Some(Index {
                         line: line,
-                        char: new_char,
+                        cluster: new_cluster,
                     })
                 } else {
                     None
@@ -176,10 +183,10 @@ impl Index {
         } else if line > 0 {
             let new_line = line - 1;
             line_infos.nth(new_line).map(|info| {
-                let new_char = info.end_char() - info.start_char;
+                let new_cluster = info.end_cluster() - info.start_cluster;
                 Index {
                     line: new_line,
-                    char: new_char,
+                    cluster: new_cluster,
                 }
             })
         } else {
@@ -200,17 +207,17 @@ impl Index {
     where
         I: Iterator<Item = text::line::Info>,
     {
-        let Index { line, char } = self;
+        let Index { line, cluster } = self;
         line_infos.nth(line).and_then(|info| {
-            if char >= info.char_range().count() {
+            if cluster >= info.cluster_range().count() {
                 line_infos.next().map(|_| Index {
                     line: line + 1,
-                    char: 0,
+                    cluster: 0,
                 })
             } else {
                 Some(Index {
                     line: line,
-                    char: char + 1,
+                    cluster: cluster + 1,
                 })
             }
         })
@@ -221,7 +228,7 @@ impl Index {
     /// If `self` would lie after the end of the last line, return the index at the end of the
     /// last line.
     ///
-    /// If `line_infos` is empty, returns cursor at line=0 char=0.
+    /// If `line_infos` is empty, returns cursor at line=0 cluster=0.
     pub fn clamp_to_lines<I>(self, line_infos: I) -> Self
     where
         I: Iterator<Item = text::line::Info>,
@@ -229,11 +236,11 @@ impl Index {
         let mut last = None;
         for (i, info) in line_infos.enumerate() {
             if i == self.line {
-                let num_chars = info.char_range().len();
-                let char = std::cmp::min(self.char, num_chars);
+                let num_clusters = info.cluster_range().len();
+                let cluster = std::cmp::min(self.cluster, num_clusters);
                 return Index {
                     line: i,
-                    char: char,
+                    cluster: cluster,
                 };
             }
             last = Some((i, info));
@@ -241,9 +248,12 @@ impl Index {
         match last {
             Some((i, info)) => Index {
                 line: i,
-                char: info.char_range().len(),
+                cluster: info.cluster_range().len(),
+            },
+            None => Index {
+                line: 0,
+                cluster: 0,
             },
-            None => Index { line: 0, char: 0 },
         }
     }
 }
@@ -260,7 +270,7 @@ pub fn xys_per_line<'a, I>(
 ) -> XysPerLine<'a, I> {
     XysPerLine {
         lines_with_rects: lines_with_rects,
-        font: font,
+        faces: text::shape::Faces::new(std::slice::from_ref(font)),
         text: text,
         font_size: font_size,
     }
@@ -299,18 +309,18 @@ pub fn xys_per_line_from_text<'a>(
     }
 }
 
-/// Convert the given character index into a cursor `Index`.
-pub fn index_before_char<I>(line_infos: I, char_index: usize) -> Option<Index>
+/// Convert the given grapheme cluster index into a cursor `Index`.
+pub fn index_before_cluster<I>(line_infos: I, cluster_index: usize) -> Option<Index>
 where
     I: Iterator<Item = text::line::Info>,
 {
     for (i, line_info) in line_infos.enumerate() {
-        let start_char = line_info.start_char;
-        let end_char = line_info.end_char();
-        if start_char <= char_index && char_index <= end_char {
+        let start_cluster = line_info.start_cluster;
+        let end_cluster = line_info.end_cluster();
+        if start_cluster <= cluster_index && cluster_index <= end_cluster {
             return Some(Index {
                 line: i,
-                char: char_index - start_char,
+                cluster: cluster_index - start_cluster,
             });
         }
     }
@@ -318,14 +328,14 @@ where
 }
 
 /// Determine the *xy* location of the cursor at the given cursor `Index`.
-pub fn xy_at<'a, I>(xys_per_line: I, idx: Index) -> Option<(Scalar, Range)>
+pub fn xy_at<I>(xys_per_line: I, idx: Index) -> Option<(Scalar, Range)>
 where
-    I: Iterator<Item = (Xs<'a, 'a>, Range)>,
+    I: Iterator<Item = (Xs, Range)>,
 {
     for (i, (xs, y)) in xys_per_line.enumerate() {
         if i == idx.line {
             for (j, x) in xs.enumerate() {
-                if j == idx.char {
+                if j == idx.cluster {
                     return Some((x, y));
                 }
             }
@@ -337,9 +347,9 @@ where
 /// Find the closest line for the given `y` position, and return the line index, Xs iterator, and y-range of that line
 ///
 /// Returns `None` if there are no lines
-pub fn closest_line<'a, I>(y_pos: Scalar, xys_per_line: I) -> Option<(usize, Xs<'a, 'a>, Range)>
+pub fn closest_line<I>(y_pos: Scalar, xys_per_line: I) -> Option<(usize, Xs, Range)>
 where
-    I: Iterator<Item = (Xs<'a, 'a>, Range)>,
+    I: Iterator<Item = (Xs, Range)>,
 {
     let mut xys_per_line_enumerated = xys_per_line.enumerate();
     xys_per_line_enumerated
@@ -369,17 +379,17 @@ where
 /// cursor.
 ///
 /// Returns `None` if the given `text` is empty.
-pub fn closest_cursor_index_and_xy<'a, I>(xy: Point, xys_per_line: I) -> Option<(Index, Point)>
+pub fn closest_cursor_index_and_xy<I>(xy: Point, xys_per_line: I) -> Option<(Index, Point)>
 where
-    I: Iterator<Item = (Xs<'a, 'a>, Range)>,
+    I: Iterator<Item = (Xs, Range)>,
 {
     closest_line(xy[1], xys_per_line).and_then(
         |(closest_line_idx, closest_line_xs, closest_line_y)| {
-            let (closest_char_idx, closest_x) =
+            let (closest_cluster_idx, closest_x) =
                 closest_cursor_index_on_line(xy[0], closest_line_xs);
             let index = Index {
                 line: closest_line_idx,
-                char: closest_char_idx,
+                cluster: closest_cluster_idx,
             };
             let point = [closest_x, closest_line_y.middle()].into();
             Some((index, point))
@@ -389,20 +399,19 @@ where
 
 /// Find the closest cursor index to the given `x` position on the given line along with the
 /// `x` position of that cursor.
-pub fn closest_cursor_index_on_line<'a>(x_pos: Scalar, line_xs: Xs<'a, 'a>) -> (usize, Scalar) {
+pub fn closest_cursor_index_on_line(x_pos: Scalar, line_xs: Xs) -> (usize, Scalar) {
     let mut xs_enumerated = line_xs.enumerate();
     // `xs` always yields at least one `x` (the start of the line).
     let (first_idx, first_x) = xs_enumerated.next().unwrap();
     let first_diff = (x_pos - first_x).abs();
     let mut closest = (first_idx, first_x);
     let mut closest_diff = first_diff;
+    // Positions within bidirectional text are not ordered along the *x* axis, so check them all.
     for (i, x) in xs_enumerated {
         let diff = (x_pos - x).abs();
         if diff < closest_diff {
             closest = (i, x);
             closest_diff = diff;
-        } else {
-            break;
         }
     }
     closest
@@ -414,48 +423,39 @@ where
 {
     // The `Range` occupied by the line across the *y* axis, along with an iterator yielding
     // each possible cursor position along the *x* axis.
-    type Item = (Xs<'a, 'a>, Range);
+    type Item = (Xs, Range);
     fn next(&mut self) -> Option<Self::Item> {
         let XysPerLine {
             ref mut lines_with_rects,
-            font,
+            ref faces,
             text,
             font_size,
         } = *self;
         let scale = text::pt_to_scale(font_size);
         lines_with_rects.next().map(|(line_info, line_rect)| {
             let line = &text[line_info.byte_range()];
-            let (x, y) = (line_rect.left() as f32, line_rect.top() as f32);
-            let point = text::rt::Point { x: x, y: y };
+            let shaped = text::shape::line_with_faces(line, faces, scale);
             let y = line_rect.y;
-            let layout = font.layout(line, scale, point);
-            let xs = Xs {
-                next_x: Some(line_rect.x.start),
-                layout: layout,
-            };
+            let xs: Vec<_> = (0..=shaped.clusters().len())
+                .map(|i| line_rect.x.start + shaped.cursor_x(i))
+                .collect();
+            let xs = Xs { xs: xs.into_iter() };
             (xs, y)
         })
     }
 }
 
 impl<'a> Iterator for XysPerLineFromText<'a> {
-    type Item = (Xs<'a, 'a>, Range);
+    type Item = (Xs, Range);
     fn next(&mut self) -> Option<Self::Item> {
         self.xys_per_line.next()
     }
 }
 
-impl<'a, 'b> Iterator for Xs<'a, 'b> {
+impl Iterator for Xs {
     // Each possible cursor position along the *x* axis.
     type Item = Scalar;
     fn next(&mut self) -> Option<Self::Item> {
-        self.next_x.map(|x| {
-            self.next_x = self.layout.next().map(|g| {
-                g.pixel_bounding_box()
-                    .map(|r| r.max.x as Scalar)
-                    .unwrap_or_else(|| x + g.unpositioned().h_metrics().advance_width as Scalar)
-            });
-            x
-        })
+        self.xs.next()
     }
 }
@@ -1,21 +1,39 @@
-//! The `font::Id` and `font::Map` types.
+//! The `Font`, `font::Id` and `font::Map` types, along with functions for loading fonts.
+//!
+//! Fonts installed on the system may be found by family, weight and style via the `system`
+//! module.
 
-use crate::text::{Font, FontCollection};
+use crate::serde_derive::{Deserialize, Serialize};
+use crate::text::FontCollection;
+use rusttype::SharedBytes;
 use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
+use std::ops::Deref;
 use std::path::{Path, PathBuf};
 
+pub mod system;
+
+/// A font along with the data from which it was loaded.
+///
+/// The data is retained so that text using the font may be shaped. See the `text::shape` module
+/// for details. Dereferences to the inner `rusttype::Font` for access to glyphs and metrics.
+#[derive(Clone, Debug)]
+pub struct Font {
+    font: rusttype::Font<'static>,
+    data: SharedBytes<'static>,
+}
+
 /// A type-safe wrapper around the `FontId`.
 ///
 /// This is used as both:
 ///
 /// - The key for the `font::Map`'s inner `HashMap`.
 /// - The `font_id` field for the rusttype::gpu_cache::Cache.
-#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Id(usize);
 
-/// A collection of mappings from `font::Id`s to `rusttype::Font`s.
-#[derive(Debug)]
+/// A collection of mappings from `font::Id`s to `Font`s.
+#[derive(Clone, Debug, Default)]
 pub struct Map {
     next_index: usize,
     map: HashMap<Id, Font>,
@@ -45,6 +63,13 @@ pub enum Error {
 /// The name of the default directory that is searched for fonts.
 pub const DEFAULT_DIRECTORY_NAME: &str = "fonts";
 
+impl Font {
+    /// The data from which the font was loaded.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+}
+
 impl Id {
     /// Returns the inner `usize` from the `Id`.
     pub fn index(self) -> usize {
@@ -61,12 +86,12 @@ impl Map {
         }
     }
 
-    /// Borrow the `rusttype::Font` associated with the given `font::Id`.
+    /// Borrow the `Font` associated with the given `font::Id`.
     pub fn get(&self, id: Id) -> Option<&Font> {
         self.map.get(&id)
     }
 
-    /// Adds the given `rusttype::Font` to the `Map` and returns a unique `Id` for it.
+    /// Adds the given `Font` to the `Map` and returns a unique `Id` for it.
     pub fn insert(&mut self, font: Font) -> Id {
         let index = self.next_index;
         self.next_index = index.wrapping_add(1);
@@ -75,6 +100,12 @@ impl Map {
         id
     }
 
+    // Adds the given `Font` to the `Map` with the given `Id`, e.g. to restore a recorded map.
+    pub(crate) fn insert_with_id(&mut self, id: Id, font: Font) {
+        self.next_index = std::cmp::max(self.next_index, id.0.wrapping_add(1));
+        self.map.insert(id, font);
+    }
+
     /// Insert a single `Font` into the map by loading it from the given file path.
     pub fn insert_from_file<P>(&mut self, path: P) -> Result<Id, Error>
     where
@@ -131,13 +162,26 @@ where
     Ok(FontCollection::from_bytes(file_buffer)?)
 }
 
+/// Load a single `Font` from the given bytes.
+///
+/// The bytes are retained by the `Font` so that text using it may be shaped.
+pub fn from_bytes<B>(bytes: B) -> Result<Font, Error>
+where
+    B: Into<SharedBytes<'static>>,
+{
+    let data = bytes.into();
+    let collection = FontCollection::from_bytes(data.clone()).map_err(std::io::Error::from)?;
+    let font = collection.into_font().or(Err(Error::NoFont))?;
+    Ok(Font { font, data })
+}
+
 /// Load a single `Font` from a file at the given path.
 pub fn from_file<P>(path: P) -> Result<Font, Error>
 where
     P: AsRef<std::path::Path>,
 {
-    let collection = collection_from_file(path)?;
-    collection.into_font().or(Err(Error::NoFont))
+    let bytes = std::fs::read(path)?;
+    from_bytes(bytes)
 }
 
 /// Load the default notosans font.
@@ -145,11 +189,7 @@ where
 /// This function is only available if the `notosans` feature is enabled, which it is by default.
 #[cfg(feature = "notosans")]
 pub fn default_notosans() -> Font {
-    let collection = FontCollection::from_bytes(notosans::REGULAR_TTF)
-        .expect("failed to load the `notosans::REGULAR_TTF` font collection");
-    collection
-        .into_font()
-        .expect("the `notosans::REGULAR_TTF` font collection contained no fonts")
+    from_bytes(notosans::REGULAR_TTF).expect("failed to load the `notosans::REGULAR_TTF` font")
 }
 
 /// The directory that is searched for default fonts.
@@ -189,6 +229,19 @@ pub fn default(assets: &Path) -> Result<Font, Error> {
     Err(Error::NoFont)
 }
 
+impl Deref for Font {
+    type Target = rusttype::Font<'static>;
+    fn deref(&self) -> &Self::Target {
+        &self.font
+    }
+}
+
+impl From<Font> for rusttype::Font<'static> {
+    fn from(font: Font) -> Self {
+        font.font
+    }
+}
+
 impl Iterator for NewIds {
     type Item = Id;
     fn next(&mut self) -> Option<Self::Item> {
@@ -0,0 +1,313 @@
+//! Discovery of the fonts installed on the system.
+//!
+//! The standard font directories are searched for TrueType and OpenType fonts, each of which is
+//! described by the family, weight and style read from its tables. Fonts may then be found and
+//! loaded by family, weight and style, e.g.
+//!
+//! ```no_run
+//! use nannou::text::font::system::{self, Style, Weight};
+//!
+//! let font = system::load("monospace", Weight::Bold, Style::Normal).unwrap();
+//! ```
+//!
+//! Along with the family names of installed fonts, the generic `serif`, `sans-serif` and
+//! `monospace` families are supported. Each resolves to the first of a list of common families
+//! that is installed. `monospace` falls back to any font whose glyphs share the same advance.
+//!
+//! Font collections (`.ttc` files) are not yet supported.
+
+use crate::text::{font, Font};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// A description of a font installed on the system.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Info {
+    /// The path to the file containing the font.
+    pub path: PathBuf,
+    /// The family to which the font belongs, e.g. "DejaVu Sans".
+    pub family: String,
+    /// The thickness of the font's strokes.
+    pub weight: Weight,
+    /// Whether the font is upright, italic or oblique.
+    pub style: Style,
+    /// Whether or not all glyphs within the font share the same advance.
+    pub monospace: bool,
+}
+
+/// The thickness of a font's strokes.
+///
+/// Each corresponds to one of the weight classes used by OpenType and CSS, from 100 for `Thin` to
+/// 900 for `Black`.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Weight {
+    Thin,
+    ExtraLight,
+    Light,
+    Normal,
+    Medium,
+    SemiBold,
+    Bold,
+    ExtraBold,
+    Black,
+}
+
+/// The slant of a font.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub enum Style {
+    Normal,
+    Italic,
+    Oblique,
+}
+
+/// The families to which the generic `serif` family resolves, in order of preference.
+pub const SERIF_FAMILIES: &[&str] = &[
+    "DejaVu Serif",
+    "Noto Serif",
+    "Liberation Serif",
+    "FreeSerif",
+    "Times New Roman",
+    "Times",
+];
+
+/// The families to which the generic `sans-serif` family resolves, in order of preference.
+pub const SANS_SERIF_FAMILIES: &[&str] = &[
+    "DejaVu Sans",
+    "Noto Sans",
+    "Liberation Sans",
+    "Ubuntu",
+    "FreeSans",
+    "Arial",
+    "Helvetica",
+];
+
+/// The families to which the generic `monospace` family resolves, in order of preference.
+pub const MONOSPACE_FAMILIES: &[&str] = &[
+    "DejaVu Sans Mono",
+    "Noto Sans Mono",
+    "Liberation Mono",
+    "Ubuntu Mono",
+    "FreeMono",
+    "Courier New",
+    "Menlo",
+];
+
+lazy_static::lazy_static! {
+    // The fonts found by the first scan of the system font directories.
+    static ref FONTS: Mutex<Option<Vec<Info>>> = Mutex::new(None);
+}
+
+impl Weight {
+    /// The numeric weight class, from 100 to 900.
+    pub fn to_number(self) -> u16 {
+        match self {
+            Weight::Thin => 100,
+            Weight::ExtraLight => 200,
+            Weight::Light => 300,
+            Weight::Normal => 400,
+            Weight::Medium => 500,
+            Weight::SemiBold => 600,
+            Weight::Bold => 700,
+            Weight::ExtraBold => 800,
+            Weight::Black => 900,
+        }
+    }
+
+    /// The weight nearest to the given numeric weight class.
+    pub fn from_number(number: u16) -> Self {
+        match (number as u32 + 50) / 100 {
+            0 | 1 => Weight::Thin,
+            2 => Weight::ExtraLight,
+            3 => Weight::Light,
+            4 => Weight::Normal,
+            5 => Weight::Medium,
+            6 => Weight::SemiBold,
+            7 => Weight::Bold,
+            8 => Weight::ExtraBold,
+            _ => Weight::Black,
+        }
+    }
+}
+
+impl Info {
+    /// Describe the font within the given bytes, read from the file at the given path.
+    ///
+    /// Returns `None` if the bytes do not contain a font or the font has no family name.
+    pub fn from_bytes(path: PathBuf, bytes: &[u8]) -> Option<Self> {
+        use ttf_parser::name_id;
+        let face = ttf_parser::Face::from_slice(bytes, 0).ok()?;
+        let name = |id: u16| {
+            face.names()
+                .filter(|name| name.name_id() == id)
+                .filter_map(|name| name.to_string())
+                .next()
+        };
+        let family = name(name_id::TYPOGRAPHIC_FAMILY).or_else(|| name(name_id::FAMILY))?;
+        let style = if face.is_italic() {
+            Style::Italic
+        } else if face.is_oblique() {
+            Style::Oblique
+        } else {
+            Style::Normal
+        };
+        Some(Info {
+            path,
+            family,
+            weight: Weight::from_number(face.weight().to_number()),
+            style,
+            monospace: face.is_monospaced(),
+        })
+    }
+
+    /// Load the font from its file.
+    pub fn load(&self) -> Result<Font, font::Error> {
+        font::from_file(&self.path)
+    }
+}
+
+/// The directories that are searched for installed fonts on this platform.
+///
+/// Only those directories that exist are returned.
+pub fn directories() -> Vec<PathBuf> {
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    let mut dirs = vec![];
+    if cfg!(target_os = "macos") {
+        dirs.push(PathBuf::from("/System/Library/Fonts"));
+        dirs.push(PathBuf::from("/Library/Fonts"));
+        dirs.extend(home.map(|home| home.join("Library/Fonts")));
+    } else if cfg!(target_os = "windows") {
+        let windir = std::env::var_os("WINDIR").unwrap_or_else(|| "C:\\Windows".into());
+        dirs.push(PathBuf::from(windir).join("Fonts"));
+    } else {
+        let data_home = std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| home.as_ref().map(|home| home.join(".local/share")));
+        dirs.extend(data_home.map(|data| data.join("fonts")));
+        dirs.extend(home.map(|home| home.join(".fonts")));
+        dirs.push(PathBuf::from("/usr/local/share/fonts"));
+        dirs.push(PathBuf::from("/usr/share/fonts"));
+    }
+    dirs.retain(|dir| dir.is_dir());
+    dirs
+}
+
+/// Recursively search the given directory for fonts.
+///
+/// Files that are not `.ttf` or `.otf` fonts, or that cannot be read, are skipped.
+pub fn scan<P>(directory: P) -> Vec<Info>
+where
+    P: AsRef<Path>,
+{
+    let mut fonts: Vec<Info> = crate::io::walk_dir(directory)
+        .follow_links(true)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .filter(|entry| {
+            let ext = entry.path().extension().and_then(|ext| ext.to_str());
+            match ext.map(str::to_lowercase) {
+                Some(ref ext) => ext == "ttf" || ext == "otf",
+                None => false,
+            }
+        })
+        .filter_map(|entry| {
+            let bytes = std::fs::read(entry.path()).ok()?;
+            Info::from_bytes(entry.into_path(), &bytes)
+        })
+        .collect();
+    fonts.sort_by(|a, b| a.path.cmp(&b.path));
+    fonts
+}
+
+/// All fonts within the system font `directories`.
+///
+/// The directories are scanned the first time this is called, after which the results are
+/// reused.
+pub fn fonts() -> Vec<Info> {
+    let mut fonts = FONTS.lock().expect("failed to acquire system fonts lock");
+    fonts
+        .get_or_insert_with(|| directories().into_iter().flat_map(scan).collect())
+        .clone()
+}
+
+/// The name of every font family installed on the system, sorted alphabetically.
+pub fn families() -> Vec<String> {
+    let mut families: Vec<String> = fonts().into_iter().map(|info| info.family).collect();
+    families.sort();
+    families.dedup();
+    families
+}
+
+/// Find the installed font that best matches the given family, weight and style.
+///
+/// See `best_match` for details.
+pub fn find(family: &str, weight: Weight, style: Style) -> Option<Info> {
+    best_match(&fonts(), family, weight, style).cloned()
+}
+
+/// Find and load the installed font that best matches the given family, weight and style.
+///
+/// Returns `Error::NoFont` if no installed font belongs to the family.
+pub fn load(family: &str, weight: Weight, style: Style) -> Result<Font, font::Error> {
+    find(family, weight, style)
+        .ok_or(font::Error::NoFont)?
+        .load()
+}
+
+/// Select the font that best matches the given family, weight and style from the given fonts.
+///
+/// Families are compared case-insensitively, and `serif`, `sans-serif` and `monospace` select the
+/// first installed family of `SERIF_FAMILIES`, `SANS_SERIF_FAMILIES` or `MONOSPACE_FAMILIES`
+/// respectively. Among the fonts of the family, a font of the requested style is preferred over
+/// one of the requested weight. Italic and oblique fonts may substitute for one another.
+///
+/// Returns `None` if no font belongs to the family.
+pub fn best_match<'a>(
+    fonts: &'a [Info],
+    family: &str,
+    weight: Weight,
+    style: Style,
+) -> Option<&'a Info> {
+    let in_family = |name: &str| -> Vec<&'a Info> {
+        fonts
+            .iter()
+            .filter(|info| info.family.eq_ignore_ascii_case(name))
+            .collect()
+    };
+    let first_installed = |names: &[&str]| {
+        names
+            .iter()
+            .map(|name| in_family(name))
+            .find(|candidates| !candidates.is_empty())
+            .unwrap_or_default()
+    };
+    let family = family.trim();
+    let candidates = match &family.to_lowercase()[..] {
+        "serif" => first_installed(SERIF_FAMILIES),
+        "sans-serif" | "sans" => first_installed(SANS_SERIF_FAMILIES),
+        "monospace" | "mono" => {
+            let candidates = first_installed(MONOSPACE_FAMILIES);
+            if candidates.is_empty() {
+                fonts.iter().filter(|info| info.monospace).collect()
+            } else {
+                candidates
+            }
+        }
+        _ => in_family(family),
+    };
+    candidates
+        .into_iter()
+        .min_by_key(|info| distance(info, weight, style))
+}
+
+// How far the font is from the requested weight and style, where the style is most significant.
+fn distance(info: &Info, weight: Weight, style: Style) -> u32 {
+    let style_distance = match (info.style, style) {
+        (a, b) if a == b => 0,
+        (Style::Normal, _) | (_, Style::Normal) => 2,
+        _ => 1,
+    };
+    let (a, b) = (info.weight.to_number(), weight.to_number());
+    let weight_distance = a.max(b) - a.min(b);
+    style_distance * 1000 + weight_distance as u32
+}
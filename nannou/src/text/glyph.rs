@@ -9,37 +9,41 @@ pub type X = Scalar;
 /// The half of the width of some character (used within `CharXs`).
 pub type HalfW = Scalar;
 
-/// An iterator yielding the `Rect` for each `char`'s `Glyph` in the given `text`.
-pub struct Rects<'a, 'b> {
-    /// The *y* axis `Range` of the `Line` for which character `Rect`s are being yielded.
+/// An iterator yielding the `Rect` for each shaped `Glyph` in the given `text`.
+pub struct Rects<'a> {
+    /// The *y* axis `Range` of the `Line` for which glyph `Rect`s are being yielded.
     ///
     /// Every yielded `Rect` will use this as its `y` `Range`.
     y: Range,
-    /// `PositionedGlyphs` yielded by the RustType `LayoutIter`.
-    layout: text::LayoutIter<'a, 'b>,
+    /// The glyphs of the shaped line, ordered from left to right.
+    glyphs: std::vec::IntoIter<text::shape::Glyph>,
+    /// The start of the line's baseline from which glyphs are positioned.
+    origin: text::rt::Point<f32>,
+    fonts: &'a [text::Font],
+    scale: text::Scale,
 }
 
 /// An iterator that, for every `(line, line_rect)` pair yielded by the given iterator,
-/// produces an iterator that yields a `Rect` for every character in that line.
+/// produces an iterator that yields a `Rect` for every glyph in that line.
 pub struct RectsPerLine<'a, I> {
     lines_with_rects: I,
-    font: &'a text::Font,
+    faces: text::shape::Faces<'a>,
     font_size: FontSize,
 }
 
-/// Yields a `Rect` for each selected character in a single line of text.
+/// Yields a `Rect` for each glyph of the selected clusters in a single line of text.
 ///
-/// This iterator can only be produced by the `SelectedCharRectsPerLine` iterator.
-pub struct SelectedRects<'a, 'b> {
-    enumerated_rects: std::iter::Enumerate<Rects<'a, 'b>>,
-    end_char_idx: usize,
+/// This iterator can only be produced by the `SelectedRectsPerLine` iterator.
+pub struct SelectedRects<'a> {
+    rects: Rects<'a>,
+    clusters: std::ops::Range<usize>,
 }
 
-/// Yields an iteraor yielding `Rect`s for each selected character in each line of text within
-/// the given iterator yielding char `Rect`s.
+/// Yields an iteraor yielding `Rect`s for each selected glyph in each line of text within
+/// the given iterator yielding glyph `Rect`s.
 ///
-/// Given some `start` and `end` indices, only `Rect`s for `char`s between these two indices
-/// will be produced.
+/// Given some `start` and `end` indices, only `Rect`s for glyphs of the clusters between these
+/// two indices will be produced.
 ///
 /// All lines that have no selected `Rect`s will be skipped.
 pub struct SelectedRectsPerLine<'a, I> {
@@ -56,17 +60,29 @@ struct ContourPathEvents {
     last: Option<lyon::math::Point>,
 }
 
-impl<'a, 'b> Iterator for Rects<'a, 'b> {
-    type Item = (ScaledGlyph<'a>, Rect);
-    fn next(&mut self) -> Option<Self::Item> {
-        let Rects { ref mut layout, y } = *self;
-        layout.next().map(|g| {
+impl<'a> Rects<'a> {
+    // Produce the next glyph and its `Rect` along with the index of its cluster.
+    fn next_with_cluster(&mut self) -> Option<(usize, ScaledGlyph<'a>, Rect)> {
+        let Rects {
+            ref mut glyphs,
+            origin,
+            fonts,
+            scale,
+            y,
+        } = *self;
+        glyphs.next().map(|glyph| {
+            let point = text::rt::Point {
+                x: origin.x + glyph.position.x,
+                y: origin.y - glyph.position.y,
+            };
+            let font = &fonts[glyph.font];
+            let g = font.glyph(glyph.id).scaled(scale).positioned(point);
             let left = g.position().x;
             let (right, height) = g
                 .pixel_bounding_box()
                 .map(|bb| (bb.max.x as Scalar, (bb.max.y - bb.min.y) as Scalar))
                 .unwrap_or_else(|| {
-                    let w = g.unpositioned().h_metrics().advance_width as Scalar;
+                    let w = glyph.advance;
                     let r = left + w;
                     let h = 0.0;
                     (r, h)
@@ -75,50 +91,56 @@ impl<'a, 'b> Iterator for Rects<'a, 'b> {
             let y = Range::new(y.start, y.start + height);
             let r = Rect { x: x, y: y };
             let g = g.into_unpositioned();
-            (g, r)
+            (glyph.cluster, g, r)
         })
     }
 }
 
+impl<'a> Iterator for Rects<'a> {
+    type Item = (ScaledGlyph<'a>, Rect);
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_with_cluster().map(|(_, g, r)| (g, r))
+    }
+}
+
 impl<'a, I> Iterator for RectsPerLine<'a, I>
 where
     I: Iterator<Item = (&'a str, Rect)>,
 {
-    type Item = Rects<'a, 'a>;
+    type Item = Rects<'a>;
     fn next(&mut self) -> Option<Self::Item> {
         let RectsPerLine {
             ref mut lines_with_rects,
-            font,
+            ref faces,
             font_size,
         } = *self;
         let scale = text::pt_to_scale(font_size);
         lines_with_rects.next().map(|(line, line_rect)| {
             let (x, y) = (line_rect.left() as f32, line_rect.top() as f32);
-            let point = text::rt::Point { x: x, y: y };
+            let origin = text::rt::Point { x: x, y: y };
+            let glyphs = text::shape::line_with_faces(line, faces, scale).into_glyphs();
             Rects {
-                layout: font.layout(line, scale, point),
+                glyphs: glyphs.into_iter(),
+                origin,
+                fonts: faces.fonts(),
+                scale,
                 y: line_rect.y,
             }
         })
     }
 }
 
-impl<'a, 'b> Iterator for SelectedRects<'a, 'b> {
+impl<'a> Iterator for SelectedRects<'a> {
     type Item = (ScaledGlyph<'a>, Rect);
     fn next(&mut self) -> Option<Self::Item> {
-        let SelectedRects {
-            ref mut enumerated_rects,
-            end_char_idx,
-        } = *self;
-        enumerated_rects.next().and_then(
-            |(i, rect)| {
-                if i < end_char_idx {
-                    Some(rect)
-                } else {
-                    None
-                }
-            },
-        )
+        // Glyphs are ordered from left to right, so the selected clusters may be interleaved with
+        // unselected clusters within bidirectional text.
+        while let Some((cluster, g, r)) = self.rects.next_with_cluster() {
+            if self.clusters.start <= cluster && cluster < self.clusters.end {
+                return Some((g, r));
+            }
+        }
+        None
     }
 }
 
@@ -126,7 +148,7 @@ impl<'a, I> Iterator for SelectedRectsPerLine<'a, I>
 where
     I: Iterator<Item = (&'a str, Rect)>,
 {
-    type Item = SelectedRects<'a, 'a>;
+    type Item = SelectedRects<'a>;
     fn next(&mut self) -> Option<Self::Item> {
         let SelectedRectsPerLine {
             ref mut enumerated_rects_per_line,
@@ -135,30 +157,28 @@ where
         } = *self;
 
         enumerated_rects_per_line.next().map(|(i, rects)| {
-            let end_char_idx =
-                // If this is the last line, the end is the char after the final selected char.
+            let end_cluster_idx =
+                // If this is the last line, the end is the cluster after the final selected one.
                 if i == end_cursor_idx.line {
-                    end_cursor_idx.char
-                // Otherwise if in range, every char in the line is selected.
+                    end_cursor_idx.cluster
+                // Otherwise if in range, every cluster in the line is selected.
                 } else if start_cursor_idx.line <= i && i < end_cursor_idx.line {
                     std::u32::MAX as usize
-                // Otherwise if out of range, no chars are selected.
+                // Otherwise if out of range, no clusters are selected.
                 } else {
                     0
                 };
 
-            let mut enumerated_rects = rects.enumerate();
-
-            // If this is the first line, skip all non-selected chars.
-            if i == start_cursor_idx.line {
-                for _ in 0..start_cursor_idx.char {
-                    enumerated_rects.next();
-                }
-            }
+            // If this is the first line, skip all non-selected clusters.
+            let start_cluster_idx = if i == start_cursor_idx.line {
+                start_cursor_idx.cluster
+            } else {
+                0
+            };
 
             SelectedRects {
-                enumerated_rects: enumerated_rects,
-                end_char_idx: end_char_idx,
+                rects,
+                clusters: start_cluster_idx..end_cluster_idx,
             }
         })
     }
@@ -188,50 +208,66 @@ impl Iterator for ContourPathEvents {
 }
 
 /// Produce an iterator that, for every `(line, line_rect)` pair yielded by the given iterator,
-/// produces an iterator that yields a `Rect` for every character in that line.
+/// produces an iterator that yields a `Rect` for every glyph in that line.
 ///
-/// This is useful when information about character positioning is needed when reasoning about
-/// text layout.
+/// Each line is shaped via `text::shape::line`. This is useful when information about glyph
+/// positioning is needed when reasoning about text layout.
 pub fn rects_per_line<'a, I>(
     lines_with_rects: I,
     font: &'a text::Font,
     font_size: FontSize,
 ) -> RectsPerLine<'a, I>
+where
+    I: Iterator<Item = (&'a str, Rect)>,
+{
+    rects_per_line_with_fallbacks(lines_with_rects, std::slice::from_ref(font), font_size)
+}
+
+/// Produce an iterator that, for every `(line, line_rect)` pair yielded by the given iterator,
+/// produces an iterator that yields a `Rect` for every glyph in that line.
+///
+/// Each line is shaped via `text::shape::line_with_fallbacks`, resolving each grapheme cluster
+/// against the given chain of fonts.
+pub fn rects_per_line_with_fallbacks<'a, I>(
+    lines_with_rects: I,
+    fonts: &'a [text::Font],
+    font_size: FontSize,
+) -> RectsPerLine<'a, I>
 where
     I: Iterator<Item = (&'a str, Rect)>,
 {
     RectsPerLine {
         lines_with_rects: lines_with_rects,
-        font: font,
+        faces: text::shape::Faces::new(fonts),
         font_size: font_size,
     }
 }
 
-/// Find the index of the character that directly follows the cursor at the given `cursor_idx`.
+/// Find the index of the cluster that directly follows the cursor at the given `cursor_idx`.
 ///
-/// Returns `None` if either the given `cursor::Index` `line` or `idx` fields are out of bounds
-/// of the line information yielded by the `line_infos` iterator.
+/// Returns `None` if either the given `cursor::Index` `line` or `cluster` fields are out of
+/// bounds of the line information yielded by the `line_infos` iterator.
 pub fn index_after_cursor<I>(mut line_infos: I, cursor_idx: text::cursor::Index) -> Option<usize>
 where
     I: Iterator<Item = text::line::Info>,
 {
     line_infos.nth(cursor_idx.line).and_then(|line_info| {
-        let start_char = line_info.start_char;
-        let end_char = line_info.end_char();
-        let char_index = start_char + cursor_idx.char;
-        if char_index <= end_char {
-            Some(char_index)
+        let start_cluster = line_info.start_cluster;
+        let end_cluster = line_info.end_cluster();
+        let cluster_index = start_cluster + cursor_idx.cluster;
+        if cluster_index <= end_cluster {
+            Some(cluster_index)
         } else {
             None
         }
     })
 }
 
-/// Produces an iterator that yields iteraors yielding `Rect`s for each selected character in
-/// each line of text within the given iterator yielding char `Rect`s.
+/// Produces an iterator that yields iteraors yielding `Rect`s for each selected glyph in each
+/// line of text within the given iterator yielding glyph `Rect`s.
 ///
-/// Given some `start` and `end` indices, only `Rect`s for `char`s between these two indices
-/// will be produced.
+/// Given some `start` and `end` indices, only `Rect`s for glyphs of the clusters between these
+/// two indices will be produced.
 ///
 /// All lines that have no selected `Rect`s will be skipped.
 pub fn selected_rects_per_line<'a, I>(
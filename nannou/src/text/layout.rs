@@ -1,15 +1,23 @@
 //! Items related to the styling of text.
 
+use crate::serde_derive::{Deserialize, Serialize};
 use crate::text::{Align, Font, FontSize, Justify, Scalar, Wrap};
 
 /// A context for building a text layout.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Builder {
     pub line_spacing: Option<Scalar>,
     pub line_wrap: Option<Option<Wrap>>,
     pub font_size: Option<FontSize>,
     pub justify: Option<Justify>,
+    /// Fonts are not serialized. Deserialized layouts use the default font unless the font is
+    /// restored separately, e.g. by a `draw::recording::Player`.
+    #[serde(skip)]
     pub font: Option<Option<Font>>,
+    /// Fonts are not serialized. Deserialized layouts have no fallback fonts unless they are
+    /// restored separately, e.g. by a `draw::recording::Player`.
+    #[serde(skip)]
+    pub fallback_fonts: Option<Vec<Font>>,
     pub y_align: Option<Align>,
 }
 
@@ -21,6 +29,8 @@ pub struct Layout {
     pub justify: Justify,
     pub font_size: FontSize,
     pub font: Option<Font>,
+    /// The fonts used, in order, for characters that the `font` has no glyph for.
+    pub fallback_fonts: Vec<Font>,
     pub y_align: Align,
 }
 
@@ -72,6 +82,24 @@ impl Builder {
         self
     }
 
+    /// Append a font to the chain of fonts used for characters that the `font` has no glyph for.
+    ///
+    /// Each grapheme cluster is displayed with the first font in the chain that supports it.
+    pub fn fallback_font(mut self, font: Font) -> Self {
+        self.fallback_fonts.get_or_insert_with(Vec::new).push(font);
+        self
+    }
+
+    /// Specify the chain of fonts used, in order, for characters that the `font` has no glyph
+    /// for.
+    pub fn fallback_fonts<I>(mut self, fonts: I) -> Self
+    where
+        I: IntoIterator<Item = Font>,
+    {
+        self.fallback_fonts = Some(fonts.into_iter().collect());
+        self
+    }
+
     /// Describe the end along the *x* axis to which the text should be aligned.
     pub fn justify(mut self, justify: Justify) -> Self {
         self.justify = Some(justify);
@@ -125,6 +153,7 @@ impl Builder {
     /// Set all the parameters via an existing `Layout`
     pub fn layout(mut self, layout: &Layout) -> Self {
         self.font = Some(layout.font.clone());
+        self.fallback_fonts = Some(layout.fallback_fonts.clone());
         self.line_spacing(layout.line_spacing)
             .line_wrap(layout.line_wrap)
             .justify(layout.justify)
@@ -140,6 +169,7 @@ impl Builder {
             justify: self.justify.unwrap_or(DEFAULT_JUSTIFY),
             font_size: self.font_size.unwrap_or(DEFAULT_FONT_SIZE),
             font: self.font.unwrap_or(None),
+            fallback_fonts: self.fallback_fonts.unwrap_or_default(),
             y_align: self.y_align.unwrap_or(DEFAULT_Y_ALIGN),
         }
     }
@@ -153,6 +183,7 @@ impl Default for Layout {
             justify: DEFAULT_JUSTIFY,
             font_size: DEFAULT_FONT_SIZE,
             font: None,
+            fallback_fonts: vec![],
             y_align: DEFAULT_Y_ALIGN,
         }
     }
@@ -1,6 +1,7 @@
 //! Text handling logic related to individual lines of text.
 //!
-//! This module is the core of multi-line text handling.
+//! This module is the core of multi-line text handling. Lines are measured by shaping them via
+//! the `text::shape` module and are only broken between grapheme clusters.
 
 use crate::geom::{Range, Rect};
 use crate::text::{self, FontSize, Scalar, Wrap};
@@ -12,18 +13,18 @@ pub enum Break {
     Wrap {
         /// The byte index at which the break occurs.
         byte: usize,
-        /// The char index at which the string should wrap due to exceeding a maximum width.
-        char: usize,
+        /// The cluster index at which the string should wrap due to exceeding a maximum width.
+        cluster: usize,
         /// The byte length which should be skipped in order to reach the first non-whitespace
-        /// character to use as the beginning of the next line.
+        /// cluster to use as the beginning of the next line.
         len_bytes: usize,
     },
     /// A break caused by a newline character.
     Newline {
         /// The byte index at which the string should wrap due to exceeding a maximum width.
         byte: usize,
-        /// The char index at which the string should wrap due to exceeding a maximum width.
-        char: usize,
+        /// The cluster index at which the string should wrap due to exceeding a maximum width.
+        cluster: usize,
         /// The width of the "newline" token in bytes.
         len_bytes: usize,
     },
@@ -31,8 +32,8 @@ pub enum Break {
     End {
         /// The ending byte index.
         byte: usize,
-        /// The ending char index.
-        char: usize,
+        /// The ending cluster index.
+        cluster: usize,
     },
 }
 
@@ -56,8 +57,8 @@ pub struct NextBreak {
 pub struct Info {
     /// The index into the `&str` that represents the first character within the line.
     pub start_byte: usize,
-    /// The character index of the first character in the line.
-    pub start_char: usize,
+    /// The index of the first grapheme cluster in the line.
+    pub start_cluster: usize,
     /// The index within the `&str` at which this line breaks into a new line, along with the
     /// index at which the following line begins. The variant describes whether the break is
     /// caused by a `Newline` character or a `Wrap` by the given wrap function.
@@ -84,8 +85,8 @@ pub struct Infos<'a, F> {
     next_break_fn: F,
     /// The index that indicates the start of the next line to be yielded.
     start_byte: usize,
-    /// The character index that indicates the start of the next line to be yielded.
-    start_char: usize,
+    /// The cluster index that indicates the start of the next line to be yielded.
+    start_cluster: usize,
     /// The break type of the previously yielded line
     last_break: Option<Break>,
 }
@@ -107,7 +108,7 @@ pub struct Rects<I> {
 ///
 /// Lines that do not contain any selected text will be skipped.
 pub struct SelectedRects<'a, I> {
-    selected_char_rects_per_line: text::glyph::SelectedRectsPerLine<'a, I>,
+    selected_cluster_rects_per_line: text::glyph::SelectedRectsPerLine<'a, I>,
 }
 
 /// An alias for function pointers that are compatible with the `Block`'s required text
@@ -124,14 +125,14 @@ impl Break {
         }
     }
 
-    /// Return the index of the `char` at which the break occurs.
+    /// Return the index of the grapheme cluster at which the break occurs.
     ///
-    /// To clarify, this index is to be used in relation to the `Chars` iterator.
-    pub fn char_index(self) -> usize {
+    /// To clarify, this index is to be used in relation to the clusters of a shaped line.
+    pub fn cluster_index(self) -> usize {
         match self {
-            Break::Wrap { char, .. } | Break::Newline { char, .. } | Break::End { char, .. } => {
-                char
-            }
+            Break::Wrap { cluster, .. }
+            | Break::Newline { cluster, .. }
+            | Break::End { cluster, .. } => cluster,
         }
     }
 }
@@ -148,7 +149,7 @@ where
             max_width: self.max_width,
             next_break_fn: self.next_break_fn.clone(),
             start_byte: self.start_byte,
-            start_char: self.start_char,
+            start_cluster: self.start_cluster,
             last_break: None,
         }
     }
@@ -161,8 +162,8 @@ impl Info {
     }
 
     /// The end of the index range for indexing into the slice.
-    pub fn end_char(&self) -> usize {
-        self.end_break.char_index()
+    pub fn end_cluster(&self) -> usize {
+        self.end_break.cluster_index()
     }
 
     /// The index range for indexing (via bytes) into the original str slice.
@@ -170,14 +171,14 @@ impl Info {
         self.start_byte..self.end_byte()
     }
 
-    /// The index range for indexing into a `char` iterator over the original str slice.
-    pub fn char_range(self) -> std::ops::Range<usize> {
-        self.start_char..self.end_char()
+    /// The index range for indexing into the grapheme clusters of the original str slice.
+    pub fn cluster_range(self) -> std::ops::Range<usize> {
+        self.start_cluster..self.end_cluster()
     }
 }
 
 impl<'a> Infos<'a, NextBreakFnPtr> {
-    /// Converts `Self` into an `Infos` whose lines are wrapped at the character that first
+    /// Converts `Self` into an `Infos` whose lines are wrapped at the grapheme cluster that first
     /// causes the line width to exceed the given `max_width`.
     pub fn wrap_by_character(mut self, max_width: Scalar) -> Self {
         self.next_break_fn = next_break_by_character;
@@ -186,7 +187,7 @@ impl<'a> Infos<'a, NextBreakFnPtr> {
     }
 
     /// Converts `Self` into an `Infos` whose lines are wrapped at the whitespace prior to the
-    /// character that causes the line width to exceed the given `max_width`.
+    /// grapheme cluster that causes the line width to exceed the given `max_width`.
     pub fn wrap_by_whitespace(mut self, max_width: Scalar) -> Self {
         self.next_break_fn = next_break_by_whitespace;
         self.max_width = max_width;
@@ -194,91 +195,73 @@ impl<'a> Infos<'a, NextBreakFnPtr> {
     }
 }
 
-/// A function for finding the advance width between the given character that also considers
-/// the kerning for some previous glyph.
+/// The byte index of the first newline within the text along with its length in bytes.
 ///
-/// This also updates the `last_glyph` with the glyph produced for the given `char`.
+/// Returns the length of the text and `None` if the text contains no newline.
+pub(crate) fn next_newline(text: &str) -> (usize, Option<usize>) {
+    match text.find('\n') {
+        Some(i) if text[..i].ends_with('\r') => (i - 1, Some(2)),
+        Some(i) => (i, Some(1)),
+        None => (text.len(), None),
+    }
+}
+
+/// Shape the text up to the first newline, producing the shaped line along with the break at its
+/// end.
 ///
 /// This is primarily for use within the `next_break` functions below.
+fn shape_to_newline(
+    text: &str,
+    faces: &text::shape::Faces,
+    scale: text::Scale,
+) -> (text::shape::Line, Break) {
+    let (byte, newline_len) = next_newline(text);
+    let line = text::shape::line_with_faces(&text[..byte], faces, scale);
+    let cluster = line.clusters().len();
+    let break_ = match newline_len {
+        Some(len_bytes) => Break::Newline {
+            byte,
+            cluster,
+            len_bytes,
+        },
+        None => Break::End { byte, cluster },
+    };
+    (line, break_)
+}
+
+/// Produce the `NextBreak` for a line wrapped at the given break.
 ///
-/// The following code is adapted from the rusttype::LayoutIter::next src.
-fn advance_width_and_height(
-    ch: char,
-    font: &text::Font,
+/// The text preceding the break is shaped again so that the width and height reflect the line as
+/// it will be displayed.
+fn next_wrap(
+    text: &str,
+    faces: &text::shape::Faces,
     scale: text::Scale,
-    last_glyph: &mut Option<text::GlyphId>,
-) -> (Scalar, Scalar) {
-    let g = font.glyph(ch).scaled(scale);
-    let kern = last_glyph
-        .map(|last| font.pair_kerning(scale, last, g.id()))
-        .unwrap_or(0.0);
-    let advance_width = g.h_metrics().advance_width;
-    let height = g
-        .exact_bounding_box()
-        .map(|bb| bb.min.y.abs() as Scalar)
-        .unwrap_or(0.0);
-    *last_glyph = Some(g.id());
-    let adv_w = (kern + advance_width) as Scalar;
-    (adv_w, height)
+    break_: Break,
+) -> NextBreak {
+    let line = text::shape::line_with_faces(&text[..break_.byte_index()], faces, scale);
+    NextBreak {
+        break_,
+        width: line.width(),
+        height: line.height(),
+    }
 }
 
 /// Returns the next index at which the text naturally breaks via a newline character,
 /// along with the width of the line.
-fn next_break(text: &str, font: &text::Font, font_size: FontSize) -> NextBreak {
+fn next_break(text: &str, faces: &text::shape::Faces, font_size: FontSize) -> NextBreak {
     let scale = text::pt_to_scale(font_size);
-    let mut width = 0.0;
-    let mut height = 0.0;
-    let mut char_i = 0;
-    let mut char_indices = text.char_indices().peekable();
-    let mut last_glyph = None;
-    while let Some((byte_i, ch)) = char_indices.next() {
-        // Check for a newline.
-        if ch == '\r' {
-            if let Some(&(_, '\n')) = char_indices.peek() {
-                let break_ = Break::Newline {
-                    byte: byte_i,
-                    char: char_i,
-                    len_bytes: 2,
-                };
-                return NextBreak {
-                    break_,
-                    width,
-                    height,
-                };
-            }
-        } else if ch == '\n' {
-            let break_ = Break::Newline {
-                byte: byte_i,
-                char: char_i,
-                len_bytes: 1,
-            };
-            return NextBreak {
-                break_,
-                width,
-                height,
-            };
-        }
-
-        // Update the width.
-        let (adv_w, h) = advance_width_and_height(ch, font, scale, &mut last_glyph);
-        width += adv_w;
-        height = height.max(h);
-        char_i += 1;
-    }
-    let break_ = Break::End {
-        byte: text.len(),
-        char: char_i,
-    };
+    let (line, break_) = shape_to_newline(text, faces, scale);
     NextBreak {
         break_,
-        width,
-        height,
+        width: line.width(),
+        height: line.height(),
     }
 }
 
 /// Returns the next index at which the text will break by either:
 /// - A newline character.
-/// - A line wrap at the beginning of the first character exceeding the `max_width`.
+/// - A line wrap at the beginning of the first cluster exceeding the `max_width`.
 ///
 /// Also returns the width of each line alongside the Break.
 fn next_break_by_character(
@@ -287,79 +270,15 @@ fn next_break_by_character(
     font_size: FontSize,
     max_width: Scalar,
 ) -> NextBreak {
-    let scale = text::pt_to_scale(font_size);
-    let mut width = 0.0;
-    let mut height = 0.0;
-    let mut char_i = 0;
-    let mut char_indices = text.char_indices().peekable();
-    let mut last_glyph = None;
-    while let Some((byte_i, ch)) = char_indices.next() {
-        // Check for a newline.
-        if ch == '\r' {
-            if let Some(&(_, '\n')) = char_indices.peek() {
-                let break_ = Break::Newline {
-                    byte: byte_i,
-                    char: char_i,
-                    len_bytes: 2,
-                };
-                return NextBreak {
-                    break_,
-                    width,
-                    height,
-                };
-            }
-        } else if ch == '\n' {
-            let break_ = Break::Newline {
-                byte: byte_i,
-                char: char_i,
-                len_bytes: 1,
-            };
-            return NextBreak {
-                break_,
-                width,
-                height,
-            };
-        }
-
-        // Add the character's width to the width so far.
-        let (adv_w, h) = advance_width_and_height(ch, font, scale, &mut last_glyph);
-        let new_width = width + adv_w;
-
-        // Check for a line wrap.
-        if new_width > max_width {
-            let break_ = Break::Wrap {
-                byte: byte_i,
-                char: char_i,
-                len_bytes: 0,
-            };
-            return NextBreak {
-                break_,
-                width,
-                height,
-            };
-        }
-
-        height = height.max(h);
-        width = new_width;
-        char_i += 1;
-    }
-
-    let break_ = Break::End {
-        byte: text.len(),
-        char: char_i,
-    };
-    NextBreak {
-        break_,
-        width,
-        height,
-    }
+    let faces = text::shape::Faces::new(std::slice::from_ref(font));
+    next_break_wrapped(text, &faces, font_size, Wrap::Character, max_width)
 }
 
 /// Returns the next index at which the text will break by either:
 /// - A newline character.
 /// - A line wrap at the beginning of the whitespace that preceeds the first word
 /// exceeding the `max_width`.
-/// - A line wrap at the beginning of the first character exceeding the `max_width`,
+/// - A line wrap at the beginning of the first cluster exceeding the `max_width`,
 /// if no whitespace appears for `max_width` characters.
 ///
 /// Also returns the width the line alongside the Break.
@@ -369,124 +288,79 @@ fn next_break_by_whitespace(
     font_size: FontSize,
     max_width: Scalar,
 ) -> NextBreak {
-    struct Last {
-        byte: usize,
-        char: usize,
-        width_before: Scalar,
-    }
+    let faces = text::shape::Faces::new(std::slice::from_ref(font));
+    next_break_wrapped(text, &faces, font_size, Wrap::Whitespace, max_width)
+}
+
+// Shared between the `next_break_by_character` and `next_break_by_whitespace` functions.
+fn next_break_wrapped(
+    text: &str,
+    faces: &text::shape::Faces,
+    font_size: FontSize,
+    wrap: Wrap,
+    max_width: Scalar,
+) -> NextBreak {
     let scale = text::pt_to_scale(font_size);
-    let mut last_whitespace_start = None;
+    let (line, break_) = shape_to_newline(text, faces, scale);
+    match wrap_break(text, line.clusters(), wrap, max_width) {
+        Some(break_) => next_wrap(text, faces, scale, break_),
+        None => NextBreak {
+            break_,
+            width: line.width(),
+            height: line.height(),
+        },
+    }
+}
+
+/// Find the `Break::Wrap` at which a line made up of the given clusters should wrap in order to
+/// fit within the `max_width`, if any.
+///
+/// The byte ranges of the `clusters` are expected to index into the given `text`. At least one
+/// cluster is kept on each line.
+pub(crate) fn wrap_break(
+    text: &str,
+    clusters: &[text::shape::Cluster],
+    wrap: Wrap,
+    max_width: Scalar,
+) -> Option<Break> {
+    let is_whitespace =
+        |c: &text::shape::Cluster| text[c.byte_range.clone()].chars().all(char::is_whitespace);
+    let mut last_whitespace = None;
     let mut width = 0.0;
-    let mut height = 0.0;
-    let mut char_i = 0;
-    let mut char_indices = text.char_indices().peekable();
-    let mut last_glyph = None;
-    while let Some((byte_i, ch)) = char_indices.next() {
-        // Check for a newline.
-        if ch == '\r' {
-            if let Some(&(_, '\n')) = char_indices.peek() {
-                let break_ = Break::Newline {
-                    byte: byte_i,
-                    char: char_i,
-                    len_bytes: 2,
-                };
-                return NextBreak {
-                    break_,
-                    width,
-                    height,
-                };
-            }
-        } else if ch == '\n' {
-            let break_ = Break::Newline {
-                byte: byte_i,
-                char: char_i,
-                len_bytes: 1,
-            };
-            return NextBreak {
-                break_,
-                width,
-                height,
-            };
+    for (i, cluster) in clusters.iter().enumerate() {
+        // Check for a new whitespace.
+        if is_whitespace(cluster) {
+            last_whitespace = Some(i);
         }
 
-        // Add the character's width to the width so far.
-        let (adv_w, h) = advance_width_and_height(ch, font, scale, &mut last_glyph);
-        let new_width = width + adv_w;
-
-        // Check for a line wrap.
-        if width > max_width {
-            match last_whitespace_start {
-                Some(Last {
-                    byte,
-                    char,
-                    width_before,
-                }) => {
-                    let break_ = Break::Wrap {
-                        byte: byte,
-                        char: char,
-                        len_bytes: 1,
-                    };
-                    let width = width_before;
-                    return NextBreak {
-                        break_,
-                        width,
-                        height,
-                    };
-                }
-                None => {
-                    let break_ = Break::Wrap {
-                        byte: byte_i,
-                        char: char_i,
-                        len_bytes: 0,
-                    };
-                    return NextBreak {
-                        break_,
-                        width,
-                        height,
-                    };
+        // Check for a line wrap, keeping at least one cluster on each line.
+        width += cluster.x.len();
+        if width > max_width && i > 0 {
+            let break_ = match (wrap, last_whitespace) {
+                (Wrap::Whitespace, Some(j)) if j > 0 => {
+                    let whitespace = &clusters[j];
+                    Break::Wrap {
+                        byte: whitespace.byte_range.start,
+                        cluster: j,
+                        len_bytes: whitespace.byte_range.len(),
+                    }
                 }
-            }
-        }
-
-        // Check for a new whitespace.
-        if ch.is_whitespace() {
-            last_whitespace_start = Some(Last {
-                byte: byte_i,
-                char: char_i,
-                width_before: width,
-            });
+                _ => Break::Wrap {
+                    byte: cluster.byte_range.start,
+                    cluster: i,
+                    len_bytes: 0,
+                },
+            };
+            return Some(break_);
         }
-
-        width = new_width;
-        height = height.max(h);
-        char_i += 1;
-    }
-
-    let break_ = Break::End {
-        byte: text.len(),
-        char: char_i,
-    };
-    NextBreak {
-        break_,
-        width,
-        height,
     }
+    None
 }
 
 /// Produce the width of the given line of text including spaces (i.e. ' ').
 pub fn width(text: &str, font: &text::Font, font_size: FontSize) -> Scalar {
-    let scale = text::Scale::uniform(text::pt_to_px(font_size));
-    let point = text::rt::Point { x: 0.0, y: 0.0 };
-
-    let mut total_w = 0.0;
-    for g in font.layout(text, scale, point) {
-        match g.pixel_bounding_box() {
-            Some(bb) => total_w = bb.max.x as f32,
-            None => total_w += g.unpositioned().h_metrics().advance_width,
-        }
-    }
-
-    total_w as Scalar
+    let scale = text::pt_to_scale(font_size);
+    text::shape::line(text, font, scale).width()
 }
 
 /// Produce an `Infos` iterator wrapped by the given `next_break_fn`.
@@ -507,7 +381,7 @@ where
         max_width: max_width,
         next_break_fn: next_break_fn,
         start_byte: 0,
-        start_char: 0,
+        start_cluster: 0,
         last_break: None,
     }
 }
@@ -527,7 +401,8 @@ pub fn infos<'a>(
         font_size: FontSize,
         _max_width: Scalar,
     ) -> NextBreak {
-        next_break(text, font, font_size)
+        let faces = text::shape::Faces::new(std::slice::from_ref(font));
+        next_break(text, &faces, font_size)
     }
 
     infos_wrapped_by(text, font, font_size, std::f32::MAX, no_wrap)
@@ -548,6 +423,32 @@ pub fn infos_maybe_wrapped<'a>(
     }
 }
 
+/// Produce an `Infos` iterator for text whose grapheme clusters are resolved against the given
+/// chain of fallback fonts.
+///
+/// Lines are shaped via `shape::line_with_faces` and wrapped in the same manner as
+/// `infos_maybe_wrapped`.
+///
+/// **Panics** if `fonts` is empty.
+pub fn infos_with_fallbacks<'a>(
+    text: &'a str,
+    fonts: &'a [text::Font],
+    font_size: FontSize,
+    maybe_wrap: Option<Wrap>,
+    max_width: Scalar,
+) -> Infos<
+    'a,
+    impl 'a + Clone + for<'b> FnMut(&'b str, &'b text::Font, FontSize, Scalar) -> NextBreak,
+> {
+    // The tables of each font are parsed once and shared between lines.
+    let faces = text::shape::Faces::new(fonts);
+    let next_break_fn = move |text: &str, _: &text::Font, font_size, max_width| match maybe_wrap {
+        None => next_break(text, &faces, font_size),
+        Some(wrap) => next_break_wrapped(text, &faces, font_size, wrap, max_width),
+    };
+    infos_wrapped_by(text, &fonts[0], font_size, max_width, next_break_fn)
+}
+
 /// Produce an iterator yielding the bounding `Rect` for each line in the text.
 ///
 /// Yielded `Rect`s will begin with the top-left of the first line at a [0.0, 0.0].
@@ -606,7 +507,7 @@ where
     I: Iterator<Item = (&'a str, Rect)>,
 {
     SelectedRects {
-        selected_char_rects_per_line: text::glyph::selected_rects_per_line(
+        selected_cluster_rects_per_line: text::glyph::selected_rects_per_line(
             lines_with_rects,
             font,
             font_size,
@@ -629,64 +530,51 @@ where
             max_width,
             ref mut next_break_fn,
             ref mut start_byte,
-            ref mut start_char,
+            ref mut start_cluster,
             ref mut last_break,
         } = *self;
 
         let next = next_break_fn(&text[*start_byte..], font, font_size, max_width);
         match next.break_ {
-            Break::Newline { .. } | Break::Wrap { .. } => {
+            Break::Newline {
+                byte,
+                cluster,
+                len_bytes,
+            }
+            | Break::Wrap {
+                byte,
+                cluster,
+                len_bytes,
+            } => {
                 let next_break = match next.break_ {
-                    Break::Newline {
-                        byte,
-                        char,
-                        len_bytes,
-                    } => Break::Newline {
+                    Break::Newline { .. } => Break::Newline {
                         byte: *start_byte + byte,
-                        char: *start_char + char,
+                        cluster: *start_cluster + cluster,
                         len_bytes: len_bytes,
                     },
-                    Break::Wrap {
-                        byte,
-                        char,
-                        len_bytes,
-                    } => Break::Wrap {
+                    _ => Break::Wrap {
                         byte: *start_byte + byte,
-                        char: *start_char + char,
+                        cluster: *start_cluster + cluster,
                         len_bytes: len_bytes,
                     },
-                    _ => unreachable!(),
                 };
 
                 let info = Info {
                     start_byte: *start_byte,
-                    start_char: *start_char,
+                    start_cluster: *start_cluster,
                     end_break: next_break,
                     width: next.width,
                     height: next.height,
                 };
 
-                match next.break_ {
-                    Break::Newline {
-                        byte,
-                        char,
-                        len_bytes,
-                    }
-                    | Break::Wrap {
-                        byte,
-                        char,
-                        len_bytes,
-                    } => {
-                        *start_byte = info.start_byte + byte + len_bytes;
-                        *start_char = info.start_char + char + 1;
-                    }
-                    _ => unreachable!(),
-                };
+                // Skip the newline or whitespace cluster at which the line broke, if any.
+                *start_byte = info.start_byte + byte + len_bytes;
+                *start_cluster = info.start_cluster + cluster + if len_bytes > 0 { 1 } else { 0 };
                 *last_break = Some(next_break);
                 Some(info)
             }
 
-            Break::End { char, .. } => {
+            Break::End { cluster, .. } => {
                 // if the last line ends in a new line, or the entire text is empty, return an
                 // empty line Info.
                 let empty_line = {
@@ -700,20 +588,20 @@ where
                 };
                 if *start_byte < text.len() || empty_line {
                     let total_bytes = text.len();
-                    let total_chars = *start_char + char;
+                    let total_clusters = *start_cluster + cluster;
                     let end_break = Break::End {
                         byte: total_bytes,
-                        char: total_chars,
+                        cluster: total_clusters,
                     };
                     let info = Info {
                         start_byte: *start_byte,
-                        start_char: *start_char,
+                        start_cluster: *start_cluster,
                         end_break: end_break,
                         width: next.width,
                         height: next.height,
                     };
                     *start_byte = total_bytes;
-                    *start_char = total_chars;
+                    *start_cluster = total_clusters;
                     *last_break = Some(end_break);
                     Some(info)
                 } else {
@@ -772,10 +660,10 @@ where
 {
     type Item = Rect;
     fn next(&mut self) -> Option<Self::Item> {
-        while let Some(mut rects) = self.selected_char_rects_per_line.next() {
+        while let Some(mut rects) = self.selected_cluster_rects_per_line.next() {
             if let Some((_, first_rect)) = rects.next() {
                 let total_selected_rect = rects.fold(first_rect, |mut total, (_, next)| {
-                    total.x.end = next.x.end;
+                    total.x = total.x.max(next.x);
                     total
                 });
                 return Some(total_selected_rect);
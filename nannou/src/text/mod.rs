@@ -2,15 +2,22 @@
 //!
 //! Currently, this crate is used primarily by the `draw.text()` API but will also play an
 //! important role in future GUI work.
+//!
+//! Each line of text is shaped via the `shape` module, applying the font's kerning, ligatures and
+//! contextual forms along with bidirectional reordering.
 
 pub mod cursor;
 pub mod font;
 pub mod glyph;
 pub mod layout;
 pub mod line;
+pub mod on_path;
+pub mod rich;
+pub mod sdf;
+pub mod shape;
 pub mod rt {
     //! Re-exported RustType geometric types.
-    pub use rusttype::{gpu_cache, point, vector, Point, Rect, Vector};
+    pub use rusttype::{gpu_cache, point, vector, Contour, Point, Rect, Segment, Vector};
 }
 
 // Re-export all relevant rusttype types here.
@@ -18,13 +25,14 @@ pub use self::layout::Layout;
 pub use rusttype::gpu_cache::Cache as GlyphCache;
 pub use rusttype::{Glyph, GlyphId, GlyphIter, LayoutIter, Scale, ScaledGlyph};
 
+pub use self::font::Font;
+
 use crate::geom;
+use crate::serde_derive::{Deserialize, Serialize};
 use std::borrow::Cow;
 
 /// The RustType `FontCollection` type used by nannou.
 pub type FontCollection = rusttype::FontCollection<'static>;
-/// The RustType `Font` type used by nannou.
-pub type Font = rusttype::Font<'static>;
 /// The RustType `PositionedGlyph` type used by nannou.
 pub type PositionedGlyph = rusttype::PositionedGlyph<'static>;
 
@@ -47,7 +55,8 @@ pub struct Builder<'a> {
 #[derive(Clone)]
 pub struct Text<'a> {
     text: Cow<'a, str>,
-    font: Font,
+    // The layout's font followed by its fallback fonts.
+    fonts: Vec<Font>,
     layout: Layout,
     line_infos: Vec<line::Info>,
     rect: geom::Rect,
@@ -89,12 +98,12 @@ pub type TextGlyphsPerLine<'a> = glyph::RectsPerLine<'a, TextLinesWithRects<'a>>
 /// An alias for the iterator yielded by `Text::glyphs`.
 pub type TextGlyphs<'a> = std::iter::FlatMap<
     TextGlyphsPerLine<'a>,
-    glyph::Rects<'a, 'a>,
-    fn(glyph::Rects<'a, 'a>) -> glyph::Rects<'a, 'a>,
+    glyph::Rects<'a>,
+    fn(glyph::Rects<'a>) -> glyph::Rects<'a>,
 >;
 
 /// Alignment along an axis.
-#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, PartialOrd, Ord)]
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum Align {
     Start,
     Middle,
@@ -102,7 +111,7 @@ pub enum Align {
 }
 
 /// A type used for referring to typographic alignment of `Text`.
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Justify {
     /// Align text to the start of the bounding `Rect`'s *x* axis.
     Left,
@@ -117,7 +126,7 @@ pub enum Justify {
 }
 
 /// The way in which text should wrap around the width.
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Wrap {
     /// Wrap at the first character that exceeds the width.
     Character,
@@ -197,6 +206,20 @@ impl<'a> Builder<'a> {
         self.map_layout(|l| l.font(font))
     }
 
+    /// Append a font to the chain of fonts used for characters that the `font` has no glyph for.
+    pub fn fallback_font(self, font: Font) -> Self {
+        self.map_layout(|l| l.fallback_font(font))
+    }
+
+    /// Specify the chain of fonts used, in order, for characters that the `font` has no glyph
+    /// for.
+    pub fn fallback_fonts<I>(self, fonts: I) -> Self
+    where
+        I: IntoIterator<Item = Font>,
+    {
+        self.map_layout(|l| l.fallback_fonts(fonts))
+    }
+
     /// Describe the end along the *x* axis to which the text should be aligned.
     pub fn justify(self, justify: Justify) -> Self {
         self.map_layout(|l| l.justify(justify))
@@ -252,30 +275,30 @@ impl<'a> Builder<'a> {
     /// Build the text.
     ///
     /// This iterates over the text in order to pre-calculates the text's multi-line information
-    /// using the `line::infos` function.
+    /// using the `line::infos_with_fallbacks` function. Lines are measured by shaping them with
+    /// the `shape::line_with_fallbacks` function.
     ///
     /// The given `rect` will be used for applying the layout including text alignment, positioning
     /// of text, multi-line wrapping, etc,
     pub fn build(self, rect: geom::Rect) -> Text<'a> {
         let text = self.text;
         let layout = self.layout_builder.build();
-        #[allow(unreachable_code)]
-        let font = layout.font.clone().unwrap_or_else(|| {
-            #[cfg(feature = "notosans")]
-            {
-                return font::default_notosans();
-            }
-            let assets = crate::app::find_assets_path()
-                .expect("failed to detect the assets directory when searching for a default font");
-            font::default(&assets).expect("failed to detect a default font")
-        });
+        let font = layout.font.clone().unwrap_or_else(default_font);
+        let fonts: Vec<_> = std::iter::once(font)
+            .chain(layout.fallback_fonts.iter().cloned())
+            .collect();
         let max_width = rect.w();
-        let line_infos =
-            line::infos_maybe_wrapped(&text, &font, layout.font_size, layout.line_wrap, max_width)
-                .collect();
+        let line_infos = line::infos_with_fallbacks(
+            &text,
+            &fonts,
+            layout.font_size,
+            layout.line_wrap,
+            max_width,
+        )
+        .collect();
         Text {
             text,
-            font,
+            fonts,
             layout,
             line_infos,
             rect,
@@ -301,7 +324,12 @@ impl<'a> Text<'a> {
 
     /// The font used for this text instance.
     pub fn font(&self) -> &Font {
-        &self.font
+        &self.fonts[0]
+    }
+
+    /// The font used for this text instance followed by its chain of fallback fonts.
+    pub fn fonts(&self) -> &[Font] {
+        &self.fonts
     }
 
     /// The number of lines in the text.
@@ -408,7 +436,11 @@ impl<'a> Text<'a> {
     /// Produce an iterator yielding iterators yielding every glyph alongside its bounding rect for
     /// each line.
     pub fn glyphs_per_line(&self) -> TextGlyphsPerLine {
-        glyph::rects_per_line(self.lines_with_rects(), &self.font, self.layout.font_size)
+        glyph::rects_per_line_with_fallbacks(
+            self.lines_with_rects(),
+            &self.fonts,
+            self.layout.font_size,
+        )
     }
 
     /// Produce an iterator yielding every glyph alongside its bounding rect.
@@ -487,9 +519,22 @@ impl<'a> Text<'a> {
         window_size: geom::Vector2,
         scale_factor: Scalar,
     ) -> impl 'a + 'b + Iterator<Item = PositionedGlyph> {
-        rt_positioned_glyphs(
+        self.rt_glyphs_with_fonts(window_size, scale_factor)
+            .map(|(_, g)| g)
+    }
+
+    /// Produce an iterator yielding positioned rusttype glyphs ready for caching, each alongside
+    /// the index of the font within `fonts` from which it was produced.
+    ///
+    /// This is the same as `rt_glyphs`, but allows for caching glyphs produced by fallback fonts.
+    pub fn rt_glyphs_with_fonts<'b: 'a>(
+        &'b self,
+        window_size: geom::Vector2,
+        scale_factor: Scalar,
+    ) -> impl 'a + 'b + Iterator<Item = (usize, PositionedGlyph)> {
+        rt_positioned_glyphs_with_fallbacks(
             self.lines_with_rects(),
-            &self.font,
+            &self.fonts,
             self.layout.font_size,
             window_size,
             scale_factor,
@@ -500,7 +545,7 @@ impl<'a> Text<'a> {
     pub fn into_owned(self) -> Text<'static> {
         let Text {
             text,
-            font,
+            fonts,
             layout,
             line_infos,
             rect,
@@ -508,7 +553,7 @@ impl<'a> Text<'a> {
         let text = Cow::Owned(text.into_owned());
         Text {
             text,
-            font,
+            fonts,
             layout,
             line_infos,
             rect,
@@ -620,6 +665,8 @@ pub fn position_offset(
 
 /// Produce the position of each glyph ready for the rusttype glyph cache.
 ///
+/// Each line is shaped via `shape::line` before its glyphs are positioned.
+///
 /// Window dimensions are expected in logical coordinates.
 pub fn rt_positioned_glyphs<'a, I>(
     lines_with_rects: I,
@@ -628,6 +675,34 @@ pub fn rt_positioned_glyphs<'a, I>(
     window_size: geom::Vector2,
     scale_factor: Scalar,
 ) -> impl 'a + Iterator<Item = PositionedGlyph>
+where
+    I: IntoIterator<Item = (&'a str, geom::Rect)>,
+    I::IntoIter: 'a,
+{
+    let fonts = std::slice::from_ref(font);
+    rt_positioned_glyphs_with_fallbacks(
+        lines_with_rects,
+        fonts,
+        font_size,
+        window_size,
+        scale_factor,
+    )
+    .map(|(_, g)| g)
+}
+
+/// Produce the position of each glyph ready for the rusttype glyph cache, along with the index of
+/// the font within the given chain from which the glyph was produced.
+///
+/// Each line is shaped via `shape::line_with_fallbacks` before its glyphs are positioned.
+///
+/// Window dimensions are expected in logical coordinates.
+pub fn rt_positioned_glyphs_with_fallbacks<'a, I>(
+    lines_with_rects: I,
+    fonts: &'a [Font],
+    font_size: FontSize,
+    window_size: geom::Vector2,
+    scale_factor: Scalar,
+) -> impl 'a + Iterator<Item = (usize, PositionedGlyph)>
 where
     I: IntoIterator<Item = (&'a str, geom::Rect)>,
     I::IntoIter: 'a,
@@ -638,6 +713,7 @@ where
 
     // Clear the existing glyphs and fill the buffer with glyphs for this Text.
     let scale = f32_pt_to_scale(font_size as f32 * scale_factor);
+    let faces = shape::Faces::new(fonts);
     lines_with_rects
         .into_iter()
         .flat_map(move |(line, line_rect)| {
@@ -645,11 +721,36 @@ where
                 trans_x(line_rect.left()) as f32,
                 trans_y(line_rect.bottom()) as f32,
             );
-            let point = rt::Point { x: x, y: y };
-            font.layout(line, scale, point).map(|g| g.standalone())
+            shape::line_with_faces(line, &faces, scale)
+                .into_glyphs()
+                .into_iter()
+                .map(move |g| {
+                    let point = rt::Point {
+                        x: x + g.position.x,
+                        y: y - g.position.y,
+                    };
+                    let glyph = fonts[g.font]
+                        .glyph(g.id)
+                        .scaled(scale)
+                        .positioned(point)
+                        .standalone();
+                    (g.font, glyph)
+                })
         })
 }
 
+// The font used by text whose layout does not specify one.
+#[allow(unreachable_code)]
+pub(crate) fn default_font() -> Font {
+    #[cfg(feature = "notosans")]
+    {
+        return font::default_notosans();
+    }
+    let assets = crate::app::find_assets_path()
+        .expect("failed to detect the assets directory when searching for a default font");
+    font::default(&assets).expect("failed to detect a default font")
+}
+
 /// Converts the given font size in "points" to its font size in pixels.
 /// This is useful for when the font size is not an integer.
 pub fn f32_pt_to_px(font_size_in_points: f32) -> f32 {
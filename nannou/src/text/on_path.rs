@@ -0,0 +1,183 @@
+//! Laying out a line of text along a path.
+//!
+//! Glyphs are positioned by their distance along the path, such that the middle of each glyph's
+//! advance lies on the path and its baseline follows the path's tangent. Paths are measured via
+//! `geom::path::measure::Measure`, so curves are placed upon their flattened approximation.
+
+use crate::geom::path::measure::Measure;
+use crate::geom::{pt2, vec2, Vector2};
+use crate::serde_derive::{Deserialize, Serialize};
+use crate::text::{glyph, shape, Font, Justify, Point, Scalar, Scale};
+use lyon::path::PathEvent;
+
+/// Options for laying out a line of text along a path.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Options {
+    /// The end of the path to which the text is aligned.
+    pub justify: Justify,
+    /// The distance along the path by which the aligned text is shifted.
+    pub offset: Scalar,
+    /// Additional space inserted between each cluster of glyphs.
+    pub spacing: Scalar,
+    /// Whether or not glyphs are rotated to follow the tangent of the path.
+    ///
+    /// If `false`, glyphs remain upright.
+    pub rotate: bool,
+    /// Whether or not glyphs that do not fit entirely along the path are omitted.
+    ///
+    /// If `false`, glyphs beyond either end continue along the tangent at that end.
+    pub clip: bool,
+}
+
+/// The placement of a single glyph along a path.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Placement {
+    /// The index of the glyph within the shaped line.
+    pub index: usize,
+    /// The position of the glyph's origin.
+    pub origin: Point,
+    /// The angle in radians by which the glyph is rotated about its origin.
+    pub rotation: Scalar,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            justify: Justify::Left,
+            offset: 0.0,
+            spacing: 0.0,
+            rotate: true,
+            clip: false,
+        }
+    }
+}
+
+impl Placement {
+    /// Transform a point relative to the glyph's origin, where *y* points upwards, to its
+    /// position along the path.
+    pub fn transform_point(&self, p: Point) -> Point {
+        self.origin + rotate(p, self.rotation)
+    }
+
+    /// Transform a path event relative to the glyph's origin to its position along the path.
+    pub fn transform_event(&self, event: PathEvent) -> PathEvent {
+        let tp = |p: lyon::math::Point| {
+            let p = self.transform_point(pt2(p.x, p.y));
+            lyon::math::point(p.x, p.y)
+        };
+        match event {
+            PathEvent::Begin { at } => PathEvent::Begin { at: tp(at) },
+            PathEvent::Line { from, to } => PathEvent::Line {
+                from: tp(from),
+                to: tp(to),
+            },
+            PathEvent::Quadratic { from, ctrl, to } => PathEvent::Quadratic {
+                from: tp(from),
+                ctrl: tp(ctrl),
+                to: tp(to),
+            },
+            PathEvent::Cubic {
+                from,
+                ctrl1,
+                ctrl2,
+                to,
+            } => PathEvent::Cubic {
+                from: tp(from),
+                ctrl1: tp(ctrl1),
+                ctrl2: tp(ctrl2),
+                to: tp(to),
+            },
+            PathEvent::End { last, first, close } => PathEvent::End {
+                last: tp(last),
+                first: tp(first),
+                close,
+            },
+        }
+    }
+}
+
+/// Place each of the given glyphs of a shaped line along the measured path.
+///
+/// Glyphs are expected to be ordered from left to right, as produced by `shape::line`. Returns
+/// no placements if the path has no length.
+pub fn place(glyphs: &[shape::Glyph], measure: &Measure, options: &Options) -> Vec<Placement> {
+    // The start of each glyph's advance along the line, including the spacing between clusters.
+    let mut clusters = 0;
+    let mut last_cluster = None;
+    let starts: Vec<Scalar> = glyphs
+        .iter()
+        .map(|g| {
+            if last_cluster.map(|c| c != g.cluster).unwrap_or(false) {
+                clusters += 1;
+            }
+            last_cluster = Some(g.cluster);
+            g.position.x + clusters as Scalar * options.spacing
+        })
+        .collect();
+    let width = glyphs
+        .iter()
+        .zip(&starts)
+        .fold(0.0, |w, (g, &start)| (start + g.advance).max(w));
+
+    let length = measure.length();
+    let start = match options.justify {
+        Justify::Left => 0.0,
+        Justify::Center => (length - width) / 2.0,
+        Justify::Right => length - width,
+    } + options.offset;
+
+    glyphs
+        .iter()
+        .zip(starts)
+        .enumerate()
+        .filter_map(|(index, (g, glyph_start))| {
+            let half_advance = g.advance / 2.0;
+            let middle = start + glyph_start + half_advance;
+            if options.clip && (middle - half_advance < 0.0 || middle + half_advance > length) {
+                return None;
+            }
+
+            // Glyphs beyond the ends of the path continue along the tangent at that end.
+            let distance = middle.max(0.0).min(length);
+            let tangent = measure.tangent_at_distance(distance)?;
+            let point = measure.point_at_distance(distance)? + tangent * (middle - distance);
+            let rotation = if options.rotate {
+                tangent.y.atan2(tangent.x)
+            } else {
+                0.0
+            };
+            let origin = point + rotate(vec2(-half_advance, g.position.y), rotation);
+            Some(Placement {
+                index,
+                origin,
+                rotation,
+            })
+        })
+        .collect()
+}
+
+/// Produce the path events for the outlines of the placed glyphs.
+///
+/// The `fonts` are the chain of fonts with which the line was shaped, and `scale` the scale at
+/// which it was shaped.
+pub fn path_events<'a>(
+    glyphs: &'a [shape::Glyph],
+    fonts: &'a [Font],
+    scale: Scale,
+    placements: &'a [Placement],
+) -> impl 'a + Iterator<Item = PathEvent> {
+    placements.iter().flat_map(move |placement| {
+        let g = &glyphs[placement.index];
+        let scaled = fonts[g.font].glyph(g.id).scaled(scale);
+        glyph::path_events(scaled)
+            .into_iter()
+            .flatten()
+            .map(move |event| placement.transform_event(event))
+    })
+}
+
+// Rotate the vector anticlockwise by the given angle in radians.
+fn rotate(v: Vector2, radians: Scalar) -> Vector2 {
+    let (sin, cos) = radians.sin_cos();
+    vec2(v.x * cos - v.y * sin, v.x * sin + v.y * cos)
+}
@@ -0,0 +1,801 @@
+//! Rich text, in which the font, size, color and decoration may vary between spans of text.
+//!
+//! Rich text is built from a sequence of styled spans via the `rich::Builder`. Lines are broken
+//! across spans via the same `line::Infos` iterator used for plain **Text**, so that a single
+//! wrapped and justified line may contain many spans. The bidirectional algorithm is applied to
+//! each line as a whole, so that right-to-left text split across many spans is displayed in the
+//! correct order. Each span is then shaped separately within each run of a single direction,
+//! meaning that kerning and ligatures apply within, but not across, spans.
+
+use crate::color::{DefaultScalar, IntoLinSrgba, LinSrgba};
+use crate::geom::{self, Range, Rect};
+use crate::serde_derive::{Deserialize, Serialize};
+use crate::text::{
+    self, cursor, font, layout, line, shape, Align, Font, FontSize, Justify, Layout,
+    PositionedGlyph, Scalar, ScaledGlyph, Wrap,
+};
+use std::ops;
+
+/// The styling applied to a single span of rich text.
+///
+/// Fields that are `None` fall back to those of the text's `Layout`. Spans without a color are
+/// drawn with the color of the text as a whole.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Style {
+    /// The font within the `font::Map` provided when building the text.
+    pub font: Option<font::Id>,
+    /// The font size of the span.
+    pub font_size: Option<FontSize>,
+    /// The color of the span's glyphs and decorations.
+    pub color: Option<LinSrgba>,
+    /// Whether or not a line should be drawn beneath the span.
+    pub underline: bool,
+    /// Whether or not a line should be drawn through the middle of the span.
+    pub strikethrough: bool,
+    /// Additional space inserted after each grapheme cluster.
+    pub letter_spacing: Scalar,
+}
+
+/// A range of text along with the style with which it is displayed.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Span {
+    /// The range of bytes within the text occupied by the span.
+    pub byte_range: ops::Range<usize>,
+    /// The style applied to the span.
+    pub style: Style,
+}
+
+/// A context for building some rich **Text** from styled spans.
+#[derive(Clone, Debug, Default)]
+pub struct Builder {
+    text: String,
+    spans: Vec<Span>,
+    layout_builder: layout::Builder,
+}
+
+/// An instance of some multi-line rich text and its layout.
+#[derive(Clone)]
+pub struct Text {
+    text: String,
+    spans: Vec<Span>,
+    // The font size and chain of fonts resolved for each span, i.e. the span's font followed by
+    // the fallback fonts of the layout.
+    fonts: Vec<Vec<Font>>,
+    font_sizes: Vec<FontSize>,
+    layout: Layout,
+    line_infos: Vec<line::Info>,
+    lines: Vec<Line>,
+    rect: Rect,
+}
+
+/// A glyph positioned within some rich **Text**.
+#[derive(Clone)]
+pub struct Glyph {
+    /// The index of the span to which the glyph belongs.
+    pub span: usize,
+    /// The glyph scaled to the font size of its span.
+    pub glyph: ScaledGlyph<'static>,
+    /// The position of the glyph's origin along the baseline of its line.
+    pub position: text::Point,
+    /// The bounding rectangle of the glyph's outline.
+    ///
+    /// Glyphs without an outline, e.g. spaces, have a rect of zero height along the baseline
+    /// spanning their advance.
+    pub rect: Rect,
+}
+
+/// The lines that may be drawn across a span of text.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Decoration {
+    Underline,
+    Strikethrough,
+}
+
+// A single laid out line of rich text.
+#[derive(Clone, Debug)]
+struct Line {
+    rect: Rect,
+    shaped: ShapedLine,
+}
+
+// A line of text shaped one span at a time.
+//
+// Byte ranges are relative to the start of the line and *x* positions are relative to the start
+// of the line's baseline.
+#[derive(Clone, Debug, Default)]
+struct ShapedLine {
+    clusters: Vec<shape::Cluster>,
+    // Each glyph along with the index of its span.
+    glyphs: Vec<(usize, shape::Glyph)>,
+    // The span index and the range along the *x* axis of each span within the line.
+    pieces: Vec<(usize, Range)>,
+    width: Scalar,
+    height: Scalar,
+}
+
+// The spans of some text along with their resolved fonts and font sizes.
+struct Runs<'a> {
+    text: &'a str,
+    spans: &'a [Span],
+    faces: Vec<shape::Faces<'a>>,
+    font_sizes: &'a [FontSize],
+}
+
+// The thickness of decorations as a fraction of the font size in pixels.
+const DECORATION_THICKNESS: Scalar = 1.0 / 16.0;
+// The distance from the baseline to the middle of an underline as a fraction of the font size in
+// pixels.
+const UNDERLINE_OFFSET: Scalar = 0.1;
+
+impl Style {
+    /// The default style, inheriting the font, font size and color of the text.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// The font within the `font::Map` provided when building the text.
+    pub fn font(mut self, id: font::Id) -> Self {
+        self.font = Some(id);
+        self
+    }
+
+    /// The font size to use for the span.
+    pub fn font_size(mut self, size: FontSize) -> Self {
+        self.font_size = Some(size);
+        self
+    }
+
+    /// The color with which the span should be drawn.
+    pub fn color<C>(mut self, color: C) -> Self
+    where
+        C: IntoLinSrgba<DefaultScalar>,
+    {
+        self.color = Some(color.into_lin_srgba());
+        self
+    }
+
+    /// Draw a line beneath the span.
+    pub fn underline(mut self) -> Self {
+        self.underline = true;
+        self
+    }
+
+    /// Draw a line through the middle of the span.
+    pub fn strikethrough(mut self) -> Self {
+        self.strikethrough = true;
+        self
+    }
+
+    /// Insert the given space after each grapheme cluster within the span.
+    pub fn letter_spacing(mut self, spacing: Scalar) -> Self {
+        self.letter_spacing = spacing;
+        self
+    }
+}
+
+impl Builder {
+    /// Begin building some rich text with no spans.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Begin building rich text from the given text and spans.
+    ///
+    /// Spans are expected to be ordered by their position within the text. Any overlap with a
+    /// preceding span is ignored, while bytes that are not covered by any span use the default
+    /// `Style`. The edges of spans that fall within a multi-byte `char` are moved back to the start
+    /// of that `char`.
+    pub fn with_spans<I>(text: &str, spans: I) -> Self
+    where
+        I: IntoIterator<Item = Span>,
+    {
+        Builder {
+            text: text.to_string(),
+            spans: spans.into_iter().collect(),
+            layout_builder: Default::default(),
+        }
+    }
+
+    /// Apply the given function to the inner text layout.
+    fn map_layout<F>(mut self, map: F) -> Self
+    where
+        F: FnOnce(layout::Builder) -> layout::Builder,
+    {
+        self.layout_builder = map(self.layout_builder);
+        self
+    }
+
+    /// Append a span of text displayed with the given style.
+    pub fn span(mut self, text: &str, style: Style) -> Self {
+        let start = self.text.len();
+        self.text.push_str(text);
+        let byte_range = start..self.text.len();
+        self.spans.push(Span { byte_range, style });
+        self
+    }
+
+    /// The font size used by spans that do not specify their own.
+    pub fn font_size(self, size: FontSize) -> Self {
+        self.map_layout(|l| l.font_size(size))
+    }
+
+    /// Specify whether or not text should be wrapped around some width and how to do so.
+    ///
+    /// The default value is `DEFAULT_LINE_WRAP`.
+    pub fn line_wrap(self, line_wrap: Option<Wrap>) -> Self {
+        self.map_layout(|l| l.line_wrap(line_wrap))
+    }
+
+    /// Specify that the **Text** should not wrap lines around the width.
+    pub fn no_line_wrap(self) -> Self {
+        self.map_layout(|l| l.no_line_wrap())
+    }
+
+    /// Line wrap the **Text** at the beginning of the first word that exceeds the width.
+    pub fn wrap_by_word(self) -> Self {
+        self.map_layout(|l| l.wrap_by_word())
+    }
+
+    /// Line wrap the **Text** at the beginning of the first character that exceeds the width.
+    pub fn wrap_by_character(self) -> Self {
+        self.map_layout(|l| l.wrap_by_character())
+    }
+
+    /// The `Font` used by spans that do not specify their own.
+    pub fn font(self, font: Font) -> Self {
+        self.map_layout(|l| l.font(font))
+    }
+
+    /// Append a font to the chain of fonts used for characters that a span's font has no glyph
+    /// for.
+    pub fn fallback_font(self, font: Font) -> Self {
+        self.map_layout(|l| l.fallback_font(font))
+    }
+
+    /// Describe the end along the *x* axis to which the text should be aligned.
+    pub fn justify(self, justify: Justify) -> Self {
+        self.map_layout(|l| l.justify(justify))
+    }
+
+    /// Specify how much vertical space should separate each line of text.
+    pub fn line_spacing(self, spacing: Scalar) -> Self {
+        self.map_layout(|l| l.line_spacing(spacing))
+    }
+
+    /// Specify how the whole text should be aligned along the y axis of its bounding rectangle
+    pub fn y_align(self, align: Align) -> Self {
+        self.map_layout(|l| l.y_align(align))
+    }
+
+    /// Set all the parameters via an existing `Layout`
+    pub fn layout(self, layout: &Layout) -> Self {
+        self.map_layout(|l| l.layout(layout))
+    }
+
+    /// Build the text.
+    ///
+    /// Fonts are looked up within the given `fonts` map by the `font::Id` of each span's
+    /// `Style`. Spans without a font, or whose font is missing from the map, use the font of the
+    /// layout.
+    ///
+    /// The given `rect` will be used for applying the layout including text alignment, positioning
+    /// of text, multi-line wrapping, etc.
+    pub fn build(self, rect: Rect, fonts: &font::Map) -> Text {
+        let Builder {
+            text,
+            spans,
+            layout_builder,
+        } = self;
+        let layout = layout_builder.build();
+        let font = layout.font.clone().unwrap_or_else(text::default_font);
+        let spans = tile_spans(&text, spans);
+        let span_fonts: Vec<Vec<Font>> = spans
+            .iter()
+            .map(|span| {
+                let font = span
+                    .style
+                    .font
+                    .and_then(|id| fonts.get(id))
+                    .unwrap_or(&font);
+                std::iter::once(font)
+                    .chain(&layout.fallback_fonts)
+                    .cloned()
+                    .collect()
+            })
+            .collect();
+        let font_sizes: Vec<FontSize> = spans
+            .iter()
+            .map(|span| span.style.font_size.unwrap_or(layout.font_size))
+            .collect();
+
+        let runs = Runs {
+            text: &text,
+            spans: &spans,
+            faces: span_fonts
+                .iter()
+                .map(|fonts| shape::Faces::new(fonts))
+                .collect(),
+            font_sizes: &font_sizes,
+        };
+
+        // Break the text into lines via the spans rather than the single font of the `Infos`.
+        let (wrap, max_width) = match layout.line_wrap {
+            Some(wrap) => (Some(wrap), rect.w()),
+            None => (None, std::f32::MAX),
+        };
+        let next_break = |line: &str, _: &Font, _: FontSize, max_width: Scalar| {
+            runs.next_break(text.len() - line.len(), wrap, max_width)
+        };
+        let line_infos: Vec<line::Info> =
+            line::infos_wrapped_by(&text, &font, layout.font_size, max_width, next_break).collect();
+
+        // Stack the lines, each as tall as the greatest font size within it.
+        let x_bounds = Range::new(0.0, rect.w());
+        let mut top = 0.0;
+        let mut lines: Vec<Line> = line_infos
+            .iter()
+            .map(|info| {
+                let shaped = runs.shape(info.byte_range());
+                let font_size = shaped
+                    .pieces
+                    .iter()
+                    .map(|&(span, _)| font_sizes[span])
+                    .max()
+                    .unwrap_or_else(|| runs.font_size_at(info.start_byte, layout.font_size));
+                let range = Range::new(0.0, shaped.width);
+                let x = match layout.justify {
+                    Justify::Left => range.align_start_of(x_bounds),
+                    Justify::Center => range.align_middle_of(x_bounds),
+                    Justify::Right => range.align_end_of(x_bounds),
+                };
+                let y = Range::new(top - font_size as Scalar, top);
+                top = y.start - layout.line_spacing;
+                Line {
+                    rect: Rect { x, y },
+                    shaped,
+                }
+            })
+            .collect();
+
+        // Shift the lines into the bounding rect.
+        let total_height = lines.first().map(|l| l.rect.top()).unwrap_or(0.0)
+            - lines.last().map(|l| l.rect.bottom()).unwrap_or(0.0);
+        let total_y = Range::new(0.0, total_height);
+        let total_y = match layout.y_align {
+            Align::Start => total_y.align_start_of(rect.y),
+            Align::Middle => total_y.align_middle_of(rect.y),
+            Align::End => total_y.align_end_of(rect.y),
+        };
+        let offset = geom::vec2(rect.x.start, total_y.end);
+        for line in &mut lines {
+            line.rect = line.rect.shift(offset);
+        }
+
+        Text {
+            text,
+            spans,
+            fonts: span_fonts,
+            font_sizes,
+            layout,
+            line_infos,
+            lines,
+            rect,
+        }
+    }
+}
+
+impl Text {
+    /// The full string of text as a slice.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The spans of the text, covering the whole text in order.
+    pub fn spans(&self) -> &[Span] {
+        &self.spans
+    }
+
+    /// The font used by the span at the given index.
+    pub fn span_font(&self, span: usize) -> &Font {
+        &self.fonts[span][0]
+    }
+
+    /// The font used by the span at the given index followed by the fallback fonts of the layout.
+    pub fn span_fonts(&self, span: usize) -> &[Font] {
+        &self.fonts[span]
+    }
+
+    /// The font size used by the span at the given index.
+    pub fn span_font_size(&self, span: usize) -> FontSize {
+        self.font_sizes[span]
+    }
+
+    /// The layout parameters for this text instance.
+    pub fn layout(&self) -> &Layout {
+        &self.layout
+    }
+
+    /// Information about each line.
+    pub fn line_infos(&self) -> &[line::Info] {
+        &self.line_infos
+    }
+
+    /// The number of lines in the text.
+    pub fn num_lines(&self) -> usize {
+        self.line_infos.len()
+    }
+
+    /// The rectangle used to layout and build the text instance.
+    pub fn layout_rect(&self) -> Rect {
+        self.rect
+    }
+
+    /// The bounding rectangle for each line.
+    ///
+    /// The height of each line is equal to the greatest font size within it.
+    pub fn line_rects<'a>(&'a self) -> impl 'a + Iterator<Item = Rect> {
+        self.lines.iter().map(|line| line.rect)
+    }
+
+    /// The rectangle that describes the min and max bounds along each axis reached by all lines.
+    pub fn bounding_rect(&self) -> Rect {
+        let mut rects = self.line_rects();
+        let first = match rects.next() {
+            None => return Rect::from_w_h(0.0, 0.0),
+            Some(rect) => rect,
+        };
+        rects.fold(first, |acc, r| Rect {
+            x: Range::new(acc.x.start.min(r.x.start), acc.x.end.max(r.x.end)),
+            y: Range::new(acc.y.start.min(r.y.start), acc.y.end.max(r.y.end)),
+        })
+    }
+
+    /// The width of the widest line of text.
+    pub fn width(&self) -> Scalar {
+        self.line_infos
+            .iter()
+            .fold(0.0, |max, info| max.max(info.width))
+    }
+
+    /// Produce an iterator yielding every glyph along with its position and bounding rect.
+    pub fn glyphs<'a>(&'a self) -> impl 'a + Iterator<Item = Glyph> {
+        self.lines.iter().flat_map(move |line| {
+            let origin = line.rect.bottom_left();
+            line.shaped.glyphs.iter().map(move |&(span, ref g)| {
+                let scale = text::pt_to_scale(self.font_sizes[span]);
+                let glyph = self.fonts[span][g.font].glyph(g.id).scaled(scale);
+                let position = origin + g.position;
+                let rect = match glyph.exact_bounding_box() {
+                    Some(bb) => Rect {
+                        x: Range::new(position.x + bb.min.x, position.x + bb.max.x),
+                        y: Range::new(position.y - bb.max.y, position.y - bb.min.y),
+                    },
+                    None => Rect {
+                        x: Range::new(position.x, position.x + g.advance),
+                        y: Range::new(position.y, position.y),
+                    },
+                };
+                Glyph {
+                    span,
+                    glyph,
+                    position,
+                    rect,
+                }
+            })
+        })
+    }
+
+    /// Produce an iterator yielding a `Rect` for the selected range in each selected line.
+    ///
+    /// Each `Rect` spans the height of its line. Lines that do not contain any selected grapheme
+    /// clusters are skipped.
+    pub fn selected_rects<'a>(
+        &'a self,
+        start: cursor::Index,
+        end: cursor::Index,
+    ) -> impl 'a + Iterator<Item = Rect> {
+        self.lines.iter().enumerate().filter_map(move |(i, line)| {
+            let clusters = if i < start.line || i > end.line {
+                return None;
+            } else if i == start.line && i == end.line {
+                start.cluster..end.cluster
+            } else if i == start.line {
+                start.cluster..std::usize::MAX
+            } else if i == end.line {
+                0..end.cluster
+            } else {
+                0..std::usize::MAX
+            };
+            let x = line
+                .shaped
+                .clusters
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| clusters.start <= j && j < clusters.end)
+                .map(|(_, cluster)| cluster.x)
+                .fold(None, |acc: Option<Range>, x| match acc {
+                    None => Some(x),
+                    Some(acc) => Some(acc.max(x)),
+                })?;
+            let x = x.shift(line.rect.x.start);
+            Some(Rect { x, y: line.rect.y })
+        })
+    }
+
+    /// Produce an iterator yielding the rect of every underline and strikethrough along with the
+    /// index of its span.
+    pub fn decorations<'a>(&'a self) -> impl 'a + Iterator<Item = (usize, Decoration, Rect)> {
+        self.lines.iter().flat_map(move |line| {
+            line.shaped.pieces.iter().flat_map(move |&(span, x)| {
+                let style = &self.spans[span].style;
+                let font = &self.fonts[span][0];
+                let scale = text::pt_to_scale(self.font_sizes[span]);
+                let thickness = scale.y * DECORATION_THICKNESS;
+                let x = x.shift(line.rect.x.start);
+                let baseline = line.rect.bottom();
+                let rect = |y: Scalar| Rect {
+                    x,
+                    y: Range::new(y - thickness / 2.0, y + thickness / 2.0),
+                };
+                let underline = if style.underline {
+                    let y = baseline - scale.y * UNDERLINE_OFFSET;
+                    Some((span, Decoration::Underline, rect(y)))
+                } else {
+                    None
+                };
+                let strikethrough = if style.strikethrough {
+                    let y = baseline + strikethrough_height(font, scale);
+                    Some((span, Decoration::Strikethrough, rect(y)))
+                } else {
+                    None
+                };
+                underline.into_iter().chain(strikethrough)
+            })
+        })
+    }
+
+    /// Produce an iterator yielding the index of the span and the path events for each glyph.
+    pub fn path_events<'a>(
+        &'a self,
+    ) -> impl 'a + Iterator<Item = (usize, Vec<lyon::path::PathEvent>)> {
+        self.glyphs().filter_map(|g| {
+            let bb = g.glyph.exact_bounding_box()?;
+            let point = text::rt::point(g.position.x, g.position.y);
+            let contours = g.glyph.positioned(point).shape()?;
+            let events = text::glyph::contours_to_path(bb, contours).collect();
+            Some((g.span, events))
+        })
+    }
+
+    /// Produce an iterator yielding positioned rusttype glyphs ready for caching, along with the
+    /// index of their span and the index of their font within the span's `span_fonts`.
+    ///
+    /// The window dimensions (in logical space) and scale_factor are required to transform glyph
+    /// positions into rusttype's pixel-space, ready for caching into the rusttype glyph cache
+    /// pixel buffer.
+    pub fn rt_glyphs<'a>(
+        &'a self,
+        window_size: geom::Vector2,
+        scale_factor: Scalar,
+    ) -> impl 'a + Iterator<Item = (usize, usize, PositionedGlyph)> {
+        // Functions for converting nannou coordinates to rusttype pixel coordinates.
+        let trans_x = move |x: Scalar| (x + window_size.x / 2.0) * scale_factor;
+        let trans_y = move |y: Scalar| ((-y) + window_size.y / 2.0) * scale_factor;
+        self.lines.iter().flat_map(move |line| {
+            let origin = line.rect.bottom_left();
+            line.shaped.glyphs.iter().map(move |&(span, ref g)| {
+                let scale = text::f32_pt_to_scale(self.font_sizes[span] as f32 * scale_factor);
+                let point = text::rt::Point {
+                    x: trans_x(origin.x) + g.position.x * scale_factor,
+                    y: trans_y(origin.y) - g.position.y * scale_factor,
+                };
+                let glyph = self.fonts[span][g.font]
+                    .glyph(g.id)
+                    .scaled(scale)
+                    .positioned(point)
+                    .standalone();
+                (span, g.font, glyph)
+            })
+        })
+    }
+}
+
+impl<'a> Runs<'a> {
+    // The font size of the span containing the given byte, used for empty lines.
+    fn font_size_at(&self, byte: usize, default: FontSize) -> FontSize {
+        self.spans
+            .iter()
+            .rposition(|span| span.byte_range.start <= byte)
+            .map(|i| self.font_sizes[i])
+            .unwrap_or(default)
+    }
+
+    // Shape the given range of text one span at a time within each run of a single direction.
+    fn shape(&self, range: ops::Range<usize>) -> ShapedLine {
+        let mut line = ShapedLine::default();
+        for (run, rtl) in shape::visual_runs(&self.text[range.clone()]) {
+            let run = range.start + run.start..range.start + run.end;
+            self.shape_run(&mut line, range.start, run, rtl);
+        }
+
+        // Restore the order of the clusters within the text, as the runs are shaped in the order
+        // in which they are displayed.
+        let mut order: Vec<usize> = (0..line.clusters.len()).collect();
+        order.sort_by_key(|&ix| line.clusters[ix].byte_range.start);
+        let mut new_index = vec![0; order.len()];
+        for (new, &old) in order.iter().enumerate() {
+            new_index[old] = new;
+        }
+        line.clusters = order.iter().map(|&ix| line.clusters[ix].clone()).collect();
+        for (_, g) in &mut line.glyphs {
+            g.cluster = new_index[g.cluster];
+        }
+        line
+    }
+
+    // Append the spans within the given run of a single direction to the line in the order in
+    // which they are displayed.
+    fn shape_run(
+        &self,
+        line: &mut ShapedLine,
+        line_start: usize,
+        run: ops::Range<usize>,
+        rtl: bool,
+    ) {
+        let mut spans: Vec<usize> = (0..self.spans.len())
+            .filter(|&ix| {
+                let span = &self.spans[ix].byte_range;
+                span.start < run.end && run.start < span.end
+            })
+            .collect();
+        if rtl {
+            spans.reverse();
+        }
+        for span_ix in spans {
+            let span = &self.spans[span_ix];
+            let start = span.byte_range.start.max(run.start);
+            let end = span.byte_range.end.min(run.end);
+            let scale = text::pt_to_scale(self.font_sizes[span_ix]);
+            let span_text = &self.text[start..end];
+            let runs = vec![(0..span_text.len(), rtl)];
+            let shaped = shape::line_with_runs(span_text, &self.faces[span_ix], scale, runs);
+            let height = shaped.height();
+            let (clusters, glyphs, width) = letter_spaced(shaped, span.style.letter_spacing);
+            let byte_offset = start - line_start;
+            let cluster_offset = line.clusters.len();
+            let x = line.width;
+            line.clusters.extend(clusters.into_iter().map(|mut c| {
+                c.byte_range = c.byte_range.start + byte_offset..c.byte_range.end + byte_offset;
+                c.x = c.x.shift(x);
+                c
+            }));
+            line.glyphs.extend(glyphs.into_iter().map(|mut g| {
+                g.cluster += cluster_offset;
+                g.position.x += x;
+                (span_ix, g)
+            }));
+            line.pieces.push((span_ix, Range::new(x, x + width)));
+            line.width += width;
+            line.height = line.height.max(height);
+        }
+    }
+
+    // Produce the next break within the text following the given byte.
+    fn next_break(&self, start: usize, wrap: Option<Wrap>, max_width: Scalar) -> line::NextBreak {
+        let rest = &self.text[start..];
+        let (byte, newline_len) = line::next_newline(rest);
+        let shaped = self.shape(start..start + byte);
+        let cluster = shaped.clusters.len();
+        let wrap_break = wrap
+            .and_then(|wrap| line::wrap_break(&rest[..byte], &shaped.clusters, wrap, max_width));
+        match wrap_break {
+            Some(break_) => {
+                let shaped = self.shape(start..start + break_.byte_index());
+                line::NextBreak {
+                    break_,
+                    width: shaped.width,
+                    height: shaped.height,
+                }
+            }
+            None => {
+                let break_ = match newline_len {
+                    Some(len_bytes) => line::Break::Newline {
+                        byte,
+                        cluster,
+                        len_bytes,
+                    },
+                    None => line::Break::End { byte, cluster },
+                };
+                line::NextBreak {
+                    break_,
+                    width: shaped.width,
+                    height: shaped.height,
+                }
+            }
+        }
+    }
+}
+
+// Insert the given spacing after each cluster of the shaped line in the order in which they are
+// displayed, producing the clusters, glyphs and width of the spaced line.
+fn letter_spaced(
+    line: shape::Line,
+    spacing: Scalar,
+) -> (Vec<shape::Cluster>, Vec<shape::Glyph>, Scalar) {
+    let width = line.width() + spacing * line.clusters().len() as Scalar;
+    let mut clusters = line.clusters().to_vec();
+    let mut order: Vec<usize> = (0..clusters.len()).collect();
+    order.sort_by(|&a, &b| {
+        let (a, b) = (clusters[a].x.start, clusters[b].x.start);
+        a.partial_cmp(&b).unwrap_or(std::cmp::Ordering::Equal)
+    });
+    let mut shift = vec![0.0; clusters.len()];
+    for (rank, &ix) in order.iter().enumerate() {
+        shift[ix] = rank as Scalar * spacing;
+    }
+    for (cluster, &shift) in clusters.iter_mut().zip(&shift) {
+        cluster.x = Range::new(cluster.x.start + shift, cluster.x.end + shift + spacing);
+    }
+    let mut glyphs = line.into_glyphs();
+    for g in &mut glyphs {
+        g.position.x += shift[g.cluster];
+    }
+    (clusters, glyphs, width)
+}
+
+// Ensure the spans cover the whole text in order without overlapping, with edges on `char`
+// boundaries.
+fn tile_spans(text: &str, spans: Vec<Span>) -> Vec<Span> {
+    let mut tiled = vec![];
+    let mut end = 0;
+    for span in spans {
+        let start = floor_char_boundary(text, span.byte_range.start).max(end);
+        let span_end = floor_char_boundary(text, span.byte_range.end);
+        if start >= span_end {
+            continue;
+        }
+        if end < start {
+            tiled.push(Span {
+                byte_range: end..start,
+                style: Style::default(),
+            });
+        }
+        tiled.push(Span {
+            byte_range: start..span_end,
+            style: span.style,
+        });
+        end = span_end;
+    }
+    if end < text.len() {
+        tiled.push(Span {
+            byte_range: end..text.len(),
+            style: Style::default(),
+        });
+    }
+    tiled
+}
+
+// The greatest `char` boundary within the text that is less than or equal to the given byte.
+fn floor_char_boundary(text: &str, byte: usize) -> usize {
+    if byte >= text.len() {
+        return text.len();
+    }
+    (0..=byte)
+        .rev()
+        .find(|&i| text.is_char_boundary(i))
+        .unwrap_or(0)
+}
+
+// The height above the baseline of the middle of a strikethrough, half the height of an `x`.
+fn strikethrough_height(font: &Font, scale: text::Scale) -> Scalar {
+    font.glyph('x')
+        .scaled(scale)
+        .exact_bounding_box()
+        .map(|bb| -bb.min.y / 2.0)
+        .unwrap_or(scale.y * 0.25)
+}
+
+/// Begin building some rich **Text**.
+pub fn text() -> Builder {
+    Builder::new()
+}
@@ -0,0 +1,346 @@
+//! Signed distance field glyph generation and packing.
+//!
+//! Rather than rasterising the coverage of each glyph at every size at which it is displayed, the
+//! distance from each pixel to the glyph's outline is stored once at a fixed `size`. Sampling the
+//! field with bilinear filtering and thresholding the result about the outline produces crisp
+//! edges at any scale, while thresholding about other distances produces outlines and glows.
+//!
+//! Fields are packed into an `Atlas`, a single-channel texture in which each pixel stores the
+//! distance mapped to the range `0` to `255`. A value of `128` lies on the outline, greater values
+//! lie inside the glyph and the distance saturates `spread` pixels from the outline.
+
+use crate::text::{font, rt, Font, GlyphId, Scale, ScaledGlyph};
+use std::collections::HashMap;
+
+/// The signed distance field of a single glyph.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Field {
+    /// The number of pixels along each row of the field.
+    pub width: u32,
+    /// The number of rows within the field.
+    pub height: u32,
+    /// The distance of each pixel from the outline, row by row from the top.
+    pub data: Vec<u8>,
+    /// The rectangle covered by the field relative to the glyph's origin, in rusttype's pixel
+    /// coordinates at the scale at which the field was generated, where *y* points down.
+    pub bounds: rt::Rect<f32>,
+}
+
+/// A texture into which the signed distance fields of glyphs are packed.
+#[derive(Clone, Debug)]
+pub struct Atlas {
+    width: u32,
+    height: u32,
+    size: f32,
+    spread: f32,
+    pixel_buffer: Vec<u8>,
+    entries: HashMap<(font::Id, GlyphId), Option<Entry>>,
+    // The position of the next field within the current shelf and the height of the shelf.
+    cursor: [u32; 2],
+    shelf_height: u32,
+    requires_upload: bool,
+    // Whether or not a field failed to fit within the atlas since the last call to `begin_frame`.
+    full: bool,
+}
+
+/// The location of a glyph's field within the **Atlas**.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Entry {
+    /// The rectangle occupied by the field in normalised texture coordinates.
+    pub uv_rect: rt::Rect<f32>,
+    /// The rectangle covered by the field relative to the glyph's origin, in rusttype's pixel
+    /// coordinates at the atlas `size`, where *y* points down.
+    pub bounds: rt::Rect<f32>,
+}
+
+/// Returned by **Atlas::glyph** when there is no room remaining for the requested glyph.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct AtlasFull;
+
+/// The default scale in pixels at which glyph fields are generated.
+pub const DEFAULT_SIZE: f32 = 48.0;
+/// The default distance in pixels from the outline at which fields saturate.
+pub const DEFAULT_SPREAD: f32 = 6.0;
+
+// The number of empty pixels separating fields within the atlas, avoiding bleeding between
+// neighbours when sampled.
+const PADDING: u32 = 1;
+
+impl Field {
+    /// The distance of the pixel at the given column and row, mapped to the range `-1.0` (`spread`
+    /// pixels outside the outline) to `1.0` (`spread` pixels inside).
+    pub fn distance(&self, x: u32, y: u32) -> f32 {
+        let value = self.data[(y * self.width + x) as usize];
+        value as f32 / 127.5 - 1.0
+    }
+}
+
+impl Atlas {
+    /// Create an empty atlas of the given dimensions in pixels.
+    ///
+    /// Glyphs are generated at the given `size` in pixels, and their fields saturate at `spread`
+    /// pixels from the outline. Larger sizes preserve finer details at the cost of space, while a
+    /// larger spread allows for wider outlines and glows.
+    pub fn new([width, height]: [u32; 2], size: f32, spread: f32) -> Self {
+        Atlas {
+            width,
+            height,
+            size,
+            spread,
+            pixel_buffer: vec![0; width as usize * height as usize],
+            entries: HashMap::new(),
+            cursor: [0, 0],
+            shelf_height: 0,
+            requires_upload: false,
+            full: false,
+        }
+    }
+
+    /// The dimensions of the atlas in pixels.
+    pub fn dimensions(&self) -> [u32; 2] {
+        [self.width, self.height]
+    }
+
+    /// The scale in pixels at which glyph fields are generated.
+    pub fn size(&self) -> f32 {
+        self.size
+    }
+
+    /// The distance in pixels from the outline at which fields saturate.
+    pub fn spread(&self) -> f32 {
+        self.spread
+    }
+
+    /// The pixels of the atlas, row by row from the top.
+    pub fn pixel_buffer(&self) -> &[u8] {
+        &self.pixel_buffer
+    }
+
+    /// Whether or not fields have been added to the pixel buffer since the last call to
+    /// `uploaded`.
+    pub fn requires_upload(&self) -> bool {
+        self.requires_upload
+    }
+
+    /// Indicate that the pixel buffer has been uploaded to the GPU.
+    pub fn uploaded(&mut self) {
+        self.requires_upload = false;
+    }
+
+    /// Whether or not a field has failed to fit within the atlas since the last call to
+    /// `begin_frame`.
+    pub fn is_full(&self) -> bool {
+        self.full
+    }
+
+    /// Prepare the atlas for a new frame.
+    ///
+    /// If a field failed to fit within the atlas during the previous frame, the atlas is cleared
+    /// so that it may be refilled with the glyphs that are in use. This must be called before any
+    /// entries are looked up for the new frame, as clearing invalidates existing entries.
+    pub fn begin_frame(&mut self) {
+        if self.full {
+            self.clear();
+        }
+    }
+
+    /// Remove all fields from the atlas.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.cursor = [0, 0];
+        self.shelf_height = 0;
+        self.full = false;
+        for px in &mut self.pixel_buffer {
+            *px = 0;
+        }
+        self.requires_upload = true;
+    }
+
+    /// Produce the entry for the given glyph, generating its field and packing it into the atlas
+    /// if it has not been already.
+    ///
+    /// Returns `Ok(None)` for glyphs without an outline, e.g. spaces.
+    ///
+    /// Returns `Err(AtlasFull)` if there is no room remaining for the field. In this case the atlas
+    /// is marked as full and will be cleared by the next call to `begin_frame`.
+    pub fn glyph(&mut self, font: &Font, id: GlyphId) -> Result<Option<Entry>, AtlasFull> {
+        let key = (font::id(font), id);
+        if let Some(&entry) = self.entries.get(&key) {
+            return Ok(entry);
+        }
+        let scaled = font.glyph(id).scaled(Scale::uniform(self.size));
+        let entry = match field(&scaled, self.spread) {
+            None => None,
+            Some(field) => match self.insert(&field) {
+                Ok(entry) => Some(entry),
+                Err(AtlasFull) => {
+                    self.full = true;
+                    return Err(AtlasFull);
+                }
+            },
+        };
+        self.entries.insert(key, entry);
+        Ok(entry)
+    }
+
+    // Pack the field into the next available space on the current or a new shelf.
+    fn insert(&mut self, field: &Field) -> Result<Entry, AtlasFull> {
+        let (w, h) = (field.width + PADDING, field.height + PADDING);
+        if w > self.width || h > self.height {
+            return Err(AtlasFull);
+        }
+        if self.cursor[0] + w > self.width {
+            self.cursor = [0, self.cursor[1] + self.shelf_height];
+            self.shelf_height = 0;
+        }
+        if self.cursor[1] + h > self.height {
+            return Err(AtlasFull);
+        }
+        let [x, y] = self.cursor;
+        self.cursor[0] += w;
+        self.shelf_height = std::cmp::max(self.shelf_height, h);
+
+        // Copy the field into the pixel buffer row by row.
+        let field_w = field.width as usize;
+        for (row, src) in field.data.chunks(field_w).enumerate() {
+            let start = (y as usize + row) * self.width as usize + x as usize;
+            self.pixel_buffer[start..start + field_w].copy_from_slice(src);
+        }
+        self.requires_upload = true;
+
+        let (atlas_w, atlas_h) = (self.width as f32, self.height as f32);
+        let uv_rect = rt::Rect {
+            min: rt::point(x as f32 / atlas_w, y as f32 / atlas_h),
+            max: rt::point(
+                (x + field.width) as f32 / atlas_w,
+                (y + field.height) as f32 / atlas_h,
+            ),
+        };
+        let bounds = field.bounds;
+        Ok(Entry { uv_rect, bounds })
+    }
+}
+
+/// Generate the signed distance field for the given glyph at its scale.
+///
+/// The field covers the glyph's bounding box extended by `spread` pixels on every side. Contours
+/// are filled according to the non-zero winding rule.
+///
+/// Returns `None` if the glyph has no outline.
+pub fn field(glyph: &ScaledGlyph, spread: f32) -> Option<Field> {
+    let segments = flatten(&glyph.shape()?);
+    if segments.is_empty() {
+        return None;
+    }
+
+    // Find the bounding box of the outline, extended by the spread and snapped to whole pixels.
+    let (mut min, mut max) = (segments[0][0], segments[0][0]);
+    for p in segments.iter().flat_map(|s| s.iter()) {
+        min = rt::point(min.x.min(p.x), min.y.min(p.y));
+        max = rt::point(max.x.max(p.x), max.y.max(p.y));
+    }
+    let min = rt::point((min.x - spread).floor(), (min.y - spread).floor());
+    let max = rt::point((max.x + spread).ceil(), (max.y + spread).ceil());
+    let width = (max.x - min.x) as u32;
+    let height = (max.y - min.y) as u32;
+
+    let mut data = Vec::with_capacity(width as usize * height as usize);
+    for row in 0..height {
+        for col in 0..width {
+            let p = rt::point(min.x + col as f32 + 0.5, min.y + row as f32 + 0.5);
+            let mut distance = signed_distance(&segments, p);
+            distance = (distance / spread).max(-1.0).min(1.0);
+            data.push(((distance + 1.0) * 127.5).round() as u8);
+        }
+    }
+
+    let bounds = rt::Rect { min, max };
+    Some(Field {
+        width,
+        height,
+        data,
+        bounds,
+    })
+}
+
+// Approximate the curves of the given contours with line segments, each no longer than roughly a
+// pixel.
+//
+// The contours produced by rusttype point *y* up, so they are flipped to match the rest of
+// rusttype's pixel coordinates.
+fn flatten(contours: &[rt::Contour]) -> Vec<[rt::Point<f32>; 2]> {
+    let flip = |p: rt::Point<f32>| rt::point(p.x, -p.y);
+    let mut segments = vec![];
+    for contour in contours {
+        for segment in &contour.segments {
+            match *segment {
+                rt::Segment::Line(ref line) => segments.push([flip(line.p[0]), flip(line.p[1])]),
+                rt::Segment::Curve(ref curve) => {
+                    let [a, b, c] = [flip(curve.p[0]), flip(curve.p[1]), flip(curve.p[2])];
+                    let len = length(b - a) + length(c - b);
+                    let steps = (len.ceil() as usize).max(1).min(32);
+                    let mut prev = a;
+                    for step in 1..=steps {
+                        let t = step as f32 / steps as f32;
+                        let u = 1.0 - t;
+                        let next = rt::point(
+                            u * u * a.x + 2.0 * u * t * b.x + t * t * c.x,
+                            u * u * a.y + 2.0 * u * t * b.y + t * t * c.y,
+                        );
+                        segments.push([prev, next]);
+                        prev = next;
+                    }
+                }
+            }
+        }
+    }
+    segments
+}
+
+// The distance from the point to the nearest segment, positive if the point lies within the
+// shape according to the non-zero winding rule.
+fn signed_distance(segments: &[[rt::Point<f32>; 2]], p: rt::Point<f32>) -> f32 {
+    let mut min_sq = std::f32::MAX;
+    let mut winding = 0i32;
+    for &[a, b] in segments {
+        // The squared distance to the nearest point on the segment.
+        let ab = b - a;
+        let ap = p - a;
+        let len_sq = ab.x * ab.x + ab.y * ab.y;
+        let t = if len_sq > 0.0 {
+            ((ap.x * ab.x + ap.y * ab.y) / len_sq).max(0.0).min(1.0)
+        } else {
+            0.0
+        };
+        let d = ap - ab * t;
+        min_sq = min_sq.min(d.x * d.x + d.y * d.y);
+
+        // Count the crossings of a ray cast from the point along the positive x axis.
+        let cross = ab.x * ap.y - ab.y * ap.x;
+        if a.y <= p.y {
+            if b.y > p.y && cross > 0.0 {
+                winding += 1;
+            }
+        } else if b.y <= p.y && cross < 0.0 {
+            winding -= 1;
+        }
+    }
+    let distance = min_sq.sqrt();
+    if winding != 0 {
+        distance
+    } else {
+        -distance
+    }
+}
+
+fn length(v: rt::Vector<f32>) -> f32 {
+    (v.x * v.x + v.y * v.y).sqrt()
+}
+
+impl std::error::Error for AtlasFull {}
+
+impl std::fmt::Display for AtlasFull {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        write!(f, "no room remains within the signed distance field atlas")
+    }
+}
@@ -0,0 +1,479 @@
+//! Shaping of lines of text into positioned glyphs.
+//!
+//! Shaping applies a font's OpenType substitution and positioning tables to a line of text,
+//! producing the kerning, ligatures and contextual forms required by scripts like Arabic and
+//! Devanagari. Each line is first split into runs via the Unicode bidirectional algorithm so that
+//! right-to-left text like Arabic and Hebrew is displayed in the correct order.
+//!
+//! Shaping requires the tables parsed from the data retained by each `Font`. Layouts that shape
+//! many lines with the same fonts parse these once via `Faces` and shape each line via
+//! `line_with_faces`. Text using a font whose tables cannot be parsed is laid out one `char` at a
+//! time with the kerning from the font's `kern` table.
+//!
+//! Lines may also be shaped with a chain of fallback fonts via `line_with_fallbacks`, in which
+//! case each grapheme cluster is drawn with the first font in the chain that has a glyph for each
+//! of its characters.
+
+use crate::geom::{pt2, Range};
+use crate::text::{self, Font, GlyphId, Scalar, Scale};
+use std::ops;
+use std::rc::Rc;
+use unicode_bidi::BidiInfo;
+use unicode_script::{Script, UnicodeScript};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// A single line of text shaped into glyphs.
+#[derive(Clone, Debug)]
+pub struct Line {
+    glyphs: Vec<Glyph>,
+    clusters: Vec<Cluster>,
+    width: Scalar,
+}
+
+/// A glyph positioned along a shaped `Line`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Glyph {
+    /// The ID of the glyph within the font.
+    pub id: GlyphId,
+    /// The index of the cluster that the glyph belongs to.
+    pub cluster: usize,
+    /// The position of the glyph's origin relative to the start of the line's baseline.
+    ///
+    /// The *y* axis points upwards.
+    pub position: text::Point,
+    /// The distance from this glyph's origin to the origin of the next.
+    pub advance: Scalar,
+    /// The index of the font within the chain of fonts from which the glyph was produced.
+    ///
+    /// This is always `0` for lines shaped via `line`.
+    pub font: usize,
+}
+
+/// A grapheme cluster within a shaped `Line`.
+///
+/// Clusters are the smallest units of text that may be broken across lines, selected or navigated
+/// by a cursor. A single cluster may be drawn with many glyphs, e.g. a letter and its accents,
+/// while a ligature may draw many clusters with a single glyph.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cluster {
+    /// The range of bytes within the line occupied by the cluster.
+    pub byte_range: ops::Range<usize>,
+    /// The range occupied by the cluster along the *x* axis, relative to the start of the line.
+    pub x: Range,
+    /// Whether or not the cluster is within a right-to-left run of text.
+    pub rtl: bool,
+    /// The greatest height reached by the cluster's glyphs above the baseline.
+    pub height: Scalar,
+}
+
+/// A chain of fonts along with the tables parsed from each for shaping.
+///
+/// Cloning is cheap, as the parsed tables are shared.
+#[derive(Clone)]
+pub struct Faces<'a> {
+    fonts: &'a [Font],
+    faces: Rc<Vec<Option<rustybuzz::Face<'a>>>>,
+}
+
+// A glyph produced by shaping a run of text, prior to its positioning along the line.
+struct Shaped {
+    id: GlyphId,
+    // The byte index within the line of the start of the cluster that produced the glyph.
+    byte: usize,
+    advance: Scalar,
+    offset: (Scalar, Scalar),
+}
+
+impl Line {
+    /// The glyphs of the line ordered from left to right.
+    pub fn glyphs(&self) -> &[Glyph] {
+        &self.glyphs
+    }
+
+    /// Convert the line into its glyphs, ordered from left to right.
+    pub fn into_glyphs(self) -> Vec<Glyph> {
+        self.glyphs
+    }
+
+    /// The clusters of the line in the order in which they appear within the text.
+    pub fn clusters(&self) -> &[Cluster] {
+        &self.clusters
+    }
+
+    /// The total advance of all glyphs within the line.
+    pub fn width(&self) -> Scalar {
+        self.width
+    }
+
+    /// The greatest height reached by all glyphs above the baseline.
+    pub fn height(&self) -> Scalar {
+        self.clusters
+            .iter()
+            .fold(0.0, |height, cluster| cluster.height.max(height))
+    }
+
+    /// The position along the *x* axis of a cursor placed before the cluster at the given index.
+    ///
+    /// An index equal to the number of clusters refers to the position following the last
+    /// cluster.
+    pub fn cursor_x(&self, index: usize) -> Scalar {
+        match self.clusters.get(index) {
+            Some(cluster) if cluster.rtl => cluster.x.end,
+            Some(cluster) => cluster.x.start,
+            None => match self.clusters.last() {
+                Some(cluster) if cluster.rtl => cluster.x.start,
+                Some(cluster) => cluster.x.end,
+                None => 0.0,
+            },
+        }
+    }
+}
+
+impl<'a> Faces<'a> {
+    /// Parse the shaping tables of each font within the chain.
+    ///
+    /// **Panics** if `fonts` is empty.
+    pub fn new(fonts: &'a [Font]) -> Self {
+        assert!(
+            !fonts.is_empty(),
+            "at least one font is required to shape text"
+        );
+        let faces = fonts
+            .iter()
+            .map(|font| rustybuzz::Face::from_slice(font.data(), 0))
+            .collect();
+        Faces {
+            fonts,
+            faces: Rc::new(faces),
+        }
+    }
+
+    /// The chain of fonts.
+    pub fn fonts(&self) -> &'a [Font] {
+        self.fonts
+    }
+}
+
+/// Shape the given line of text into glyphs.
+///
+/// The line is expected to contain no newlines.
+pub fn line(text: &str, font: &Font, scale: Scale) -> Line {
+    line_with_fallbacks(text, std::slice::from_ref(font), scale)
+}
+
+/// Shape the given line of text into glyphs, resolving each grapheme cluster against the given
+/// chain of fonts.
+///
+/// Each cluster is drawn with the first font that has a glyph for each of its characters. Clusters
+/// that no font supports are drawn with the first font. The `font` field of each glyph refers to
+/// the index of its font within the chain.
+///
+/// The line is expected to contain no newlines. The tables of each font are parsed for every
+/// call, so prefer `line_with_faces` when shaping many lines with the same fonts.
+///
+/// **Panics** if `fonts` is empty.
+pub fn line_with_fallbacks(text: &str, fonts: &[Font], scale: Scale) -> Line {
+    line_with_faces(text, &Faces::new(fonts), scale)
+}
+
+/// The same as `line_with_fallbacks`, but shapes the line with tables that have already been
+/// parsed.
+pub fn line_with_faces(text: &str, faces: &Faces, scale: Scale) -> Line {
+    line_with_runs(text, faces, scale, visual_runs(text))
+}
+
+// Shape the line given the byte range of each run in the order in which they are displayed, along
+// with whether or not the run is right-to-left. Clusters outside of the runs occupy no space.
+pub(crate) fn line_with_runs(
+    text: &str,
+    faces: &Faces,
+    scale: Scale,
+    runs: Vec<(ops::Range<usize>, bool)>,
+) -> Line {
+    let fonts = faces.fonts;
+    let mut clusters: Vec<Cluster> = text
+        .grapheme_indices(true)
+        .map(|(i, g)| Cluster {
+            byte_range: i..i + g.len(),
+            x: Range::new(0.0, 0.0),
+            rtl: false,
+            height: 0.0,
+        })
+        .collect();
+    let cluster_fonts: Vec<usize> = clusters
+        .iter()
+        .map(|c| cluster_font(fonts, &text[c.byte_range.clone()]))
+        .collect();
+
+    let mut positioned = vec![false; clusters.len()];
+    let mut glyphs = vec![];
+    let mut x = 0.0;
+
+    for (run, rtl) in runs {
+        let mut script_runs = script_runs(&text[run.clone()]);
+        if rtl {
+            script_runs.reverse();
+        }
+        let mut font_runs = vec![];
+        for script_run in script_runs {
+            let run = run.start + script_run.start..run.start + script_run.end;
+            let mut runs = font_runs_within(&clusters, &cluster_fonts, run);
+            if rtl {
+                runs.reverse();
+            }
+            font_runs.extend(runs);
+        }
+        for (run, font_ix) in font_runs {
+            let font = &fonts[font_ix];
+            let shaped = match faces.faces[font_ix] {
+                Some(ref face) => {
+                    let unit_scale = unit_scale(font, scale);
+                    shape_run(face, text, run.clone(), rtl, unit_scale)
+                }
+                None => layout_run(font, scale, text, run.clone(), rtl),
+            };
+
+            // The start of every cluster produced by shaping the run, in logical order.
+            let mut starts: Vec<usize> = shaped.iter().map(|s| s.byte).collect();
+            starts.sort();
+            starts.dedup();
+
+            // Position each group of glyphs that were shaped from the same cluster, sharing the
+            // width of the group between the grapheme clusters it covers, e.g. for ligatures.
+            let mut shaped = shaped.into_iter().peekable();
+            while let Some(first) = shaped.next() {
+                let byte = first.byte;
+                let cluster = cluster_index(&clusters, byte);
+                let mut group = vec![first];
+                while shaped.peek().map(|s| s.byte) == Some(byte) {
+                    group.extend(shaped.next());
+                }
+                let x_start = x;
+                for s in group {
+                    let height = font
+                        .glyph(s.id)
+                        .scaled(scale)
+                        .exact_bounding_box()
+                        .map(|bb| (s.offset.1 - bb.min.y).max(0.0))
+                        .unwrap_or(0.0);
+                    clusters[cluster].height = clusters[cluster].height.max(height);
+                    glyphs.push(Glyph {
+                        id: s.id,
+                        cluster,
+                        position: pt2(x + s.offset.0, s.offset.1),
+                        advance: s.advance,
+                        font: font_ix,
+                    });
+                    x += s.advance;
+                }
+
+                let end = match starts.binary_search(&byte) {
+                    Ok(i) => starts.get(i + 1).cloned().unwrap_or(run.end),
+                    Err(_) => run.end,
+                };
+                let covered = clusters[cluster + 1..]
+                    .iter()
+                    .take_while(|c| c.byte_range.start < end)
+                    .count()
+                    + 1;
+                let w = (x - x_start) / covered as Scalar;
+                for j in 0..covered {
+                    let (start, end) = if rtl {
+                        (x - (j + 1) as Scalar * w, x - j as Scalar * w)
+                    } else {
+                        (x_start + j as Scalar * w, x_start + (j + 1) as Scalar * w)
+                    };
+                    let ix = cluster + j;
+                    let range = Range::new(start, end);
+                    clusters[ix].x = if positioned[ix] {
+                        clusters[ix].x.max(range)
+                    } else {
+                        range
+                    };
+                    clusters[ix].rtl = rtl;
+                    positioned[ix] = true;
+                }
+            }
+        }
+    }
+
+    // Clusters that produced no glyphs occupy no space.
+    let mut last_x = 0.0;
+    for (cluster, &positioned) in clusters.iter_mut().zip(&positioned) {
+        if !positioned {
+            cluster.x = Range::new(last_x, last_x);
+        }
+        last_x = cluster.x.end;
+    }
+
+    Line {
+        glyphs,
+        clusters,
+        width: x,
+    }
+}
+
+// The index of the first font that has a glyph for every visible char of the cluster.
+fn cluster_font(fonts: &[Font], cluster: &str) -> usize {
+    if fonts.len() == 1 {
+        return 0;
+    }
+    fonts
+        .iter()
+        .position(|font| {
+            cluster
+                .chars()
+                .all(|ch| is_invisible(ch) || font.glyph(ch).id() != GlyphId(0))
+        })
+        .unwrap_or(0)
+}
+
+// Whether or not the char is drawn without a glyph of its own, e.g. spaces, joiners and variation
+// selectors, in which case it need not be supported by the font.
+fn is_invisible(ch: char) -> bool {
+    ch.is_whitespace()
+        || ch.is_control()
+        || ('\u{200B}'..='\u{200F}').contains(&ch)
+        || ('\u{FE00}'..='\u{FE0F}').contains(&ch)
+}
+
+// Split the run into runs of consecutive clusters that resolve to the same font, in logical
+// order.
+fn font_runs_within(
+    clusters: &[Cluster],
+    cluster_fonts: &[usize],
+    run: ops::Range<usize>,
+) -> Vec<(ops::Range<usize>, usize)> {
+    let mut runs: Vec<(ops::Range<usize>, usize)> = vec![];
+    let first = cluster_index(clusters, run.start);
+    let clusters = clusters[first..].iter().zip(&cluster_fonts[first..]);
+    for (cluster, &font) in clusters.take_while(|(c, _)| c.byte_range.start < run.end) {
+        let start = cluster.byte_range.start.max(run.start);
+        let end = cluster.byte_range.end.min(run.end);
+        match runs.last_mut() {
+            Some(last) if last.1 == font => last.0.end = end,
+            _ => runs.push((start..end, font)),
+        }
+    }
+    runs
+}
+
+// Font units are scaled in the same manner as rusttype scales its glyphs.
+fn unit_scale(font: &Font, scale: Scale) -> (Scalar, Scalar) {
+    let v_metrics = font.v_metrics_unscaled();
+    let units = v_metrics.ascent - v_metrics.descent;
+    (scale.x / units, scale.y / units)
+}
+
+// The byte range of each run of text in the order in which they are displayed, along with whether
+// or not the run is right-to-left.
+pub(crate) fn visual_runs(text: &str) -> Vec<(ops::Range<usize>, bool)> {
+    if text.is_empty() {
+        return vec![];
+    }
+    let bidi = BidiInfo::new(text, None);
+    if !bidi.has_rtl() {
+        return vec![(0..text.len(), false)];
+    }
+    let mut runs = vec![];
+    for paragraph in &bidi.paragraphs {
+        let (levels, visual) = bidi.visual_runs(paragraph, paragraph.range.clone());
+        runs.extend(visual.into_iter().map(|run| {
+            let rtl = levels[run.start].is_rtl();
+            (run, rtl)
+        }));
+    }
+    runs
+}
+
+// Split the text into runs of a single script. Spaces, punctuation, accents and other chars
+// common to many scripts are included within the surrounding run.
+fn script_runs(text: &str) -> Vec<ops::Range<usize>> {
+    let mut runs = vec![];
+    let mut start = 0;
+    let mut current = None;
+    for (i, ch) in text.char_indices() {
+        match ch.script() {
+            Script::Common | Script::Inherited | Script::Unknown => (),
+            script => {
+                if current.is_some() && current != Some(script) {
+                    runs.push(start..i);
+                    start = i;
+                }
+                current = Some(script);
+            }
+        }
+    }
+    if start < text.len() {
+        runs.push(start..text.len());
+    }
+    runs
+}
+
+// Shape the run via the font's OpenType tables, producing glyphs in the order they are displayed.
+fn shape_run(
+    face: &rustybuzz::Face,
+    text: &str,
+    run: ops::Range<usize>,
+    rtl: bool,
+    unit_scale: (Scalar, Scalar),
+) -> Vec<Shaped> {
+    let mut buffer = rustybuzz::UnicodeBuffer::new();
+    buffer.push_str(&text[run.clone()]);
+    buffer.set_direction(if rtl {
+        rustybuzz::Direction::RightToLeft
+    } else {
+        rustybuzz::Direction::LeftToRight
+    });
+    buffer.guess_segment_properties();
+    let glyphs = rustybuzz::shape(face, &[], buffer);
+    let (sx, sy) = unit_scale;
+    glyphs
+        .glyph_infos()
+        .iter()
+        .zip(glyphs.glyph_positions())
+        .map(|(info, pos)| Shaped {
+            id: GlyphId(info.codepoint),
+            byte: run.start + info.cluster as usize,
+            advance: pos.x_advance as Scalar * sx,
+            offset: (pos.x_offset as Scalar * sx, pos.y_offset as Scalar * sy),
+        })
+        .collect()
+}
+
+// Lay out the run one `char` at a time for fonts whose tables could not be parsed for shaping.
+fn layout_run(
+    font: &Font,
+    scale: Scale,
+    text: &str,
+    run: ops::Range<usize>,
+    rtl: bool,
+) -> Vec<Shaped> {
+    let mut chars: Vec<_> = text[run.clone()].char_indices().collect();
+    if rtl {
+        chars.reverse();
+    }
+    let mut shaped: Vec<Shaped> = vec![];
+    for (i, ch) in chars {
+        let glyph = font.glyph(ch).scaled(scale);
+        let id = glyph.id();
+        if let Some(last) = shaped.last_mut() {
+            last.advance += font.pair_kerning(scale, last.id, id);
+        }
+        shaped.push(Shaped {
+            id,
+            byte: run.start + i,
+            advance: glyph.h_metrics().advance_width,
+            offset: (0.0, 0.0),
+        });
+    }
+    shaped
+}
+
+// The index of the cluster containing the given byte.
+fn cluster_index(clusters: &[Cluster], byte: usize) -> usize {
+    match clusters.binary_search_by(|c| c.byte_range.start.cmp(&byte)) {
+        Ok(i) => i,
+        Err(i) => i.saturating_sub(1),
+    }
+}
@@ -287,7 +287,7 @@ impl<'a> Builder<'a> {
 
         // If no font was specified use one from the notosans crate, otherwise load the given font.
         let default_font = default_font(default_font_path.as_ref().map(|path| path.as_path()))?;
-        ui.fonts_mut().insert(default_font);
+        ui.fonts_mut().insert(default_font.into());
 
         Ok(ui)
     }
@@ -534,7 +534,7 @@ pub fn winit_window_event_to_input(
     event: &winit::event::WindowEvent,
     window: &Window,
 ) -> Option<Input> {
-    conrod_winit_conv::convert_window_event(event, &window.window)
+    conrod_winit_conv::convert_window_event(event, window.window.as_ref()?)
 }
 
 impl Deref for Ui {
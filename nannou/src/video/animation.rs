@@ -0,0 +1,262 @@
+use super::{apng, gif, Error, Stats};
+use crate::frame::Frame;
+use crate::wgpu;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// Types that encode a sequence of frames, each with its own delay, as an animation.
+///
+/// Implemented for the GIF and APNG **Encoder**s.
+pub trait AnimationEncoder {
+    /// Encode the given image as the next frame, displayed for the given delay.
+    fn encode_frame(&mut self, image: image::RgbaImage, delay: Duration) -> Result<(), Error>;
+
+    /// Complete the animation and flush the output.
+    fn finish(self) -> Result<(), Error>;
+}
+
+/// Records captured frames into an animation encoder, such as a GIF or APNG **Encoder**.
+///
+/// Like the **VideoRecorder**, GPU frames are read back asynchronously and re-ordered before
+/// being encoded on a dedicated thread, so that the window is not stalled by slow encoding.
+///
+/// If a frame cannot be read back from the GPU, its delay is added to that of the next frame so
+/// that the overall timing of the animation is preserved.
+pub struct AnimationRecorder {
+    sender: Option<mpsc::Sender<Message>>,
+    writer: Option<thread::JoinHandle<Result<(), Error>>>,
+    capturer: wgpu::TextureCapturer,
+    next_seq: u64,
+    stats: Arc<Mutex<Stats>>,
+}
+
+// A frame sent to the encoder thread.
+struct Message {
+    seq: u64,
+    delay: Duration,
+    // `None` if the frame could not be read back from the GPU.
+    image: Option<image::RgbaImage>,
+}
+
+impl AnimationRecorder {
+    /// Begin recording frames into the given encoder.
+    pub fn new<E>(encoder: E) -> Self
+    where
+        E: 'static + AnimationEncoder + Send,
+    {
+        Self::with_capturer(encoder, wgpu::TextureCapturer::new(None, None))
+    }
+
+    /// The same as `new`, but frames passed to `record_frame` are captured using the given
+    /// capturer.
+    pub fn with_capturer<E>(encoder: E, capturer: wgpu::TextureCapturer) -> Self
+    where
+        E: 'static + AnimationEncoder + Send,
+    {
+        let stats = Arc::new(Mutex::new(Stats::default()));
+        let (sender, receiver) = mpsc::channel();
+        let thread_stats = stats.clone();
+        let writer = thread::Builder::new()
+            .name("nannou_animation_recorder".into())
+            .spawn(move || run(encoder, receiver, thread_stats))
+            .expect("failed to spawn animation recorder thread");
+        AnimationRecorder {
+            sender: Some(sender),
+            writer: Some(writer),
+            capturer,
+            next_seq: 0,
+            stats,
+        }
+    }
+
+    /// Record the given window **Frame**, displayed for the given delay.
+    ///
+    /// The frame's texture is captured and the frame is submitted. Use this at the end of `view`
+    /// in place of letting the frame drop.
+    ///
+    /// Call `await_active_snapshots` before `finish` to ensure all captured frames are encoded.
+    pub fn record_frame(&mut self, frame: Frame, delay: Duration) -> Result<(), Error> {
+        let snapshot = {
+            let device = frame.device_queue_pair().device();
+            let mut encoder = frame.command_encoder();
+            self.capturer
+                .capture(device, &mut *encoder, frame.texture())
+        };
+        frame.submit();
+        self.record_snapshot(snapshot, delay)
+    }
+
+    /// Record a snapshot captured via a `wgpu::TextureCapturer`, displayed for the given delay.
+    ///
+    /// The commands used to capture the snapshot **must** have been submitted to the queue before
+    /// this is called.
+    pub fn record_snapshot(
+        &mut self,
+        snapshot: wgpu::TextureSnapshot,
+        delay: Duration,
+    ) -> Result<(), Error> {
+        let seq = self.next_seq();
+        let sender = match self.sender {
+            Some(ref sender) => sender.clone(),
+            None => return Err(Error::Closed),
+        };
+        let result = snapshot.read(move |result| {
+            let image = result.ok().map(|image| image.to_owned());
+            // If the encoder thread has stopped, the error is reported via `finish`.
+            let _ = sender.send(Message { seq, delay, image });
+        });
+        // If no worker became available in time, the frame is dropped.
+        if result.is_err() {
+            self.send(seq, delay, None)?;
+        }
+        Ok(())
+    }
+
+    /// Record an image, e.g. one produced by offline rendering, displayed for the given delay.
+    pub fn record_image(&mut self, image: image::RgbaImage, delay: Duration) -> Result<(), Error> {
+        let seq = self.next_seq();
+        self.send(seq, delay, Some(image))
+    }
+
+    /// Block until all frames captured via `record_frame` have been read back from the GPU,
+    /// polling the given device as necessary.
+    pub fn await_active_snapshots(
+        &self,
+        device: &wgpu::Device,
+    ) -> Result<(), wgpu::TextureCapturerAwaitWorkerTimeout<()>> {
+        self.capturer.await_active_snapshots(device)
+    }
+
+    /// The frame accounting so far.
+    pub fn stats(&self) -> Stats {
+        *self
+            .stats
+            .lock()
+            .expect("failed to lock animation recorder stats")
+    }
+
+    /// Encode all remaining frames and complete the animation.
+    ///
+    /// Frames whose snapshots are still being read back from the GPU will be waited upon, so
+    /// ensure that the device is polled (e.g. via `await_active_snapshots`) beforehand.
+    pub fn finish(mut self) -> Result<Stats, Error> {
+        self.finish_inner()?;
+        Ok(self.stats())
+    }
+
+    fn finish_inner(&mut self) -> Result<(), Error> {
+        self.sender.take();
+        match self.writer.take() {
+            None => Ok(()),
+            Some(writer) => writer.join().expect("animation recorder thread panicked"),
+        }
+    }
+
+    fn next_seq(&mut self) -> u64 {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        self.stats
+            .lock()
+            .expect("failed to lock animation recorder stats")
+            .frames_recorded += 1;
+        seq
+    }
+
+    fn send(
+        &self,
+        seq: u64,
+        delay: Duration,
+        image: Option<image::RgbaImage>,
+    ) -> Result<(), Error> {
+        let msg = Message { seq, delay, image };
+        match self.sender {
+            Some(ref sender) => sender.send(msg).map_err(|_| Error::Closed),
+            None => Err(Error::Closed),
+        }
+    }
+}
+
+impl<W> AnimationEncoder for gif::Encoder<W>
+where
+    W: Write,
+{
+    fn encode_frame(&mut self, image: image::RgbaImage, delay: Duration) -> Result<(), Error> {
+        gif::Encoder::encode_frame(self, image, delay)
+    }
+
+    fn finish(self) -> Result<(), Error> {
+        gif::Encoder::finish(self).map(|_| ())
+    }
+}
+
+impl<W> AnimationEncoder for apng::Encoder<W>
+where
+    W: Write,
+{
+    fn encode_frame(&mut self, image: image::RgbaImage, delay: Duration) -> Result<(), Error> {
+        apng::Encoder::encode_frame(self, image, delay)
+    }
+
+    fn finish(self) -> Result<(), Error> {
+        apng::Encoder::finish(self).map(|_| ())
+    }
+}
+
+impl Drop for AnimationRecorder {
+    fn drop(&mut self) {
+        if let Err(e) = self.finish_inner() {
+            // TODO: Log errors, don't print to stderr.
+            eprintln!("failed to finish animation recording: {}", e);
+        }
+    }
+}
+
+// Receive frames, encoding them in the order in which they were recorded.
+fn run<E>(
+    mut encoder: E,
+    receiver: mpsc::Receiver<Message>,
+    stats: Arc<Mutex<Stats>>,
+) -> Result<(), Error>
+where
+    E: AnimationEncoder,
+{
+    let mut pending = BTreeMap::new();
+    let mut next_seq = 0;
+    // The delay of dropped frames, carried over to the next frame encoded.
+    let mut carry = Duration::default();
+    let mut encode = |msg: Message| -> Result<(), Error> {
+        let delay = carry + msg.delay;
+        match msg.image {
+            None => {
+                carry = delay;
+                stats.lock().expect("failed to lock stats").frames_dropped += 1;
+            }
+            Some(image) => {
+                carry = Duration::default();
+                encoder.encode_frame(image, delay)?;
+                stats.lock().expect("failed to lock stats").frames_written += 1;
+            }
+        }
+        Ok(())
+    };
+
+    for msg in receiver {
+        pending.insert(msg.seq, msg);
+        while let Some(msg) = pending.remove(&next_seq) {
+            encode(msg)?;
+            next_seq += 1;
+        }
+    }
+
+    // The recorder has finished. Any sequence gaps are frames that never arrived.
+    for (seq, msg) in pending {
+        stats.lock().expect("failed to lock stats").frames_dropped += seq - next_seq;
+        encode(msg)?;
+        next_seq = seq + 1;
+    }
+
+    encoder.finish()
+}
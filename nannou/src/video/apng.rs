@@ -0,0 +1,229 @@
+//! Encoding of sRGBA images as an animated PNG (APNG).
+//!
+//! Unlike GIF, APNG frames are lossless and support full 8-bit alpha. Each frame is compressed
+//! using the `image` crate's PNG encoder before being re-packaged into the APNG chunks.
+//!
+//! The APNG animation control chunk must describe the total number of frames before the first
+//! frame, so the compressed frames are retained in memory and the animation is written in full
+//! once the **Encoder** is finished.
+
+use super::{Error, Repeat};
+use image::codecs::png::{CompressionType, FilterType, PngEncoder};
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::Duration;
+use std::{fs, u16};
+
+/// A builder for an APNG **Encoder**.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Builder {
+    repeat: Repeat,
+    compression: CompressionType,
+}
+
+/// Encodes a sequence of frames as an animated PNG.
+pub struct Encoder<W: Write> {
+    w: W,
+    repeat: Repeat,
+    compression: CompressionType,
+    size: Option<[u32; 2]>,
+    frames: Vec<Frame>,
+}
+
+// A compressed frame awaiting the end of the animation.
+struct Frame {
+    // The concatenated contents of the frame's `IDAT` chunks.
+    data: Vec<u8>,
+    delay: Duration,
+}
+
+const SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+impl Builder {
+    /// The default number of times the animation is played.
+    pub const DEFAULT_REPEAT: Repeat = Repeat::Infinite;
+
+    /// The default compression used for each frame.
+    pub const DEFAULT_COMPRESSION: CompressionType = CompressionType::Fast;
+
+    /// Begin building a new APNG **Encoder**.
+    pub fn new() -> Self {
+        Builder {
+            repeat: Self::DEFAULT_REPEAT,
+            compression: Self::DEFAULT_COMPRESSION,
+        }
+    }
+
+    /// The number of times the animation is played.
+    pub fn repeat(mut self, repeat: Repeat) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    /// The compression used for each frame.
+    pub fn compression(mut self, compression: CompressionType) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Build an encoder that writes to the given writer.
+    pub fn build<W>(self, w: W) -> Encoder<W>
+    where
+        W: Write,
+    {
+        Encoder {
+            w,
+            repeat: self.repeat,
+            compression: self.compression,
+            size: None,
+            frames: vec![],
+        }
+    }
+
+    /// Build an encoder that writes to a file at the given path.
+    pub fn build_file<P>(self, path: P) -> Result<Encoder<io::BufWriter<fs::File>>, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let file = fs::File::create(path)?;
+        Ok(self.build(io::BufWriter::new(file)))
+    }
+}
+
+impl<W: Write> Encoder<W> {
+    /// Encode the given image as the next frame, displayed for the given delay.
+    ///
+    /// Delays are stored with millisecond precision.
+    pub fn encode_frame(&mut self, image: image::RgbaImage, delay: Duration) -> Result<(), Error> {
+        let (w, h) = image.dimensions();
+        match self.size {
+            None => self.size = Some([w, h]),
+            Some(expected) if expected != [w, h] => {
+                return Err(Error::FrameSize {
+                    expected,
+                    actual: [w, h],
+                });
+            }
+            Some(_) => (),
+        }
+
+        let mut png = vec![];
+        PngEncoder::new_with_quality(&mut png, self.compression, FilterType::Sub).encode(
+            &image,
+            w,
+            h,
+            image::ColorType::Rgba8,
+        )?;
+        let data = idat_data(&png);
+        self.frames.push(Frame { data, delay });
+        Ok(())
+    }
+
+    /// Write the animation, flush the writer and return it.
+    ///
+    /// If no frames were encoded, nothing is written.
+    pub fn finish(mut self) -> Result<W, Error> {
+        let [width, height] = match self.size {
+            None => return Ok(self.w),
+            Some(size) => size,
+        };
+        let w = &mut self.w;
+        w.write_all(SIGNATURE)?;
+
+        // 8-bit RGBA, no interlacing.
+        let mut ihdr = vec![];
+        ihdr.extend_from_slice(&width.to_be_bytes());
+        ihdr.extend_from_slice(&height.to_be_bytes());
+        ihdr.extend_from_slice(&[8, 6, 0, 0, 0]);
+        write_chunk(w, b"IHDR", &ihdr)?;
+
+        let plays: u32 = match self.repeat {
+            Repeat::Infinite => 0,
+            Repeat::Finite(n) => std::cmp::max(n, 1) as u32,
+        };
+        let mut actl = vec![];
+        actl.extend_from_slice(&(self.frames.len() as u32).to_be_bytes());
+        actl.extend_from_slice(&plays.to_be_bytes());
+        write_chunk(w, b"acTL", &actl)?;
+
+        // `fcTL` and `fdAT` chunks share a single sequence.
+        let mut seq = 0u32;
+        for (i, frame) in self.frames.iter().enumerate() {
+            let delay_ms = (frame.delay.as_secs_f64() * 1000.0)
+                .round()
+                .min(u16::MAX as f64) as u16;
+            let mut fctl = vec![];
+            fctl.extend_from_slice(&seq.to_be_bytes());
+            fctl.extend_from_slice(&width.to_be_bytes());
+            fctl.extend_from_slice(&height.to_be_bytes());
+            // The x and y offsets.
+            fctl.extend_from_slice(&[0; 8]);
+            fctl.extend_from_slice(&delay_ms.to_be_bytes());
+            fctl.extend_from_slice(&1000u16.to_be_bytes());
+            // Dispose op `NONE` and blend op `SOURCE`.
+            fctl.extend_from_slice(&[0, 0]);
+            write_chunk(w, b"fcTL", &fctl)?;
+            seq += 1;
+
+            // The first frame doubles as the default image.
+            if i == 0 {
+                write_chunk(w, b"IDAT", &frame.data)?;
+            } else {
+                let mut fdat = Vec::with_capacity(4 + frame.data.len());
+                fdat.extend_from_slice(&seq.to_be_bytes());
+                fdat.extend_from_slice(&frame.data);
+                write_chunk(w, b"fdAT", &fdat)?;
+                seq += 1;
+            }
+        }
+
+        write_chunk(w, b"IEND", &[])?;
+        w.flush()?;
+        Ok(self.w)
+    }
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Collect the contents of all `IDAT` chunks within the given PNG.
+fn idat_data(png: &[u8]) -> Vec<u8> {
+    let mut data = vec![];
+    let mut rest = &png[SIGNATURE.len()..];
+    while rest.len() >= 12 {
+        let len = u32::from_be_bytes([rest[0], rest[1], rest[2], rest[3]]) as usize;
+        let (ty, contents) = (&rest[4..8], &rest[8..8 + len]);
+        if ty == b"IDAT" {
+            data.extend_from_slice(contents);
+        }
+        // Skip the length, type, contents and CRC.
+        rest = &rest[12 + len..];
+    }
+    data
+}
+
+fn write_chunk<W>(w: &mut W, ty: &[u8; 4], data: &[u8]) -> io::Result<()>
+where
+    W: Write,
+{
+    w.write_all(&(data.len() as u32).to_be_bytes())?;
+    w.write_all(ty)?;
+    w.write_all(data)?;
+    let crc = crc32(crc32(!0, ty), data);
+    w.write_all(&(!crc).to_be_bytes())
+}
+
+// Update the given CRC-32 (as used by PNG) with the given bytes.
+fn crc32(mut crc: u32, bytes: &[u8]) -> u32 {
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (!(crc & 1)).wrapping_add(1);
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    crc
+}
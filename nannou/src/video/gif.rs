@@ -0,0 +1,256 @@
+//! Encoding of sRGBA images as an animated GIF.
+//!
+//! GIF frames are limited to a palette of at most 256 colors. Frames with more colors than the
+//! palette allows are reduced using the NeuQuant algorithm, optionally with Floyd-Steinberg
+//! dithering to hide the resulting banding. Frames that already fit within the palette are
+//! written exactly.
+//!
+//! GIF only supports 1-bit transparency: pixels with an alpha of zero are transparent while all
+//! other pixels are treated as opaque.
+
+use super::{Error, Repeat};
+use image::codecs::gif::GifEncoder;
+use image::imageops::ColorMap;
+use std::collections::HashSet;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use std::{fs, u16};
+
+/// A builder for a GIF **Encoder**.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Builder {
+    repeat: Repeat,
+    colors: u16,
+    speed: i32,
+    dither: bool,
+}
+
+/// Encodes a sequence of frames as an animated GIF.
+///
+/// Frames are written as they are encoded. The GIF trailer is written once the **Encoder** is
+/// finished or dropped.
+pub struct Encoder<W: Write> {
+    encoder: GifEncoder<Shared<W>>,
+    writer: Shared<W>,
+    colors: u16,
+    speed: i32,
+    dither: bool,
+    size: Option<[u32; 2]>,
+}
+
+// A NeuQuant palette usable with `image::imageops::dither`.
+struct Palette(color_quant::NeuQuant);
+
+// Allows for retrieving the writer once the `GifEncoder` has written the trailer on drop.
+struct Shared<W>(Arc<Mutex<W>>);
+
+impl Builder {
+    /// The default number of times the animation is played.
+    pub const DEFAULT_REPEAT: Repeat = Repeat::Infinite;
+
+    /// The default maximum number of colors in each frame's palette.
+    pub const DEFAULT_COLORS: u16 = 256;
+
+    /// The default quantization speed.
+    pub const DEFAULT_SPEED: i32 = 10;
+
+    /// Begin building a new GIF **Encoder**.
+    pub fn new() -> Self {
+        Builder {
+            repeat: Self::DEFAULT_REPEAT,
+            colors: Self::DEFAULT_COLORS,
+            speed: Self::DEFAULT_SPEED,
+            dither: false,
+        }
+    }
+
+    /// The number of times the animation is played.
+    pub fn repeat(mut self, repeat: Repeat) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    /// The maximum number of colors in each frame's palette.
+    ///
+    /// Must be within the range `64..=256`.
+    pub fn colors(mut self, colors: u16) -> Self {
+        assert!(
+            colors >= 64 && colors <= 256,
+            "colors must be within the range 64..=256"
+        );
+        self.colors = colors;
+        self
+    }
+
+    /// The speed of palette quantization within the range `1..=30`.
+    ///
+    /// Higher values are faster at the cost of palette quality. By default this is `10`.
+    pub fn speed(mut self, speed: i32) -> Self {
+        assert!(
+            speed >= 1 && speed <= 30,
+            "speed must be within the range 1..=30"
+        );
+        self.speed = speed;
+        self
+    }
+
+    /// Whether or not Floyd-Steinberg dithering is applied to frames that must be quantized.
+    ///
+    /// By default, this is `false`.
+    pub fn dither(mut self, dither: bool) -> Self {
+        self.dither = dither;
+        self
+    }
+
+    /// Build an encoder that writes to the given writer.
+    pub fn build<W>(self, w: W) -> Result<Encoder<W>, Error>
+    where
+        W: Write,
+    {
+        let Builder {
+            repeat,
+            colors,
+            speed,
+            dither,
+        } = self;
+        let writer = Shared(Arc::new(Mutex::new(w)));
+        let mut encoder = GifEncoder::new_with_speed(writer.clone(), speed);
+        match repeat {
+            // Without a loop extension, the animation is played once.
+            Repeat::Finite(0) | Repeat::Finite(1) => (),
+            Repeat::Finite(n) => {
+                encoder.set_repeat(image::codecs::gif::Repeat::Finite(n.saturating_sub(1)))?
+            }
+            Repeat::Infinite => encoder.set_repeat(image::codecs::gif::Repeat::Infinite)?,
+        }
+        Ok(Encoder {
+            encoder,
+            writer,
+            colors,
+            speed,
+            dither,
+            size: None,
+        })
+    }
+
+    /// Build an encoder that writes to a file at the given path.
+    pub fn build_file<P>(self, path: P) -> Result<Encoder<io::BufWriter<fs::File>>, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let file = fs::File::create(path)?;
+        self.build(io::BufWriter::new(file))
+    }
+}
+
+impl<W: Write> Encoder<W> {
+    /// Encode the given image as the next frame, displayed for the given delay.
+    ///
+    /// GIF delays have a resolution of 10ms and are rounded to the nearest multiple. Note that
+    /// many viewers display frames with a delay of less than 20ms for 100ms instead.
+    pub fn encode_frame(
+        &mut self,
+        mut image: image::RgbaImage,
+        delay: Duration,
+    ) -> Result<(), Error> {
+        let (w, h) = image.dimensions();
+        match self.size {
+            None => self.size = Some([w, h]),
+            Some(expected) if expected != [w, h] => {
+                return Err(Error::FrameSize {
+                    expected,
+                    actual: [w, h],
+                });
+            }
+            Some(_) => (),
+        }
+
+        if count_colors(&image, self.colors as usize) > self.colors as usize {
+            quantize(&mut image, self.colors, self.speed, self.dither);
+        }
+
+        let centis = (delay.as_secs_f64() * 100.0).round().min(u16::MAX as f64) as u32;
+        let delay = image::Delay::from_numer_denom_ms(centis * 10, 1);
+        let frame = image::Frame::from_parts(image, 0, 0, delay);
+        self.encoder.encode_frame(frame)?;
+        Ok(())
+    }
+
+    /// Write the GIF trailer, flush the writer and return it.
+    pub fn finish(self) -> Result<W, Error> {
+        let Encoder {
+            encoder, writer, ..
+        } = self;
+        // The trailer is written when the inner encoder is dropped.
+        drop(encoder);
+        let mut w = match Arc::try_unwrap(writer.0) {
+            Ok(mutex) => mutex.into_inner().expect("failed to lock GIF writer"),
+            Err(_) => unreachable!("the GIF writer should be uniquely owned"),
+        };
+        w.flush()?;
+        Ok(w)
+    }
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<W> Clone for Shared<W> {
+    fn clone(&self) -> Self {
+        Shared(self.0.clone())
+    }
+}
+
+impl<W: Write> Write for Shared<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().expect("failed to lock GIF writer").write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.lock().expect("failed to lock GIF writer").flush()
+    }
+}
+
+impl ColorMap for Palette {
+    type Color = image::Rgba<u8>;
+
+    fn index_of(&self, color: &Self::Color) -> usize {
+        self.0.index_of(&color.0)
+    }
+
+    fn map_color(&self, color: &mut Self::Color) {
+        self.0.map_pixel(&mut color.0)
+    }
+}
+
+// Count the distinct colors within the image, stopping once `limit` is exceeded.
+fn count_colors(image: &image::RgbaImage, limit: usize) -> usize {
+    let mut colors = HashSet::new();
+    for p in image.pixels() {
+        // GIF treats all non-zero alpha as opaque.
+        let [r, g, b, a] = p.0;
+        if colors.insert([r, g, b, if a == 0 { 0 } else { 255 }]) && colors.len() > limit {
+            break;
+        }
+    }
+    colors.len()
+}
+
+// Reduce the image to a palette of at most `colors` colors.
+fn quantize(image: &mut image::RgbaImage, colors: u16, speed: i32, dither: bool) {
+    let palette = Palette(color_quant::NeuQuant::new(speed, colors as usize, image));
+    let (w, h) = image.dimensions();
+    // `dither` requires at least a 2x2 image.
+    if dither && w > 1 && h > 1 {
+        image::imageops::dither(image, &palette);
+    } else {
+        for p in image.pixels_mut() {
+            palette.map_color(p);
+        }
+    }
+}
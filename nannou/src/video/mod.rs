@@ -0,0 +1,678 @@
+//! Items related to encoding sequences of captured frames as video.
+//!
+//! The [**VideoRecorder**](./struct.VideoRecorder.html) streams frames, in order, either into a
+//! YUV4MPEG2 (`.y4m`) file or into the stdin of an encoder process such as `ffmpeg`. Frames may be
+//! recorded directly from a window's **Frame**, from a `wgpu::TextureSnapshot` or from an
+//! `image::RgbaImage` (e.g. when rendering offline).
+//!
+//! GPU frames are read back to the CPU asynchronously via the `Snapshot` read path, meaning they
+//! may become available out of order. The recorder re-orders frames before writing them, so the
+//! output always matches the order in which frames were recorded.
+//!
+//! The output frame rate is independent of the rate at which frames are recorded. Frames recorded
+//! with a timestamp are placed at the nearest output frame for that time, duplicating the
+//! previous frame to fill gaps and dropping frames that arrive for an output frame that has
+//! already been written. See [**Stats**](./struct.Stats.html) for the accounting.
+//!
+//! ```no_run
+//! # use nannou::prelude::*;
+//! # use nannou::video;
+//! # fn main() -> Result<(), video::Error> {
+//! // Pipe frames into ffmpeg to produce an mp4 at 30 fps.
+//! let mut command = std::process::Command::new("ffmpeg");
+//! command.args(&["-y", "-f", "yuv4mpegpipe", "-i", "-", "output.mp4"]);
+//! let recorder = video::Builder::new().fps(30.0).build_pipe(command)?;
+//! # recorder.finish()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Alternatively, [**Format::ImageSequence**](./enum.Format.html#variant.ImageSequence) writes each
+//! frame as a PNG alongside a manifest listing the frames and their durations, allowing frames to
+//! be inspected individually or encoded later.
+//!
+//! For short looping previews, the [**gif**](./gif/index.html) and [**apng**](./apng/index.html)
+//! modules provide encoders for animated images in which each frame has its own delay. These may
+//! be used directly with images rendered offline, or via an
+//! [**AnimationRecorder**](./struct.AnimationRecorder.html) to record frames captured from a
+//! window.
+
+use crate::frame::Frame;
+use crate::wgpu;
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Duration;
+use std::{fmt, fs, thread};
+
+pub use self::animation::{AnimationEncoder, AnimationRecorder};
+
+mod animation;
+pub mod apng;
+pub mod gif;
+pub mod y4m;
+
+/// A builder for a **VideoRecorder**.
+#[derive(Clone, Debug)]
+pub struct Builder {
+    fps: f64,
+    format: Format,
+    capture_workers: Option<u32>,
+    capture_timeout: Option<Duration>,
+}
+
+/// The format in which frames are written to the output.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// A YUV4MPEG2 stream with the given chroma subsampling.
+    ///
+    /// When piping to `ffmpeg`, use `-f yuv4mpegpipe -i -`.
+    Y4m(y4m::Chroma),
+    /// Raw, headerless, non-linear 8-bit sRGBA pixels.
+    ///
+    /// The receiving encoder must be told the frame size and rate. E.g. when piping to `ffmpeg`,
+    /// use `-f rawvideo -pix_fmt rgba -s <width>x<height> -r <fps> -i -`.
+    RawRgba,
+    /// Each frame is saved as a PNG next to the output file, which becomes a manifest listing the
+    /// frames in order along with the duration of each.
+    ///
+    /// Frames are named after the manifest, e.g. a manifest at `out/capture.txt` produces frames
+    /// `out/capture-000000.png`, `out/capture-000001.png`, etc. Duplicated frames are listed again
+    /// rather than saved twice. The manifest uses `ffmpeg`'s concat format, so the sequence may be
+    /// encoded later via `ffmpeg -f concat -i out/capture.txt output.mp4`.
+    ///
+    /// This format may only be written via `Builder::build_file`.
+    ImageSequence,
+}
+
+/// Streams captured frames, in order, to a video file or encoder process.
+///
+/// Frames are encoded and written on a dedicated thread. Call `finish` once all frames have been
+/// recorded to flush the output and retrieve the final **Stats**.
+pub struct VideoRecorder {
+    sender: Option<mpsc::Sender<Message>>,
+    writer: Option<thread::JoinHandle<Result<(), Error>>>,
+    capturer: wgpu::TextureCapturer,
+    next_seq: u64,
+    stats: Arc<Mutex<Stats>>,
+}
+
+/// The number of times an animation is played.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Repeat {
+    /// The animation is played the given number of times. `Finite(0)` is treated as `Finite(1)`.
+    Finite(u16),
+    /// The animation loops forever.
+    Infinite,
+}
+
+/// Frame accounting for a **VideoRecorder** or **AnimationRecorder**.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Stats {
+    /// The number of frames recorded by the user.
+    pub frames_recorded: u64,
+    /// The number of frames written to the output, including duplicates.
+    pub frames_written: u64,
+    /// The number of recorded frames that were not written.
+    ///
+    /// This includes frames that could not be read back from the GPU as well as frames whose
+    /// timestamp maps to an output frame that had already been written.
+    pub frames_dropped: u64,
+    /// The number of times the previous frame was written again to fill a gap in the timeline.
+    pub frames_duplicated: u64,
+}
+
+/// Errors that might occur while recording video.
+#[derive(Debug)]
+pub enum Error {
+    /// An error occurred while writing to the output or spawning the encoder process.
+    Io(io::Error),
+    /// An error occurred while encoding an image.
+    Image(image::ImageError),
+    /// A frame's dimensions differ from those of the first frame written.
+    FrameSize {
+        expected: [u32; 2],
+        actual: [u32; 2],
+    },
+    /// The encoder process exited unsuccessfully.
+    Encoder(process::ExitStatus),
+    /// The writer thread has stopped due to an earlier error. The error itself is returned by
+    /// the recorder's `finish` method.
+    Closed,
+}
+
+// The output to which encoded frames are written.
+enum Output {
+    File(io::BufWriter<fs::File>),
+    Process {
+        child: process::Child,
+        stdin: process::ChildStdin,
+    },
+}
+
+// A frame sent to the writer thread.
+struct Message {
+    // The order in which the frame was recorded.
+    seq: u64,
+    // The time at which the frame should be presented, if specified.
+    time: Option<Duration>,
+    // `None` if the frame could not be read back from the GPU.
+    image: Option<image::RgbaImage>,
+}
+
+// State for the writer thread.
+struct Writer {
+    output: Output,
+    format: Format,
+    fps: f64,
+    stats: Arc<Mutex<Stats>>,
+    // The size of the first frame written.
+    size: Option<[u32; 2]>,
+    // The time of the first timestamped frame along with the output index at which it was placed.
+    origin: Option<(Duration, u64)>,
+    // The index of the next frame to be written to the output.
+    next_index: u64,
+    // The most recently encoded frame, retained for duplication.
+    encoded: Vec<u8>,
+    // The directory and file stem of the manifest when writing an image sequence.
+    sequence: Option<(PathBuf, String)>,
+}
+
+impl Builder {
+    /// The default output frame rate.
+    pub const DEFAULT_FPS: f64 = 60.0;
+
+    /// The default output format.
+    pub const DEFAULT_FORMAT: Format = Format::Y4m(y4m::Chroma::C420);
+
+    /// Begin building a new **VideoRecorder**.
+    pub fn new() -> Self {
+        Builder {
+            fps: Self::DEFAULT_FPS,
+            format: Self::DEFAULT_FORMAT,
+            capture_workers: None,
+            capture_timeout: None,
+        }
+    }
+
+    /// The frame rate of the output video.
+    ///
+    /// This is independent of the rate at which frames are recorded. See the module docs for
+    /// details.
+    pub fn fps(mut self, fps: f64) -> Self {
+        assert!(fps > 0.0, "fps must be greater than zero");
+        self.fps = fps;
+        self
+    }
+
+    /// The format in which frames are written to the output.
+    pub fn format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// The number of worker threads used to read captured frames back from the GPU.
+    ///
+    /// By default, this is the number of CPUs available on the system.
+    pub fn capture_workers(mut self, workers: u32) -> Self {
+        self.capture_workers = Some(workers);
+        self
+    }
+
+    /// How long to wait for a capture worker to become available before dropping a frame.
+    ///
+    /// By default, there is no timeout.
+    pub fn capture_timeout(mut self, timeout: Duration) -> Self {
+        self.capture_timeout = Some(timeout);
+        self
+    }
+
+    /// Build a recorder that writes to a file at the given path.
+    ///
+    /// When the format is `Format::ImageSequence`, the file is the manifest and frames are saved
+    /// within the same directory.
+    pub fn build_file<P>(self, path: P) -> Result<VideoRecorder, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let sequence = match self.format {
+            Format::ImageSequence => {
+                let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+                let stem = path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| "frame".into());
+                Some((dir, stem))
+            }
+            _ => None,
+        };
+        let file = fs::File::create(path)?;
+        let output = Output::File(io::BufWriter::new(file));
+        Ok(self.build(output, sequence))
+    }
+
+    /// Build a recorder that writes to the stdin of the process spawned by the given command.
+    ///
+    /// The command's stdin is replaced with a pipe. The recorder waits for the process to exit
+    /// within `VideoRecorder::finish`.
+    ///
+    /// Returns an error if the format is `Format::ImageSequence`, as there is nowhere to save the
+    /// frames.
+    pub fn build_pipe(self, mut command: process::Command) -> Result<VideoRecorder, Error> {
+        if let Format::ImageSequence = self.format {
+            let msg = "image sequences may only be written to a file";
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, msg).into());
+        }
+        let mut child = command.stdin(process::Stdio::piped()).spawn()?;
+        let stdin = child
+            .stdin
+            .take()
+            .expect("the child process stdin should be piped");
+        let output = Output::Process { child, stdin };
+        Ok(self.build(output, None))
+    }
+
+    fn build(self, output: Output, sequence: Option<(PathBuf, String)>) -> VideoRecorder {
+        let Builder {
+            fps,
+            format,
+            capture_workers,
+            capture_timeout,
+        } = self;
+        let stats = Arc::new(Mutex::new(Stats::default()));
+        let (sender, receiver) = mpsc::channel();
+        let writer = Writer {
+            output,
+            format,
+            fps,
+            stats: stats.clone(),
+            size: None,
+            origin: None,
+            next_index: 0,
+            encoded: vec![],
+            sequence,
+        };
+        let writer = thread::Builder::new()
+            .name("nannou_video_recorder".into())
+            .spawn(move || writer.run(receiver))
+            .expect("failed to spawn video recorder thread");
+        VideoRecorder {
+            sender: Some(sender),
+            writer: Some(writer),
+            capturer: wgpu::TextureCapturer::new(capture_workers, capture_timeout),
+            next_seq: 0,
+            stats,
+        }
+    }
+}
+
+impl VideoRecorder {
+    /// Record the given window **Frame**.
+    ///
+    /// The frame's texture is captured and the frame is submitted. The captured image is read
+    /// back from the GPU asynchronously. Use this at the end of `view` in place of letting the
+    /// frame drop.
+    ///
+    /// Call `await_active_snapshots` before `finish` to ensure all captured frames are written.
+    pub fn record_frame(&mut self, frame: Frame) -> Result<(), Error> {
+        let snapshot = {
+            let device = frame.device_queue_pair().device();
+            let mut encoder = frame.command_encoder();
+            self.capturer
+                .capture(device, &mut *encoder, frame.texture())
+        };
+        frame.submit();
+        self.record_snapshot(snapshot)
+    }
+
+    /// Record a snapshot captured via a `wgpu::TextureCapturer`.
+    ///
+    /// The commands used to capture the snapshot **must** have been submitted to the queue before
+    /// this is called.
+    pub fn record_snapshot(&mut self, snapshot: wgpu::TextureSnapshot) -> Result<(), Error> {
+        self.record_snapshot_inner(snapshot, None)
+    }
+
+    /// The same as `record_snapshot`, but places the frame at the given time within the output.
+    pub fn record_snapshot_at(
+        &mut self,
+        snapshot: wgpu::TextureSnapshot,
+        time: Duration,
+    ) -> Result<(), Error> {
+        self.record_snapshot_inner(snapshot, Some(time))
+    }
+
+    /// Record an image, e.g. one produced by offline rendering.
+    pub fn record_image(&mut self, image: image::RgbaImage) -> Result<(), Error> {
+        let seq = self.next_seq();
+        self.send(seq, None, Some(image))
+    }
+
+    /// The same as `record_image`, but places the frame at the given time within the output.
+    pub fn record_image_at(
+        &mut self,
+        image: image::RgbaImage,
+        time: Duration,
+    ) -> Result<(), Error> {
+        let seq = self.next_seq();
+        self.send(seq, Some(time), Some(image))
+    }
+
+    /// Block until all frames captured via `record_frame` have been read back from the GPU,
+    /// polling the given device as necessary.
+    pub fn await_active_snapshots(
+        &self,
+        device: &wgpu::Device,
+    ) -> Result<(), wgpu::TextureCapturerAwaitWorkerTimeout<()>> {
+        self.capturer.await_active_snapshots(device)
+    }
+
+    /// The frame accounting so far.
+    pub fn stats(&self) -> Stats {
+        *self
+            .stats
+            .lock()
+            .expect("failed to lock video recorder stats")
+    }
+
+    /// Flush all remaining frames, close the output and wait for the encoder process (if any) to
+    /// exit.
+    ///
+    /// Frames whose snapshots are still being read back from the GPU will be waited upon, so
+    /// ensure that the device is polled (e.g. via `await_active_snapshots`) beforehand.
+    pub fn finish(mut self) -> Result<Stats, Error> {
+        self.finish_inner()?;
+        Ok(self.stats())
+    }
+
+    fn finish_inner(&mut self) -> Result<(), Error> {
+        self.sender.take();
+        match self.writer.take() {
+            None => Ok(()),
+            Some(writer) => writer.join().expect("video recorder thread panicked"),
+        }
+    }
+
+    fn next_seq(&mut self) -> u64 {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        self.stats
+            .lock()
+            .expect("failed to lock video recorder stats")
+            .frames_recorded += 1;
+        seq
+    }
+
+    fn send(
+        &self,
+        seq: u64,
+        time: Option<Duration>,
+        image: Option<image::RgbaImage>,
+    ) -> Result<(), Error> {
+        let msg = Message { seq, time, image };
+        match self.sender {
+            Some(ref sender) => sender.send(msg).map_err(|_| Error::Closed),
+            None => Err(Error::Closed),
+        }
+    }
+
+    fn record_snapshot_inner(
+        &mut self,
+        snapshot: wgpu::TextureSnapshot,
+        time: Option<Duration>,
+    ) -> Result<(), Error> {
+        let seq = self.next_seq();
+        let sender = match self.sender {
+            Some(ref sender) => sender.clone(),
+            None => return Err(Error::Closed),
+        };
+        let result = snapshot.read(move |result| {
+            let image = result.ok().map(|image| image.to_owned());
+            // If the writer has stopped, the error is reported via `finish`.
+            let _ = sender.send(Message { seq, time, image });
+        });
+        // If no worker became available in time, the frame is dropped.
+        if result.is_err() {
+            self.send(seq, time, None)?;
+        }
+        Ok(())
+    }
+}
+
+impl Writer {
+    // Write all received frames, then finish the output.
+    //
+    // If a frame cannot be written the output is abandoned, killing the encoder process (if any)
+    // rather than leaving it running.
+    fn run(mut self, receiver: mpsc::Receiver<Message>) -> Result<(), Error> {
+        match self.write_frames(receiver) {
+            Ok(()) => self.output.finish(),
+            Err(err) => {
+                self.output.abort();
+                Err(err)
+            }
+        }
+    }
+
+    // Receive frames, writing them to the output in the order in which they were recorded.
+    fn write_frames(&mut self, receiver: mpsc::Receiver<Message>) -> Result<(), Error> {
+        let mut pending = BTreeMap::new();
+        let mut next_seq = 0;
+        for msg in receiver {
+            pending.insert(msg.seq, msg);
+            while let Some(msg) = pending.remove(&next_seq) {
+                self.write_frame(msg)?;
+                next_seq += 1;
+            }
+        }
+
+        // The recorder has finished. Any sequence gaps are frames that never arrived.
+        for (seq, msg) in pending {
+            self.stats().frames_dropped += seq - next_seq;
+            self.write_frame(msg)?;
+            next_seq = seq + 1;
+        }
+        Ok(())
+    }
+
+    fn stats(&self) -> std::sync::MutexGuard<Stats> {
+        self.stats
+            .lock()
+            .expect("failed to lock video recorder stats")
+    }
+
+    fn write_frame(&mut self, msg: Message) -> Result<(), Error> {
+        let image = match msg.image {
+            Some(image) => image,
+            None => {
+                self.stats().frames_dropped += 1;
+                return Ok(());
+            }
+        };
+
+        // Determine the output index at which the frame belongs.
+        let index = match msg.time {
+            None => self.next_index,
+            Some(time) => {
+                let next_index = self.next_index;
+                let (origin, origin_index) = *self.origin.get_or_insert((time, next_index));
+                let secs = time.checked_sub(origin).unwrap_or_default().as_secs_f64();
+                origin_index + (secs * self.fps).round() as u64
+            }
+        };
+        if index < self.next_index {
+            self.stats().frames_dropped += 1;
+            return Ok(());
+        }
+
+        // Write the header before the first frame.
+        let (w, h) = image.dimensions();
+        match self.size {
+            None => {
+                self.size = Some([w, h]);
+                match self.format {
+                    Format::Y4m(chroma) => {
+                        let fps = fps_ratio(self.fps);
+                        y4m::write_header(self.output.writer(), [w, h], fps, chroma)?;
+                    }
+                    Format::ImageSequence => {
+                        self.output.writer().write_all(b"ffconcat version 1.0\n")?;
+                    }
+                    Format::RawRgba => (),
+                }
+            }
+            Some(expected) if expected != [w, h] => {
+                return Err(Error::FrameSize {
+                    expected,
+                    actual: [w, h],
+                });
+            }
+            Some(_) => {
+                // Fill any gap in the timeline with the previous frame.
+                while self.next_index < index {
+                    self.output.writer().write_all(&self.encoded)?;
+                    self.next_index += 1;
+                    let mut stats = self.stats();
+                    stats.frames_duplicated += 1;
+                    stats.frames_written += 1;
+                }
+            }
+        }
+
+        self.encoded.clear();
+        match self.format {
+            Format::Y4m(chroma) => y4m::encode_frame(&image, chroma, &mut self.encoded),
+            Format::RawRgba => self.encoded.extend_from_slice(&image),
+            Format::ImageSequence => {
+                let (dir, stem) = self
+                    .sequence
+                    .as_ref()
+                    .expect("image sequences are only written to files");
+                let name = format!("{}-{:06}.png", stem, index);
+                image.save(dir.join(&name))?;
+                // Each entry is retained for duplication, listing the same frame again.
+                let duration = 1.0 / self.fps;
+                write!(self.encoded, "file '{}'\nduration {}\n", name, duration)?;
+            }
+        }
+        self.output.writer().write_all(&self.encoded)?;
+        self.next_index = index + 1;
+        self.stats().frames_written += 1;
+        Ok(())
+    }
+}
+
+impl Output {
+    fn writer(&mut self) -> &mut dyn Write {
+        match *self {
+            Output::File(ref mut file) => file,
+            Output::Process { ref mut stdin, .. } => stdin,
+        }
+    }
+
+    // Flush the output and wait for the encoder process to exit if necessary.
+    fn finish(self) -> Result<(), Error> {
+        match self {
+            Output::File(mut file) => file.flush()?,
+            Output::Process { mut child, stdin } => {
+                // Close stdin so that the encoder knows the stream has ended.
+                drop(stdin);
+                let status = child.wait()?;
+                if !status.success() {
+                    return Err(Error::Encoder(status));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Close the output after a failure, killing and reaping the encoder process if necessary.
+    fn abort(self) {
+        if let Output::Process { mut child, stdin } = self {
+            drop(stdin);
+            // The process may have already exited, e.g. causing the failure.
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for VideoRecorder {
+    fn drop(&mut self) {
+        if let Err(e) = self.finish_inner() {
+            // TODO: Log errors, don't print to stderr.
+            eprintln!("failed to finish video recording: {}", e);
+        }
+    }
+}
+
+// Describe the given frame rate as a rational suitable for a YUV4MPEG2 header.
+fn fps_ratio(fps: f64) -> [u32; 2] {
+    if fps.fract() == 0.0 {
+        return [fps as u32, 1];
+    }
+    // E.g. 29.97 becomes 30000:1001.
+    let ntsc = (fps * 1.001).round();
+    if ((ntsc / 1.001) - fps).abs() < 1e-3 {
+        return [ntsc as u32 * 1000, 1001];
+    }
+    let num = (fps * 1000.0).round() as u32;
+    let den = 1000;
+    let gcd = gcd(num, den);
+    [num / gcd, den / gcd]
+}
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<image::ImageError> for Error {
+    fn from(e: image::ImageError) -> Self {
+        Error::Image(e)
+    }
+}
+
+impl std::error::Error for Error {
+    fn cause(&self) -> Option<&dyn std::error::Error> {
+        match *self {
+            Error::Io(ref e) => Some(e),
+            Error::Image(ref e) => Some(e),
+            Error::FrameSize { .. } | Error::Encoder(_) | Error::Closed => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Io(ref e) => fmt::Display::fmt(e, f),
+            Error::Image(ref e) => fmt::Display::fmt(e, f),
+            Error::FrameSize { expected, actual } => write!(
+                f,
+                "frame size {}x{} differs from the video size {}x{}",
+                actual[0], actual[1], expected[0], expected[1],
+            ),
+            Error::Encoder(status) => write!(f, "encoder process exited with {}", status),
+            Error::Closed => write!(f, "the recorder has stopped due to an earlier error"),
+        }
+    }
+}
@@ -0,0 +1,110 @@
+//! Encoding of sRGBA images as YUV4MPEG2 (`.y4m`) video frames.
+//!
+//! YUV4MPEG2 is a simple, uncompressed video format consisting of a single line of text
+//! describing the stream followed by a sequence of frames, each beginning with `FRAME\n`. It is
+//! understood by most video encoders (e.g. `ffmpeg`, `x264`), making it a convenient intermediary
+//! format for piping frames into an encoder process.
+//!
+//! Pixels are converted using the BT.601 limited range coefficients. The alpha channel is ignored.
+
+use std::io::{self, Write};
+
+/// The chroma subsampling used by a YUV4MPEG2 stream.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Chroma {
+    /// Chroma is averaged over each 2x2 block of pixels. The most widely supported.
+    C420,
+    /// Chroma is stored for every pixel.
+    C444,
+}
+
+/// Write the YUV4MPEG2 stream header.
+///
+/// The frame rate is described as the rational `fps_num / fps_den`.
+pub fn write_header<W>(
+    mut w: W,
+    [width, height]: [u32; 2],
+    [fps_num, fps_den]: [u32; 2],
+    chroma: Chroma,
+) -> io::Result<()>
+where
+    W: Write,
+{
+    let colorspace = match chroma {
+        Chroma::C420 => "420jpeg",
+        Chroma::C444 => "444",
+    };
+    writeln!(
+        w,
+        "YUV4MPEG2 W{} H{} F{}:{} Ip A1:1 C{}",
+        width, height, fps_num, fps_den, colorspace
+    )
+}
+
+/// Encode a single frame, including the `FRAME` marker, and append it to the given buffer.
+pub fn encode_frame(image: &image::RgbaImage, chroma: Chroma, buffer: &mut Vec<u8>) {
+    let (w, h) = image.dimensions();
+    buffer.extend_from_slice(b"FRAME\n");
+
+    // The luma plane.
+    buffer.extend(image.pixels().map(|p| {
+        let [r, g, b, _] = p.0;
+        luma(r, g, b)
+    }));
+
+    // The two chroma planes.
+    match chroma {
+        Chroma::C444 => {
+            buffer.extend(image.pixels().map(|p| {
+                let [r, g, b, _] = p.0;
+                cb(r, g, b)
+            }));
+            buffer.extend(image.pixels().map(|p| {
+                let [r, g, b, _] = p.0;
+                cr(r, g, b)
+            }));
+        }
+        Chroma::C420 => {
+            let (cw, ch) = ((w + 1) / 2, (h + 1) / 2);
+            let start = buffer.len();
+            buffer.resize(start + (cw * ch * 2) as usize, 0);
+            let (u_plane, v_plane) = buffer[start..].split_at_mut((cw * ch) as usize);
+            for cy in 0..ch {
+                for cx in 0..cw {
+                    // Average the RGB of the (up to) 2x2 block of pixels.
+                    let mut sum = [0u32; 3];
+                    let mut count = 0;
+                    for y in cy * 2..std::cmp::min(cy * 2 + 2, h) {
+                        for x in cx * 2..std::cmp::min(cx * 2 + 2, w) {
+                            let [r, g, b, _] = image.get_pixel(x, y).0;
+                            sum[0] += r as u32;
+                            sum[1] += g as u32;
+                            sum[2] += b as u32;
+                            count += 1;
+                        }
+                    }
+                    let avg = |s: u32| ((s + count / 2) / count) as u8;
+                    let (r, g, b) = (avg(sum[0]), avg(sum[1]), avg(sum[2]));
+                    let i = (cy * cw + cx) as usize;
+                    u_plane[i] = cb(r, g, b);
+                    v_plane[i] = cr(r, g, b);
+                }
+            }
+        }
+    }
+}
+
+fn luma(r: u8, g: u8, b: u8) -> u8 {
+    let (r, g, b) = (r as i32, g as i32, b as i32);
+    (((66 * r + 129 * g + 25 * b + 128) >> 8) + 16) as u8
+}
+
+fn cb(r: u8, g: u8, b: u8) -> u8 {
+    let (r, g, b) = (r as i32, g as i32, b as i32);
+    (((-38 * r - 74 * g + 112 * b + 128) >> 8) + 128) as u8
+}
+
+fn cr(r: u8, g: u8, b: u8) -> u8 {
+    let (r, g, b) = (r as i32, g as i32, b as i32);
+    (((112 * r - 94 * g - 18 * b + 128) >> 8) + 128) as u8
+}
@@ -19,7 +19,18 @@ use std::{env, fmt};
 use winit::dpi::LogicalSize;
 
 pub use winit::window::Fullscreen;
-pub use winit::window::WindowId as Id;
+
+/// A unique identifier associated with a window.
+///
+/// When running headless, the offscreen stand-in window is identified by its own unique **Id**.
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Id(IdKind);
+
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+enum IdKind {
+    Winit(winit::window::WindowId),
+    Headless,
+}
 
 /// The default dimensions used for a window in the case that none are specified.
 pub const DEFAULT_DIMENSIONS: LogicalSize<geom::scalar::Default> = LogicalSize {
@@ -220,10 +231,15 @@ fn_any!(ClosedFn<M>, ClosedFnAny);
 ///
 /// The **Window** acts as a wrapper around the `winit::window::Window` and the `wgpu::Surface`
 /// types. It also manages the associated swap chain, providing a more nannou-friendly API.
+///
+/// When running headless, a stand-in **Window** describes the offscreen texture instead. It has no
+/// OS window or surface, so methods that modify the OS window have no effect.
 #[derive(Debug)]
 pub struct Window {
-    pub(crate) window: winit::window::Window,
-    pub(crate) surface: wgpu::Surface,
+    id: Id,
+    // `None` for the stand-in window of a headless app.
+    pub(crate) window: Option<winit::window::Window>,
+    surface: Option<wgpu::Surface>,
     pub(crate) device_queue_pair: Arc<wgpu::DeviceQueuePair>,
     msaa_samples: u32,
     pub(crate) swap_chain: WindowSwapChain,
@@ -827,7 +843,7 @@ impl<'app> Builder<'app> {
             Some(View::WithModelRaw(_)) => (None, 1),
         };
 
-        let window_id = window.id();
+        let window_id = Id::from(window.id());
         let frame_count = 0;
         let swap_chain = WindowSwapChain {
             descriptor: swap_chain_desc,
@@ -840,8 +856,9 @@ impl<'app> Builder<'app> {
         };
 
         let window = Window {
-            window,
-            surface,
+            id: window_id,
+            window: Some(window),
+            surface: Some(surface),
             device_queue_pair,
             msaa_samples,
             swap_chain,
@@ -981,7 +998,53 @@ impl<'app> Builder<'app> {
     }
 }
 
+impl Id {
+    // The ID of the stand-in window used by a headless app.
+    pub(crate) fn headless() -> Self {
+        Id(IdKind::Headless)
+    }
+}
+
 impl Window {
+    // Create the stand-in window that describes the offscreen texture of a headless app.
+    pub(crate) fn headless(
+        device_queue_pair: Arc<wgpu::DeviceQueuePair>,
+        size_px: [u32; 2],
+        format: wgpu::TextureFormat,
+        scale_factor: f64,
+        msaa_samples: u32,
+        frame_data: FrameData,
+    ) -> Self {
+        let [width, height] = size_px;
+        let descriptor = wgpu::SwapChainDescriptor {
+            usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT,
+            format,
+            width,
+            height,
+            present_mode: wgpu::PresentMode::Fifo,
+        };
+        let swap_chain = WindowSwapChain {
+            descriptor,
+            swap_chain: None,
+        };
+        let tracked_state = TrackedState {
+            scale_factor,
+            physical_size: winit::dpi::PhysicalSize::new(width, height),
+        };
+        Window {
+            id: Id::headless(),
+            window: None,
+            surface: None,
+            device_queue_pair,
+            msaa_samples,
+            swap_chain,
+            frame_data: Some(frame_data),
+            frame_count: 0,
+            user_functions: Default::default(),
+            tracked_state,
+        }
+    }
+
     // `winit::window::Window` methods.
     //
     // NOTE: On new versions of winit, we should check whether or not new `Window` methods have
@@ -990,7 +1053,7 @@ impl Window {
 
     /// A unique identifier associated with this window.
     pub fn id(&self) -> Id {
-        self.window.id()
+        self.id
     }
 
     /// Returns the scale factor that can be used to map logical pixels to physical pixels and vice
@@ -1013,7 +1076,10 @@ impl Window {
     /// - **iOS:** Can only be called on the main thread. Returns the underlying `UiView`'s
     ///   `contentScaleFactor`.
     pub fn scale_factor(&self) -> geom::scalar::Default {
-        self.window.scale_factor() as _
+        match self.window {
+            Some(ref window) => window.scale_factor() as _,
+            None => self.tracked_state.scale_factor as _,
+        }
     }
 
     /// The position of the top-left hand corner of the window relative to the top-left hand corner
@@ -1031,8 +1097,13 @@ impl Window {
     /// - **iOS:** Can only be called on the main thread. Returns the top left coordinates of the
     /// window in the screen space coordinate system.
     /// - **Web:** Returns the top-left coordinates relative to the viewport.
+    ///
+    /// Always `[0, 0]` for the stand-in window of a headless app.
     pub fn outer_position_pixels(&self) -> Result<(i32, i32), winit::error::NotSupportedError> {
-        self.window.outer_position().map(Into::into)
+        match self.window {
+            Some(ref window) => window.outer_position().map(Into::into),
+            None => Ok((0, 0)),
+        }
     }
 
     /// Modifies the position of the window.
@@ -1046,15 +1117,16 @@ impl Window {
     ///   window in the screen space coordinate system.
     /// - **Web:** Sets the top-left coordinates relative to the viewport.
     pub fn set_outer_position_pixels(&self, x: i32, y: i32) {
-        self.window
-            .set_outer_position(winit::dpi::PhysicalPosition { x, y })
+        if let Some(ref window) = self.window {
+            window.set_outer_position(winit::dpi::PhysicalPosition { x, y })
+        }
     }
 
     /// The width and height in pixels of the client area of the window.
     ///
     /// The client area is the content of the window, excluding the title bar and borders.
     pub fn inner_size_pixels(&self) -> (u32, u32) {
-        self.window.inner_size().into()
+        self.physical_inner_size().into()
     }
 
     /// The width and height in points of the client area of the window.
@@ -1063,8 +1135,7 @@ impl Window {
     ///
     /// This is the same as dividing the result  of `inner_size_pixels()` by `scale_factor()`.
     pub fn inner_size_points(&self) -> (geom::scalar::Default, geom::scalar::Default) {
-        self.window
-            .inner_size()
+        self.physical_inner_size()
             .to_logical::<f32>(self.tracked_state.scale_factor)
             .into()
     }
@@ -1073,16 +1144,18 @@ impl Window {
     ///
     /// See the `inner_size` methods for more informations about the values.
     pub fn set_inner_size_pixels(&self, width: u32, height: u32) {
-        self.window
-            .set_inner_size(winit::dpi::PhysicalSize { width, height })
+        if let Some(ref window) = self.window {
+            window.set_inner_size(winit::dpi::PhysicalSize { width, height })
+        }
     }
 
     /// Modifies the inner size of the window using point values.
     ///
     /// See the `inner_size` methods for more informations about the values.
     pub fn set_inner_size_points(&self, width: f32, height: f32) {
-        self.window
-            .set_inner_size(winit::dpi::LogicalSize { width, height })
+        if let Some(ref window) = self.window {
+            window.set_inner_size(winit::dpi::LogicalSize { width, height })
+        }
     }
 
     /// The width and height of the window in pixels.
@@ -1090,7 +1163,7 @@ impl Window {
     /// These dimensions include title bar and borders. If you don't want these, you should use
     /// `inner_size_pixels` instead.
     pub fn outer_size_pixels(&self) -> (u32, u32) {
-        self.window.outer_size().into()
+        self.physical_outer_size().into()
     }
 
     /// The width and height of the window in points.
@@ -1100,8 +1173,7 @@ impl Window {
     ///
     /// This is the same as dividing the result  of `outer_size_pixels()` by `scale_factor()`.
     pub fn outer_size_points(&self) -> (f32, f32) {
-        self.window
-            .outer_size()
+        self.physical_outer_size()
             .to_logical::<f32>(self.tracked_state.scale_factor)
             .into()
     }
@@ -1109,20 +1181,26 @@ impl Window {
     /// Sets a minimum size for the window.
     pub fn set_min_inner_size_points(&self, size: Option<(f32, f32)>) {
         let size = size.map(|(width, height)| winit::dpi::LogicalSize { width, height });
-        self.window.set_min_inner_size(size)
+        if let Some(ref window) = self.window {
+            window.set_min_inner_size(size)
+        }
     }
 
     /// Sets a maximum size for the window.
     pub fn set_max_inner_size_points(&self, size: Option<(f32, f32)>) {
         let size = size.map(|(width, height)| winit::dpi::LogicalSize { width, height });
-        self.window.set_max_inner_size(size)
+        if let Some(ref window) = self.window {
+            window.set_max_inner_size(size)
+        }
     }
 
     /// Modifies the title of the window.
     ///
     /// This is a no-op if the window has already been closed.
     pub fn set_title(&self, title: &str) {
-        self.window.set_title(title);
+        if let Some(ref window) = self.window {
+            window.set_title(title);
+        }
     }
 
     /// Set the visibility of the window.
@@ -1133,7 +1211,9 @@ impl Window {
     /// - iOS: Can only be called on the main thread.
     /// - Web: Has no effect.
     pub fn set_visible(&self, visible: bool) {
-        self.window.set_visible(visible)
+        if let Some(ref window) = self.window {
+            window.set_visible(visible)
+        }
     }
 
     /// Sets whether the window is resizable or not.
@@ -1141,17 +1221,23 @@ impl Window {
     /// Note that making the window unresizable doesn't exempt you from handling **Resized**, as
     /// that event can still be triggered by DPI scaling, entering fullscreen mode, etc.
     pub fn set_resizable(&self, resizable: bool) {
-        self.window.set_resizable(resizable)
+        if let Some(ref window) = self.window {
+            window.set_resizable(resizable)
+        }
     }
 
     /// Sets the window to minimized or back.
     pub fn set_minimized(&self, minimized: bool) {
-        self.window.set_minimized(minimized)
+        if let Some(ref window) = self.window {
+            window.set_minimized(minimized)
+        }
     }
 
     /// Sets the window to maximized or back.
     pub fn set_maximized(&self, maximized: bool) {
-        self.window.set_maximized(maximized)
+        if let Some(ref window) = self.window {
+            window.set_maximized(maximized)
+        }
     }
 
     /// Set the window to fullscreen on the primary monitor.
@@ -1191,7 +1277,9 @@ impl Window {
     /// - Wayland: Does not support exclusive fullscreen mode.
     /// - Windows: Screen saver is disabled in fullscreen mode.
     pub fn set_fullscreen_with(&self, fullscreen: Option<Fullscreen>) {
-        self.window.set_fullscreen(fullscreen)
+        if let Some(ref window) = self.window {
+            window.set_fullscreen(fullscreen)
+        }
     }
 
     /// Gets the window's current fullscreen state.
@@ -1200,7 +1288,7 @@ impl Window {
     ///
     /// - **iOS:** Can only be called on the main thread.
     pub fn fullscreen(&self) -> Option<Fullscreen> {
-        self.window.fullscreen()
+        self.window.as_ref().and_then(|window| window.fullscreen())
     }
 
     /// Turn window decorations on or off.
@@ -1211,12 +1299,16 @@ impl Window {
     ///   via `setPrefersStatusBarHidden`.
     /// - **Web:** Has no effect.
     pub fn set_decorations(&self, decorations: bool) {
-        self.window.set_decorations(decorations)
+        if let Some(ref window) = self.window {
+            window.set_decorations(decorations)
+        }
     }
 
     /// Change whether or not the window will always be on top of other windows.
     pub fn set_always_on_top(&self, always_on_top: bool) {
-        self.window.set_always_on_top(always_on_top)
+        if let Some(ref window) = self.window {
+            window.set_always_on_top(always_on_top)
+        }
     }
 
     /// Sets the window icon. On Windows and X11, this is typically the small icon in the top-left
@@ -1232,7 +1324,9 @@ impl Window {
     /// X11 has no universal guidelines for icon sizes, so you're at the whims of the WM. That
     /// said, it's usually in the same ballpark as on Windows.
     pub fn set_window_icon(&self, window_icon: Option<winit::window::Icon>) {
-        self.window.set_window_icon(window_icon)
+        if let Some(ref window) = self.window {
+            window.set_window_icon(window_icon)
+        }
     }
 
     /// Sets the location of IME candidate box in client area coordinates relative to the top left.
@@ -1242,8 +1336,9 @@ impl Window {
     /// - **iOS:** Has no effect.
     /// - **Web:** Has no effect.
     pub fn set_ime_position_points(&self, x: f32, y: f32) {
-        self.window
-            .set_ime_position(winit::dpi::LogicalPosition { x, y })
+        if let Some(ref window) = self.window {
+            window.set_ime_position(winit::dpi::LogicalPosition { x, y })
+        }
     }
 
     /// Modifies the mouse cursor of the window.
@@ -1253,7 +1348,9 @@ impl Window {
     /// - **iOS:** Has no effect.
     /// - **Android:** Has no effect.
     pub fn set_cursor_icon(&self, state: winit::window::CursorIcon) {
-        self.window.set_cursor_icon(state);
+        if let Some(ref window) = self.window {
+            window.set_cursor_icon(state);
+        }
     }
 
     /// Changes the position of the cursor in logical window coordinates.
@@ -1267,8 +1364,10 @@ impl Window {
         x: f32,
         y: f32,
     ) -> Result<(), winit::error::ExternalError> {
-        self.window
-            .set_cursor_position(winit::dpi::LogicalPosition { x, y })
+        match self.window {
+            Some(ref window) => window.set_cursor_position(winit::dpi::LogicalPosition { x, y }),
+            None => Ok(()),
+        }
     }
 
     /// Grabs the cursor, preventing it from leaving the window.
@@ -1281,7 +1380,10 @@ impl Window {
     /// - **iOS:** Always returns an Err.
     /// - **Web:** Has no effect.
     pub fn set_cursor_grab(&self, grab: bool) -> Result<(), winit::error::ExternalError> {
-        self.window.set_cursor_grab(grab)
+        match self.window {
+            Some(ref window) => window.set_cursor_grab(grab),
+            None => Ok(()),
+        }
     }
 
     /// Set the cursor's visibility.
@@ -1298,23 +1400,33 @@ impl Window {
     ///
     /// This has no effect on **Android** or **iOS**.
     pub fn set_cursor_visible(&self, visible: bool) {
-        self.window.set_cursor_visible(visible)
+        if let Some(ref window) = self.window {
+            window.set_cursor_visible(visible)
+        }
     }
 
     /// The current monitor that the window is, on or the primary monitor if nothing matches.
     /// If there's neither a current nor a primary monitor, returns none.
+    ///
+    /// Always `None` for the stand-in window of a headless app.
     pub fn current_monitor(&self) -> Option<winit::monitor::MonitorHandle> {
-        self.window.current_monitor()
+        self.window
+            .as_ref()
+            .and_then(|window| window.current_monitor())
     }
 
     // Access to wgpu API.
 
     /// Returns a reference to the window's wgpu swap chain surface.
-    pub fn surface(&self) -> &wgpu::Surface {
-        &self.surface
+    ///
+    /// Returns `None` for the stand-in window of a headless app, as it has no surface.
+    pub fn surface(&self) -> Option<&wgpu::Surface> {
+        self.surface.as_ref()
     }
 
     /// The descriptor for the swap chain associated with this window's wgpu surface.
+    ///
+    /// For the stand-in window of a headless app, this describes the offscreen texture.
     pub fn swap_chain_descriptor(&self) -> &wgpu::SwapChainDescriptor {
         &self.swap_chain.descriptor
     }
@@ -1354,13 +1466,18 @@ impl Window {
 
     // A utility function to simplify the recreation of a swap_chain.
     pub(crate) fn rebuild_swap_chain(&mut self, size_px: [u32; 2]) {
+        let surface = match self.surface {
+            Some(ref surface) => surface,
+            None => return,
+        };
         std::mem::drop(self.swap_chain.swap_chain.take());
         let [width, height] = size_px;
         self.swap_chain.descriptor.width = width;
         self.swap_chain.descriptor.height = height;
         self.swap_chain.swap_chain = Some(
-            self.swap_chain_device()
-                .create_swap_chain(&self.surface, &self.swap_chain.descriptor),
+            self.device_queue_pair
+                .device()
+                .create_swap_chain(surface, &self.swap_chain.descriptor),
         );
         if self.frame_data.is_some() {
             let render_data = frame::RenderData::new(
@@ -1373,6 +1490,22 @@ impl Window {
         }
     }
 
+    // The physical size of the client area, tracked in place of the OS window when headless.
+    fn physical_inner_size(&self) -> winit::dpi::PhysicalSize<u32> {
+        match self.window {
+            Some(ref window) => window.inner_size(),
+            None => self.tracked_state.physical_size,
+        }
+    }
+
+    // The physical size including decorations, equal to the inner size when headless.
+    fn physical_outer_size(&self) -> winit::dpi::PhysicalSize<u32> {
+        match self.window {
+            Some(ref window) => window.outer_size(),
+            None => self.tracked_state.physical_size,
+        }
+    }
+
     /// Attempts to determine whether or not the window is currently fullscreen.
     pub fn is_fullscreen(&self) -> bool {
         self.fullscreen().is_some()
@@ -1478,6 +1611,14 @@ impl fmt::Debug for WindowSwapChain {
     }
 }
 
+// Conversion implementations.
+
+impl From<winit::window::WindowId> for Id {
+    fn from(id: winit::window::WindowId) -> Self {
+        Id(IdKind::Winit(id))
+    }
+}
+
 // Error implementations.
 
 impl fmt::Display for BuildError {
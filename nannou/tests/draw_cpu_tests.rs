@@ -0,0 +1,222 @@
+use nannou::draw::renderer::cpu;
+use nannou::draw::{Dash, Gradient};
+use nannou::prelude::*;
+
+#[test]
+fn cpu_render_rect() {
+    let draw = Draw::new();
+    draw.background().color(BLACK);
+    draw.rect().w_h(51.0, 51.0).color(WHITE);
+    let mut renderer = cpu::Renderer::new();
+    let image = renderer.render(&draw, 1.0, [100, 100]);
+    assert_eq!(image.get_pixel(50, 50).0, [255, 255, 255, 255]);
+    assert_eq!(image.get_pixel(5, 5).0, [0, 0, 0, 255]);
+    // Pixels along the edge of the rect are only partially covered.
+    let edge = image.get_pixel(24, 50).0;
+    assert!(edge[0] > 0 && edge[0] < 255);
+}
+
+#[test]
+fn cpu_render_concave_mesh_polygon() {
+    let draw = Draw::new();
+    draw.background().color(BLACK);
+    // An L shape with a square hole in its corner.
+    let contour = [
+        pt2(-40.0, -40.0),
+        pt2(40.0, -40.0),
+        pt2(40.0, 0.0),
+        pt2(0.0, 0.0),
+        pt2(0.0, 40.0),
+        pt2(-40.0, 40.0),
+    ];
+    let hole = [
+        pt2(-30.0, -30.0),
+        pt2(-30.0, -10.0),
+        pt2(-10.0, -10.0),
+        pt2(-10.0, -30.0),
+    ];
+    draw.mesh().polygon(&contour, &[&hole]).color(WHITE);
+    let mut renderer = cpu::Renderer::new();
+    let image = renderer.render(&draw, 1.0, [100, 100]);
+    assert_eq!(image.get_pixel(30, 30).0, [255, 255, 255, 255]);
+    assert_eq!(image.get_pixel(80, 80).0, [255, 255, 255, 255]);
+    // The notch of the L and the hole remain empty.
+    assert_eq!(image.get_pixel(70, 30).0, [0, 0, 0, 255]);
+    assert_eq!(image.get_pixel(30, 70).0, [0, 0, 0, 255]);
+}
+
+#[test]
+fn cpu_render_linear_gradient() {
+    let draw = Draw::new();
+    draw.background().color(BLACK);
+    let gradient = Gradient::linear(pt2(-40.0, 0.0), pt2(40.0, 0.0))
+        .stop(0.0, BLACK)
+        .stop(1.0, WHITE);
+    draw.rect().w_h(80.0, 40.0).gradient(gradient);
+    let mut renderer = cpu::Renderer::new();
+    let image = renderer.render(&draw, 1.0, [100, 100]);
+    let left = image.get_pixel(20, 50).0[0];
+    let mid = image.get_pixel(50, 50).0[0];
+    let right = image.get_pixel(80, 50).0[0];
+    assert!(left < mid && mid < right);
+}
+
+#[test]
+fn cpu_render_conic_gradient_seam() {
+    let draw = Draw::new();
+    draw.background().color(BLACK);
+    let gradient = Gradient::conic(pt2(0.0, 0.0), 0.0)
+        .stop(0.0, BLACK)
+        .stop(1.0, WHITE);
+    draw.rect().w_h(80.0, 80.0).gradient(gradient);
+    let mut renderer = cpu::Renderer::new();
+    let image = renderer.render(&draw, 1.0, [100, 100]);
+    // The colors wrap around sharply at the seam along the positive x axis.
+    let above = image.get_pixel(80, 48).0[0];
+    let below = image.get_pixel(80, 52).0[0];
+    assert!(above < 48, "{}", above);
+    assert!(below > 224, "{}", below);
+    // Opposite the seam, halfway around.
+    let opposite = image.get_pixel(20, 50).0[0];
+    assert!(opposite > 64 && opposite < 224, "{}", opposite);
+}
+
+#[test]
+fn gradient_spread() {
+    use nannou::draw::properties::gradient::Spread;
+    assert_eq!(Spread::Pad.apply(1.5), 1.0);
+    assert_eq!(Spread::Repeat.apply(1.25), 0.25);
+    assert_eq!(Spread::Reflect.apply(1.25), 0.75);
+    assert_eq!(Spread::Reflect.apply(-0.25), 0.25);
+}
+
+#[test]
+fn cpu_render_dashed_line() {
+    let draw = Draw::new();
+    draw.background().color(BLACK);
+    draw.line()
+        .start(pt2(-40.0, 0.0))
+        .end(pt2(40.0, 0.0))
+        .weight(6.0)
+        .dash(10.0, 10.0)
+        .color(WHITE);
+    let mut renderer = cpu::Renderer::new();
+    let image = renderer.render(&draw, 1.0, [100, 100]);
+    // The first dash covers -40..-30 and is followed by a gap covering -30..-20.
+    assert_eq!(image.get_pixel(15, 50).0, [255, 255, 255, 255]);
+    assert_eq!(image.get_pixel(25, 50).0, [0, 0, 0, 255]);
+    assert_eq!(image.get_pixel(35, 50).0, [255, 255, 255, 255]);
+}
+
+#[test]
+fn dash_split_interpolates_attributes() {
+    let dash = Dash::new(vec![3.0, 2.0]).offset(1.0);
+    let points = vec![(pt2(0.0, 0.0), 0.0), (pt2(10.0, 0.0), 10.0)];
+    let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+    let dashes = dash.split(&points, false, lerp);
+    let expected = vec![
+        vec![(pt2(0.0, 0.0), 0.0), (pt2(2.0, 0.0), 2.0)],
+        vec![(pt2(4.0, 0.0), 4.0), (pt2(7.0, 0.0), 7.0)],
+        vec![(pt2(9.0, 0.0), 9.0), (pt2(10.0, 0.0), 10.0)],
+    ];
+    assert_eq!(dashes, expected);
+}
+
+#[test]
+fn cpu_render_sdf_text_with_outline() {
+    let draw = Draw::new();
+    draw.background().color(BLACK);
+    draw.text("I")
+        .font(nannou::text::font::default_notosans())
+        .font_size(64)
+        .color(WHITE)
+        .outline(3.0, rgb(1.0, 0.0, 0.0));
+    let mut renderer = cpu::Renderer::new();
+    let image = renderer.render(&draw, 1.0, [100, 100]);
+    // The stem of the glyph is filled, surrounded by its outline.
+    let row: Vec<_> = (0..100).map(|x| image.get_pixel(x, 50).0).collect();
+    let stem = row.iter().position(|p| *p == [255, 255, 255, 255]).unwrap();
+    assert!(row[..stem].iter().any(|p| p[0] == 255 && p[1] == 0));
+    assert_eq!(row[5], [0, 0, 0, 255]);
+}
+
+#[test]
+fn cpu_render_scissor() {
+    let draw = Draw::new();
+    draw.background().color(BLACK);
+    // Only the left half of the frame is drawn to.
+    draw.scissor(geom::Rect::from_x_y_w_h(-25.0, 0.0, 50.0, 100.0))
+        .rect()
+        .w_h(100.0, 100.0)
+        .color(WHITE);
+    let mut renderer = cpu::Renderer::new();
+    let image = renderer.render(&draw, 1.0, [100, 100]);
+    assert_eq!(image.get_pixel(10, 50).0, [255, 255, 255, 255]);
+    assert_eq!(image.get_pixel(49, 10).0, [255, 255, 255, 255]);
+    assert_eq!(image.get_pixel(50, 10).0, [0, 0, 0, 255]);
+    assert_eq!(image.get_pixel(90, 90).0, [0, 0, 0, 255]);
+}
+
+#[test]
+fn cpu_render_additive_blend() {
+    let additive = wgpu::BlendDescriptor {
+        src_factor: wgpu::BlendFactor::One,
+        dst_factor: wgpu::BlendFactor::One,
+        operation: wgpu::BlendOperation::Add,
+    };
+    let draw = Draw::new();
+    draw.background().color(BLACK);
+    draw.rect().x(-10.0).w_h(40.0, 40.0).color(RED);
+    draw.blend(additive)
+        .rect()
+        .x(10.0)
+        .w_h(40.0, 40.0)
+        .color(rgb(0.0, 1.0, 0.0));
+    let mut renderer = cpu::Renderer::new();
+    let image = renderer.render(&draw, 1.0, [100, 100]);
+    // Where the rects overlap, red and green are summed rather than the green replacing the red.
+    for x in 41..60 {
+        for y in 31..70 {
+            assert_eq!(image.get_pixel(x, y).0, [255, 255, 0, 255]);
+        }
+    }
+    assert_eq!(image.get_pixel(25, 50).0, [255, 0, 0, 255]);
+    assert_eq!(image.get_pixel(75, 50).0, [0, 255, 0, 255]);
+
+    // With the default blend, the green rect is drawn over the red.
+    let draw = Draw::new();
+    draw.background().color(BLACK);
+    draw.rect().x(-10.0).w_h(40.0, 40.0).color(RED);
+    draw.rect()
+        .x(10.0)
+        .w_h(40.0, 40.0)
+        .color(rgb(0.0, 1.0, 0.0));
+    let image = renderer.render(&draw, 1.0, [100, 100]);
+    assert_eq!(image.get_pixel(50, 50).0, [0, 255, 0, 255]);
+}
+
+#[test]
+fn cpu_render_edge_coverage() {
+    let render = |sample_count| {
+        let draw = Draw::new();
+        draw.background().color(BLACK);
+        // The left edge of the rect falls through the middle of the pixels in column 24.
+        draw.rect().w_h(51.0, 51.0).color(WHITE);
+        let mut renderer = cpu::Builder::new().sample_count(sample_count).build();
+        renderer.render(&draw, 1.0, [100, 100])
+    };
+
+    // Half of the samples are covered, blended in linear space and encoded as sRGB.
+    for &sample_count in &[4, 16] {
+        let image = render(sample_count);
+        let edge = image.get_pixel(24, 50).0;
+        assert!(edge[0] >= 186 && edge[0] <= 189, "{:?}", edge);
+        assert_eq!(image.get_pixel(23, 50).0, [0, 0, 0, 255]);
+        assert_eq!(image.get_pixel(25, 50).0, [255, 255, 255, 255]);
+    }
+
+    // A single sample per pixel produces a hard edge.
+    let image = render(1);
+    let edge = image.get_pixel(24, 50).0[0];
+    assert!(edge == 0 || edge == 255);
+}
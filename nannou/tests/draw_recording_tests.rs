@@ -0,0 +1,137 @@
+use nannou::draw::recording::{FontRef, Player, Recorder};
+use nannou::draw::renderer::cpu;
+use nannou::prelude::*;
+use nannou::text::{font, rich, Font};
+use std::path::{Path, PathBuf};
+
+fn draw_frame(draw: &Draw, t: f32) {
+    draw.background().color(BLACK);
+    draw.rect().x(t).w_h(30.0, 20.0).color(WHITE);
+    draw.rotate(t * 0.1)
+        .polyline()
+        .weight(3.0)
+        .points(vec![pt2(-40.0, -40.0), pt2(0.0, 30.0), pt2(40.0, -40.0)])
+        .color(RED);
+    draw.mesh().points_colored(vec![
+        (pt3(-50.0, 0.0, 0.0), BLUE),
+        (pt3(-30.0, 20.0, 0.0), GREEN),
+        (pt3(-30.0, 0.0, 0.0), BLUE),
+    ]);
+}
+
+#[test]
+fn recorded_frames_replay_identically() {
+    let mut recorder = Recorder::new(vec![]);
+    let mut expected = vec![];
+    let mut renderer = cpu::Renderer::new();
+    for frame in 0..3 {
+        let draw = Draw::new();
+        draw_frame(&draw, frame as f32 * 10.0);
+        recorder.record(frame, &draw).unwrap();
+        expected.push(renderer.render(&draw, 1.0, [100, 100]));
+    }
+    let log = recorder.finish().unwrap();
+    assert_eq!(log.iter().filter(|&&b| b == b'\n').count(), 3);
+
+    let player = Player::read(&log[..]).unwrap();
+    assert_eq!(player.frames().collect::<Vec<_>>(), vec![0, 1, 2]);
+    assert!(player.texture_refs().is_empty());
+    let draw = Draw::new();
+    for (frame, expected) in expected.iter().enumerate() {
+        assert!(player.replay(frame as u64, &draw));
+        let image = renderer.render(&draw, 1.0, [100, 100]);
+        assert_eq!(&image, expected);
+    }
+    assert!(!player.replay(3, &draw));
+}
+
+fn fonts_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../assets/fonts/NotoSans")
+}
+
+fn tiny_font_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fonts/SourceSansPro-Regular-Tiny.ttf")
+}
+
+fn draw_text_frame(draw: &Draw, bold: &Font, tiny: &Font) {
+    draw.background().color(BLACK);
+    draw.text("Bold").font(bold.clone()).font_size(24).y(30.0);
+    // A rich span in the tiny font, falling back to the bold font for the heart.
+    let mut fonts = font::Map::new();
+    let tiny_id = fonts.insert(tiny.clone());
+    draw.text("")
+        .span("Tiny \u{2665}", rich::Style::new().font(tiny_id))
+        .fonts(&fonts)
+        .fallback_font(bold.clone())
+        .font_size(24);
+    let path = geom::path()
+        .move_to(pt2(-40.0, -30.0))
+        .line_to(pt2(40.0, -30.0))
+        .build();
+    draw.text_on_path("Path", &path)
+        .font(tiny.clone())
+        .font_size(16);
+}
+
+#[test]
+fn recorded_text_replays_with_its_fonts() {
+    let bold = font::from_file(fonts_dir().join("NotoSans-Bold.ttf")).unwrap();
+    let tiny = font::from_file(tiny_font_path()).unwrap();
+    let mut recorder = Recorder::new(vec![]);
+    let mut renderer = cpu::Renderer::new();
+    let draw = Draw::new();
+    draw_text_frame(&draw, &bold, &tiny);
+    recorder.record(0, &draw).unwrap();
+    recorder.record(1, &draw).unwrap();
+    let expected = renderer.render(&draw, 1.0, [100, 100]);
+
+    // The same text drawn in the default font differs.
+    let default_font = font::default_notosans();
+    let draw_default = Draw::new();
+    draw_text_frame(&draw_default, &default_font, &default_font);
+    assert_ne!(renderer.render(&draw_default, 1.0, [100, 100]), expected);
+
+    // Each font's data is written only once, with the first frame that uses it.
+    let log = recorder.finish().unwrap();
+    let log = String::from_utf8(log).unwrap();
+    let mut lines = log.lines();
+    let (first, second) = (lines.next().unwrap(), lines.next().unwrap());
+    assert!(second.len() < first.len() / 10);
+
+    let player = Player::read(log.as_bytes()).unwrap();
+    assert!(player.font_refs().is_empty());
+    let draw = Draw::new();
+    for frame in 0..2 {
+        assert!(player.replay(frame, &draw));
+        assert_eq!(renderer.render(&draw, 1.0, [100, 100]), expected);
+    }
+}
+
+#[test]
+fn recorded_font_paths_are_loaded_on_replay() {
+    let path = fonts_dir().join("NotoSans-Bold.ttf");
+    let bold = font::from_file(&path).unwrap();
+    let tiny = font::from_file(tiny_font_path()).unwrap();
+    let mut recorder = Recorder::new(vec![]);
+    recorder.register_font(&bold, &path);
+    let mut renderer = cpu::Renderer::new();
+    let draw = Draw::new();
+    draw_text_frame(&draw, &bold, &tiny);
+    recorder.record(0, &draw).unwrap();
+    let expected = renderer.render(&draw, 1.0, [100, 100]);
+    let log = recorder.finish().unwrap();
+
+    let mut player = Player::read(&log[..]).unwrap();
+    let font_ref = FontRef {
+        id: 0,
+        path: Some(path.clone()),
+    };
+    assert_eq!(player.font_refs(), vec![font_ref]);
+    // Until the font is loaded, text that uses it is drawn with the default font.
+    let draw = Draw::new();
+    assert!(player.replay(0, &draw));
+    assert_ne!(renderer.render(&draw, 1.0, [100, 100]), expected);
+    player.load_fonts().unwrap();
+    assert!(player.replay(0, &draw));
+    assert_eq!(renderer.render(&draw, 1.0, [100, 100]), expected);
+}
@@ -0,0 +1,99 @@
+use nannou::draw::svg;
+use nannou::prelude::*;
+
+// The elements of the rendered document, excluding the header, background and root group.
+fn body(draw: &Draw) -> String {
+    let svg = svg::render(draw, [100.0, 80.0]);
+    let start = svg
+        .find("<g transform=\"matrix(1 0 0 -1 50 40)\">\n")
+        .unwrap();
+    let body = &svg[start..];
+    let body = &body[body.find('\n').unwrap() + 1..];
+    body[..body.len() - "</g>\n</svg>\n".len()].to_string()
+}
+
+#[test]
+fn svg_document_is_centred_and_flipped() {
+    let draw = Draw::new();
+    draw.background().color(BLACK);
+    let svg = svg::render(&draw, [100.0, 80.0]);
+    let expected = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+        <svg xmlns=\"http://www.w3.org/2000/svg\" width=\"100\" height=\"80\" \
+        viewBox=\"0 0 100 80\">\n\
+        <rect width=\"100%\" height=\"100%\" fill=\"rgb(0,0,0)\"/>\n\
+        <g transform=\"matrix(1 0 0 -1 50 40)\">\n\
+        </g>\n</svg>\n";
+    assert_eq!(svg, expected);
+}
+
+#[test]
+fn svg_ellipse() {
+    let draw = Draw::new();
+    draw.ellipse().x_y(10.0, 20.0).w_h(30.0, 40.0).color(RED);
+    assert_eq!(
+        body(&draw),
+        "<ellipse cx=\"0\" cy=\"0\" rx=\"15\" ry=\"20\" transform=\"matrix(1 0 0 1 10 20)\" \
+         fill=\"rgb(255,0,0)\"/>\n",
+    );
+}
+
+#[test]
+fn svg_rect() {
+    let draw = Draw::new();
+    draw.rect().x_y(-10.0, 5.0).w_h(20.0, 10.0).color(WHITE);
+    assert_eq!(
+        body(&draw),
+        "<rect x=\"-10\" y=\"-5\" width=\"20\" height=\"10\" \
+         transform=\"matrix(1 0 0 1 -10 5)\" fill=\"rgb(255,255,255)\"/>\n",
+    );
+}
+
+#[test]
+fn svg_path() {
+    let draw = Draw::new();
+    draw.polyline()
+        .weight(2.0)
+        .points(vec![pt2(0.0, 0.0), pt2(10.0, 10.0), pt2(20.0, 0.0)])
+        .color(BLUE);
+    assert_eq!(
+        body(&draw),
+        "<path d=\"M0,0L10,10L20,0\" fill=\"none\" stroke=\"rgb(0,0,255)\" stroke-width=\"2\" \
+         stroke-miterlimit=\"4\"/>\n",
+    );
+}
+
+#[test]
+fn svg_scissor_produces_clip_path() {
+    let draw = Draw::new();
+    draw.scissor(geom::Rect::from_x_y_w_h(0.0, 0.0, 40.0, 20.0))
+        .rect()
+        .w_h(50.0, 50.0)
+        .color(WHITE);
+    draw.rect().w_h(10.0, 10.0).color(WHITE);
+    assert_eq!(
+        body(&draw),
+        "<clipPath id=\"nannou-scissor-0\">\
+         <rect x=\"-20\" y=\"-10\" width=\"40\" height=\"20\"/></clipPath>\n\
+         <g clip-path=\"url(#nannou-scissor-0)\">\n\
+         <rect x=\"-25\" y=\"-25\" width=\"50\" height=\"50\" fill=\"rgb(255,255,255)\"/>\n\
+         </g>\n\
+         <rect x=\"-5\" y=\"-5\" width=\"10\" height=\"10\" fill=\"rgb(255,255,255)\"/>\n",
+    );
+}
+
+#[test]
+fn svg_blend_mode() {
+    let draw = Draw::new();
+    draw.color_blend(BLEND_LIGHTEST)
+        .ellipse()
+        .w_h(5.0, 5.0)
+        .color(WHITE);
+    assert_eq!(
+        body(&draw),
+        "<g style=\"mix-blend-mode:lighten\">\n\
+         <ellipse cx=\"0\" cy=\"0\" rx=\"2.5\" ry=\"2.5\" fill=\"rgb(255,255,255)\"/>\n\
+         </g>\n",
+    );
+    assert_eq!(svg::blend_mode(&BLEND_NORMAL), None);
+    assert_eq!(svg::blend_mode(&BLEND_DARKEST), Some("darken"));
+}
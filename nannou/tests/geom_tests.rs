@@ -13,3 +13,388 @@ fn angle_test() {
     let vector = Vector2::new(70.7, -60.8);
     assert_eq!(vector.angle(), -0.7102547457375739);
 }
+
+// The signed area enclosed by the flat path, treating each sub-path as closed.
+fn path_area(path: &geom::Path) -> f32 {
+    use nannou::lyon::path::PathEvent;
+    let mut area = 0.0;
+    let mut first = None;
+    let mut last = pt2(0.0, 0.0);
+    let mut add = |a: Point2, b: Point2| area += a.x * b.y - b.x * a.y;
+    for event in path {
+        match event {
+            PathEvent::Begin { at } => {
+                first = Some(pt2(at.x, at.y));
+                last = pt2(at.x, at.y);
+            }
+            PathEvent::Line { to, .. } => {
+                add(last, pt2(to.x, to.y));
+                last = pt2(to.x, to.y);
+            }
+            PathEvent::End { .. } => {
+                if let Some(first) = first.take() {
+                    add(last, first);
+                }
+            }
+            _ => panic!("unexpected curve in flattened path"),
+        }
+    }
+    area * 0.5
+}
+
+fn square(x: f32, y: f32, size: f32) -> geom::Path {
+    let points = [
+        pt2(x, y),
+        pt2(x + size, y),
+        pt2(x + size, y + size),
+        pt2(x, y + size),
+    ];
+    geom::path().polygon(&points).build()
+}
+
+#[test]
+fn path_boolean_ops() {
+    let a = square(0.0, 0.0, 2.0);
+    let b = square(1.0, 1.0, 2.0);
+    assert_eq!(path_area(&a.union(&b)), 7.0);
+    assert_eq!(path_area(&a.intersection(&b)), 1.0);
+    assert_eq!(path_area(&a.difference(&b)), 3.0);
+    assert_eq!(path_area(&a.xor(&b)), 6.0);
+}
+
+#[test]
+fn path_boolean_fill_rules() {
+    use nannou::geom::path::boolean::{Op, Options};
+    use nannou::lyon::tessellation::FillRule;
+    // Two nested squares wound in the same direction.
+    let nested = square(0.0, 0.0, 10.0).merge(&square(3.0, 3.0, 4.0));
+    let bar = square(-1.0, 4.0, 2.0);
+    let even_odd = Options::default();
+    let non_zero = even_odd.fill_rule(FillRule::NonZero);
+    let ring = nested.boolean(&bar, Op::Difference, &even_odd);
+    assert_eq!(path_area(&ring), 100.0 - 16.0 - 2.0);
+    let solid = nested.boolean(&bar, Op::Difference, &non_zero);
+    assert_eq!(path_area(&solid), 100.0 - 2.0);
+}
+
+#[test]
+fn path_offset() {
+    use nannou::geom::path::offset::Options;
+    let opts = Options::default();
+    let sq = square(0.0, 0.0, 4.0);
+    assert_eq!(path_area(&sq.offset(1.0, &opts)), 36.0);
+    assert_eq!(path_area(&sq.offset(1.0, &opts.join_bevel())), 34.0);
+    assert_eq!(path_area(&sq.offset(-1.0, &opts)), 4.0);
+    assert_eq!(path_area(&sq.offset(-3.0, &opts)), 0.0);
+}
+
+#[test]
+fn polygon_offset_splits() {
+    use nannou::geom::path::offset::Options;
+    // Two squares joined by a narrow bar.
+    let points = vec![
+        pt2(0.0, 0.0),
+        pt2(4.0, 0.0),
+        pt2(4.0, 1.5),
+        pt2(8.0, 1.5),
+        pt2(8.0, 0.0),
+        pt2(12.0, 0.0),
+        pt2(12.0, 4.0),
+        pt2(8.0, 4.0),
+        pt2(8.0, 2.5),
+        pt2(4.0, 2.5),
+        pt2(4.0, 4.0),
+        pt2(0.0, 4.0),
+    ];
+    let contours = geom::Polygon::new(points).offset(-0.5, &Options::default());
+    assert_eq!(contours.len(), 2);
+}
+
+#[test]
+fn path_measure() {
+    let sq = square(0.0, 0.0, 4.0);
+    let measure = sq.measure(0.1);
+    assert_eq!(measure.length(), 16.0);
+    assert_eq!(measure.point_at(0.5), Some(pt2(4.0, 4.0)));
+    assert_eq!(measure.point_at_distance(6.0), Some(pt2(4.0, 2.0)));
+    assert_eq!(measure.tangent_at(0.125), Some(vec2(1.0, 0.0)));
+    assert_eq!(measure.tangent_at_distance(6.0), Some(vec2(0.0, 1.0)));
+    let corners = vec![
+        pt2(0.0, 0.0),
+        pt2(4.0, 0.0),
+        pt2(4.0, 4.0),
+        pt2(0.0, 4.0),
+        pt2(0.0, 0.0),
+    ];
+    assert_eq!(measure.resample(5), corners);
+    let (before, after) = measure.split_at_distance(6.0);
+    assert_eq!(before.length(0.1), 6.0);
+    assert_eq!(after.length(0.1), 10.0);
+    assert!(geom::Path::new().measure(0.1).point_at(0.5).is_none());
+}
+
+#[test]
+fn path_hatch() {
+    use nannou::geom::path::hatch::Options;
+    let opts = Options::default().spacing(1.0);
+    let sq = square(0.5, 0.5, 10.0);
+    let lines = sq.hatch(&opts);
+    assert_eq!(lines.len(), 10);
+    assert!(lines
+        .iter()
+        .all(|l| l.len() == 2 && (l[1] - l[0]).magnitude() == 10.0));
+    // Travel-optimised lines alternate direction, so each begins directly above the last.
+    let travel: f32 = lines
+        .windows(2)
+        .map(|w| (w[1][0] - w[0][1]).magnitude())
+        .sum();
+    assert_eq!(travel, 9.0);
+    assert_eq!(sq.hatch(&opts.cross_hatch()).len(), 20);
+    // Contours are closed and inset from the outline by half the spacing, then the spacing.
+    let contours = square(0.0, 0.0, 10.0).hatch(&opts.spacing(2.0).concentric());
+    assert_eq!(contours.len(), 2);
+    assert!(contours.iter().all(|c| c.len() == 5 && c[0] == c[4]));
+    assert!(contours[0].contains(&pt2(1.0, 1.0)));
+    assert!(contours[1].contains(&pt2(3.0, 3.0)));
+    // Contours spaced more closely than the tolerance are rejected.
+    let opts = opts.concentric().tolerance(0.1);
+    assert_eq!(square(0.0, 0.0, 10.0).hatch(&opts.spacing(0.05)).len(), 0);
+    assert_eq!(square(0.0, 0.0, 10.0).hatch(&opts.spacing(0.1)).len(), 50);
+}
+
+#[test]
+fn polygon_triangulate() {
+    use nannou::geom::polygon;
+    let tris_area = |tris: &[geom::Tri<Point2>]| {
+        tris.iter()
+            .map(|t| (t[1] - t[0]).perp_dot(t[2] - t[0]) * 0.5)
+            .sum::<f32>()
+    };
+    // A concave "L" shape, whose notch a fan from the first point would cover.
+    let l = vec![
+        pt2(1.0, 1.0),
+        pt2(4.0, 1.0),
+        pt2(4.0, 4.0),
+        pt2(3.0, 4.0),
+        pt2(3.0, 2.0),
+        pt2(1.0, 2.0),
+    ];
+    let tris = polygon::triangulate(&l, &[]);
+    assert_eq!(tris.len(), 4);
+    assert_eq!(tris_area(&tris), 5.0);
+    assert!(geom::Polygon::new(l.clone())
+        .contains(&pt2(2.0, 3.0))
+        .is_none());
+    assert!(geom::Polygon::new(l).contains(&pt2(3.5, 3.0)).is_some());
+    // A square with a square hole.
+    let outer = [
+        pt2(0.0, 0.0),
+        pt2(10.0, 0.0),
+        pt2(10.0, 10.0),
+        pt2(0.0, 10.0),
+    ];
+    let hole = [pt2(3.0, 3.0), pt2(7.0, 3.0), pt2(7.0, 7.0), pt2(3.0, 7.0)];
+    let tris = polygon::triangulate(&outer, &[&hole]);
+    assert_eq!(tris_area(&tris), 84.0);
+    assert!(geom::tri::iter_contains(&tris, &pt2(5.0, 5.0)).is_none());
+    let indices = polygon::triangulate_indices(&outer, &[&hole]);
+    assert_eq!(indices.len(), tris.len() * 3);
+    assert!(indices.iter().all(|&i| i < outer.len() + hole.len()));
+}
+
+#[test]
+fn delaunay_and_voronoi() {
+    use nannou::geom::{delaunay, voronoi};
+    let points = vec![
+        pt2(0.0, 0.0),
+        pt2(10.0, 0.0),
+        pt2(10.0, 10.0),
+        pt2(0.0, 10.0),
+        pt2(5.0, 5.0),
+    ];
+    assert_eq!(delaunay::convex_hull(&points), vec![0, 1, 2, 3]);
+    let triangulation = delaunay::Triangulation::new(points.clone());
+    assert_eq!(triangulation.len(), 4);
+    assert_eq!(triangulation.point_neighbours(4), vec![0, 1, 2, 3]);
+    assert_eq!(triangulation.edges().count(), 8);
+    for t in 0..triangulation.len() {
+        let hull_edges = triangulation
+            .neighbours(t)
+            .iter()
+            .filter(|n| n.is_none())
+            .count();
+        assert_eq!(hull_edges, 1);
+    }
+    let bounds = geom::Rect::from_corners(pt2(-5.0, -5.0), pt2(15.0, 15.0));
+    let cells = voronoi::cells(points, bounds);
+    let areas: Vec<f32> = cells
+        .iter()
+        .map(|c| {
+            let n = c.len();
+            (0..n).map(|i| c[i].perp_dot(c[(i + 1) % n])).sum::<f32>() * 0.5
+        })
+        .collect();
+    assert_eq!(areas, vec![87.5, 87.5, 87.5, 87.5, 50.0]);
+}
+
+#[test]
+fn delaunay_keeps_nearly_collinear_hull_triangles() {
+    use nannou::geom::delaunay::{convex_hull, Triangulation};
+    fn area(tri: [Point2; 3]) -> f32 {
+        let [a, b, c] = tri;
+        (b - a).perp_dot(c - a).abs() * 0.5
+    }
+    fn check(points: Vec<Point2>, expected_triangles: usize) {
+        let hull = convex_hull(&points);
+        let hull_area: f32 = (1..hull.len() - 1)
+            .map(|i| area([points[hull[0]], points[hull[i]], points[hull[i + 1]]]))
+            .sum();
+        let triangulation = Triangulation::new(points);
+        assert_eq!(triangulation.len(), expected_triangles);
+        let total: f32 = triangulation.triangles().map(|t| area(t.0)).sum();
+        assert!(
+            (total - hull_area).abs() < 1e-3,
+            "{} != {}",
+            total,
+            hull_area
+        );
+    }
+    for &eps in &[1e-2, 1e-3, 1e-4, 1e-5, 0.0] {
+        // A point just inside one edge of a triangle.
+        let points = vec![
+            pt2(-10.0, 0.0),
+            pt2(10.0, 0.0),
+            pt2(0.0, 10.0),
+            pt2(0.0, eps),
+        ];
+        check(points, if eps == 0.0 { 2 } else { 3 });
+        // Shallow arcs bulging into and out of the hull.
+        for &dir in &[-1.0, 1.0] {
+            let mut points: Vec<Point2> = (-10..=10)
+                .map(|i| pt2(i as f32, dir * eps * (i * i) as f32))
+                .collect();
+            points.push(pt2(0.0, dir * -5.0));
+            // With `h` points on the hull, `n` points are covered by `2n - h - 2` triangles.
+            check(points, if eps == 0.0 { 20 } else { 39 });
+        }
+    }
+}
+
+#[test]
+fn spatial_indexes() {
+    use nannou::geom::spatial::{Grid, KdTree, QuadTree};
+    let bounds = geom::Rect::from_corners(pt2(0.0, 0.0), pt2(10.0, 10.0));
+    macro_rules! check_index {
+        ($index:expr) => {{
+            let mut index = $index;
+            let mut centre = None;
+            for x in 0..10 {
+                for y in 0..10 {
+                    let id = index.insert(pt2(x as f32, y as f32), (x, y));
+                    if (x, y) == (5, 5) {
+                        centre = Some(id);
+                    }
+                }
+            }
+            assert_eq!(index.len(), 100);
+            assert_eq!(index.within_radius(pt2(5.0, 5.0), 1.0).len(), 5);
+            let rect = geom::Rect::from_corners(pt2(2.0, 2.0), pt2(4.0, 3.0));
+            assert_eq!(index.within_bounds(&rect).len(), 6);
+            let nearest: Vec<_> = index
+                .nearest(pt2(0.1, 0.2), 3)
+                .into_iter()
+                .map(|(_, &v)| v)
+                .collect();
+            assert_eq!(nearest, vec![(0, 0), (0, 1), (1, 0)]);
+            let centre = centre.unwrap();
+            assert_eq!(index.remove(centre), Some((pt2(5.0, 5.0), (5, 5))));
+            assert!(index.get(centre).is_none());
+            assert_eq!(index.within_radius(pt2(5.0, 5.0), 1.0).len(), 4);
+            index.insert(pt2(20.0, 20.0), (20, 20));
+            let (_, &far) = index.nearest(pt2(30.0, 30.0), 1)[0];
+            assert_eq!(far, (20, 20));
+        }};
+    }
+    check_index!(Grid::new(bounds, 2.0));
+    check_index!(QuadTree::new(bounds));
+    check_index!(KdTree::new());
+}
+
+#[test]
+fn intersection_queries() {
+    use nannou::geom::intersect::{self, Ray2, Ray3};
+    let rect = geom::Rect::from_corners(pt2(0.0, 0.0), pt2(10.0, 10.0));
+    let hit = intersect::ray_rect(&Ray2::new(pt2(-5.0, 5.0), vec2(2.0, 0.0)), &rect).unwrap();
+    assert_eq!(hit.point, pt2(0.0, 5.0));
+    assert_eq!(hit.normal, vec2(-1.0, 0.0));
+    assert_eq!(hit.distance, 5.0);
+    let ray = Ray2::new(pt2(-5.0, 15.0), vec2(1.0, 0.0));
+    assert!(intersect::ray_rect(&ray, &rect).is_none());
+    let crossing = intersect::segment_segment(
+        pt2(0.0, 0.0),
+        pt2(10.0, 10.0),
+        pt2(0.0, 10.0),
+        pt2(10.0, 0.0),
+    );
+    assert_eq!(crossing, Some(pt2(5.0, 5.0)));
+
+    let cuboid = geom::Cuboid::from_xyz_whd(pt3(0.0, 0.0, 0.0), vec3(2.0, 2.0, 2.0));
+    let ray = Ray3::new(pt3(0.0, 0.0, -5.0), vec3(0.0, 0.0, 1.0));
+    let hit = intersect::ray_cuboid(&ray, &cuboid).unwrap();
+    assert_eq!(hit.point, pt3(0.0, 0.0, -1.0));
+    assert_eq!(hit.normal, vec3(0.0, 0.0, -1.0));
+    let tri = geom::Tri([
+        pt3(-1.0, -1.0, 0.0),
+        pt3(1.0, -1.0, 0.0),
+        pt3(0.0, 1.0, 0.0),
+    ]);
+    assert_eq!(intersect::ray_triangle(&ray, tri).unwrap().distance, 5.0);
+
+    let contact = intersect::circle_rect(pt2(-0.5, 5.0), 1.0, &rect).unwrap();
+    assert_eq!(contact.normal, vec2(-1.0, 0.0));
+    assert_eq!(contact.depth, 0.5);
+    let square = [
+        pt2(0.0, 0.0),
+        pt2(10.0, 0.0),
+        pt2(10.0, 10.0),
+        pt2(0.0, 10.0),
+    ];
+    let shifted: Vec<_> = square.iter().map(|&p| p + vec2(8.0, 1.0)).collect();
+    let contact = intersect::polygon_polygon(&square, &shifted).unwrap();
+    assert_eq!(contact.depth, 2.0);
+    assert_eq!(contact.normal, vec2(-1.0, 0.0));
+
+    assert_eq!(intersect::signed_distance_rect(pt2(5.0, 3.0), &rect), -3.0);
+    assert_eq!(intersect::signed_distance_rect(pt2(13.0, 14.0), &rect), 5.0);
+    let d = intersect::signed_distance_polygon(pt2(13.0, 14.0), square.iter().cloned());
+    assert_eq!(d, 5.0);
+    assert_eq!(
+        intersect::closest_point_on_rect(pt2(5.0, 3.0), &rect),
+        pt2(5.0, 0.0)
+    );
+}
+
+#[test]
+fn sdf_contours() {
+    use nannou::geom::sdf::{self, Sdf};
+
+    let circle = sdf::circle(pt2(10.0, -5.0), 100.0);
+    assert_eq!(circle.distance(pt2(10.0, 95.0)), 0.0);
+    let field = circle.sample(geom::Rect::from_w_h(400.0, 400.0), 4.0);
+    assert_eq!((field.cols(), field.rows()), (101, 101));
+
+    // A single closed ring winding counter-clockwise around the circle.
+    let contours = field.contours(0.0);
+    assert_eq!(contours.len(), 1);
+    let ring = &contours[0];
+    assert_eq!(ring.first(), ring.last());
+    let area: f32 = ring.windows(2).map(|w| w[0].perp_dot(w[1])).sum::<f32>() * 0.5;
+    assert!((area - PI * 100.0 * 100.0).abs() < 100.0);
+
+    // The edge of a hollow circle is made up of an outer and an inner ring.
+    let hollow = circle.onion(20.0);
+    let contours = hollow
+        .sample(geom::Rect::from_w_h(400.0, 400.0), 4.0)
+        .contours(0.0);
+    assert_eq!(contours.len(), 2);
+}
@@ -0,0 +1,117 @@
+use nannou::prelude::*;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+const FRAMES: u64 = 4;
+
+static FRAMES_RENDERED: AtomicU64 = AtomicU64::new(0);
+static NTIMES_UPDATES: AtomicU64 = AtomicU64::new(0);
+
+struct Model {
+    updates: u64,
+}
+
+fn model(app: &App) -> Model {
+    assert!(app.is_headless());
+    // There is no event loop to wake, so the proxy must be a no-op rather than a panic.
+    assert!(app.create_proxy().wakeup().is_ok());
+    // The window API used by sketches is backed by a stand-in for the offscreen texture.
+    let window = app.main_window();
+    assert_eq!(window.id(), app.window_id());
+    assert_eq!(window.inner_size_pixels(), (8, 8));
+    assert_eq!(window.rect(), geom::Rect::from_w_h(8.0, 8.0));
+    window.set_title("headless");
+    Model { updates: 0 }
+}
+
+fn update(_app: &App, model: &mut Model, _update: Update) {
+    model.updates += 1;
+}
+
+fn view(app: &App, _model: &Model, frame: Frame) {
+    assert_eq!(frame.window_id(), app.window_id());
+    assert_eq!(frame.rect(), app.main_window().rect());
+    let color = if frame.nth() % 2 == 0 { RED } else { BLUE };
+    let draw = app.draw();
+    draw.background().color(color);
+    draw.to_frame(app, &frame).unwrap();
+}
+
+fn exit(app: &App, model: Model) {
+    assert_eq!(app.elapsed_frames(), FRAMES);
+    assert_eq!(model.updates, FRAMES);
+    FRAMES_RENDERED.store(app.elapsed_frames(), Ordering::SeqCst);
+}
+
+fn ntimes_model(app: &App) -> Model {
+    app.set_loop_mode(LoopMode::loop_ntimes(2));
+    Model { updates: 0 }
+}
+
+fn ntimes_exit(_app: &App, model: Model) {
+    NTIMES_UPDATES.store(model.updates, Ordering::SeqCst);
+}
+
+fn adapter_available() -> bool {
+    let instance = wgpu::Instance::new(wgpu::DEFAULT_BACKENDS);
+    let options = wgpu::RequestAdapterOptions {
+        power_preference: wgpu::DEFAULT_POWER_PREFERENCE,
+        compatible_surface: None,
+    };
+    wgpu::AdapterMap::default()
+        .get_or_request(options, &instance)
+        .is_some()
+}
+
+#[test]
+fn headless_app_renders_and_captures_frames() {
+    if !adapter_available() {
+        eprintln!("skipping headless app test: no wgpu adapter available");
+        return;
+    }
+
+    let dir = std::env::temp_dir().join("nannou_headless_app_frames");
+    let _ = std::fs::remove_dir_all(&dir);
+
+    nannou::app(model)
+        .update(update)
+        .view(view)
+        .exit(exit)
+        .headless(8, 8)
+        .frames(FRAMES)
+        .capture_frames(&dir)
+        .run();
+
+    assert_eq!(FRAMES_RENDERED.load(Ordering::SeqCst), FRAMES);
+    for n in 0..FRAMES {
+        let path = dir.join(format!("{:05}.png", n));
+        let image = nannou::image::open(&path).unwrap().to_rgba8();
+        assert_eq!(image.dimensions(), (8, 8));
+        let expected = if n % 2 == 0 {
+            [255, 0, 0, 255]
+        } else {
+            [0, 0, 255, 255]
+        };
+        for pixel in image.pixels() {
+            assert_eq!(pixel.0, expected, "unexpected pixel in frame {}", n);
+        }
+    }
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn headless_app_honours_ntimes_loop_mode() {
+    if !adapter_available() {
+        eprintln!("skipping headless app test: no wgpu adapter available");
+        return;
+    }
+
+    nannou::app(ntimes_model)
+        .update(update)
+        .exit(ntimes_exit)
+        .headless(8, 8)
+        .frames(FRAMES)
+        .run();
+
+    assert_eq!(NTIMES_UPDATES.load(Ordering::SeqCst), 2);
+}
@@ -0,0 +1,111 @@
+use nannou::mesh::io::{obj, ply, stl, Encoding, MeshData};
+use nannou::prelude::*;
+
+fn quad() -> MeshData {
+    let src = "
+        v 0 0 0
+        v 1 0 0
+        v 1 1 0
+        v 0 1 0
+        vt 0 0
+        vt 1 0
+        vt 1 1
+        vt 0 1
+        vn 0 0 1
+        f 1/1/1 2/2/1 3/3/1 -1/-1/-1
+    ";
+    obj::read(src.as_bytes()).unwrap()
+}
+
+#[test]
+fn obj_faces_are_triangulated() {
+    let mesh = quad();
+    assert_eq!(mesh.points.len(), 4);
+    assert_eq!(mesh.indices, vec![0, 1, 2, 0, 2, 3]);
+    assert_eq!(mesh.normals, vec![vec3(0.0, 0.0, 1.0); 4]);
+    assert_eq!(mesh.tex_coords[2], pt2(1.0, 1.0));
+    assert!(mesh.colors.is_empty());
+    assert!(obj::read("f 1 2 3".as_bytes()).is_err());
+}
+
+#[test]
+fn mesh_files_round_trip() {
+    let mesh = quad();
+
+    let mut bytes = vec![];
+    obj::write(&mesh, &mut bytes).unwrap();
+    assert_eq!(obj::read(&bytes[..]).unwrap(), mesh);
+
+    for &encoding in &[Encoding::Ascii, Encoding::Binary] {
+        let mut bytes = vec![];
+        ply::write(&mesh, &mut bytes, encoding).unwrap();
+        assert_eq!(ply::read(&bytes[..]).unwrap(), mesh);
+
+        // STL stores three vertices for every triangle, each with the triangle's normal.
+        let mut bytes = vec![];
+        stl::write(&mesh, &mut bytes, encoding).unwrap();
+        let triangles = stl::read(&bytes[..]).unwrap();
+        assert_eq!(triangles.points.len(), 6);
+        assert_eq!(triangles.points[5], mesh.points[3]);
+        assert_eq!(triangles.normals, vec![vec3(0.0, 0.0, 1.0); 6]);
+    }
+}
+
+#[test]
+fn ply_colors_round_trip() {
+    let mut mesh = quad();
+    mesh.colors = [
+        [255, 0, 0, 255],
+        [0, 128, 0, 255],
+        [10, 20, 30, 128],
+        [0; 4],
+    ]
+    .iter()
+    .map(|&[r, g, b, a]| Srgba::<u8>::new(r, g, b, a).into_format().into_linear())
+    .collect();
+
+    for &encoding in &[Encoding::Ascii, Encoding::Binary] {
+        let mut bytes = vec![];
+        ply::write(&mesh, &mut bytes, encoding).unwrap();
+        let read = ply::read(&bytes[..]).unwrap();
+        assert_eq!(read.points, mesh.points);
+        assert_eq!(read.colors.len(), mesh.colors.len());
+        for (a, b) in read.colors.iter().zip(&mesh.colors) {
+            let (ar, ag, ab, aa): (f32, f32, f32, f32) = (*a).into();
+            let (br, bg, bb, ba): (f32, f32, f32, f32) = (*b).into();
+            for &(a, b) in &[(ar, br), (ag, bg), (ab, bb), (aa, ba)] {
+                assert!((a - b).abs() < 1e-5, "{} != {}", a, b);
+            }
+        }
+    }
+}
+
+#[test]
+fn stl_write_rejects_invalid_meshes() {
+    let mut mesh = quad();
+    mesh.indices.push(7);
+    mesh.indices.extend_from_slice(&[0, 1]);
+    for &encoding in &[Encoding::Ascii, Encoding::Binary] {
+        let err = stl::write(&mesh, &mut vec![], encoding).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    }
+}
+
+#[test]
+fn degenerate_triangles_have_zero_normals() {
+    let mut mesh = MeshData {
+        points: vec![pt3(0.0, 0.0, 0.0), pt3(1.0, 0.0, 0.0), pt3(2.0, 0.0, 0.0)],
+        indices: vec![0, 1, 2],
+        ..Default::default()
+    };
+    mesh.compute_normals();
+    assert_eq!(mesh.normals, vec![vec3(0.0, 0.0, 0.0); 3]);
+
+    for &encoding in &[Encoding::Ascii, Encoding::Binary] {
+        let mut bytes = vec![];
+        stl::write(&mesh, &mut bytes, encoding).unwrap();
+        let triangles = stl::read(&bytes[..]).unwrap();
+        assert_eq!(triangles.points, mesh.points);
+        assert_eq!(triangles.normals, vec![vec3(0.0, 0.0, 0.0); 3]);
+    }
+}
@@ -0,0 +1,252 @@
+use nannou::image;
+use nannou::prelude::*;
+use nannou::rand::Rng;
+use nannou::testing;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::{env, fs, panic};
+
+// Serialises the tests that compare against references, as they are affected by the
+// `NANNOU_UPDATE_SNAPSHOTS` environment variable.
+static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+fn view(ctxt: &mut testing::Context, draw: &Draw) {
+    draw.background().color(BLACK);
+    let x = ctxt.rng().gen_range(-20.0, 20.0);
+    draw.ellipse()
+        .x_y(x, ctxt.time() * 60.0)
+        .radius(10.0)
+        .color(WHITE);
+}
+
+fn random_view(_ctxt: &mut testing::Context, draw: &Draw) {
+    draw.background().color(BLACK);
+    draw.ellipse()
+        .x_y(random_range(-20.0, 20.0), random_f32() * 20.0)
+        .radius(10.0)
+        .color(WHITE);
+}
+
+fn reference_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("nannou_testing_{}", name));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+fn frame_path(dir: &Path, name: &str, file: &str) -> PathBuf {
+    dir.join(name).join(file)
+}
+
+#[test]
+fn snapshot_frames_are_deterministic() {
+    let snapshot = testing::sketch("deterministic", view)
+        .size(64, 64)
+        .frames(3);
+    let a = snapshot.render();
+    let b = snapshot.render();
+    assert_eq!(a.len(), 3);
+    for (a, b) in a.iter().zip(&b) {
+        assert_eq!(testing::compare(a, b, 0).differing_pixels, 0);
+    }
+    // The clock advances between frames, so consecutive frames should differ.
+    assert!(testing::compare(&a[0], &a[1], 0).differing_pixels > 0);
+}
+
+#[test]
+fn random_functions_are_seeded() {
+    let snapshot = testing::sketch("seeded", random_view).size(64, 64);
+    let a = snapshot.render();
+    let b = snapshot.render();
+    assert_eq!(testing::compare(&a[0], &b[0], 0).differing_pixels, 0);
+
+    let c = testing::sketch("seeded", random_view)
+        .size(64, 64)
+        .seed(42)
+        .render();
+    assert!(testing::compare(&a[0], &c[0], 0).differing_pixels > 0);
+
+    // The seed only applies for the duration of the closure.
+    let seeded = nannou::rand::with_seed(7, || (random_f32(), random_range(0, 1000)));
+    assert_eq!(
+        seeded,
+        nannou::rand::with_seed(7, || (random_f32(), random_range(0, 1000)))
+    );
+}
+
+#[test]
+fn missing_references_are_written_when_updating() {
+    let _lock = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let name = "missing";
+    let dir = reference_dir(name);
+    let snapshot = testing::sketch(name, view)
+        .size(32, 32)
+        .frames(2)
+        .reference_dir(&dir);
+
+    let reference = frame_path(&dir, name, "frame-0000.png");
+    let actual = frame_path(&dir, name, "frame-0000.actual.png");
+    match snapshot.run() {
+        Err(testing::Error::MissingReference {
+            reference: r,
+            actual: a,
+        }) => {
+            assert_eq!(r, reference);
+            assert_eq!(a, actual);
+        }
+        other => panic!("expected a missing reference, got {:?}", other),
+    }
+    assert!(actual.exists());
+    assert!(!reference.exists());
+    assert!(panic::catch_unwind(panic::AssertUnwindSafe(|| snapshot.assert())).is_err());
+
+    env::set_var(testing::UPDATE_SNAPSHOTS_ENV_VAR, "1");
+    let result = snapshot.run();
+    env::remove_var(testing::UPDATE_SNAPSHOTS_ENV_VAR);
+    result.unwrap();
+    assert!(reference.exists());
+    assert!(frame_path(&dir, name, "frame-0001.png").exists());
+    assert!(!actual.exists());
+
+    // The written references now match.
+    snapshot.assert();
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn frames_are_compared_against_references() {
+    let _lock = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let name = "compare";
+    let dir = reference_dir(name);
+    let snapshot = testing::sketch(name, view)
+        .size(32, 32)
+        .tolerance(4)
+        .reference_dir(&dir);
+    fs::create_dir_all(dir.join(name)).unwrap();
+    let reference = frame_path(&dir, name, "frame-0000.png");
+    let actual = frame_path(&dir, name, "frame-0000.actual.png");
+    let diff = frame_path(&dir, name, "frame-0000.diff.png");
+    let rendered = snapshot.render().remove(0);
+
+    // Differences within the tolerance are accepted.
+    let mut image = rendered.clone();
+    image.get_pixel_mut(0, 0).0[0] ^= 4;
+    image.save(&reference).unwrap();
+    snapshot.assert();
+    assert!(!actual.exists() && !diff.exists());
+
+    // Differences beyond the tolerance are reported and written alongside the reference.
+    image.get_pixel_mut(0, 0).0[0] = rendered.get_pixel(0, 0).0[0] ^ 0x80;
+    image.get_pixel_mut(1, 0).0[1] = rendered.get_pixel(1, 0).0[1] ^ 0x80;
+    image.save(&reference).unwrap();
+    match snapshot.run() {
+        Err(testing::Error::Mismatch(mismatches)) => {
+            assert_eq!(mismatches.len(), 1);
+            assert_eq!(mismatches[0].frame, 0);
+            assert_eq!(mismatches[0].differing_pixels, 2);
+            assert_eq!(mismatches[0].max_difference, 0x80);
+            assert_eq!(mismatches[0].actual, actual);
+            assert_eq!(mismatches[0].diff, diff);
+        }
+        other => panic!("expected a mismatch, got {:?}", other),
+    }
+    let written = image::open(&actual).unwrap().to_rgba8();
+    assert_eq!(testing::compare(&rendered, &written, 0).differing_pixels, 0);
+    let diff_image = image::open(&diff).unwrap().to_rgba8();
+    assert_eq!(diff_image.get_pixel(0, 0).0, [255, 0, 0, 255]);
+    assert_eq!(diff_image.get_pixel(1, 0).0, [255, 0, 0, 255]);
+    assert_ne!(diff_image.get_pixel(2, 0).0, [255, 0, 0, 255]);
+    assert!(panic::catch_unwind(panic::AssertUnwindSafe(|| snapshot.assert())).is_err());
+
+    // Up to `max_differing_pixels` may exceed the tolerance.
+    snapshot.max_differing_pixels(2).assert();
+    assert!(!actual.exists() && !diff.exists());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn references_of_a_different_size_are_rejected() {
+    let _lock = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let name = "size";
+    let dir = reference_dir(name);
+    let snapshot = testing::sketch(name, view).size(32, 16).reference_dir(&dir);
+    fs::create_dir_all(dir.join(name)).unwrap();
+    let reference = frame_path(&dir, name, "frame-0000.png");
+    image::RgbaImage::new(16, 16).save(&reference).unwrap();
+    match snapshot.run() {
+        Err(testing::Error::SizeMismatch {
+            reference: r,
+            expected,
+            actual,
+        }) => {
+            assert_eq!(r, reference);
+            assert_eq!(expected, [16, 16]);
+            assert_eq!(actual, [32, 16]);
+        }
+        other => panic!("expected a size mismatch, got {:?}", other),
+    }
+    assert!(frame_path(&dir, name, "frame-0000.actual.png").exists());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+struct Model {
+    color: Rgb8,
+}
+
+fn model(_app: &App) -> Model {
+    Model { color: RED }
+}
+
+fn update(app: &App, model: &mut Model, _update: Update) {
+    model.color = if app.elapsed_frames() % 2 == 0 {
+        RED
+    } else {
+        BLUE
+    };
+}
+
+fn app_view(app: &App, model: &Model, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(model.color);
+    draw.to_frame(app, &frame).unwrap();
+}
+
+fn sketch_view(app: &App, frame: Frame) {
+    let draw = app.draw();
+    draw.background().color(GREEN);
+    draw.to_frame(app, &frame).unwrap();
+}
+
+fn adapter_available() -> bool {
+    let instance = wgpu::Instance::new(wgpu::DEFAULT_BACKENDS);
+    let options = wgpu::RequestAdapterOptions {
+        power_preference: wgpu::DEFAULT_POWER_PREFERENCE,
+        compatible_surface: None,
+    };
+    wgpu::AdapterMap::default()
+        .get_or_request(options, &instance)
+        .is_some()
+}
+
+#[test]
+fn headless_snapshots_run_unmodified_apps() {
+    if !adapter_available() {
+        eprintln!("skipping headless snapshot test: no wgpu adapter available");
+        return;
+    }
+
+    let frames = testing::headless("app", nannou::app(model).update(update).view(app_view))
+        .size(8, 8)
+        .frames(2)
+        .render();
+    assert_eq!(frames.len(), 2);
+    for (frame, expected) in frames.iter().zip(&[[255, 0, 0, 255], [0, 0, 255, 255]]) {
+        assert_eq!(frame.dimensions(), (8, 8));
+        assert!(frame.pixels().all(|p| p.0 == *expected));
+    }
+
+    let frames = testing::headless("sketch", nannou::sketch(sketch_view))
+        .size(8, 8)
+        .render();
+    assert!(frames[0].pixels().all(|p| p.0 == [0, 128, 0, 255]));
+}
@@ -0,0 +1,322 @@
+use nannou::prelude::*;
+use nannou::text::font::system::{self, Weight};
+use nannou::text::rich::{self, Style};
+use nannou::text::{self, cursor, font, on_path, sdf, shape};
+
+#[test]
+fn shaping_applies_kerning() {
+    let font = font::default_notosans();
+    let scale = text::pt_to_scale(24);
+    let width = |s| shape::line(s, &font, scale).width();
+    assert!(width("AV") < width("A") + width("V"));
+}
+
+#[test]
+fn clusters_follow_graphemes_and_direction() {
+    let font = font::default_notosans();
+    let scale = text::pt_to_scale(24);
+
+    // A letter followed by a combining accent forms a single cluster.
+    let line = shape::line("e\u{301}x", &font, scale);
+    assert_eq!(line.clusters().len(), 2);
+    assert_eq!(line.clusters()[1].byte_range, 3..4);
+
+    // Hebrew is displayed from right to left following the latin text.
+    let line = shape::line("abc \u{5d0}\u{5d1}\u{5d2}", &font, scale);
+    let clusters = line.clusters();
+    assert!(!clusters[0].rtl);
+    assert!(clusters[4].rtl && clusters[5].rtl && clusters[6].rtl);
+    assert!(clusters[4].x.start > clusters[5].x.start);
+    assert!(clusters[5].x.start > clusters[6].x.start);
+    assert!(clusters[6].x.start >= clusters[2].x.end);
+}
+
+#[test]
+fn lines_wrap_between_clusters() {
+    let text = text::text("hello wide world\r\nsecond e\u{301}e\u{301} line")
+        .font_size(24)
+        .build(Rect::from_w_h(150.0, 400.0));
+    let lines: Vec<_> = text
+        .line_infos()
+        .iter()
+        .map(|info| (&text.text()[info.byte_range()], info.start_cluster))
+        .collect();
+    assert_eq!(
+        lines,
+        vec![
+            ("hello wide", 0),
+            ("world", 11),
+            ("second e\u{301}e\u{301}", 17),
+            ("line", 27),
+        ]
+    );
+}
+
+#[test]
+fn rich_text_lines_span_many_styles() {
+    let fonts = font::Map::new();
+    let text = rich::text()
+        .span("Hello ", Style::new())
+        .span("big ", Style::new().font_size(36).underline())
+        .span("world of spans", Style::new().letter_spacing(2.0))
+        .font_size(24)
+        .build(Rect::from_w_h(220.0, 400.0), &fonts);
+
+    // Lines wrap across spans and are as tall as their largest span.
+    let lines: Vec<_> = text
+        .line_infos()
+        .iter()
+        .map(|info| &text.text()[info.byte_range()])
+        .collect();
+    assert_eq!(lines, vec!["Hello big world", "of spans"]);
+    let heights: Vec<_> = text.line_rects().map(|r| r.h()).collect();
+    assert_eq!(heights, vec![36.0, 24.0]);
+
+    // Glyphs and decorations are attributed to their spans.
+    let underlines: Vec<_> = text.decorations().map(|(span, _, _)| span).collect();
+    assert_eq!(underlines, vec![1]);
+    let glyph = text.glyphs().nth(6).unwrap();
+    assert_eq!(glyph.span, 1);
+    assert!(text.line_rects().next().unwrap().contains(glyph.position));
+
+    // Selections cover the selected clusters of each line.
+    let start = cursor::Index {
+        line: 0,
+        cluster: 6,
+    };
+    let end = cursor::Index {
+        line: 1,
+        cluster: 2,
+    };
+    let selected: Vec<_> = text.selected_rects(start, end).collect();
+    assert_eq!(selected.len(), 2);
+    assert_eq!(
+        selected[0].right(),
+        text.line_rects().next().unwrap().right()
+    );
+}
+
+#[test]
+fn rich_text_span_edges_snap_to_char_boundaries() {
+    let fonts = font::Map::new();
+    // Both edges of the second span fall within the two-byte `é`.
+    let spans = vec![
+        rich::Span {
+            byte_range: 0..2,
+            style: Style::new(),
+        },
+        rich::Span {
+            byte_range: 2..5,
+            style: Style::new().underline(),
+        },
+    ];
+    let text =
+        rich::Builder::with_spans("h\u{e9}llo", spans).build(Rect::from_w_h(200.0, 100.0), &fonts);
+    let ranges: Vec<_> = text.spans().iter().map(|s| s.byte_range.clone()).collect();
+    assert_eq!(ranges, vec![0..1, 1..5, 5..6]);
+    assert_eq!(text.glyphs().count(), 5);
+}
+
+#[test]
+fn rich_text_orders_rtl_spans_across_the_line() {
+    let fonts = font::Map::new();
+    // A Hebrew phrase split across two styled spans, followed by latin text.
+    let text = rich::text()
+        .span("\u{5e9}\u{5dc}\u{5d5}\u{5dd} ", Style::new())
+        .span("\u{5e2}\u{5d5}\u{5dc}\u{5dd}", Style::new().underline())
+        .span(" abc", Style::new())
+        .font_size(24)
+        .no_line_wrap()
+        .build(Rect::from_w_h(400.0, 100.0), &fonts);
+    let span_x = |span| {
+        let xs: Vec<_> = text
+            .glyphs()
+            .filter(|g| g.span == span)
+            .map(|g| g.position.x)
+            .collect();
+        let min = xs.iter().cloned().fold(std::f32::MAX, f32::min);
+        let max = xs.iter().cloned().fold(std::f32::MIN, f32::max);
+        (min, max)
+    };
+
+    // The first word is read first and so is displayed to the right of the second, while the
+    // latin text follows the right-to-left paragraph and so is displayed leftmost.
+    let (first_min, _) = span_x(0);
+    let (second_min, second_max) = span_x(1);
+    let (_, latin_max) = span_x(2);
+    assert!(second_max < first_min);
+    assert!(latin_max < second_min);
+
+    // Decorations cover the span where it is displayed.
+    let (_, underline, rect) = text.decorations().next().unwrap();
+    assert_eq!(underline, rich::Decoration::Underline);
+    assert!(rect.right() <= first_min + 0.01);
+}
+
+#[test]
+fn system_fonts_match_family_weight_and_style() {
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../assets/fonts");
+    let fonts = system::scan(&dir);
+    assert_eq!(fonts.len(), 4);
+    assert!(fonts.iter().all(|info| info.family == "Noto Sans"));
+
+    let find = |weight, style| {
+        system::best_match(&fonts, "noto sans", weight, style)
+            .and_then(|info| info.path.file_name())
+            .and_then(|name| name.to_str())
+            .map(|name| name.to_string())
+    };
+    let bold_italic = Some("NotoSans-BoldItalic.ttf".to_string());
+    assert_eq!(find(Weight::Bold, system::Style::Italic), bold_italic);
+    assert_eq!(find(Weight::Black, system::Style::Oblique), bold_italic);
+    assert_eq!(
+        find(Weight::Light, system::Style::Normal),
+        Some("NotoSans-Regular.ttf".to_string())
+    );
+    assert_eq!(
+        system::best_match(&fonts, "serif", Weight::Normal, system::Style::Normal),
+        None
+    );
+}
+
+#[test]
+fn clusters_resolve_against_fallback_fonts() {
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fonts");
+    let tiny = font::from_file(dir.join("SourceSansPro-Regular-Tiny.ttf")).unwrap();
+    let fonts = vec![tiny, font::default_notosans()];
+    let scale = text::pt_to_scale(24);
+
+    // The first font lacks a glyph for the heart, so its cluster is resolved against the second.
+    let heart = '\u{2665}';
+    assert_eq!(fonts[0].glyph(heart).id(), text::GlyphId(0));
+    let line = shape::line_with_fallbacks("a\u{2665}", &fonts, scale);
+    let glyphs = line.glyphs();
+    assert_eq!(glyphs.len(), 2);
+    assert_eq!((glyphs[0].cluster, glyphs[0].font), (0, 0));
+    assert_eq!((glyphs[1].cluster, glyphs[1].font), (1, 1));
+    assert_ne!(glyphs[1].id, text::GlyphId(0));
+    let notdef = shape::line("\u{2665}", &fonts[0], scale).glyphs()[0];
+    assert_eq!(notdef.id, text::GlyphId(0));
+    assert!((glyphs[1].advance - notdef.advance).abs() > 1.0);
+    let fallback = shape::line("\u{2665}", &fonts[1], scale).glyphs()[0];
+    assert_eq!(glyphs[1].advance, fallback.advance);
+    assert_eq!(line.clusters()[1].x.start, glyphs[0].advance);
+
+    // Clusters supported by no font at all use the first font.
+    let line = shape::line_with_fallbacks("ab\u{5d0}", &fonts, scale);
+    assert!(line.glyphs().iter().all(|g| g.font == 0));
+    let single = shape::line("ab\u{5d0}", &fonts[0], scale);
+    assert_eq!(line.glyphs(), single.glyphs());
+
+    let text = text::text("a\u{2665}")
+        .font(fonts[0].clone())
+        .fallback_font(fonts[1].clone())
+        .build(Rect::from_w_h(100.0, 100.0));
+    assert_eq!(text.fonts().len(), 2);
+    let glyph_fonts: Vec<_> = text
+        .rt_glyphs_with_fonts(vec2(100.0, 100.0), 1.0)
+        .map(|(font, _)| font)
+        .collect();
+    assert_eq!(glyph_fonts, vec![0, 1]);
+}
+
+#[test]
+fn sdf_fields_are_packed_into_the_atlas() {
+    let font = font::default_notosans();
+    let glyph = font
+        .glyph('O')
+        .scaled(text::Scale::uniform(sdf::DEFAULT_SIZE));
+    let field = sdf::field(&glyph, sdf::DEFAULT_SPREAD).unwrap();
+
+    // The field saturates at its borders, lies outside within the hole and inside along the ring.
+    let (w, h) = (field.width, field.height);
+    assert_eq!(field.distance(0, 0), -1.0);
+    assert!(field.distance(w / 2, h / 2) < 0.0);
+    let ring = (0..w).find(|&x| field.distance(x, h / 2) > 0.0).unwrap();
+    assert!(ring > sdf::DEFAULT_SPREAD as u32 - 1 && ring < w / 2);
+
+    // Glyphs are generated once, and whitespace has no field.
+    let mut atlas = sdf::Atlas::new([128, 128], sdf::DEFAULT_SIZE, sdf::DEFAULT_SPREAD);
+    let o = atlas.glyph(&font, glyph.id()).unwrap().unwrap();
+    assert_eq!(o.bounds, field.bounds);
+    assert_eq!(o.uv_rect.min, text::rt::point(0.0, 0.0));
+    assert_eq!(atlas.glyph(&font, glyph.id()), Ok(Some(o)));
+    assert_eq!(atlas.glyph(&font, font.glyph(' ').id()), Ok(None));
+
+    // Once full, the atlas is cleared at the beginning of the next frame and may be refilled.
+    atlas.begin_frame();
+    assert_eq!(atlas.glyph(&font, glyph.id()), Ok(Some(o)));
+    let result = "ABCDEFGHIJKLMNOPQRSTUVWXYZ"
+        .chars()
+        .map(|c| atlas.glyph(&font, font.glyph(c).id()))
+        .collect::<Result<Vec<_>, _>>();
+    assert_eq!(result, Err(sdf::AtlasFull));
+    assert!(atlas.is_full());
+    atlas.begin_frame();
+    assert!(!atlas.is_full());
+    assert_eq!(atlas.glyph(&font, glyph.id()), Ok(Some(o)));
+}
+
+#[test]
+fn glyphs_are_placed_along_paths_by_distance() {
+    let font = font::default_notosans();
+    let line = shape::line("path", &font, text::pt_to_scale(24));
+    let glyphs = line.glyphs();
+    let approx = |a: f32, b: f32| (a - b).abs() < 1e-3;
+    let measure = |to| {
+        geom::path()
+            .move_to(pt2(0.0, 0.0))
+            .line_to(to)
+            .build()
+            .measure(0.1)
+    };
+
+    // Along a horizontal path, glyphs lie where they would along a line of text.
+    let horizontal = measure(pt2(200.0, 0.0));
+    let options = on_path::Options::default();
+    let placements = on_path::place(glyphs, &horizontal, &options);
+    assert_eq!(placements.len(), glyphs.len());
+    for (p, g) in placements.iter().zip(glyphs) {
+        assert!(approx(p.origin.x, g.position.x) && approx(p.origin.y, 0.0));
+        assert_eq!(p.rotation, 0.0);
+    }
+
+    // Right justified text ends at the end of the path, less the offset.
+    let options = on_path::Options {
+        justify: text::Justify::Right,
+        offset: -10.0,
+        ..options
+    };
+    let placements = on_path::place(glyphs, &horizontal, &options);
+    let last = glyphs.last().unwrap();
+    assert!(approx(
+        placements.last().unwrap().origin.x + last.advance,
+        190.0
+    ));
+
+    // Along a vertical path, glyphs are rotated to the tangent unless they remain upright.
+    let vertical = measure(pt2(0.0, 200.0));
+    let options = on_path::Options::default();
+    let first = on_path::place(glyphs, &vertical, &options)[0];
+    assert!(approx(first.rotation, PI / 2.0) && approx(first.origin.x, 0.0));
+    let options = on_path::Options {
+        rotate: false,
+        ..options
+    };
+    let first = on_path::place(glyphs, &vertical, &options)[0];
+    assert!(approx(first.rotation, 0.0) && approx(first.origin.x, -glyphs[0].advance / 2.0));
+
+    // Glyphs beyond the end of a short path are omitted when clipping.
+    let short = measure(pt2(line.width() / 2.0, 0.0));
+    let options = on_path::Options::default();
+    assert_eq!(on_path::place(glyphs, &short, &options).len(), glyphs.len());
+    let options = on_path::Options {
+        clip: true,
+        ..options
+    };
+    let clipped = on_path::place(glyphs, &short, &options);
+    assert!(!clipped.is_empty() && clipped.len() < glyphs.len());
+    for p in clipped {
+        assert!(p.origin.x + glyphs[p.index].advance <= short.length());
+    }
+}
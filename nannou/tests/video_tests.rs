@@ -0,0 +1,152 @@
+use nannou::image::codecs::{gif::GifDecoder, png::PngDecoder};
+use nannou::image::{AnimationDecoder, Rgba, RgbaImage};
+use nannou::video;
+use std::time::Duration;
+
+#[test]
+fn y4m_recorder_accounting() {
+    let path = std::env::temp_dir().join("nannou_y4m_recorder_accounting.y4m");
+    let fps = 30.0;
+    let mut recorder = video::Builder::new().fps(fps).build_file(&path).unwrap();
+    let frame = |v| RgbaImage::from_pixel(4, 2, Rgba([v, v, v, 255]));
+    let at = |n: f64| Duration::from_secs_f64(n / fps);
+    recorder.record_image_at(frame(0), at(0.0)).unwrap();
+    // Skips an output frame, so the previous frame should be duplicated.
+    recorder.record_image_at(frame(128), at(2.0)).unwrap();
+    // Arrives for an output frame that has already been written, so should be dropped.
+    recorder.record_image_at(frame(255), at(2.0)).unwrap();
+    let stats = recorder.finish().unwrap();
+    assert_eq!(stats.frames_recorded, 3);
+    assert_eq!(stats.frames_written, 3);
+    assert_eq!(stats.frames_duplicated, 1);
+    assert_eq!(stats.frames_dropped, 1);
+
+    let bytes = std::fs::read(&path).unwrap();
+    let header = b"YUV4MPEG2 W4 H2 F30:1 Ip A1:1 C420jpeg\n";
+    assert!(bytes.starts_with(header));
+    let frame_len = b"FRAME\n".len() + 4 * 2 + 2 * 2;
+    assert_eq!(bytes.len(), header.len() + frame_len * 3);
+    std::fs::remove_file(&path).ok();
+}
+
+#[test]
+fn image_sequence_manifest_lists_frames_with_timing() {
+    let dir = std::env::temp_dir().join("nannou_image_sequence_manifest");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("capture.txt");
+    let fps = 25.0;
+    let mut recorder = video::Builder::new()
+        .fps(fps)
+        .format(video::Format::ImageSequence)
+        .build_file(&path)
+        .unwrap();
+    let frame = |v| RgbaImage::from_pixel(4, 2, Rgba([v, v, v, 255]));
+    let at = |n: f64| Duration::from_secs_f64(n / fps);
+    recorder.record_image_at(frame(0), at(0.0)).unwrap();
+    // Skips an output frame, so the previous frame should be listed again.
+    recorder.record_image_at(frame(128), at(2.0)).unwrap();
+    let stats = recorder.finish().unwrap();
+    assert_eq!(stats.frames_written, 3);
+    assert_eq!(stats.frames_duplicated, 1);
+
+    let manifest = std::fs::read_to_string(&path).unwrap();
+    let expected = "ffconcat version 1.0\n\
+        file 'capture-000000.png'\nduration 0.04\n\
+        file 'capture-000000.png'\nduration 0.04\n\
+        file 'capture-000002.png'\nduration 0.04\n";
+    assert_eq!(manifest, expected);
+    for (name, v) in &[("capture-000000.png", 0), ("capture-000002.png", 128)] {
+        let image = nannou::image::open(dir.join(name)).unwrap().to_rgba8();
+        assert_eq!(image, frame(*v));
+    }
+    assert!(!dir.join("capture-000001.png").exists());
+
+    // There is nowhere to save the frames of a sequence written to a pipe.
+    let result = video::Builder::new()
+        .format(video::Format::ImageSequence)
+        .build_pipe(std::process::Command::new("cat"));
+    assert!(result.is_err());
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[cfg(unix)]
+#[test]
+fn encoder_process_is_killed_when_writing_fails() {
+    let pid_path = std::env::temp_dir().join("nannou_video_encoder_pid");
+    std::fs::remove_file(&pid_path).ok();
+    let mut command = std::process::Command::new("sh");
+    let script = format!("echo $$ > {}; exec sleep 30", pid_path.display());
+    command.arg("-c").arg(script);
+    let mut recorder = video::Builder::new()
+        .format(video::Format::RawRgba)
+        .build_pipe(command)
+        .unwrap();
+    let pid = loop {
+        match std::fs::read_to_string(&pid_path) {
+            Ok(pid) if pid.ends_with('\n') => break pid.trim().to_string(),
+            _ => std::thread::sleep(Duration::from_millis(10)),
+        }
+    };
+    // Frames of differing sizes cannot be written to the same stream.
+    let frame = |w| RgbaImage::from_pixel(w, 2, Rgba([0, 0, 0, 255]));
+    recorder.record_image(frame(4)).unwrap();
+    recorder.record_image(frame(8)).ok();
+    match recorder.finish() {
+        Err(video::Error::FrameSize { expected, actual }) => {
+            assert_eq!((expected, actual), ([4, 2], [8, 2]));
+        }
+        result => panic!("unexpected result: {:?}", result.map(|_| ())),
+    }
+    // The encoder was killed and reaped rather than left sleeping.
+    let alive = std::process::Command::new("kill")
+        .arg("-0")
+        .arg(&pid)
+        .stderr(std::process::Stdio::null())
+        .status()
+        .unwrap()
+        .success();
+    assert!(!alive);
+    std::fs::remove_file(&pid_path).ok();
+}
+
+fn gradient(v: u8) -> RgbaImage {
+    RgbaImage::from_fn(8, 4, |x, y| Rgba([v, x as u8 * 30, y as u8 * 60, 255]))
+}
+
+#[test]
+fn apng_round_trip() {
+    let mut encoder = video::apng::Builder::new().build(vec![]);
+    for i in 0..3 {
+        let delay = Duration::from_millis(25 * (i + 1));
+        encoder
+            .encode_frame(gradient(i as u8 * 100), delay)
+            .unwrap();
+    }
+    let bytes = encoder.finish().unwrap();
+    let decoder = PngDecoder::new(&bytes[..]).unwrap();
+    assert!(decoder.is_apng());
+    let frames = decoder.apng().into_frames().collect_frames().unwrap();
+    assert_eq!(frames.len(), 3);
+    for (i, frame) in frames.iter().enumerate() {
+        assert_eq!(frame.buffer(), &gradient(i as u8 * 100));
+        assert_eq!(frame.delay().numer_denom_ms(), (25 * (i as u32 + 1), 1));
+    }
+}
+
+#[test]
+fn gif_frames_within_palette_are_exact() {
+    let mut encoder = video::gif::Builder::new().build(vec![]).unwrap();
+    for i in 0..3 {
+        encoder
+            .encode_frame(gradient(i * 100), Duration::from_millis(40))
+            .unwrap();
+    }
+    let bytes = encoder.finish().unwrap();
+    let decoder = GifDecoder::new(&bytes[..]).unwrap();
+    let frames = decoder.into_frames().collect_frames().unwrap();
+    assert_eq!(frames.len(), 3);
+    for (i, frame) in frames.iter().enumerate() {
+        assert_eq!(frame.buffer(), &gradient(i as u8 * 100));
+        assert_eq!(frame.delay().numer_denom_ms(), (40, 1));
+    }
+}